@@ -1,12 +1,20 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use std::collections::HashSet;
+use std::path::Path;
 
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
 use deno_core::serde_json;
+use deno_runtime::fs_util::specifier_to_file_path;
 use deno_semver::jsr::JsrDepPackageReq;
 use deno_semver::jsr::JsrPackageReqReference;
 use deno_semver::npm::NpmPackageReqReference;
 
+use super::flags_net;
+use super::PermissionFlags;
+
 pub fn deno_json_deps(
   config: &deno_config::ConfigFile,
 ) -> HashSet<JsrDepPackageReq> {
@@ -49,3 +57,175 @@ fn values_to_set<'a>(
   }
   entries
 }
+
+/// Resolves a named permission set from the `permissions` section of the
+/// config file, which `deno_config` doesn't know about:
+///
+/// ```jsonc
+/// "permissions": {
+///   "server": {
+///     "allow": { "net": ["0.0.0.0:8000"], "read": ["./static"], "env": true },
+///     "deny": { "env": ["AWS_SECRET_ACCESS_KEY"] }
+///   }
+/// }
+/// ```
+///
+/// Paths are resolved relative to the config file.
+pub fn permission_set(
+  config: &deno_config::ConfigFile,
+  name: &str,
+) -> Result<PermissionFlags, AnyError> {
+  let config_path = specifier_to_file_path(&config.specifier)?;
+  let text = std::fs::read_to_string(&config_path)
+    .with_context(|| format!("Failed reading {}.", config_path.display()))?;
+  let value = jsonc_parser::parse_to_serde_value(&text, &Default::default())
+    .with_context(|| format!("Failed parsing {}.", config_path.display()))?
+    .unwrap_or_default();
+  let Some(set) = value
+    .get("permissions")
+    .and_then(|permissions| permissions.get(name))
+  else {
+    bail!(
+      "Permission set '{}' is not defined in the \"permissions\" section of {}.",
+      name,
+      config.specifier
+    );
+  };
+  parse_permission_set(set, config_path.parent().unwrap())
+    .with_context(|| format!("Invalid permission set '{}'.", name))
+}
+
+fn parse_permission_set(
+  set: &serde_json::Value,
+  config_dir: &Path,
+) -> Result<PermissionFlags, AnyError> {
+  let serde_json::Value::Object(set) = set else {
+    bail!("Expected an object with \"allow\" and \"deny\" lists.");
+  };
+  let mut flags = PermissionFlags::default();
+  for (key, value) in set {
+    let is_allow = match key.as_str() {
+      "allow" => true,
+      "deny" => false,
+      _ => bail!("Unknown key \"{}\".", key),
+    };
+    let serde_json::Value::Object(kinds) = value else {
+      bail!("Expected \"{}\" to be an object.", key);
+    };
+    for (kind, value) in kinds {
+      let list = || -> Result<Option<Vec<String>>, AnyError> {
+        match value {
+          serde_json::Value::Bool(true) => Ok(Some(vec![])),
+          serde_json::Value::Bool(false) => Ok(None),
+          serde_json::Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+              serde_json::Value::String(item) => Ok(item.clone()),
+              _ => bail!("Expected \"{}.{}\" to list strings.", key, kind),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some),
+          _ => {
+            bail!("Expected \"{}.{}\" to be a boolean or an array.", key, kind)
+          }
+        }
+      };
+      let paths = || -> Result<Option<Vec<String>>, AnyError> {
+        Ok(list()?.map(|paths| {
+          paths
+            .iter()
+            .map(|path| config_dir.join(path).to_string_lossy().to_string())
+            .collect()
+        }))
+      };
+      let flag = |value: &serde_json::Value| match value {
+        serde_json::Value::Bool(value) => Ok(*value),
+        _ => bail!("Expected \"{}.{}\" to be a boolean.", key, kind),
+      };
+      match (kind.as_str(), is_allow) {
+        ("all", true) => flags.allow_all = flag(value)?,
+        ("hrtime", true) => flags.allow_hrtime = flag(value)?,
+        ("hrtime", false) => flags.deny_hrtime = flag(value)?,
+        ("env", true) => flags.allow_env = list()?,
+        ("env", false) => flags.deny_env = list()?,
+        ("ffi", true) => flags.allow_ffi = paths()?,
+        ("ffi", false) => flags.deny_ffi = paths()?,
+        ("net", _) => {
+          let net = list()?
+            .map(flags_net::parse)
+            .transpose()
+            .map_err(|err| deno_core::anyhow::anyhow!("{}", err))?;
+          if is_allow {
+            flags.allow_net = net;
+          } else {
+            flags.deny_net = net;
+          }
+        }
+        ("read", true) => flags.allow_read = paths()?,
+        ("read", false) => flags.deny_read = paths()?,
+        ("run", true) => flags.allow_run = list()?,
+        ("run", false) => flags.deny_run = list()?,
+        ("sys", true) => flags.allow_sys = list()?,
+        ("sys", false) => flags.deny_sys = list()?,
+        ("write", true) => flags.allow_write = paths()?,
+        ("write", false) => flags.deny_write = paths()?,
+        _ => bail!("Unknown permission \"{}.{}\".", key, kind),
+      }
+    }
+  }
+  Ok(flags)
+}
+
+#[cfg(test)]
+mod test {
+  use deno_core::serde_json::json;
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn parses_permission_set() {
+    let config_dir = Path::new("/project");
+    let flags = parse_permission_set(
+      &json!({
+        "allow": {
+          "env": true,
+          "net": ["example.com", "0.0.0.0:8000"],
+          "read": ["./static"],
+          "hrtime": true,
+        },
+        "deny": {
+          "env": ["AWS_SECRET_ACCESS_KEY"],
+          "write": true,
+        },
+      }),
+      config_dir,
+    )
+    .unwrap();
+    assert_eq!(
+      flags,
+      PermissionFlags {
+        allow_env: Some(vec![]),
+        deny_env: Some(vec!["AWS_SECRET_ACCESS_KEY".to_string()]),
+        allow_net: Some(vec![
+          "example.com".to_string(),
+          "0.0.0.0:8000".to_string()
+        ]),
+        allow_read: Some(vec![config_dir
+          .join("./static")
+          .to_string_lossy()
+          .to_string()]),
+        deny_write: Some(vec![]),
+        allow_hrtime: true,
+        ..Default::default()
+      }
+    );
+
+    parse_permission_set(&json!({ "allow": { "disk": true } }), config_dir)
+      .unwrap_err();
+    parse_permission_set(&json!({ "deny": { "all": true } }), config_dir)
+      .unwrap_err();
+    parse_permission_set(&json!({ "allow": { "net": 1 } }), config_dir)
+      .unwrap_err();
+  }
+}
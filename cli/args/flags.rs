@@ -84,7 +84,21 @@ pub struct CompileFlags {
   pub target: Option<String>,
   pub no_terminal: bool,
   pub include: Vec<String>,
-}
+  pub include_assets: Vec<String>,
+  /// The zstd level the embedded files are compressed with.
+  pub compress: Option<i32>,
+  pub base_binaries: Option<String>,
+  pub list_targets: bool,
+}
+
+/// The targets that `deno compile` can produce executables for.
+pub const COMPILE_TARGETS: [&str; 5] = [
+  "x86_64-unknown-linux-gnu",
+  "aarch64-unknown-linux-gnu",
+  "x86_64-pc-windows-msvc",
+  "x86_64-apple-darwin",
+  "aarch64-apple-darwin",
+];
 
 impl CompileFlags {
   pub fn resolve_target(&self) -> String {
@@ -136,12 +150,25 @@ pub struct DocHtmlFlag {
   pub output: String,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DocMarkdownFlag {
+  pub name: Option<String>,
+  pub output: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DocManFlag {
+  pub name: Option<String>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DocFlags {
   pub private: bool,
   pub json: bool,
   pub lint: bool,
   pub html: Option<DocHtmlFlag>,
+  pub markdown: Option<DocMarkdownFlag>,
+  pub man: Option<DocManFlag>,
   pub source_files: DocSourceFileFlag,
   pub filter: Option<String>,
 }
@@ -162,6 +189,10 @@ pub struct FmtFlags {
   pub single_quote: Option<bool>,
   pub prose_wrap: Option<String>,
   pub no_semicolons: Option<bool>,
+  /// Only format the lines changed relative to this git revision.
+  pub changed: Option<String>,
+  /// Only format the lines of the changes staged in git.
+  pub staged: bool,
   pub watch: Option<WatchFlags>,
 }
 
@@ -241,6 +272,9 @@ pub struct LintFlags {
   pub maybe_rules_exclude: Option<Vec<String>>,
   pub json: bool,
   pub compact: bool,
+  pub reporter: Option<String>,
+  pub baseline: Option<String>,
+  pub write_baseline: bool,
   pub watch: Option<WatchFlags>,
 }
 
@@ -331,6 +365,7 @@ pub enum TestReporterConfig {
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct TestFlags {
   pub doc: bool,
+  pub run_doc: bool,
   pub no_run: bool,
   pub coverage_dir: Option<String>,
   pub clean: bool,
@@ -369,11 +404,21 @@ pub struct PublishFlags {
   pub allow_slow_types: bool,
   pub allow_dirty: bool,
   pub no_provenance: bool,
+  pub check_semver: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApiDiffFlags {
+  /// The version to compare against. Defaults to the latest published
+  /// version of the package.
+  pub base: Option<String>,
+  pub json: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DenoSubcommand {
   Add(AddFlags),
+  ApiDiff(ApiDiffFlags),
   Bench(BenchFlags),
   Bundle(BundleFlags),
   Cache(CacheFlags),
@@ -551,6 +596,11 @@ pub struct PermissionFlags {
   pub allow_write: Option<Vec<String>>,
   pub deny_write: Option<Vec<String>>,
   pub no_prompt: bool,
+  /// The name of a permission set from the `permissions` section of the
+  /// config file.
+  pub permission_set: Option<String>,
+  /// Where to write the audit log of permission checks. `-` is stderr.
+  pub permission_audit: Option<String>,
 }
 
 impl PermissionFlags {
@@ -572,6 +622,52 @@ impl PermissionFlags {
       || self.deny_sys.is_some()
       || self.allow_write.is_some()
       || self.deny_write.is_some()
+      || self.permission_set.is_some()
+  }
+
+  /// Layers these flags on top of the ones of a permission set. The allow and
+  /// deny lists of both are combined, and the result no longer names the set
+  /// since it includes its lists.
+  pub fn layer_on(self, base: PermissionFlags) -> PermissionFlags {
+    fn combine(
+      base: Option<Vec<String>>,
+      flag: Option<Vec<String>>,
+    ) -> Option<Vec<String>> {
+      match (base, flag) {
+        // an empty list applies to everything
+        (Some(base), Some(flag)) if base.is_empty() || flag.is_empty() => {
+          Some(vec![])
+        }
+        (Some(mut base), Some(flag)) => {
+          base.extend(flag);
+          Some(base)
+        }
+        (base, flag) => base.or(flag),
+      }
+    }
+
+    PermissionFlags {
+      allow_all: base.allow_all || self.allow_all,
+      allow_env: combine(base.allow_env, self.allow_env),
+      deny_env: combine(base.deny_env, self.deny_env),
+      allow_hrtime: base.allow_hrtime || self.allow_hrtime,
+      deny_hrtime: base.deny_hrtime || self.deny_hrtime,
+      allow_ffi: combine(base.allow_ffi, self.allow_ffi),
+      deny_ffi: combine(base.deny_ffi, self.deny_ffi),
+      allow_net: combine(base.allow_net, self.allow_net),
+      deny_net: combine(base.deny_net, self.deny_net),
+      allow_read: combine(base.allow_read, self.allow_read),
+      deny_read: combine(base.deny_read, self.deny_read),
+      allow_run: combine(base.allow_run, self.allow_run),
+      deny_run: combine(base.deny_run, self.deny_run),
+      allow_sys: combine(base.allow_sys, self.allow_sys),
+      deny_sys: combine(base.deny_sys, self.deny_sys),
+      allow_write: combine(base.allow_write, self.allow_write),
+      deny_write: combine(base.deny_write, self.deny_write),
+      no_prompt: base.no_prompt || self.no_prompt,
+      permission_set: None,
+      permission_audit: self.permission_audit,
+    }
   }
 
   pub fn to_options(
@@ -649,6 +745,10 @@ impl Flags {
   pub fn to_permission_args(&self) -> Vec<String> {
     let mut args = vec![];
 
+    if let Some(permission_set) = &self.permissions.permission_set {
+      args.push(format!("--permission-set={}", permission_set));
+    }
+
     if self.permissions.allow_all {
       args.push("--allow-all".to_string());
       return args;
@@ -913,7 +1013,7 @@ impl Flags {
           .ok()
       }
       Task(_) | Check(_) | Coverage(_) | Cache(_) | Info(_) | Eval(_)
-      | Test(_) | Bench(_) | Repl(_) | Compile(_) | Publish(_) => {
+      | Test(_) | Bench(_) | Repl(_) | Compile(_) | Publish(_) | ApiDiff(_) => {
         Some(current_dir.to_path_buf())
       }
       Add(_) | Bundle(_) | Completions(_) | Doc(_) | Fmt(_) | Init(_)
@@ -952,6 +1052,7 @@ impl Flags {
         || arg.starts_with("--deny-sys")
         || arg.starts_with("--allow-write")
         || arg.starts_with("--deny-write")
+        || arg.starts_with("--permission-set")
     })
   }
 
@@ -1126,6 +1227,10 @@ pub fn flags_from_vec(args: Vec<OsString>) -> clap::error::Result<Flags> {
   }
 
   for (name, _, _) in crate::UNSTABLE_GRANULAR_FLAGS {
+    // `--unstable-hmr` is an argument of the subcommands supporting it
+    if *name == "hmr" {
+      continue;
+    }
     if matches.get_flag(&format!("unstable-{}", name)) {
       flags.unstable_config.features.push(name.to_string());
     }
@@ -1151,6 +1256,7 @@ pub fn flags_from_vec(args: Vec<OsString>) -> clap::error::Result<Flags> {
   if let Some((subcommand, mut m)) = matches.remove_subcommand() {
     match subcommand.as_str() {
       "add" => add_parse(&mut flags, &mut m),
+      "api-diff" => api_diff_parse(&mut flags, &mut m),
       "bench" => bench_parse(&mut flags, &mut m),
       "bundle" => bundle_parse(&mut flags, &mut m),
       "cache" => cache_parse(&mut flags, &mut m),
@@ -1284,6 +1390,9 @@ fn clap_root() -> Command {
     );
 
   for (flag_name, help, _) in crate::UNSTABLE_GRANULAR_FLAGS {
+    if *flag_name == "hmr" {
+      continue;
+    }
     cmd = cmd.arg(
       Arg::new(format!("unstable-{}", flag_name))
         .long(format!("unstable-{}", flag_name))
@@ -1318,6 +1427,7 @@ fn clap_root() -> Command {
     .defer(|cmd| {
       cmd
         .subcommand(add_subcommand())
+        .subcommand(api_diff_subcommand())
         .subcommand(bench_subcommand())
         .subcommand(bundle_subcommand())
         .subcommand(cache_subcommand())
@@ -1550,6 +1660,10 @@ Cross-compiling to different target architectures is supported using the
 `--target` flag. On the first invocation with deno will download proper
 binary and cache it in $DENO_DIR. The aarch64-apple-darwin target is not
 supported in canary.
+
+To compile without network access, point `--base-binaries` at a directory
+containing the `denort-<target>.zip` archives for this version of Deno, or at
+a single archive. Use `--list-targets` to see the targets available offline.
 ",
     )
     .defer(|cmd| {
@@ -1568,6 +1682,19 @@ supported in canary.
           .action(ArgAction::Append)
           .value_hint(ValueHint::FilePath),
       )
+      .arg(
+        Arg::new("include-assets")
+          .long("include-assets")
+          .help("Data files to embed in the executable")
+          .long_help(
+            "Embeds the files matching the given path or glob in the compiled
+    executable, such as templates or WASM files. They can be read with the file
+    system APIs at the paths they had when compiling. This flag can be passed
+    multiple times.",
+          )
+          .action(ArgAction::Append)
+          .value_hint(ValueHint::AnyPath),
+      )
       .arg(
         Arg::new("output")
           .long("output")
@@ -1580,13 +1707,27 @@ supported in canary.
         Arg::new("target")
           .long("target")
           .help("Target OS architecture")
-          .value_parser([
-            "x86_64-unknown-linux-gnu",
-            "aarch64-unknown-linux-gnu",
-            "x86_64-pc-windows-msvc",
-            "x86_64-apple-darwin",
-            "aarch64-apple-darwin",
-          ]),
+          .value_parser(COMPILE_TARGETS),
+      )
+      .arg(
+        Arg::new("base-binaries")
+          .long("base-binaries")
+          .help("Directory or archive of base binaries to use instead of downloading them")
+          .long_help(
+            "Uses the base runtime binaries in the given directory or archive
+    instead of downloading them. The directory may contain the
+    `denort-<target>.zip` archives directly or in the layout of dl.deno.land.
+    Archives are verified against a `<archive>.sha256sum` or `SHA256SUMS` file
+    next to them, when present.",
+          )
+          .value_parser(value_parser!(String))
+          .value_hint(ValueHint::AnyPath),
+      )
+      .arg(
+        Arg::new("list-targets")
+          .long("list-targets")
+          .help("List the targets and whether they are available offline")
+          .action(ArgAction::SetTrue),
       )
       .arg(
         Arg::new("no-terminal")
@@ -1594,9 +1735,29 @@ supported in canary.
           .help("Hide terminal on Windows")
           .action(ArgAction::SetTrue),
       )
+      .arg(
+        Arg::new("compress")
+          .long("compress")
+          .num_args(0..=1)
+          .require_equals(true)
+          .value_name("LEVEL")
+          .value_parser(value_parser!(i32).range(1..=22))
+          .default_missing_value("19")
+          .help("Compress the files embedded in the executable")
+          .long_help(
+            "Compresses the files embedded in the executable, such as the
+    node_modules directory, with the given zstd level from 1 to 22. Higher
+    levels produce smaller executables, but take longer to compile.
+    Defaults to 19. Files are decompressed when they are first read.",
+          ),
+      )
       .arg(executable_ext_arg())
       .arg(env_file_arg())
-      .arg(script_arg().required(true).trailing_var_arg(true))
+      .arg(
+        script_arg()
+          .required_unless_present("list-targets")
+          .trailing_var_arg(true),
+      )
     })
 }
 
@@ -1745,6 +1906,14 @@ Output documentation in HTML format:
     deno doc --html --name=\"My library\" ./main.ts ./dev.ts
     deno doc --html --name=\"My library\" --output=./documentation/ ./path/to/module.ts
 
+Output documentation in Markdown format, with a file per module and symbol:
+
+    deno doc --markdown --name=\"My library\" --output=./wiki/ ./path/to/module.ts
+
+Output a man page for a command line tool:
+
+    deno doc --man --name=my-tool ./main.ts > my-tool.1
+
 Output private documentation to standard output:
 
     deno doc --private ./path/to/module.ts
@@ -1787,6 +1956,20 @@ Show documentation for runtime built-ins:
             .action(ArgAction::SetTrue)
             .conflicts_with("json")
         )
+        .arg(
+          Arg::new("markdown")
+            .long("markdown")
+            .help("Output documentation in Markdown format")
+            .action(ArgAction::SetTrue)
+            .conflicts_with_all(["json", "html"])
+        )
+        .arg(
+          Arg::new("man")
+            .long("man")
+            .help("Output a man page to standard output")
+            .action(ArgAction::SetTrue)
+            .conflicts_with_all(["json", "html", "markdown", "output"])
+        )
         .arg(
           Arg::new("name")
             .long("name")
@@ -1797,7 +1980,7 @@ Show documentation for runtime built-ins:
         .arg(
           Arg::new("output")
             .long("output")
-            .help("Directory for HTML documentation output")
+            .help("Directory for HTML or Markdown documentation output")
             .action(ArgAction::Set)
             .require_equals(true)
             .value_hint(ValueHint::DirPath)
@@ -1816,7 +1999,9 @@ Show documentation for runtime built-ins:
             .required(false)
             .conflicts_with("json")
             .conflicts_with("lint")
-            .conflicts_with("html"),
+            .conflicts_with("html")
+            .conflicts_with("markdown")
+            .conflicts_with("man"),
         )
         .arg(
           Arg::new("lint")
@@ -1834,7 +2019,12 @@ Show documentation for runtime built-ins:
             .num_args(1..)
             .action(ArgAction::Append)
             .value_hint(ValueHint::FilePath)
-            .required_if_eq_any([("html", "true"), ("lint", "true")]),
+            .required_if_eq_any([
+              ("html", "true"),
+              ("markdown", "true"),
+              ("man", "true"),
+              ("lint", "true"),
+            ]),
         )
     })
 }
@@ -1890,7 +2080,9 @@ fn fmt_subcommand() -> Command {
   Command::new("fmt")
     .about("Format source files")
     .long_about(
-      "Auto-format JavaScript, TypeScript, Markdown, and JSON files.
+      "Auto-format JavaScript, TypeScript, Markdown, and JSON files. CSS, HTML,
+YAML and SQL files, as well as the `css`, `html` and `sql` tagged templates,
+are formatted when enabled by their \"fmt\" config section.
 
   deno fmt
   deno fmt myfile1.ts myfile2.ts
@@ -1906,7 +2098,14 @@ Ignore formatting code by preceding it with an ignore comment:
 
 Ignore formatting a file by adding an ignore comment at the top of the file:
 
-  // deno-fmt-ignore-file",
+  // deno-fmt-ignore-file
+
+Only format the lines changed relative to a git revision (defaults to HEAD)
+or the lines of the changes staged in git:
+
+  deno fmt --changed
+  deno fmt --changed=main --check
+  deno fmt --staged",
     )
     .defer(|cmd| {
       cmd
@@ -1925,7 +2124,9 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
             // prefer using ts for formatting instead of js because ts works in more scenarios
             .default_value("ts")
             .value_parser([
-              "ts", "tsx", "js", "jsx", "md", "json", "jsonc", "ipynb",
+              "ts", "tsx", "js", "jsx", "md", "json", "jsonc", "ipynb", "css",
+              "scss", "sass", "less", "html", "vue", "svelte", "astro", "yml",
+              "yaml", "sql",
             ]),
         )
         .arg(
@@ -2002,6 +2203,23 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
               "Don't use semicolons except where necessary. Defaults to false.",
             ),
         )
+        .arg(
+          Arg::new("changed")
+            .long("changed")
+            .num_args(0..=1)
+            .require_equals(true)
+            .default_missing_value("HEAD")
+            .value_name("REV")
+            .help("Only format the lines changed relative to a git revision")
+            .conflicts_with_all(["staged", "watch"]),
+        )
+        .arg(
+          Arg::new("staged")
+            .long("staged")
+            .action(ArgAction::SetTrue)
+            .help("Only format the lines of the changes staged in git")
+            .conflicts_with("watch"),
+        )
     })
 }
 
@@ -2295,6 +2513,13 @@ Print result as JSON:
 
   deno lint --json
 
+Print result in another format, like SARIF for code scanning, GitHub Actions
+workflow annotations or JUnit XML:
+
+  deno lint --reporter=sarif
+  deno lint --reporter=github
+  deno lint --reporter=junit
+
 Read from stdin:
 
   cat file.ts | deno lint -
@@ -2315,6 +2540,12 @@ Names of rules to ignore must be specified after ignore comment.
 Ignore linting a file by adding an ignore comment at the top of the file:
 
   // deno-lint-ignore-file
+
+Record the existing diagnostics in a baseline file and only report new ones:
+
+  deno lint --write-baseline
+  deno lint --baseline
+  deno lint --baseline=lint-baseline.json
 ",
     )
     .defer(|cmd| {
@@ -2382,6 +2613,32 @@ Ignore linting a file by adding an ignore comment at the top of the file:
             .action(ArgAction::SetTrue)
             .conflicts_with("json"),
         )
+        .arg(
+          Arg::new("reporter")
+            .long("reporter")
+            .help("Select reporter to use. Default to 'pretty'.")
+            .value_parser([
+              "pretty", "compact", "json", "sarif", "github", "junit",
+            ])
+            .conflicts_with_all(["json", "compact"]),
+        )
+        .arg(
+          Arg::new("baseline")
+            .long("baseline")
+            .num_args(0..=1)
+            .require_equals(true)
+            .default_missing_value("deno-lint-baseline.json")
+            .value_name("FILE")
+            .help("Don't report the diagnostics recorded in a baseline file. Defaults to 'deno-lint-baseline.json'.")
+            .value_hint(ValueHint::FilePath),
+        )
+        .arg(
+          Arg::new("write-baseline")
+            .long("write-baseline")
+            .help("Write the current diagnostics to the baseline file instead of reporting them")
+            .action(ArgAction::SetTrue)
+            .conflicts_with_all(["fix", "watch"]),
+        )
         .arg(
           Arg::new("files")
             .num_args(1..)
@@ -2533,6 +2790,9 @@ fn task_subcommand() -> Command {
             .help("Specify the directory to run the task in")
             .value_hint(ValueHint::DirPath),
         )
+        .arg(permission_set_arg().help(
+          "Use a set of permissions from the \"permissions\" section of the configuration file for the deno commands of the task",
+        ))
     })
 }
 
@@ -2550,7 +2810,13 @@ report results to standard output:
 Directory arguments are expanded to all contained files matching the glob
 {*_,*.,}test.{js,mjs,ts,mts,jsx,tsx}:
 
-  deno test src/",
+  deno test src/
+
+Run the code blocks of the documentation as tests. Each example becomes a
+test named after its location, and the README.md of the current directory is
+included:
+
+  deno test --run-doc src/",
     )
   .defer(|cmd| runtime_args(cmd, true, true)
     .arg(check_arg(true))
@@ -2588,6 +2854,12 @@ Directory arguments are expanded to all contained files matching the glob
         .help("Type-check code blocks in JSDoc and Markdown")
         .action(ArgAction::SetTrue),
     )
+    .arg(
+      Arg::new("run-doc")
+        .long("run-doc")
+        .help("Type-check and run code blocks in JSDoc and Markdown as tests")
+        .action(ArgAction::SetTrue),
+    )
     .arg(
       Arg::new("fail-fast")
         .long("fail-fast")
@@ -2839,11 +3111,57 @@ fn publish_subcommand() -> Command {
           .help("Disable provenance attestation. Enabled by default on Github actions, publicly links the package to where it was built and published from.")
           .action(ArgAction::SetTrue)
       )
+      .arg(
+        Arg::new("check-semver")
+          .long("check-semver")
+          .help("Refuse to publish if the version bump is smaller than the changes to the public API since the latest published version require")
+          .action(ArgAction::SetTrue),
+      )
       .arg(check_arg(/* type checks by default */ true))
       .arg(no_check_arg())
     })
 }
 
+fn api_diff_subcommand() -> Command {
+  Command::new("api-diff")
+    .hide(true)
+    .about("Unstable preview feature: Compare the public API of the current working directory's package to a previous version")
+    .long_about(
+      "Compare the public API of the current working directory's package to a
+previous version, and show the version bump the changes require.
+
+Removed and changed symbols require a major bump, added symbols, overloads
+and optional parameters a minor bump and documentation changes a patch bump.
+
+Compare to the latest published version:
+
+  deno api-diff
+
+Compare to a published version, a git revision or a tarball:
+
+  deno api-diff 1.2.0
+  deno api-diff git:main
+  deno api-diff ./package-1.2.0.tgz",
+    )
+    .defer(|cmd| {
+      cmd
+        .arg(
+          Arg::new("base")
+            .help("The version to compare against: a version, jsr:<package>@<version>, git:<revision> or a path to a tarball"),
+        )
+        .arg(
+          Arg::new("json")
+            .long("json")
+            .help("Output the changes in JSON format")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(config_arg())
+        .arg(no_config_arg())
+        .arg(reload_arg())
+        .arg(ca_file_arg())
+    })
+}
+
 fn compile_args(app: Command) -> Command {
   compile_args_without_check_args(app.arg(no_check_arg()))
 }
@@ -2866,62 +3184,74 @@ fn compile_args_without_check_args(app: Command) -> Command {
 
 static ALLOW_READ_HELP: &str = concat!(
   "Allow file system read access. Optionally specify allowed paths.\n",
+  "Paths containing `*` or `?` are glob patterns.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
   "Examples:\n",
   "  --allow-read\n",
-  "  --allow-read=\"/etc,/var/log.txt\""
+  "  --allow-read=\"/etc,/var/log.txt\"\n",
+  "  --allow-read=\"./data/**/*.json\""
 );
 
 static DENY_READ_HELP: &str = concat!(
   "Deny file system read access. Optionally specify denied paths.\n",
+  "Paths containing `*` or `?` are glob patterns.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
   "Examples:\n",
   "  --deny-read\n",
-  "  --deny-read=\"/etc,/var/log.txt\""
+  "  --deny-read=\"/etc,/var/log.txt\"\n",
+  "  --deny-read=\"**/.env\""
 );
 
 static ALLOW_WRITE_HELP: &str = concat!(
   "Allow file system write access. Optionally specify allowed paths.\n",
+  "Paths containing `*` or `?` are glob patterns.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
   "Examples:\n",
   "  --allow-write\n",
-  "  --allow-write=\"/etc,/var/log.txt\""
+  "  --allow-write=\"/etc,/var/log.txt\"\n",
+  "  --allow-write=\"./out/*.txt\""
 );
 
 static DENY_WRITE_HELP: &str = concat!(
   "Deny file system write access. Optionally specify denied paths.\n",
+  "Paths containing `*` or `?` are glob patterns.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
   "Examples:\n",
   "  --deny-write\n",
-  "  --deny-write=\"/etc,/var/log.txt\""
+  "  --deny-write=\"/etc,/var/log.txt\"\n",
+  "  --deny-write=\"**/.env\""
 );
 
 static ALLOW_NET_HELP: &str = concat!(
   "Allow network access. Optionally specify allowed IP addresses and host names, with ports as necessary.\n",
+  "Hosts can be `*.` wildcards of subdomains or CIDR blocks, and ports can be ranges.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
   "Examples:\n",
   "  --allow-net\n",
-  "  --allow-net=\"localhost:8080,deno.land\""
+  "  --allow-net=\"localhost:8080,deno.land\"\n",
+  "  --allow-net=\"*.internal.example.com,10.0.0.0/8,localhost:8000-8100\""
 );
 
 static DENY_NET_HELP: &str = concat!(
   "Deny network access. Optionally specify denied IP addresses and host names, with ports as necessary.\n",
+  "Hosts can be `*.` wildcards of subdomains or CIDR blocks, and ports can be ranges.\n",
   "Docs: https://deno.land/manual@v",
   env!("CARGO_PKG_VERSION"),
   "/basics/permissions\n",
   "Examples:\n",
   "  --deny-net\n",
-  "  --deny-net=\"localhost:8080,deno.land\""
+  "  --deny-net=\"localhost:8080,deno.land\"\n",
+  "  --deny-net=\"*.internal.example.com,10.0.0.0/8,localhost:8000-8100\""
 );
 
 static ALLOW_ENV_HELP: &str = concat!(
@@ -3018,6 +3348,24 @@ static DENY_HRTIME_HELP: &str = concat!(
   "/basics/permissions\n"
 );
 
+static PERMISSION_SET_HELP: &str = concat!(
+  "Use a set of permissions from the \"permissions\" section of the configuration file.\n",
+  "Other permission flags are combined with the ones of the set. Defaults to the \"default\" set.\n",
+  "Examples:\n",
+  "  -P\n",
+  "  --permission-set=server"
+);
+
+static PERMISSION_AUDIT_HELP: &str = concat!(
+  "Write a line of JSON for every permission check to the given file, or to\n",
+  "stderr if no file is given. The lines hold the permission, the checked path,\n",
+  "host, variable or command, the API name, the result and the calling location.\n",
+  "The --allow-* flags granting the checked permissions are printed at exit.\n",
+  "Examples:\n",
+  "  --permission-audit\n",
+  "  --permission-audit=audit.jsonl"
+);
+
 static ALLOW_ALL_HELP: &str = concat!(
   "Allow all permissions. Learn more about permissions in Deno:\n",
   "https://deno.land/manual@v",
@@ -3211,12 +3559,34 @@ fn permission_args(app: Command) -> Command {
         .action(ArgAction::SetTrue)
         .help(ALLOW_ALL_HELP),
     )
+    .arg(permission_set_arg())
     .arg(
       Arg::new("no-prompt")
         .long("no-prompt")
         .action(ArgAction::SetTrue)
         .help("Always throw if required permission wasn't passed"),
     )
+    .arg(
+      Arg::new("permission-audit")
+        .long("permission-audit")
+        .num_args(0..=1)
+        .require_equals(true)
+        .default_missing_value("-")
+        .value_name("FILE")
+        .help(PERMISSION_AUDIT_HELP)
+        .value_hint(ValueHint::FilePath),
+    )
+}
+
+fn permission_set_arg() -> Arg {
+  Arg::new("permission-set")
+    .short('P')
+    .long("permission-set")
+    .num_args(0..=1)
+    .require_equals(true)
+    .default_missing_value("default")
+    .value_name("NAME")
+    .help(PERMISSION_SET_HELP)
 }
 
 fn runtime_args(
@@ -3757,16 +4127,27 @@ fn compile_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.type_check_mode = TypeCheckMode::Local;
   runtime_args_parse(flags, matches, true, false);
 
-  let mut script = matches.remove_many::<String>("script_arg").unwrap();
-  let source_file = script.next().unwrap();
+  let mut script = matches
+    .remove_many::<String>("script_arg")
+    .into_iter()
+    .flatten();
+  // the script is optional when listing the targets
+  let source_file = script.next().unwrap_or_default();
   let args = script.collect();
   let output = matches.remove_one::<String>("output");
   let target = matches.remove_one::<String>("target");
   let no_terminal = matches.get_flag("no-terminal");
+  let compress = matches.remove_one::<i32>("compress");
+  let base_binaries = matches.remove_one::<String>("base-binaries");
+  let list_targets = matches.get_flag("list-targets");
   let include = match matches.remove_many::<String>("include") {
     Some(f) => f.collect(),
     None => vec![],
   };
+  let include_assets = match matches.remove_many::<String>("include-assets") {
+    Some(f) => f.collect(),
+    None => vec![],
+  };
   ext_arg_parse(flags, matches);
 
   flags.subcommand = DenoSubcommand::Compile(CompileFlags {
@@ -3776,6 +4157,10 @@ fn compile_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     target,
     no_terminal,
     include,
+    include_assets,
+    compress,
+    base_binaries,
+    list_targets,
   });
 }
 
@@ -3886,12 +4271,29 @@ fn doc_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   } else {
     None
   };
+  let markdown = if matches.get_flag("markdown") {
+    let name = matches.remove_one::<String>("name");
+    let output = matches
+      .remove_one::<String>("output")
+      .unwrap_or(String::from("./docs/"));
+    Some(DocMarkdownFlag { name, output })
+  } else {
+    None
+  };
+  let man = if matches.get_flag("man") {
+    let name = matches.remove_one::<String>("name");
+    Some(DocManFlag { name })
+  } else {
+    None
+  };
 
   flags.subcommand = DenoSubcommand::Doc(DocFlags {
     source_files,
     json,
     lint,
     html,
+    markdown,
+    man,
     filter,
     private,
   });
@@ -3955,6 +4357,8 @@ fn fmt_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     single_quote,
     prose_wrap,
     no_semicolons,
+    changed: matches.remove_one::<String>("changed"),
+    staged: matches.get_flag("staged"),
     watch: watch_arg_parse(matches),
   });
 }
@@ -4072,6 +4476,9 @@ fn lint_parse(flags: &mut Flags, matches: &mut ArgMatches) {
 
   let json = matches.get_flag("json");
   let compact = matches.get_flag("compact");
+  let reporter = matches.remove_one::<String>("reporter");
+  let baseline = matches.remove_one::<String>("baseline");
+  let write_baseline = matches.get_flag("write-baseline");
   flags.subcommand = DenoSubcommand::Lint(LintFlags {
     files: FileFlags {
       include: files,
@@ -4084,6 +4491,9 @@ fn lint_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     maybe_rules_exclude,
     json,
     compact,
+    reporter,
+    baseline,
+    write_baseline,
     watch: watch_arg_parse(matches),
   });
 }
@@ -4203,6 +4613,7 @@ fn task_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     cwd: matches.remove_one::<String>("cwd"),
     task: None,
   };
+  permission_set_arg_parse(flags, matches);
 
   if let Some((task, mut matches)) = matches.remove_subcommand() {
     task_flags.task = Some(task);
@@ -4247,6 +4658,7 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     );
   }
   let doc = matches.get_flag("doc");
+  let run_doc = matches.get_flag("run-doc");
   let allow_none = matches.get_flag("allow-none");
   let filter = matches.remove_one::<String>("filter");
   let clean = matches.get_flag("clean");
@@ -4334,6 +4746,7 @@ fn test_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   flags.subcommand = DenoSubcommand::Test(TestFlags {
     no_run,
     doc,
+    run_doc,
     coverage_dir: matches.remove_one::<String>("coverage"),
     clean,
     fail_fast,
@@ -4400,6 +4813,18 @@ fn publish_parse(flags: &mut Flags, matches: &mut ArgMatches) {
     allow_slow_types: matches.get_flag("allow-slow-types"),
     allow_dirty: matches.get_flag("allow-dirty"),
     no_provenance: matches.get_flag("no-provenance"),
+    check_semver: matches.get_flag("check-semver"),
+  });
+}
+
+fn api_diff_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  config_args_parse(flags, matches);
+  reload_arg_parse(flags, matches);
+  ca_file_arg_parse(flags, matches);
+
+  flags.subcommand = DenoSubcommand::ApiDiff(ApiDiffFlags {
+    base: matches.remove_one::<String>("base"),
+    json: matches.get_flag("json"),
   });
 }
 
@@ -4506,6 +4931,16 @@ fn permission_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   if matches.get_flag("no-prompt") {
     flags.permissions.no_prompt = true;
   }
+
+  flags.permissions.permission_audit =
+    matches.remove_one::<String>("permission-audit");
+
+  permission_set_arg_parse(flags, matches);
+}
+
+fn permission_set_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.permissions.permission_set =
+    matches.remove_one::<String>("permission-set");
 }
 
 fn unsafely_ignore_certificate_errors_parse(
@@ -5445,6 +5880,155 @@ mod tests {
     );
   }
 
+  #[test]
+  fn permission_audit() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--permission-audit", "gist.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "gist.ts".to_string()
+        )),
+        permissions: PermissionFlags {
+          permission_audit: Some("-".to_string()),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--allow-read",
+      "--permission-audit=audit.jsonl"
+    ]);
+    assert_eq!(
+      r.unwrap().permissions,
+      PermissionFlags {
+        allow_read: Some(vec![]),
+        no_prompt: true,
+        permission_audit: Some("audit.jsonl".to_string()),
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn permission_set() {
+    let r = flags_from_vec(svec!["deno", "run", "-P", "gist.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "gist.ts".to_string()
+        )),
+        permissions: PermissionFlags {
+          permission_set: Some("default".to_string()),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--permission-set=server",
+      "--allow-env",
+      "gist.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "gist.ts".to_string()
+        )),
+        permissions: PermissionFlags {
+          permission_set: Some("server".to_string()),
+          allow_env: Some(vec![]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "task", "-P=migrate", "migrate"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("migrate".to_string()),
+        }),
+        permissions: PermissionFlags {
+          permission_set: Some("migrate".to_string()),
+          ..Default::default()
+        },
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn permission_flags_layer_on() {
+    let base = PermissionFlags {
+      allow_read: Some(svec!["/project/static"]),
+      allow_net: Some(svec!["example.com"]),
+      deny_env: Some(svec!["SECRET"]),
+      ..Default::default()
+    };
+    let flags = PermissionFlags {
+      allow_read: Some(svec!["/tmp"]),
+      allow_net: Some(vec![]),
+      permission_set: Some("server".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(
+      flags.layer_on(base),
+      PermissionFlags {
+        allow_read: Some(svec!["/project/static", "/tmp"]),
+        allow_net: Some(vec![]),
+        deny_env: Some(svec!["SECRET"]),
+        ..Default::default()
+      }
+    );
+  }
+
+  #[test]
+  fn permission_set_to_permission_args() {
+    let flags = Flags {
+      permissions: PermissionFlags {
+        allow_read: Some(svec!["/tmp"]),
+        permission_set: Some("server".to_string()),
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    assert_eq!(
+      flags.to_permission_args(),
+      svec!["--permission-set=server", "--allow-read=/tmp"]
+    );
+
+    // the lists of the set are passed instead of its name once it's applied
+    let base = PermissionFlags {
+      allow_net: Some(svec!["example.com"]),
+      ..Default::default()
+    };
+    let flags = Flags {
+      permissions: flags.permissions.layer_on(base),
+      ..Default::default()
+    };
+    assert_eq!(
+      flags.to_permission_args(),
+      svec!["--allow-read=/tmp", "--allow-net=example.com"]
+    );
+  }
+
   #[test]
   fn allow_read() {
     let r = flags_from_vec(svec!["deno", "run", "--allow-read", "gist.ts"]);
@@ -5570,6 +6154,8 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          staged: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -5593,6 +6179,8 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          staged: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -5616,6 +6204,8 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          staged: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -5639,6 +6229,8 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          staged: false,
           watch: Some(Default::default()),
         }),
         ext: Some("ts".to_string()),
@@ -5663,6 +6255,8 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          staged: false,
           watch: Some(WatchFlags {
             hmr: false,
             no_clear_screen: true,
@@ -5697,6 +6291,8 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          staged: false,
           watch: Some(Default::default()),
         }),
         ext: Some("ts".to_string()),
@@ -5720,6 +6316,8 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          staged: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -5751,6 +6349,8 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          changed: None,
+          staged: false,
           watch: Some(Default::default()),
         }),
         config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
@@ -5787,6 +6387,8 @@ mod tests {
           single_quote: Some(true),
           prose_wrap: Some("never".to_string()),
           no_semicolons: Some(true),
+          changed: None,
+          staged: false,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
@@ -5817,12 +6419,97 @@ mod tests {
           single_quote: Some(false),
           prose_wrap: None,
           no_semicolons: Some(false),
+          changed: None,
+          staged: false,
+          watch: Default::default(),
+        }),
+        ext: Some("ts".to_string()),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn fmt_changed() {
+    let r = flags_from_vec(svec!["deno", "fmt", "--changed", "--check"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: true,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          changed: Some("HEAD".to_string()),
+          staged: false,
+          watch: Default::default(),
+        }),
+        ext: Some("ts".to_string()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "fmt", "--changed=main", "src/"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          files: FileFlags {
+            include: vec!["src/".to_string()],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          changed: Some("main".to_string()),
+          staged: false,
+          watch: Default::default(),
+        }),
+        ext: Some("ts".to_string()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "fmt", "--staged"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          changed: None,
+          staged: true,
           watch: Default::default(),
         }),
         ext: Some("ts".to_string()),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "fmt", "--changed", "--staged"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "fmt", "--staged", "--watch"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -5843,6 +6530,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          reporter: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         ..Flags::default()
@@ -5871,6 +6561,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          reporter: None,
+          baseline: None,
+          write_baseline: false,
           watch: Some(Default::default()),
         }),
         ..Flags::default()
@@ -5900,6 +6593,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          reporter: None,
+          baseline: None,
+          write_baseline: false,
           watch: Some(WatchFlags {
             hmr: false,
             no_clear_screen: true,
@@ -5931,6 +6627,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          reporter: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         ..Flags::default()
@@ -5953,6 +6652,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          reporter: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         ..Flags::default()
@@ -5980,6 +6682,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          reporter: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         ..Flags::default()
@@ -6008,6 +6713,9 @@ mod tests {
           maybe_rules_exclude: Some(svec!["no-const-assign"]),
           json: false,
           compact: false,
+          reporter: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         ..Flags::default()
@@ -6030,6 +6738,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: true,
           compact: false,
+          reporter: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         ..Flags::default()
@@ -6059,6 +6770,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: true,
           compact: false,
+          reporter: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         config_flag: ConfigFlag::Path("Deno.jsonc".to_string()),
@@ -6089,12 +6803,105 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: true,
+          reporter: None,
+          baseline: None,
+          write_baseline: false,
           watch: Default::default(),
         }),
         config_flag: ConfigFlag::Path("Deno.jsonc".to_string()),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--reporter=sarif"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          fix: false,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          reporter: Some("sarif".to_string()),
+          baseline: None,
+          write_baseline: false,
+          watch: Default::default(),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--reporter=junit", "--json"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn lint_baseline() {
+    let r = flags_from_vec(svec!["deno", "lint", "--baseline"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          fix: false,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          reporter: None,
+          baseline: Some("deno-lint-baseline.json".to_string()),
+          write_baseline: false,
+          watch: Default::default(),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--baseline=lint-baseline.json",
+      "--write-baseline",
+      "src/"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec!["src/".to_string()],
+            ignore: vec![],
+          },
+          fix: false,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          reporter: None,
+          baseline: Some("lint-baseline.json".to_string()),
+          write_baseline: true,
+          watch: Default::default(),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--write-baseline", "--fix"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -7324,6 +8131,8 @@ mod tests {
           private: false,
           json: false,
           html: None,
+          markdown: None,
+          man: None,
           lint: false,
           filter: None,
         }),
@@ -8207,6 +9016,7 @@ mod tests {
         subcommand: DenoSubcommand::Test(TestFlags {
           no_run: true,
           doc: false,
+          run_doc: false,
           fail_fast: None,
           filter: Some("- foo".to_string()),
           allow_none: true,
@@ -8295,6 +9105,7 @@ mod tests {
           no_run: false,
           reporter: Default::default(),
           doc: false,
+          run_doc: false,
           fail_fast: None,
           filter: None,
           allow_none: false,
@@ -8332,6 +9143,7 @@ mod tests {
         subcommand: DenoSubcommand::Test(TestFlags {
           no_run: false,
           doc: false,
+          run_doc: false,
           fail_fast: Some(NonZeroUsize::new(3).unwrap()),
           filter: None,
           allow_none: false,
@@ -8374,6 +9186,7 @@ mod tests {
         subcommand: DenoSubcommand::Test(TestFlags {
           no_run: false,
           doc: false,
+          run_doc: false,
           fail_fast: None,
           filter: None,
           allow_none: false,
@@ -8401,6 +9214,30 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_run_doc() {
+    let r = flags_from_vec(svec!["deno", "test", "--run-doc", "README.md"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          run_doc: true,
+          files: FileFlags {
+            include: vec!["README.md".to_string()],
+            ignore: vec![],
+          },
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn test_reporter() {
     let r = flags_from_vec(svec!["deno", "test", "--reporter=pretty"]);
@@ -8510,6 +9347,7 @@ mod tests {
         subcommand: DenoSubcommand::Test(TestFlags {
           no_run: false,
           doc: false,
+          run_doc: false,
           fail_fast: None,
           filter: None,
           allow_none: false,
@@ -8545,6 +9383,7 @@ mod tests {
         subcommand: DenoSubcommand::Test(TestFlags {
           no_run: false,
           doc: false,
+          run_doc: false,
           fail_fast: None,
           filter: None,
           allow_none: false,
@@ -8579,6 +9418,7 @@ mod tests {
         subcommand: DenoSubcommand::Test(TestFlags {
           no_run: false,
           doc: false,
+          run_doc: false,
           fail_fast: None,
           filter: None,
           allow_none: false,
@@ -8615,6 +9455,7 @@ mod tests {
         subcommand: DenoSubcommand::Test(TestFlags {
           no_run: false,
           doc: false,
+          run_doc: false,
           fail_fast: None,
           filter: None,
           allow_none: false,
@@ -8762,6 +9603,8 @@ mod tests {
           private: false,
           json: true,
           html: None,
+          markdown: None,
+          man: None,
           lint: false,
           source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
           filter: None,
@@ -8773,6 +9616,64 @@ mod tests {
     let r = flags_from_vec(svec!["deno", "doc", "--html", "path/to/module.ts"]);
     assert!(r.is_ok());
 
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--markdown",
+      "--output=./wiki/",
+      "path/to/module.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          lint: false,
+          html: None,
+          markdown: Some(DocMarkdownFlag {
+            name: None,
+            output: String::from("./wiki/"),
+          }),
+          man: None,
+          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
+          filter: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--man",
+      "--name=my-tool",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          lint: false,
+          html: None,
+          markdown: None,
+          man: Some(DocManFlag {
+            name: Some("my-tool".to_string()),
+          }),
+          source_files: DocSourceFileFlag::Paths(svec!["main.ts"]),
+          filter: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "doc", "--markdown"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "doc", "--man", "--html", "main.ts"]);
+    assert!(r.is_err());
+
     let r = flags_from_vec(svec![
       "deno",
       "doc",
@@ -8791,6 +9692,8 @@ mod tests {
             name: Some("My library".to_string()),
             output: String::from("./docs/"),
           }),
+          markdown: None,
+          man: None,
           source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
           filter: None,
         }),
@@ -8817,6 +9720,8 @@ mod tests {
             name: Some("My library".to_string()),
             output: String::from("./foo"),
           }),
+          markdown: None,
+          man: None,
           lint: true,
           source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
           filter: None,
@@ -8843,6 +9748,8 @@ mod tests {
           private: false,
           json: false,
           html: None,
+          markdown: None,
+          man: None,
           lint: false,
           source_files: DocSourceFileFlag::Paths(vec![
             "path/to/module.ts".to_string()
@@ -8861,6 +9768,8 @@ mod tests {
           private: false,
           json: false,
           html: None,
+          markdown: None,
+          man: None,
           lint: false,
           source_files: Default::default(),
           filter: None,
@@ -8884,6 +9793,8 @@ mod tests {
           lint: false,
           json: false,
           html: None,
+          markdown: None,
+          man: None,
           source_files: DocSourceFileFlag::Builtin,
           filter: Some("Deno.Listener".to_string()),
         }),
@@ -8907,6 +9818,8 @@ mod tests {
           lint: false,
           json: false,
           html: None,
+          markdown: None,
+          man: None,
           source_files: DocSourceFileFlag::Paths(svec!["path/to/module.js"]),
           filter: None,
         }),
@@ -8930,6 +9843,8 @@ mod tests {
           lint: false,
           json: false,
           html: None,
+          markdown: None,
+          man: None,
           source_files: DocSourceFileFlag::Paths(vec![
             "path/to/module.js".to_string(),
             "path/to/module2.js".to_string()
@@ -8954,6 +9869,8 @@ mod tests {
           private: false,
           json: false,
           html: None,
+          markdown: None,
+          man: None,
           lint: false,
           source_files: DocSourceFileFlag::Paths(vec![
             "path/to/module.js".to_string(),
@@ -8983,6 +9900,8 @@ mod tests {
           lint: true,
           json: false,
           html: None,
+          markdown: None,
+          man: None,
           source_files: DocSourceFileFlag::Paths(vec![
             "path/to/module.js".to_string(),
             "path/to/module2.js".to_string()
@@ -9061,7 +9980,11 @@ mod tests {
           args: vec![],
           target: None,
           no_terminal: false,
-          include: vec![]
+          include: vec![],
+          include_assets: vec![],
+          compress: None,
+          base_binaries: None,
+          list_targets: false,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -9072,7 +9995,7 @@ mod tests {
   #[test]
   fn compile_with_flags() {
     #[rustfmt::skip]
-    let r = flags_from_vec(svec!["deno", "compile", "--import-map", "import_map.json", "--no-remote", "--config", "tsconfig.json", "--no-check", "--unsafely-ignore-certificate-errors", "--reload", "--lock", "lock.json", "--lock-write", "--cert", "example.crt", "--cached-only", "--location", "https:foo", "--allow-read", "--allow-net", "--v8-flags=--help", "--seed", "1", "--no-terminal", "--output", "colors", "--env=.example.env", "https://examples.deno.land/color-logging.ts", "foo", "bar", "-p", "8080"]);
+    let r = flags_from_vec(svec!["deno", "compile", "--import-map", "import_map.json", "--no-remote", "--config", "tsconfig.json", "--no-check", "--unsafely-ignore-certificate-errors", "--reload", "--lock", "lock.json", "--lock-write", "--cert", "example.crt", "--cached-only", "--location", "https:foo", "--allow-read", "--allow-net", "--v8-flags=--help", "--seed", "1", "--no-terminal", "--compress", "--include-assets", "templates/*.html", "--base-binaries", "denort", "--output", "colors", "--env=.example.env", "https://examples.deno.land/color-logging.ts", "foo", "bar", "-p", "8080"]);
     assert_eq!(
       r.unwrap(),
       Flags {
@@ -9083,7 +10006,11 @@ mod tests {
           args: svec!["foo", "bar", "-p", "8080"],
           target: None,
           no_terminal: true,
-          include: vec![]
+          include: vec![],
+          include_assets: svec!["templates/*.html"],
+          compress: Some(19),
+          base_binaries: Some("denort".to_string()),
+          list_targets: false,
         }),
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
@@ -9109,6 +10036,55 @@ mod tests {
     );
   }
 
+  #[test]
+  fn compile_list_targets() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--list-targets",
+      "--base-binaries",
+      "./denort"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          no_terminal: false,
+          include: vec![],
+          include_assets: vec![],
+          compress: None,
+          base_binaries: Some("./denort".to_string()),
+          list_targets: true,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "compile"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn compile_compression_level() {
+    let r = flags_from_vec(svec!["deno", "compile", "--compress=3", "main.ts"]);
+    let Ok(Flags {
+      subcommand: DenoSubcommand::Compile(compile_flags),
+      ..
+    }) = r
+    else {
+      panic!("unexpected result: {r:?}");
+    };
+    assert_eq!(compile_flags.compress, Some(3));
+
+    let r =
+      flags_from_vec(svec!["deno", "compile", "--compress=23", "main.ts"]);
+    assert!(r.is_err());
+  }
   #[test]
   fn coverage() {
     let r = flags_from_vec(svec!["deno", "coverage", "foo.json"]);
@@ -9744,6 +10720,7 @@ mod tests {
       "--dry-run",
       "--allow-slow-types",
       "--allow-dirty",
+      "--check-semver",
       "--token=asdf",
     ]);
     assert_eq!(
@@ -9755,6 +10732,7 @@ mod tests {
           allow_slow_types: true,
           allow_dirty: true,
           no_provenance: true,
+          check_semver: true,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -9762,6 +10740,33 @@ mod tests {
     );
   }
 
+  #[test]
+  fn api_diff() {
+    let r = flags_from_vec(svec!["deno", "api-diff"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::ApiDiff(ApiDiffFlags {
+          base: None,
+          json: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "api-diff", "--json", "git:main"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::ApiDiff(ApiDiffFlags {
+          base: Some("git:main".to_string()),
+          json: true,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn add_subcommand() {
     let r = flags_from_vec(svec!["deno", "add"]);
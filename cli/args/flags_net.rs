@@ -1,6 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use deno_core::url::Url;
+use deno_runtime::deno_permissions::NetDescriptor;
 use std::net::IpAddr;
 use std::str::FromStr;
 
@@ -26,9 +27,20 @@ impl FromStr for BarePort {
   }
 }
 
-pub fn validator(host_and_port: &str) -> Result<String, String> {
-  if Url::parse(&format!("internal://{host_and_port}")).is_ok()
+/// Whether the value is a host, IP address, wildcard host or CIDR block,
+/// optionally followed by a port or port range.
+fn is_host_and_port(host_and_port: &str) -> bool {
+  if host_and_port.contains('/') {
+    // only CIDR blocks may contain a slash
+    return NetDescriptor::from_str(host_and_port).is_ok();
+  }
+  Url::parse(&format!("internal://{host_and_port}")).is_ok()
     || host_and_port.parse::<IpAddr>().is_ok()
+    || NetDescriptor::from_str(host_and_port).is_ok()
+}
+
+pub fn validator(host_and_port: &str) -> Result<String, String> {
+  if is_host_and_port(host_and_port)
     || host_and_port.parse::<BarePort>().is_ok()
   {
     Ok(host_and_port.to_string())
@@ -43,9 +55,7 @@ pub fn validator(host_and_port: &str) -> Result<String, String> {
 pub fn parse(paths: Vec<String>) -> clap::error::Result<Vec<String>> {
   let mut out: Vec<String> = vec![];
   for host_and_port in paths.iter() {
-    if Url::parse(&format!("internal://{host_and_port}")).is_ok()
-      || host_and_port.parse::<IpAddr>().is_ok()
-    {
+    if is_host_and_port(host_and_port) {
       out.push(host_and_port.to_owned())
     } else if let Ok(port) = host_and_port.parse::<BarePort>() {
       // we got bare port, let's add default hosts
@@ -164,6 +174,23 @@ mod tests {
     assert_eq!(actual, expected);
   }
 
+  #[test]
+  fn parse_net_args_patterns() {
+    let entries = svec![
+      "*.internal.example.com",
+      "10.0.0.0/8",
+      "fd00::/8",
+      "[fd00::]/8:443",
+      "localhost:8000-8100",
+      "*.example.com:443"
+    ];
+    let actual = parse(entries.clone()).unwrap();
+    assert_eq!(actual, entries);
+
+    assert!(parse(svec!["10.0.0.0/33"]).is_err());
+    assert!(parse(svec!["localhost:8100-8000"]).is_err());
+  }
+
   #[test]
   fn parse_net_args_expansion() {
     let entries = svec![":8080"];
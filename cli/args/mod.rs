@@ -53,6 +53,7 @@ use deno_runtime::deno_tls::rustls;
 use deno_runtime::deno_tls::rustls::RootCertStore;
 use deno_runtime::deno_tls::rustls_pemfile;
 use deno_runtime::deno_tls::webpki_roots;
+use deno_runtime::fs_util::specifier_to_file_path;
 use deno_runtime::inspector_server::InspectorServer;
 use deno_terminal::colors;
 use dotenvy::from_filename;
@@ -277,9 +278,100 @@ impl BenchOptions {
 pub struct FmtOptions {
   pub check: bool,
   pub options: FmtOptionsConfig,
+  pub languages: FmtLanguagesConfig,
   pub files: FilePatterns,
 }
 
+/// Options of a `fmt.css`, `fmt.html`, `fmt.yaml` or `fmt.sql` config
+/// section, which take precedence over the general `fmt` options.
+#[derive(Clone, Debug, Default, Deserialize, Hash, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct LanguageFmtOptionsConfig {
+  pub use_tabs: Option<bool>,
+  pub line_width: Option<u32>,
+  pub indent_width: Option<u8>,
+  pub single_quote: Option<bool>,
+  /// Only used for SQL.
+  pub uppercase_keywords: Option<bool>,
+}
+
+impl LanguageFmtOptionsConfig {
+  /// The general options with the ones of this section applied.
+  pub fn resolve(&self, options: &FmtOptionsConfig) -> FmtOptionsConfig {
+    FmtOptionsConfig {
+      use_tabs: self.use_tabs.or(options.use_tabs),
+      line_width: self.line_width.or(options.line_width),
+      indent_width: self.indent_width.or(options.indent_width),
+      single_quote: self.single_quote.or(options.single_quote),
+      ..options.clone()
+    }
+  }
+
+  fn clear_flag_options(&mut self, fmt_flags: &FmtFlags) {
+    // flags take precedence over any config
+    if fmt_flags.use_tabs.is_some() {
+      self.use_tabs = None;
+    }
+    if fmt_flags.line_width.is_some() {
+      self.line_width = None;
+    }
+    if fmt_flags.indent_width.is_some() {
+      self.indent_width = None;
+    }
+    if fmt_flags.single_quote.is_some() {
+      self.single_quote = None;
+    }
+  }
+}
+
+/// The language specific sections of the `fmt` config. Formatting a language
+/// is opt-in, so it's only enabled when its section is present (even empty).
+#[derive(Clone, Debug, Default, Hash, PartialEq)]
+pub struct FmtLanguagesConfig {
+  pub css: Option<LanguageFmtOptionsConfig>,
+  pub html: Option<LanguageFmtOptionsConfig>,
+  pub yaml: Option<LanguageFmtOptionsConfig>,
+  pub sql: Option<LanguageFmtOptionsConfig>,
+}
+
+/// Resolves the fmt config of a config file along with its language specific
+/// sections, which `deno_config` doesn't know about.
+pub fn resolve_fmt_config_and_languages(
+  config_file: &ConfigFile,
+) -> Result<(Option<FmtConfig>, FmtLanguagesConfig), AnyError> {
+  const LANGUAGES: [&str; 4] = ["css", "html", "yaml", "sql"];
+  let Some(serde_json::Value::Object(fmt)) = &config_file.json.fmt else {
+    return Ok((config_file.to_fmt_config()?, Default::default()));
+  };
+  if !LANGUAGES.iter().any(|language| fmt.contains_key(*language)) {
+    return Ok((config_file.to_fmt_config()?, Default::default()));
+  }
+  let parse =
+    |language: &str| -> Result<Option<LanguageFmtOptionsConfig>, AnyError> {
+      fmt
+        .get(language)
+        .map(|value| {
+          serde_json::from_value(value.clone()).with_context(|| {
+            format!("Invalid \"fmt.{language}\" options in config file.")
+          })
+        })
+        .transpose()
+    };
+  let languages = FmtLanguagesConfig {
+    css: parse("css")?,
+    html: parse("html")?,
+    yaml: parse("yaml")?,
+    sql: parse("sql")?,
+  };
+  let mut config_file = config_file.clone();
+  if let Some(serde_json::Value::Object(fmt)) = &mut config_file.json.fmt {
+    for language in LANGUAGES {
+      fmt.remove(language);
+    }
+  }
+  Ok((config_file.to_fmt_config()?, languages))
+}
+
 impl Default for FmtOptions {
   fn default() -> Self {
     Self::new_with_base(PathBuf::from("/"))
@@ -291,17 +383,32 @@ impl FmtOptions {
     Self {
       check: false,
       options: FmtOptionsConfig::default(),
+      languages: Default::default(),
       files: FilePatterns::new_with_base(base),
     }
   }
 
   pub fn resolve(
     maybe_fmt_config: Option<FmtConfig>,
+    mut languages: FmtLanguagesConfig,
     maybe_fmt_flags: Option<FmtFlags>,
     initial_cwd: &Path,
   ) -> Result<Self, AnyError> {
     let (maybe_config_options, maybe_config_files) =
       maybe_fmt_config.map(|c| (c.options, c.files)).unzip();
+    if let Some(fmt_flags) = &maybe_fmt_flags {
+      for language in [
+        &mut languages.css,
+        &mut languages.html,
+        &mut languages.yaml,
+        &mut languages.sql,
+      ]
+      .into_iter()
+      .flatten()
+      {
+        language.clear_flag_options(fmt_flags);
+      }
+    }
 
     Ok(Self {
       check: maybe_fmt_flags.as_ref().map(|f| f.check).unwrap_or(false),
@@ -309,6 +416,7 @@ impl FmtOptions {
         maybe_fmt_flags.as_ref(),
         maybe_config_options,
       ),
+      languages,
       files: resolve_files(
         maybe_config_files,
         maybe_fmt_flags.map(|f| f.files),
@@ -363,6 +471,8 @@ fn resolve_fmt_options(
 pub struct TestOptions {
   pub files: FilePatterns,
   pub doc: bool,
+  /// Run the code blocks of the documentation as tests.
+  pub run_doc: bool,
   pub no_run: bool,
   pub fail_fast: Option<NonZeroUsize>,
   pub allow_none: bool,
@@ -392,7 +502,8 @@ impl TestOptions {
       concurrent_jobs: test_flags
         .concurrent_jobs
         .unwrap_or_else(|| NonZeroUsize::new(1).unwrap()),
-      doc: test_flags.doc,
+      doc: test_flags.doc || test_flags.run_doc,
+      run_doc: test_flags.run_doc,
       fail_fast: test_flags.fail_fast,
       filter: test_flags.filter,
       no_run: test_flags.no_run,
@@ -410,6 +521,25 @@ pub enum LintReporterKind {
   Pretty,
   Json,
   Compact,
+  /// SARIF 2.1.0, as consumed by code scanning tools.
+  Sarif,
+  /// GitHub Actions workflow commands, which annotate the affected lines.
+  Github,
+  Junit,
+}
+
+impl LintReporterKind {
+  fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "pretty" => Some(Self::Pretty),
+      "json" => Some(Self::Json),
+      "compact" => Some(Self::Compact),
+      "sarif" => Some(Self::Sarif),
+      "github" => Some(Self::Github),
+      "junit" => Some(Self::Junit),
+      _ => None,
+    }
+  }
 }
 
 #[derive(Clone, Debug)]
@@ -418,6 +548,11 @@ pub struct LintOptions {
   pub files: FilePatterns,
   pub reporter_kind: LintReporterKind,
   pub fix: bool,
+  /// Modules providing additional lint rules.
+  pub plugins: Vec<ModuleSpecifier>,
+  /// File of existing diagnostics that shouldn't be reported.
+  pub baseline: Option<PathBuf>,
+  pub write_baseline: bool,
 }
 
 impl Default for LintOptions {
@@ -433,15 +568,33 @@ impl LintOptions {
       files: FilePatterns::new_with_base(base),
       reporter_kind: Default::default(),
       fix: false,
+      plugins: Vec::new(),
+      baseline: None,
+      write_baseline: false,
     }
   }
 
   pub fn resolve(
     maybe_lint_config: Option<LintConfig>,
+    config_extras: LintConfigExtras,
     maybe_lint_flags: Option<LintFlags>,
     initial_cwd: &Path,
   ) -> Result<Self, AnyError> {
     let fix = maybe_lint_flags.as_ref().map(|f| f.fix).unwrap_or(false);
+    let write_baseline = maybe_lint_flags
+      .as_ref()
+      .map(|f| f.write_baseline)
+      .unwrap_or(false);
+    // flags take precedence over the config file
+    let baseline = maybe_lint_flags
+      .as_ref()
+      .and_then(|f| f.baseline.as_ref())
+      .map(|baseline| initial_cwd.join(baseline))
+      .or(config_extras.baseline)
+      .or_else(|| {
+        write_baseline
+          .then(|| initial_cwd.join(DEFAULT_LINT_BASELINE_FILE_NAME))
+      });
     let mut maybe_reporter_kind =
       maybe_lint_flags.as_ref().and_then(|lint_flags| {
         if lint_flags.json {
@@ -449,7 +602,10 @@ impl LintOptions {
         } else if lint_flags.compact {
           Some(LintReporterKind::Compact)
         } else {
-          None
+          lint_flags
+            .reporter
+            .as_deref()
+            .and_then(LintReporterKind::from_name)
         }
       });
 
@@ -457,12 +613,10 @@ impl LintOptions {
       // Flag not set, so try to get lint reporter from the config file.
       if let Some(lint_config) = &maybe_lint_config {
         maybe_reporter_kind = match lint_config.report.as_deref() {
-          Some("json") => Some(LintReporterKind::Json),
-          Some("compact") => Some(LintReporterKind::Compact),
-          Some("pretty") => Some(LintReporterKind::Pretty),
-          Some(_) => {
-            bail!("Invalid lint report type in config file")
-          }
+          Some(report) => match LintReporterKind::from_name(report) {
+            Some(kind) => Some(kind),
+            None => bail!("Invalid lint report type in config file"),
+          },
           None => None,
         }
       }
@@ -500,10 +654,71 @@ impl LintOptions {
         maybe_rules_exclude,
       ),
       fix,
+      plugins: config_extras.plugins,
+      baseline,
+      write_baseline,
     })
   }
 }
 
+pub const DEFAULT_LINT_BASELINE_FILE_NAME: &str = "deno-lint-baseline.json";
+
+/// Lint config file options that `deno_config` doesn't know about.
+#[derive(Clone, Debug, Default)]
+pub struct LintConfigExtras {
+  /// Specifiers of the `lint.plugins` modules.
+  pub plugins: Vec<ModuleSpecifier>,
+  /// Path of the `lint.baseline` file.
+  pub baseline: Option<PathBuf>,
+}
+
+/// Resolves the lint config of a config file along with the options in its
+/// `lint` section that `deno_config` doesn't know about.
+pub fn resolve_lint_config_and_extras(
+  config_file: &ConfigFile,
+) -> Result<(Option<LintConfig>, LintConfigExtras), AnyError> {
+  let Some(serde_json::Value::Object(lint)) = &config_file.json.lint else {
+    return Ok((config_file.to_lint_config()?, Default::default()));
+  };
+  let mut extras = LintConfigExtras::default();
+  if let Some(plugins) = lint.get("plugins") {
+    extras.plugins = match plugins {
+      serde_json::Value::Array(plugins) => plugins
+        .iter()
+        .map(|plugin| match plugin {
+          serde_json::Value::String(plugin) => {
+            deno_core::resolve_import(plugin, config_file.specifier.as_str())
+              .with_context(|| {
+                format!("Invalid lint plugin '{plugin}' in config file.")
+              })
+          }
+          _ => bail!("Lint plugins in the config file must be strings."),
+        })
+        .collect::<Result<Vec<_>, AnyError>>()?,
+      _ => {
+        bail!("Expected \"lint.plugins\" in the config file to be an array.")
+      }
+    };
+  }
+  if let Some(baseline) = lint.get("baseline") {
+    let serde_json::Value::String(baseline) = baseline else {
+      bail!("Expected \"lint.baseline\" in the config file to be a string.");
+    };
+    let specifier =
+      deno_core::resolve_import(baseline, config_file.specifier.as_str())?;
+    extras.baseline = Some(specifier_to_file_path(&specifier)?);
+  }
+  if !lint.contains_key("plugins") && !lint.contains_key("baseline") {
+    return Ok((config_file.to_lint_config()?, extras));
+  }
+  let mut config_file = config_file.clone();
+  if let Some(serde_json::Value::Object(lint)) = &mut config_file.json.lint {
+    lint.remove("plugins");
+    lint.remove("baseline");
+  }
+  Ok((config_file.to_lint_config()?, extras))
+}
+
 fn resolve_lint_rules_options(
   maybe_lint_rules_config: Option<LintRulesConfig>,
   mut maybe_rules_tags: Option<Vec<String>>,
@@ -809,7 +1024,7 @@ pub struct CliOptions {
 
 impl CliOptions {
   pub fn new(
-    flags: Flags,
+    mut flags: Flags,
     initial_cwd: PathBuf,
     maybe_config_file: Option<ConfigFile>,
     maybe_lockfile: Option<Arc<Mutex<Lockfile>>>,
@@ -834,6 +1049,18 @@ impl CliOptions {
       }
     }
 
+    if let Some(name) = &flags.permissions.permission_set {
+      let Some(config_file) = &maybe_config_file else {
+        bail!(
+          "Using permission set '{}' requires a configuration file with a \"permissions\" section.",
+          name
+        );
+      };
+      let permission_set = deno_json::permission_set(config_file, name)?;
+      flags.permissions =
+        std::mem::take(&mut flags.permissions).layer_on(permission_set);
+    }
+
     let maybe_lockfile = maybe_lockfile.filter(|_| !force_global_cache);
     let maybe_node_modules_folder = resolve_node_modules_folder(
       &initial_cwd,
@@ -894,12 +1121,14 @@ impl CliOptions {
   pub fn from_flags(flags: Flags) -> Result<Self, AnyError> {
     let initial_cwd =
       std::env::current_dir().with_context(|| "Failed getting cwd.")?;
-    let additional_config_file_names =
-      if matches!(flags.subcommand, DenoSubcommand::Publish(..)) {
-        Some(vec!["jsr.json", "jsr.jsonc"])
-      } else {
-        None
-      };
+    let additional_config_file_names = if matches!(
+      flags.subcommand,
+      DenoSubcommand::Publish(..) | DenoSubcommand::ApiDiff(..)
+    ) {
+      Some(vec!["jsr.json", "jsr.jsonc"])
+    } else {
+      None
+    };
     let parse_options = deno_config::ParseOptions {
       include_task_comments: matches!(
         flags.subcommand,
@@ -1422,24 +1651,36 @@ impl CliOptions {
     &self,
     fmt_flags: FmtFlags,
   ) -> Result<FmtOptions, AnyError> {
-    let maybe_fmt_config = if let Some(config_file) = &self.maybe_config_file {
-      config_file.to_fmt_config()?
-    } else {
-      None
-    };
-    FmtOptions::resolve(maybe_fmt_config, Some(fmt_flags), &self.initial_cwd)
+    let (maybe_fmt_config, languages) =
+      if let Some(config_file) = &self.maybe_config_file {
+        resolve_fmt_config_and_languages(config_file)?
+      } else {
+        (None, Default::default())
+      };
+    FmtOptions::resolve(
+      maybe_fmt_config,
+      languages,
+      Some(fmt_flags),
+      &self.initial_cwd,
+    )
   }
 
   pub fn resolve_lint_options(
     &self,
     lint_flags: LintFlags,
   ) -> Result<LintOptions, AnyError> {
-    let maybe_lint_config = if let Some(config_file) = &self.maybe_config_file {
-      config_file.to_lint_config()?
-    } else {
-      None
-    };
-    LintOptions::resolve(maybe_lint_config, Some(lint_flags), &self.initial_cwd)
+    let (maybe_lint_config, config_extras) =
+      if let Some(config_file) = &self.maybe_config_file {
+        resolve_lint_config_and_extras(config_file)?
+      } else {
+        (None, Default::default())
+      };
+    LintOptions::resolve(
+      maybe_lint_config,
+      config_extras,
+      Some(lint_flags),
+      &self.initial_cwd,
+    )
   }
 
   pub fn resolve_lint_config(
@@ -1774,6 +2015,10 @@ impl CliOptions {
 
     from_config_file.extend_from_slice(&self.flags.unstable_config.features);
 
+    if self.has_hmr() {
+      from_config_file.push("hmr".to_string());
+    }
+
     if *DENO_FUTURE {
       from_config_file.extend_from_slice(&[
         deno_runtime::deno_ffi::UNSTABLE_FEATURE_NAME.to_string(),
@@ -2057,6 +2302,52 @@ mod test {
     assert_eq!(actual, None);
   }
 
+  #[test]
+  fn resolve_lint_config_extras() {
+    let config_text = r#"{
+      "lint": {
+        "plugins": ["./plugins/my_plugin.ts"],
+        "baseline": "./lint-baseline.json",
+        "rules": { "exclude": ["my-plugin/no-foo"] }
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.jsonc").unwrap();
+    let config_file = ConfigFile::new(
+      config_text,
+      config_specifier,
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    let (lint_config, extras) =
+      resolve_lint_config_and_extras(&config_file).unwrap();
+    assert_eq!(
+      extras.plugins,
+      vec![ModuleSpecifier::parse("file:///deno/plugins/my_plugin.ts").unwrap()]
+    );
+    assert_eq!(
+      extras.baseline,
+      Some(
+        specifier_to_file_path(
+          &ModuleSpecifier::parse("file:///deno/lint-baseline.json").unwrap()
+        )
+        .unwrap()
+      )
+    );
+    assert_eq!(
+      lint_config.unwrap().rules.exclude,
+      Some(vec!["my-plugin/no-foo".to_string()])
+    );
+
+    let config_file = ConfigFile::new(
+      r#"{ "lint": { "plugins": "./my_plugin.ts" } }"#,
+      ModuleSpecifier::parse("file:///deno/deno.jsonc").unwrap(),
+      &deno_config::ParseOptions::default(),
+    )
+    .unwrap();
+    assert!(resolve_lint_config_and_extras(&config_file).is_err());
+  }
+
   #[test]
   fn storage_key_resolver_test() {
     let resolver = StorageKeyResolver(None);
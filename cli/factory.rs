@@ -45,6 +45,7 @@ use crate::resolver::SloppyImportsResolver;
 use crate::standalone::DenoCompileBinaryWriter;
 use crate::tools::check::TypeChecker;
 use crate::tools::coverage::CoverageCollector;
+use crate::tools::run::hmr::HmrModuleVersions;
 use crate::tools::run::hmr::HmrRunner;
 use crate::util::file_watcher::WatcherCommunicator;
 use crate::util::fs::canonicalize_path_maybe_not_exists;
@@ -155,6 +156,7 @@ struct CliFactoryServices {
   emit_cache: Deferred<EmitCache>,
   emitter: Deferred<Arc<Emitter>>,
   fs: Deferred<Arc<dyn deno_fs::FileSystem>>,
+  hmr_module_versions: Deferred<Arc<HmrModuleVersions>>,
   main_graph_container: Deferred<Arc<MainModuleGraphContainer>>,
   lockfile: Deferred<Option<Arc<Mutex<Lockfile>>>>,
   maybe_import_map: Deferred<Option<Arc<ImportMap>>>,
@@ -237,6 +239,13 @@ impl CliFactory {
     })
   }
 
+  pub fn hmr_module_versions(&self) -> &Arc<HmrModuleVersions> {
+    self
+      .services
+      .hmr_module_versions
+      .get_or_init(Default::default)
+  }
+
   pub fn blob_store(&self) -> &Arc<BlobStore> {
     self.services.blob_store.get_or_init(Default::default)
   }
@@ -793,6 +802,11 @@ impl CliFactory {
           None
         },
         self.emitter()?.clone(),
+        if self.options.has_hmr() {
+          Some(self.hmr_module_versions().clone())
+        } else {
+          None
+        },
         self.main_module_graph_container().await?.clone(),
         self.module_load_preparer().await?.clone(),
         cli_node_resolver.clone(),
@@ -811,7 +825,7 @@ impl CliFactory {
       self.maybe_inspector_server()?.clone(),
       self.maybe_lockfile().clone(),
       self.feature_checker().clone(),
-      self.create_cli_main_worker_options()?,
+      self.create_cli_main_worker_options().await?,
       self.options.node_ipc_fd(),
       self.options.serve_port(),
       self.options.serve_host(),
@@ -828,15 +842,19 @@ impl CliFactory {
     ))
   }
 
-  fn create_cli_main_worker_options(
+  async fn create_cli_main_worker_options(
     &self,
   ) -> Result<CliMainWorkerOptions, AnyError> {
     let create_hmr_runner = if self.options.has_hmr() {
       let watcher_communicator = self.watcher_communicator.clone().unwrap();
       let emitter = self.emitter()?.clone();
+      let graph_container = self.main_module_graph_container().await?.clone();
+      let module_versions = self.hmr_module_versions().clone();
       let fn_: crate::worker::CreateHmrRunnerCb = Box::new(move |session| {
         Box::new(HmrRunner::new(
           emitter.clone(),
+          graph_container.clone(),
+          module_versions.clone(),
           session,
           watcher_communicator.clone(),
         ))
@@ -337,7 +337,15 @@ async function formatInner(obj, raw) {
 internals.jupyter = { formatInner };
 
 function enableJupyter() {
-  const { op_jupyter_broadcast } = core.ops;
+  const {
+    op_jupyter_broadcast,
+    op_jupyter_comm_register_target,
+    op_jupyter_comm_unregister_target,
+    op_jupyter_comm_open,
+    op_jupyter_comm_send,
+    op_jupyter_comm_close,
+    op_jupyter_comm_take_events,
+  } = core.ops;
 
   async function broadcast(
     msgType,
@@ -386,6 +394,132 @@ function enableJupyter() {
 
   internals.jupyter.broadcastResult = broadcastResult;
 
+  /** Open comms, keyed by comm id. */
+  const comms = new Map();
+  /** Comm target handlers, keyed by target name. */
+  const commTargets = new Map();
+
+  /**
+   * A comm is a bidirectional channel between the kernel and a frontend,
+   * used for example by interactive widgets.
+   */
+  class Comm {
+    #id;
+    #targetName;
+    #closed = false;
+    #messageListeners = [];
+    #closeListeners = [];
+
+    constructor(id, targetName) {
+      this.#id = id;
+      this.#targetName = targetName;
+    }
+
+    get id() {
+      return this.#id;
+    }
+
+    get targetName() {
+      return this.#targetName;
+    }
+
+    get closed() {
+      return this.#closed;
+    }
+
+    async send(
+      data = {},
+      { metadata = { __proto__: null }, buffers = [] } = { __proto__: null },
+    ) {
+      if (this.#closed) {
+        throw new TypeError(`Comm "${this.#id}" is closed`);
+      }
+      await op_jupyter_comm_send(this.#id, data, metadata, buffers);
+    }
+
+    async close(
+      data = {},
+      { metadata = { __proto__: null } } = { __proto__: null },
+    ) {
+      if (this.#closed) {
+        return;
+      }
+      this.#closed = true;
+      comms.delete(this.#id);
+      await op_jupyter_comm_close(this.#id, data, metadata);
+    }
+
+    /** Registers a callback for messages sent by the frontend. */
+    onMessage(callback) {
+      this.#messageListeners.push(callback);
+    }
+
+    /** Registers a callback for when the frontend closes the comm. */
+    onClose(callback) {
+      this.#closeListeners.push(callback);
+    }
+
+    async dispatchMessage(data, buffers) {
+      for (const callback of this.#messageListeners) {
+        await callback(data, buffers);
+      }
+    }
+
+    async dispatchClose(data) {
+      this.#closed = true;
+      comms.delete(this.#id);
+      for (const callback of this.#closeListeners) {
+        await callback(data);
+      }
+    }
+  }
+
+  async function openComm(
+    targetName,
+    data = {},
+    { metadata = { __proto__: null }, buffers = [] } = { __proto__: null },
+  ) {
+    const comm = new Comm(crypto.randomUUID(), targetName);
+    await op_jupyter_comm_open(comm.id, targetName, data, metadata, buffers);
+    comms.set(comm.id, comm);
+    return comm;
+  }
+
+  function registerCommTarget(targetName, callback) {
+    if (typeof callback !== "function") {
+      throw new TypeError("Comm target callback must be a function");
+    }
+    commTargets.set(targetName, callback);
+    op_jupyter_comm_register_target(targetName);
+  }
+
+  function unregisterCommTarget(targetName) {
+    commTargets.delete(targetName);
+    op_jupyter_comm_unregister_target(targetName);
+  }
+
+  async function dispatchCommEvents() {
+    const events = op_jupyter_comm_take_events();
+    for (const { kind, commId, targetName, data, buffers } of events) {
+      try {
+        if (kind === "open") {
+          const callback = commTargets.get(targetName);
+          const comm = new Comm(commId, targetName);
+          comms.set(commId, comm);
+          await callback?.(comm, data, buffers);
+        } else if (kind === "msg") {
+          await comms.get(commId)?.dispatchMessage(data, buffers);
+        } else if (kind === "close") {
+          await comms.get(commId)?.dispatchClose(data);
+        }
+      } catch (err) {
+        console.error(err);
+      }
+    }
+  }
+
+  internals.jupyter.dispatchCommEvents = dispatchCommEvents;
+
   /**
    * Display function for Jupyter Deno Kernel.
    * Mimics the behavior of IPython's `display(obj, raw=True)` function to allow
@@ -420,6 +554,9 @@ function enableJupyter() {
     html,
     svg,
     $display,
+    openComm,
+    registerCommTarget,
+    unregisterCommTarget,
   };
 }
 
@@ -0,0 +1,42 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+// deno-lint-ignore-file
+
+/*
+ * @module mod
+ * @description
+ * This module provides `Deno.selfUpdate()` to executables created with
+ * `deno compile`, which replaces the running executable with a new build.
+ *
+ * @example
+ * ```typescript
+ * await Deno.selfUpdate("https://example.com/releases/my-app", {
+ *   sha256: "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9",
+ * });
+ * console.log("Updated, the new version is used on the next start.");
+ * ```
+ */
+import { core, internals } from "ext:core/mod.js";
+
+function enableSelfUpdate() {
+  const { op_self_update } = core.ops;
+
+  /**
+   * Replaces the current executable with the one at the given path or URL,
+   * after verifying it against a checksum or an Ed25519 signature. The
+   * original executable is restored if it can't be replaced.
+   *
+   * @param {string | URL} source
+   * @param {{ sha256?: string, signature?: string, publicKey?: string }} options
+   */
+  async function selfUpdate(source, options = {}) {
+    const { sha256, signature, publicKey } = options;
+    await op_self_update(
+      source instanceof URL ? source.href : String(source),
+      { sha256, signature, publicKey },
+    );
+  }
+
+  globalThis.Deno.selfUpdate = selfUpdate;
+}
+
+internals.enableSelfUpdate = enableSelfUpdate;
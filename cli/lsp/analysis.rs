@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use super::config::Config;
 use super::diagnostics::DenoDiagnostic;
 use super::diagnostics::DiagnosticSource;
 use super::documents::Documents;
@@ -9,9 +10,14 @@ use super::tsc;
 
 use crate::args::jsr_url;
 use crate::tools::lint::create_linter;
+use crate::tools::lint::LintBaseline;
+use crate::tools::lint::LintPluginRunner;
 use deno_lint::linter::LintConfig;
 use deno_runtime::fs_util::specifier_to_file_path;
 
+use deno_ast::swc::ast::Program;
+use deno_ast::swc::parser::token::Token;
+use deno_ast::SourcePos;
 use deno_ast::SourceRange;
 use deno_ast::SourceRangedForSpanned;
 use deno_ast::SourceTextInfo;
@@ -42,6 +48,7 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::path::Path;
+use std::sync::Arc;
 use tower_lsp::lsp_types as lsp;
 use tower_lsp::lsp_types::Position;
 use tower_lsp::lsp_types::Range;
@@ -76,6 +83,9 @@ static PREFERRED_FIXES: Lazy<HashMap<&'static str, (u32, bool)>> =
 static IMPORT_SPECIFIER_RE: Lazy<Regex> =
   lazy_regex::lazy_regex!(r#"\sfrom\s+["']([^"']*)["']"#);
 
+static COMMENT_URL_RE: Lazy<Regex> =
+  lazy_regex::lazy_regex!(r#"https?://[^\s<>"'`|)\]}]+"#);
+
 const SUPPORTED_EXTENSIONS: &[&str] = &[
   ".ts", ".tsx", ".js", ".jsx", ".mjs", ".mts", ".cjs", ".cts", ".d.ts",
   ".d.mts", ".d.cts",
@@ -173,10 +183,17 @@ fn as_lsp_range(
 pub fn get_lint_references(
   parsed_source: &deno_ast::ParsedSource,
   lint_rules: Vec<&'static dyn LintRule>,
+  lint_plugin_runner: Option<Arc<LintPluginRunner>>,
+  lint_baseline: Option<&LintBaseline>,
   lint_config: LintConfig,
 ) -> Result<Vec<Reference>, AnyError> {
-  let linter = create_linter(lint_rules);
-  let lint_diagnostics = linter.lint_with_ast(parsed_source, lint_config);
+  let linter = create_linter(lint_rules, lint_plugin_runner);
+  let mut lint_diagnostics =
+    linter.lint_with_ast(parsed_source, lint_config)?;
+  if let Some(baseline) = lint_baseline {
+    lint_diagnostics =
+      baseline.apply(parsed_source.specifier(), lint_diagnostics);
+  }
 
   Ok(
     lint_diagnostics
@@ -426,6 +443,68 @@ impl<'a> TsResponseImportMapper<'a> {
     None
   }
 
+  /// Returns a specifier identifying the module independently of the current
+  /// project, like `jsr:@std/path/posix` or `npm:preact/hooks`, when the
+  /// module is an export of a jsr or npm package or of a workspace member
+  /// with a package name.
+  pub fn package_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+    config: &Config,
+  ) -> Option<String> {
+    fn concat_specifier(prefix: &str, name: &str, export: &str) -> String {
+      let export = export.strip_prefix("./").unwrap_or(export);
+      if export.is_empty() || export == "." {
+        format!("{}{}", prefix, name)
+      } else {
+        format!("{}{}/{}", prefix, name, export)
+      }
+    }
+
+    let file_referrer = self.documents.get_file_referrer(specifier);
+    if let Some(jsr_path) = specifier.as_str().strip_prefix(jsr_url().as_str())
+    {
+      let mut segments = jsr_path.split('/');
+      let name = if jsr_path.starts_with('@') {
+        format!("{}/{}", segments.next()?, segments.next()?)
+      } else {
+        segments.next()?.to_string()
+      };
+      let version = Version::parse_standard(segments.next()?).ok()?;
+      let nv = PackageNv { name, version };
+      let path = segments.collect::<Vec<_>>().join("/");
+      let export = self.resolver.jsr_lookup_export_for_path(
+        &nv,
+        &path,
+        file_referrer.as_deref(),
+      )?;
+      return Some(concat_specifier("jsr:", &nv.name, &export));
+    }
+
+    if self.resolver.in_node_modules(specifier) {
+      let package_json = self
+        .resolver
+        .get_closest_package_json(specifier)
+        .ok()
+        .flatten()?;
+      let name = package_json.name.as_ref()?;
+      let export = self.resolve_package_path(specifier).unwrap_or_default();
+      return Some(concat_specifier("npm:", name, &export));
+    }
+
+    let config_data = config.tree.data_for_specifier(specifier)?;
+    let package_config = config_data.package_config.as_ref()?;
+    let path = specifier
+      .as_str()
+      .strip_prefix(config_data.scope.as_str())?;
+    let export = self.resolver.jsr_lookup_export_for_path(
+      &package_config.nv,
+      path,
+      file_referrer.as_deref(),
+    )?;
+    Some(concat_specifier("jsr:", &package_config.nv.name, &export))
+  }
+
   /// Iterate over the supported extensions, concatenating the extension on the
   /// specifier, returning the first specifier that is resolve-able, otherwise
   /// None if none match.
@@ -1150,6 +1229,94 @@ fn prepend_whitespace(content: String, line_content: Option<String>) -> String {
   }
 }
 
+/// Finds the `http:` and `https:` URLs in the comments of a module, such as
+/// `@see` and `{@link}` tags in JSDoc.
+pub fn collect_comment_urls(
+  parsed_source: &deno_ast::ParsedSource,
+) -> Vec<(lsp::Range, ModuleSpecifier)> {
+  let text_info = parsed_source.text_info_lazy();
+  let mut urls = Vec::new();
+  for comment in parsed_source.comments().get_vec().iter() {
+    // skip the `//` or `/*` that isn't part of the comment's text
+    let text_start = comment.start() + 2;
+    for m in COMMENT_URL_RE.find_iter(&comment.text) {
+      let url_text = m.as_str().trim_end_matches(['.', ',', ';', ':']);
+      let Ok(url) = ModuleSpecifier::parse(url_text) else {
+        continue;
+      };
+      let start = text_start + m.start();
+      let range = SourceRange::new(start, start + url_text.len());
+      urls.push((source_range_to_lsp_range(&range, text_info), url));
+    }
+  }
+  urls
+}
+
+/// Returns the range of the top level statements that overlap `range`, which
+/// are formatted on their own for range formatting requests.
+pub fn statements_format_range(
+  parsed_source: &deno_ast::ParsedSource,
+  range: SourceRange,
+) -> Option<SourceRange> {
+  let program = parsed_source.program();
+  let ranges = match &*program {
+    Program::Module(module) => module
+      .body
+      .iter()
+      .map(|item| item.range())
+      .collect::<Vec<_>>(),
+    Program::Script(script) => script
+      .body
+      .iter()
+      .map(|stmt| stmt.range())
+      .collect::<Vec<_>>(),
+  };
+  let mut overlapping = ranges.into_iter().filter(|stmt_range| {
+    if range.start == range.end {
+      stmt_range.start <= range.start && range.start <= stmt_range.end
+    } else {
+      stmt_range.start < range.end && range.start < stmt_range.end
+    }
+  });
+  let first = overlapping.next()?;
+  let last = overlapping.last().unwrap_or(first);
+  Some(SourceRange::new(first.start, last.end))
+}
+
+/// Returns the range to format after `ch` was typed just before `pos`, which
+/// is the `;` itself or the block closed by a `}`. Characters typed in
+/// strings, comments and templates aren't tokens, so they're ignored.
+pub fn on_type_format_range(
+  parsed_source: &deno_ast::ParsedSource,
+  pos: SourcePos,
+  ch: &str,
+) -> Option<SourceRange> {
+  let tokens = parsed_source.tokens();
+  let index = tokens
+    .iter()
+    .position(|token| token.span.range().end == pos)?;
+  match (ch, &tokens[index].token) {
+    (";", Token::Semi) => Some(tokens[index].span.range()),
+    ("}", Token::RBrace) => {
+      let mut depth = 0;
+      for token in tokens[..=index].iter().rev() {
+        match token.token {
+          Token::RBrace => depth += 1,
+          Token::LBrace | Token::DollarLBrace => {
+            depth -= 1;
+            if depth == 0 {
+              return Some(SourceRange::new(token.span.range().start, pos));
+            }
+          }
+          _ => {}
+        }
+      }
+      None
+    }
+    _ => None,
+  }
+}
+
 pub fn source_range_to_lsp_range(
   range: &SourceRange,
   source_text_info: &SourceTextInfo,
@@ -1174,6 +1341,94 @@ mod tests {
 
   use super::*;
 
+  #[test]
+  fn test_collect_comment_urls() {
+    let specifier = ModuleSpecifier::parse("file:///a/mod.ts").unwrap();
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier,
+      text: concat!(
+        "/**\n",
+        " * See https://deno.land/manual.\n",
+        " * {@link https://example.com/a?b=c|Example}\n",
+        " */\n",
+        "const url = \"https://not-a-comment.com\"; // http://b.com/\n",
+      )
+      .into(),
+      media_type: deno_ast::MediaType::TypeScript,
+      capture_tokens: true,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let urls = collect_comment_urls(&parsed_source)
+      .into_iter()
+      .map(|(range, url)| {
+        (
+          (range.start.line, range.start.character, range.end.character),
+          url.to_string(),
+        )
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      urls,
+      vec![
+        ((1, 7, 31), "https://deno.land/manual".to_string()),
+        ((2, 10, 35), "https://example.com/a?b=c".to_string()),
+        ((4, 44, 57), "http://b.com/".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_format_ranges() {
+    let specifier = ModuleSpecifier::parse("file:///a/mod.ts").unwrap();
+    let text = concat!(
+      "const a  =  \"}\";\n",
+      "function b() {\n",
+      "  // }\n",
+      "  if (a) {\n",
+      "  return `${a}`;\n",
+      "  }\n",
+      "}\n",
+    );
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier,
+      text: text.into(),
+      media_type: deno_ast::MediaType::TypeScript,
+      capture_tokens: true,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let start = parsed_source.text_info_lazy().range().start;
+    let slice = |range: SourceRange| {
+      &text[range.start.as_byte_index(start)..range.end.as_byte_index(start)]
+    };
+    let on_type = |offset: usize, ch: &str| {
+      on_type_format_range(&parsed_source, start + offset, ch).map(slice)
+    };
+    // the closing brace of the if statement
+    let if_end = text.find("  }\n}").unwrap() + 3;
+    assert_eq!(on_type(if_end, "}"), Some("{\n  return `${a}`;\n  }"));
+    // the closing brace of the function
+    assert_eq!(on_type(text.len() - 1, "}").unwrap().lines().count(), 6);
+    // braces in strings and comments
+    assert_eq!(on_type(text.find("\";").unwrap(), "}"), None);
+    assert_eq!(on_type(text.find("\n  if").unwrap(), "}"), None);
+    assert_eq!(on_type(text.find('\n').unwrap(), ";"), Some(";"));
+
+    let statements = |offset: usize, end: usize| {
+      statements_format_range(
+        &parsed_source,
+        SourceRange::new(start + offset, start + end),
+      )
+      .map(slice)
+    };
+    assert_eq!(statements(0, 0), Some("const a  =  \"}\";"));
+    assert_eq!(statements(if_end, if_end).unwrap().lines().count(), 6);
+    assert_eq!(statements(0, text.len()).unwrap().lines().count(), 7);
+  }
+
   #[test]
   fn test_reference_to_diagnostic() {
     let range = Range {
@@ -37,8 +37,22 @@ fn code_action_capabilities(
     .unwrap_or(CodeActionProviderCapability::Simple(true))
 }
 
+fn diagnostic_capabilities(
+  pull_diagnostics: bool,
+) -> Option<DiagnosticServerCapabilities> {
+  pull_diagnostics.then(|| {
+    DiagnosticServerCapabilities::Options(DiagnosticOptions {
+      identifier: Some("deno".to_string()),
+      inter_file_dependencies: true,
+      workspace_diagnostics: true,
+      work_done_progress_options: Default::default(),
+    })
+  })
+}
+
 pub fn server_capabilities(
   client_capabilities: &ClientCapabilities,
+  pull_diagnostics: bool,
 ) -> ServerCapabilities {
   let code_action_provider = code_action_capabilities(client_capabilities);
   ServerCapabilities {
@@ -109,14 +123,24 @@ pub fn server_capabilities(
       resolve_provider: Some(true),
     }),
     document_formatting_provider: Some(OneOf::Left(true)),
-    document_range_formatting_provider: None,
-    document_on_type_formatting_provider: None,
+    document_range_formatting_provider: Some(OneOf::Left(true)),
+    document_on_type_formatting_provider: Some(
+      DocumentOnTypeFormattingOptions {
+        first_trigger_character: "}".to_string(),
+        more_trigger_character: Some(vec![";".to_string()]),
+      },
+    ),
     selection_range_provider: Some(SelectionRangeProviderCapability::Simple(
       true,
     )),
     folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
     rename_provider: Some(OneOf::Left(true)),
-    document_link_provider: None,
+    document_link_provider: Some(DocumentLinkOptions {
+      resolve_provider: Some(false),
+      work_done_progress_options: WorkDoneProgressOptions {
+        work_done_progress: None,
+      },
+    }),
     color_provider: None,
     execute_command_provider: Some(ExecuteCommandOptions {
       commands: vec![
@@ -143,16 +167,17 @@ pub fn server_capabilities(
       }),
       file_operations: None,
     }),
-    linked_editing_range_provider: None,
-    moniker_provider: None,
+    linked_editing_range_provider: Some(
+      LinkedEditingRangeServerCapabilities::Simple(true),
+    ),
+    moniker_provider: Some(OneOf::Left(true)),
     experimental: Some(json!({
       "denoConfigTasks": true,
       "testingApi":true,
     })),
     inlay_hint_provider: Some(OneOf::Left(true)),
     position_encoding: None,
-    // TODO(nayeemrmn): Support pull-based diagnostics.
-    diagnostic_provider: None,
+    diagnostic_provider: diagnostic_capabilities(pull_diagnostics),
     inline_value_provider: None,
   }
 }
@@ -15,6 +15,7 @@ use crate::lsp::repl::get_repl_workspace_settings;
 
 use super::config::WorkspaceSettings;
 use super::config::SETTINGS_SECTION;
+use super::logging::lsp_warn;
 use super::lsp_custom;
 use super::testing::lsp_custom as testing_lsp_custom;
 use super::urls::LspClientUrl;
@@ -87,6 +88,15 @@ impl Client {
     });
   }
 
+  /// Asks a client that pulls diagnostics to request them again.
+  pub fn send_workspace_diagnostic_refresh(&self) {
+    // do on a task in case the caller currently is in the lsp lock
+    let client = self.0.clone();
+    spawn(async move {
+      client.workspace_diagnostic_refresh().await;
+    });
+  }
+
   pub fn send_test_notification(&self, params: TestingNotification) {
     // do on a task in case the caller currently is in the lsp lock
     let client = self.0.clone();
@@ -172,6 +182,7 @@ trait ClientTrait: Send + Sync {
     params: lsp_custom::DiagnosticBatchNotificationParams,
   );
   async fn send_test_notification(&self, params: TestingNotification);
+  async fn workspace_diagnostic_refresh(&self);
   async fn send_did_change_deno_configuration_notification(
     &self,
     params: lsp_custom::DidChangeDenoConfigurationNotificationParams,
@@ -225,6 +236,16 @@ impl ClientTrait for TowerClient {
       .await
   }
 
+  async fn workspace_diagnostic_refresh(&self) {
+    if let Err(err) = self
+      .0
+      .send_request::<lsp::request::WorkspaceDiagnosticRefresh>(())
+      .await
+    {
+      lsp_warn!("Error refreshing diagnostics: {}", err);
+    }
+  }
+
   async fn send_test_notification(&self, notification: TestingNotification) {
     match notification {
       TestingNotification::Module(params) => {
@@ -369,6 +390,8 @@ impl ClientTrait for ReplClient {
 
   async fn send_test_notification(&self, _params: TestingNotification) {}
 
+  async fn workspace_diagnostic_refresh(&self) {}
+
   async fn send_did_change_deno_configuration_notification(
     &self,
     _params: lsp_custom::DidChangeDenoConfigurationNotificationParams,
@@ -3,6 +3,8 @@
 use super::logging::lsp_log;
 use crate::args::discover_npmrc;
 use crate::args::read_lockfile_at_path;
+use crate::args::resolve_fmt_config_and_languages;
+use crate::args::resolve_lint_config_and_extras;
 use crate::args::ConfigFile;
 use crate::args::FmtOptions;
 use crate::args::LintOptions;
@@ -10,8 +12,11 @@ use crate::args::DENO_FUTURE;
 use crate::cache::FastInsecureHasher;
 use crate::file_fetcher::FileFetcher;
 use crate::lsp::logging::lsp_warn;
+use crate::tools::lint::create_plugin_runner;
 use crate::tools::lint::get_configured_rules;
 use crate::tools::lint::ConfiguredRules;
+use crate::tools::lint::LintBaseline;
+use crate::tools::lint::LintPluginRunner;
 use crate::util::fs::canonicalize_path_maybe_not_exists;
 use deno_ast::MediaType;
 use deno_config::FmtOptionsConfig;
@@ -500,6 +505,11 @@ pub struct WorkspaceSettings {
   #[serde(default = "default_document_preload_limit")]
   pub document_preload_limit: usize,
 
+  /// Let clients that support it pull diagnostics instead of having them
+  /// published. Only read when the language server is initialized.
+  #[serde(default)]
+  pub pull_diagnostics: bool,
+
   #[serde(default)]
   pub suggest: DenoCompletionSettings,
 
@@ -544,6 +554,7 @@ impl Default for WorkspaceSettings {
       log_file: false,
       lint: true,
       document_preload_limit: default_document_preload_limit(),
+      pull_diagnostics: false,
       suggest: Default::default(),
       testing: Default::default(),
       tls_certificate: None,
@@ -1015,6 +1026,25 @@ impl Config {
     .unwrap_or(false)
   }
 
+  /// Whether the client pulls diagnostics, which it has to support and opt
+  /// into with the `pullDiagnostics` setting.
+  pub fn pull_diagnostics_enabled(&self) -> bool {
+    self.settings.unscoped.pull_diagnostics
+      && self
+        .client_capabilities
+        .text_document
+        .as_ref()
+        .is_some_and(|it| it.diagnostic.is_some())
+  }
+
+  pub fn diagnostic_refresh_capable(&self) -> bool {
+    (|| {
+      let workspace = self.client_capabilities.workspace.as_ref()?;
+      workspace.diagnostic.as_ref()?.refresh_support
+    })()
+    .unwrap_or(false)
+  }
+
   pub fn code_action_disabled_capable(&self) -> bool {
     (|| {
       let text_document = self.client_capabilities.text_document.as_ref()?;
@@ -1097,6 +1127,8 @@ pub enum ConfigWatchedFileType {
   Lockfile,
   PackageJson,
   ImportMap,
+  LintPlugin,
+  LintBaseline,
 }
 
 /// Contains the config file and dependent information.
@@ -1108,6 +1140,8 @@ pub struct ConfigData {
   pub lint_options: Arc<LintOptions>,
   pub lint_config: LintConfig,
   pub lint_rules: Arc<ConfiguredRules>,
+  pub lint_plugin_runner: Option<Arc<LintPluginRunner>>,
+  pub lint_baseline: Option<Arc<LintBaseline>>,
   pub ts_config: Arc<LspTsConfig>,
   pub byonm: bool,
   pub node_modules_dir: Option<PathBuf>,
@@ -1231,14 +1265,13 @@ impl ConfigData {
       config_file
         .as_ref()
         .and_then(|config_file| {
-          config_file
-            .to_fmt_config()
-            .and_then(|o| {
+          resolve_fmt_config_and_languages(config_file)
+            .and_then(|(o, languages)| {
               let base_path = config_file
                 .specifier
                 .to_file_path()
                 .map_err(|_| anyhow!("Invalid base path."))?;
-              FmtOptions::resolve(o, None, &base_path)
+              FmtOptions::resolve(o, languages, None, &base_path)
             })
             .inspect_err(|err| {
               lsp_warn!("  Couldn't read formatter configuration: {}", err)
@@ -1258,34 +1291,53 @@ impl ConfigData {
         lint_options_rules = Some((
           workspace_data.lint_options.clone(),
           workspace_data.lint_rules.clone(),
+          workspace_data.lint_plugin_runner.clone(),
         ))
       }
     }
-    let (lint_options, lint_rules) = lint_options_rules.unwrap_or_else(|| {
-      let lint_options = config_file
-        .as_ref()
-        .and_then(|config_file| {
-          config_file
-            .to_lint_config()
-            .and_then(|o| {
-              let base_path = config_file
-                .specifier
-                .to_file_path()
-                .map_err(|_| anyhow!("Invalid base path."))?;
-              LintOptions::resolve(o, None, &base_path)
-            })
-            .inspect_err(|err| {
-              lsp_warn!("  Couldn't read lint configuration: {}", err)
-            })
-            .ok()
-        })
+    let (lint_options, lint_rules, lint_plugin_runner) = lint_options_rules
+      .unwrap_or_else(|| {
+        let lint_options = config_file
+          .as_ref()
+          .and_then(|config_file| {
+            resolve_lint_config_and_extras(config_file)
+              .and_then(|(o, extras)| {
+                let base_path = config_file
+                  .specifier
+                  .to_file_path()
+                  .map_err(|_| anyhow!("Invalid base path."))?;
+                LintOptions::resolve(o, extras, None, &base_path)
+              })
+              .inspect_err(|err| {
+                lsp_warn!("  Couldn't read lint configuration: {}", err)
+              })
+              .ok()
+          })
+          .map(Arc::new)
+          .unwrap_or_default();
+        let lint_rules = Arc::new(get_configured_rules(
+          lint_options.rules.clone(),
+          config_file.as_ref(),
+        ));
+        let lint_plugin_runner =
+          create_plugin_runner(&lint_options.plugins, &lint_options.rules);
+        (lint_options, lint_rules, lint_plugin_runner)
+      });
+    for plugin in &lint_options.plugins {
+      watched_files
+        .entry(plugin.clone())
+        .or_insert(ConfigWatchedFileType::LintPlugin);
+    }
+    let lint_baseline = lint_options.baseline.as_ref().and_then(|path| {
+      if let Ok(specifier) = ModuleSpecifier::from_file_path(path) {
+        watched_files
+          .entry(specifier)
+          .or_insert(ConfigWatchedFileType::LintBaseline);
+      }
+      LintBaseline::load(path.clone(), false)
+        .inspect_err(|err| lsp_warn!("  Couldn't read lint baseline: {}", err))
+        .ok()
         .map(Arc::new)
-        .unwrap_or_default();
-      let lint_rules = Arc::new(get_configured_rules(
-        lint_options.rules.clone(),
-        config_file.as_ref(),
-      ));
-      (lint_options, lint_rules)
     });
 
     let ts_config = LspTsConfig::new(config_file.as_ref());
@@ -1555,6 +1607,8 @@ impl ConfigData {
       lint_options,
       lint_config,
       lint_rules,
+      lint_plugin_runner,
+      lint_baseline,
       ts_config: Arc::new(ts_config),
       byonm,
       node_modules_dir,
@@ -1959,6 +2013,7 @@ mod tests {
         log_file: false,
         lint: true,
         document_preload_limit: 1_000,
+        pull_diagnostics: false,
         suggest: DenoCompletionSettings {
           imports: ImportCompletionSettings {
             auto_discover: true,
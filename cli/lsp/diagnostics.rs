@@ -9,6 +9,7 @@ use super::documents::Documents;
 use super::documents::DocumentsFilter;
 use super::language_server;
 use super::language_server::StateSnapshot;
+use super::logging::lsp_warn;
 use super::performance::Performance;
 use super::tsc;
 use super::tsc::TsServer;
@@ -21,6 +22,9 @@ use crate::graph_util::enhanced_resolution_error_message;
 use crate::lsp::lsp_custom::DiagnosticBatchNotificationParams;
 use crate::resolver::SloppyImportsResolution;
 use crate::resolver::SloppyImportsResolver;
+use crate::tools::lint::LintBaseline;
+use crate::tools::lint::LintPluginRunner;
+use crate::util::checksum;
 use crate::util::path::to_percent_decoded_str;
 
 use deno_ast::MediaType;
@@ -248,6 +252,14 @@ impl TsDiagnosticsStore {
     self.0.lock().clear();
   }
 
+  fn extend(&self, diagnostics: &DiagnosticVec) {
+    let mut stored_ts_diagnostics = self.0.lock();
+    for record in diagnostics {
+      stored_ts_diagnostics
+        .insert(record.specifier.clone(), record.versioned.clone());
+    }
+  }
+
   fn update(&self, diagnostics: &DiagnosticVec) {
     let mut stored_ts_diagnostics = self.0.lock();
     *stored_ts_diagnostics = diagnostics
@@ -375,10 +387,35 @@ impl DiagnosticsState {
   }
 }
 
+/// The diagnostics of a document as reported for a pull diagnostics request.
+#[derive(Debug, Clone)]
+pub struct PulledDiagnostics {
+  pub specifier: ModuleSpecifier,
+  pub version: Option<i32>,
+  /// A hash of the diagnostics, which lets the client skip unchanged results.
+  pub result_id: String,
+  pub diagnostics: Vec<lsp::Diagnostic>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedPulledDiagnostics {
+  /// The modules the document depends on, which invalidate the diagnostics
+  /// when they change.
+  dependencies: HashSet<ModuleSpecifier>,
+  pulled: PulledDiagnostics,
+}
+
+type PulledDiagnosticsCache = Arc<
+  deno_core::parking_lot::Mutex<
+    HashMap<ModuleSpecifier, CachedPulledDiagnostics>,
+  >,
+>;
+
 #[derive(Debug)]
 pub struct DiagnosticsServer {
   channel: Option<mpsc::UnboundedSender<ChannelMessage>>,
   ts_diagnostics: TsDiagnosticsStore,
+  pulled_diagnostics: PulledDiagnosticsCache,
   client: Client,
   performance: Arc<Performance>,
   ts_server: Arc<TsServer>,
@@ -396,6 +433,7 @@ impl DiagnosticsServer {
     DiagnosticsServer {
       channel: Default::default(),
       ts_diagnostics: Default::default(),
+      pulled_diagnostics: Default::default(),
       client,
       performance,
       ts_server,
@@ -414,17 +452,137 @@ impl DiagnosticsServer {
 
   pub fn invalidate(&self, specifiers: &[ModuleSpecifier]) {
     self.ts_diagnostics.invalidate(specifiers);
+    self.pulled_diagnostics.lock().retain(|specifier, cached| {
+      !specifiers.iter().any(|changed| {
+        changed == specifier || cached.dependencies.contains(changed)
+      })
+    });
   }
 
   pub fn invalidate_all(&self) {
     self.ts_diagnostics.invalidate_all();
+    self.pulled_diagnostics.lock().clear();
     if let Some(tx) = &self.channel {
       let _ = tx.send(ChannelMessage::Clear);
     }
   }
 
+  /// Generates the diagnostics of the provided documents for a pull
+  /// diagnostics request. Results are reused until the document or one of
+  /// its dependencies is invalidated.
+  pub async fn pull(
+    &self,
+    snapshot: Arc<StateSnapshot>,
+    documents: Vec<Arc<Document>>,
+  ) -> Result<Vec<PulledDiagnostics>, AnyError> {
+    let mut pulled_by_specifier = HashMap::with_capacity(documents.len());
+    let mut stale_documents = Vec::new();
+    {
+      let pulled_diagnostics = self.pulled_diagnostics.lock();
+      for document in &documents {
+        match pulled_diagnostics.get(document.specifier()) {
+          Some(cached)
+            if cached.pulled.version == document.maybe_lsp_version() =>
+          {
+            pulled_by_specifier
+              .insert(document.specifier().clone(), cached.pulled.clone());
+          }
+          _ => stale_documents.push(document.clone()),
+        }
+      }
+    }
+
+    if !stale_documents.is_empty() {
+      let mark = self.performance.mark("lsp.pull_diagnostics");
+      let token = CancellationToken::new();
+      let config = snapshot.config.clone();
+      let specifiers = stale_documents
+        .iter()
+        .map(|d| d.specifier().clone())
+        .collect();
+      let ts_diagnostics = generate_ts_diagnostics_for_specifiers(
+        snapshot.clone(),
+        &config,
+        &self.ts_server,
+        specifiers,
+        token.clone(),
+      )
+      .await?;
+      let other_diagnostics = spawn_blocking({
+        let snapshot = snapshot.clone();
+        let documents = stale_documents.clone();
+        move || {
+          let mut diagnostics = generate_deno_diagnostics_for_documents(
+            &snapshot,
+            &config,
+            documents.clone(),
+            token.clone(),
+          );
+          diagnostics.extend(generate_lint_diagnostics_for_documents(
+            &snapshot, &config, documents, token,
+          ));
+          diagnostics
+        }
+      })
+      .await?;
+
+      // keep the stores used by code actions up to date, as the diagnostics
+      // aren't generated in the background when they're pulled
+      self.ts_diagnostics.extend(&ts_diagnostics);
+      let mut diagnostics_by_specifier: HashMap<_, Vec<_>> = HashMap::new();
+      for record in ts_diagnostics.into_iter().chain(other_diagnostics) {
+        diagnostics_by_specifier
+          .entry(record.specifier)
+          .or_default()
+          .extend(record.versioned.diagnostics);
+      }
+      let dependencies = stale_documents
+        .iter()
+        .map(|document| collect_dependencies(&snapshot.documents, document))
+        .collect::<Vec<_>>();
+      let mut pulled_diagnostics = self.pulled_diagnostics.lock();
+      for (document, dependencies) in
+        stale_documents.into_iter().zip(dependencies)
+      {
+        let specifier = document.specifier().clone();
+        let diagnostics = diagnostics_by_specifier
+          .remove(&specifier)
+          .unwrap_or_default();
+        let version = document.maybe_lsp_version();
+        self.state.update(&specifier, version, &diagnostics);
+        let pulled = PulledDiagnostics {
+          specifier: specifier.clone(),
+          version,
+          result_id: checksum::gen(&[
+            serde_json::to_string(&diagnostics)?.as_bytes()
+          ]),
+          diagnostics,
+        };
+        pulled_diagnostics.insert(
+          specifier.clone(),
+          CachedPulledDiagnostics {
+            dependencies,
+            pulled: pulled.clone(),
+          },
+        );
+        pulled_by_specifier.insert(specifier, pulled);
+      }
+      self.performance.measure(mark);
+    }
+
+    Ok(
+      documents
+        .iter()
+        .filter_map(|d| pulled_by_specifier.remove(d.specifier()))
+        .collect(),
+    )
+  }
+
+  /// Starts the background task that updates the diagnostics. When
+  /// `pull_mode` is set, the client requests the diagnostics itself, so they
+  /// aren't generated and the client is only asked to refresh them.
   #[allow(unused_must_use)]
-  pub fn start(&mut self) {
+  pub fn start(&mut self, pull_mode: bool) {
     let (tx, mut rx) = mpsc::unbounded_channel::<ChannelMessage>();
     self.channel = Some(tx);
     let client = self.client.clone();
@@ -444,6 +602,11 @@ impl DiagnosticsServer {
         let diagnostics_publisher =
           Arc::new(DiagnosticsPublisher::new(client.clone(), state.clone()));
 
+        // Debounce timer delay. 150ms between keystrokes is about 45 WPM, so we
+        // want something that is longer than that, but not too long to
+        // introduce detectable UI delay; 200ms is a decent compromise.
+        const DELAY: Duration = Duration::from_millis(200);
+
         loop {
           match rx.recv().await {
             // channel has closed
@@ -464,6 +627,34 @@ impl DiagnosticsServer {
               } = message;
               let url_map = Arc::new(url_map);
 
+              if pull_mode {
+                // debounce the refresh like the generation of the diagnostics
+                // in push mode, so the client doesn't pull on every keystroke
+                token.cancel();
+                token = CancellationToken::new();
+                if snapshot.config.diagnostic_refresh_capable() {
+                  let client = client.clone();
+                  let token = token.clone();
+                  spawn(async move {
+                    tokio::select! {
+                      _ = token.cancelled() => {}
+                      _ = tokio::time::sleep(DELAY) => {
+                        client.send_workspace_diagnostic_refresh();
+                      }
+                    }
+                  });
+                }
+                if let Some(batch_index) = batch_index {
+                  client.send_diagnostic_batch_notification(
+                    DiagnosticBatchNotificationParams {
+                      batch_index,
+                      messages_len: 0,
+                    },
+                  );
+                }
+                continue;
+              }
+
               // cancel the previous run
               token.cancel();
               token = CancellationToken::new();
@@ -487,10 +678,6 @@ impl DiagnosticsServer {
                     previous_handle.await;
                   }
 
-                  // Debounce timer delay. 150ms between keystrokes is about 45 WPM, so we
-                  // want something that is longer than that, but not too long to
-                  // introduce detectable UI delay; 200ms is a decent compromise.
-                  const DELAY: Duration = Duration::from_millis(200);
                   tokio::select! {
                     _ = token.cancelled() => { return; }
                     _ = tokio::time::sleep(DELAY) => {}
@@ -791,6 +978,44 @@ fn generate_lint_diagnostics(
   let documents = snapshot
     .documents
     .documents(DocumentsFilter::OpenDiagnosable);
+  generate_lint_diagnostics_for_documents(snapshot, config, documents, token)
+}
+
+/// Collects the specifiers of the modules the document depends on, directly
+/// or through other modules.
+fn collect_dependencies(
+  documents: &Documents,
+  document: &Document,
+) -> HashSet<ModuleSpecifier> {
+  let mut dependencies = HashSet::new();
+  let mut pending = vec![document.specifier().clone()];
+  while let Some(specifier) = pending.pop() {
+    let Some(document) = documents.get(&specifier) else {
+      continue;
+    };
+    for dependency in document.dependencies().values() {
+      for specifier in [
+        dependency.maybe_code.maybe_specifier(),
+        dependency.maybe_type.maybe_specifier(),
+      ]
+      .into_iter()
+      .flatten()
+      {
+        if dependencies.insert(specifier.clone()) {
+          pending.push(specifier.clone());
+        }
+      }
+    }
+  }
+  dependencies
+}
+
+fn generate_lint_diagnostics_for_documents(
+  snapshot: &language_server::StateSnapshot,
+  config: &Config,
+  documents: Vec<Arc<Document>>,
+  token: CancellationToken,
+) -> DiagnosticVec {
   let config_data_by_scope = config.tree.data_by_scope();
   let mut diagnostics_vec = Vec::new();
   for document in documents {
@@ -814,7 +1039,13 @@ fn generate_lint_diagnostics(
       continue;
     }
     let version = document.maybe_lsp_version();
-    let (lint_options, lint_config, lint_rules) = config
+    let (
+      lint_options,
+      lint_config,
+      lint_rules,
+      lint_plugin_runner,
+      lint_baseline,
+    ) = config
       .tree
       .scope_for_specifier(specifier)
       .and_then(|s| config_data_by_scope.get(s))
@@ -823,6 +1054,8 @@ fn generate_lint_diagnostics(
           d.lint_options.clone(),
           d.lint_config.clone(),
           d.lint_rules.clone(),
+          d.lint_plugin_runner.clone(),
+          d.lint_baseline.clone(),
         )
       })
       .unwrap_or_else(|| {
@@ -833,6 +1066,8 @@ fn generate_lint_diagnostics(
             default_jsx_fragment_factory: None,
           },
           Arc::default(),
+          None,
+          None,
         )
       });
     diagnostics_vec.push(DiagnosticRecord {
@@ -844,6 +1079,8 @@ fn generate_lint_diagnostics(
           &lint_options,
           lint_config,
           lint_rules.rules.clone(),
+          lint_plugin_runner,
+          lint_baseline.as_deref(),
         ),
       },
     });
@@ -856,21 +1093,29 @@ fn generate_document_lint_diagnostics(
   lint_options: &LintOptions,
   lint_config: LintConfig,
   lint_rules: Vec<&'static dyn LintRule>,
+  lint_plugin_runner: Option<Arc<LintPluginRunner>>,
+  lint_baseline: Option<&LintBaseline>,
 ) -> Vec<lsp::Diagnostic> {
   if !lint_options.files.matches_specifier(document.specifier()) {
     return Vec::new();
   }
   match document.maybe_parsed_source() {
     Some(Ok(parsed_source)) => {
-      if let Ok(references) =
-        analysis::get_lint_references(parsed_source, lint_rules, lint_config)
-      {
-        references
+      match analysis::get_lint_references(
+        parsed_source,
+        lint_rules,
+        lint_plugin_runner,
+        lint_baseline,
+        lint_config,
+      ) {
+        Ok(references) => references
           .into_iter()
           .map(|r| r.to_diagnostic())
-          .collect::<Vec<_>>()
-      } else {
-        Vec::new()
+          .collect::<Vec<_>>(),
+        Err(err) => {
+          lsp_warn!("Failed linting {}: {:#}", document.specifier(), err);
+          Vec::new()
+        }
       }
     }
     Some(Err(_)) => Vec::new(),
@@ -887,12 +1132,26 @@ async fn generate_ts_diagnostics(
   ts_server: &tsc::TsServer,
   token: CancellationToken,
 ) -> Result<DiagnosticVec, AnyError> {
-  let mut diagnostics_vec = Vec::new();
   let specifiers = snapshot
     .documents
     .documents(DocumentsFilter::OpenDiagnosable)
     .into_iter()
-    .map(|d| d.specifier().clone());
+    .map(|d| d.specifier().clone())
+    .collect();
+  generate_ts_diagnostics_for_specifiers(
+    snapshot, config, ts_server, specifiers, token,
+  )
+  .await
+}
+
+async fn generate_ts_diagnostics_for_specifiers(
+  snapshot: Arc<language_server::StateSnapshot>,
+  config: &Config,
+  ts_server: &tsc::TsServer,
+  specifiers: Vec<ModuleSpecifier>,
+  token: CancellationToken,
+) -> Result<DiagnosticVec, AnyError> {
+  let mut diagnostics_vec = Vec::new();
   let (enabled_specifiers, disabled_specifiers) = specifiers
     .into_iter()
     .partition::<Vec<_>, _>(|s| config.specifier_enabled(s));
@@ -1569,13 +1828,22 @@ fn generate_deno_diagnostics(
   snapshot: &language_server::StateSnapshot,
   config: &Config,
   token: CancellationToken,
+) -> DiagnosticVec {
+  let documents = snapshot
+    .documents
+    .documents(DocumentsFilter::OpenDiagnosable);
+  generate_deno_diagnostics_for_documents(snapshot, config, documents, token)
+}
+
+fn generate_deno_diagnostics_for_documents(
+  snapshot: &language_server::StateSnapshot,
+  config: &Config,
+  documents: Vec<Arc<Document>>,
+  token: CancellationToken,
 ) -> DiagnosticVec {
   let mut diagnostics_vec = Vec::new();
 
-  for document in snapshot
-    .documents
-    .documents(DocumentsFilter::OpenDiagnosable)
-  {
+  for document in documents {
     if token.is_cancelled() {
       break;
     }
@@ -2,6 +2,9 @@
 
 use base64::Engine;
 use deno_ast::MediaType;
+use deno_ast::ParsedSource;
+use deno_ast::SourcePos;
+use deno_ast::SourceRange;
 use deno_core::anyhow::anyhow;
 use deno_core::error::AnyError;
 use deno_core::resolve_url;
@@ -27,6 +30,7 @@ use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::env;
 use std::fmt::Write as _;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::mpsc::unbounded_channel;
@@ -37,7 +41,10 @@ use tower_lsp::jsonrpc::Result as LspResult;
 use tower_lsp::lsp_types::request::*;
 use tower_lsp::lsp_types::*;
 
+use super::analysis::collect_comment_urls;
 use super::analysis::fix_ts_import_changes;
+use super::analysis::on_type_format_range;
+use super::analysis::statements_format_range;
 use super::analysis::ts_changes_to_edit;
 use super::analysis::CodeActionCollection;
 use super::analysis::CodeActionData;
@@ -90,6 +97,8 @@ use crate::args::CaData;
 use crate::args::CacheSetting;
 use crate::args::CliOptions;
 use crate::args::Flags;
+use crate::args::FmtLanguagesConfig;
+use crate::args::FmtOptionsConfig;
 use crate::factory::CliFactory;
 use crate::file_fetcher::FileFetcher;
 use crate::graph_util;
@@ -706,8 +715,6 @@ impl Inner {
       parent_process_checker::start(parent_pid)
     }
 
-    let capabilities = capabilities::server_capabilities(&params.capabilities);
-
     let version = format!(
       "{} ({}, {})",
       crate::version::deno(),
@@ -773,7 +780,12 @@ impl Inner {
       self.config.set_client_capabilities(params.capabilities);
     }
 
-    self.diagnostics_server.start();
+    let pull_diagnostics = self.config.pull_diagnostics_enabled();
+    let capabilities = capabilities::server_capabilities(
+      &self.config.client_capabilities,
+      pull_diagnostics,
+    );
+    self.diagnostics_server.start(pull_diagnostics);
     if let Err(e) = self
       .ts_server
       .start(self.config.internal_inspect().to_address())
@@ -1293,11 +1305,65 @@ impl Inner {
     &self,
     params: DocumentFormattingParams,
   ) -> LspResult<Option<Vec<TextEdit>>> {
-    let file_referrer = (params.text_document.uri.scheme() == "file")
-      .then(|| params.text_document.uri.clone());
-    let mut specifier = self
-      .url_map
-      .normalize_url(&params.text_document.uri, LspUrlKind::File);
+    let mark = self.performance.mark_with_args("lsp.formatting", &params);
+    let result = self
+      .format_edits(
+        &params.text_document.uri,
+        &params.options,
+        FormatTarget::Document,
+      )
+      .await;
+    self.performance.measure(mark);
+    result
+  }
+
+  async fn range_formatting(
+    &self,
+    params: DocumentRangeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    let mark = self
+      .performance
+      .mark_with_args("lsp.range_formatting", &params);
+    let result = self
+      .format_edits(
+        &params.text_document.uri,
+        &params.options,
+        FormatTarget::Range(params.range),
+      )
+      .await;
+    self.performance.measure(mark);
+    result
+  }
+
+  async fn on_type_formatting(
+    &self,
+    params: DocumentOnTypeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    let mark = self
+      .performance
+      .mark_with_args("lsp.on_type_formatting", &params);
+    let result = self
+      .format_edits(
+        &params.text_document_position.text_document.uri,
+        &params.options,
+        FormatTarget::OnType(
+          params.text_document_position.position,
+          params.ch.clone(),
+        ),
+      )
+      .await;
+    self.performance.measure(mark);
+    result
+  }
+
+  async fn format_edits(
+    &self,
+    uri: &Url,
+    options: &FormattingOptions,
+    target: FormatTarget,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    let file_referrer = (uri.scheme() == "file").then(|| uri.clone());
+    let mut specifier = self.url_map.normalize_url(uri, LspUrlKind::File);
     // skip formatting any files ignored by the config file
     if !self
       .config
@@ -1316,45 +1382,76 @@ impl Inner {
     // Detect vendored paths. Vendor file URLs will normalize to their remote
     // counterparts, but for formatting we want to favour the file URL.
     // TODO(nayeemrmn): Implement `Document::file_resource_path()` or similar.
-    if specifier.scheme() != "file"
-      && params.text_document.uri.scheme() == "file"
-    {
-      specifier = params.text_document.uri.clone();
+    if specifier.scheme() != "file" && uri.scheme() == "file" {
+      specifier = uri.clone();
     }
     let file_path = specifier_to_file_path(&specifier).map_err(|err| {
       error!("{:#}", err);
       LspError::invalid_request()
     })?;
-    let mark = self.performance.mark_with_args("lsp.formatting", &params);
 
     // spawn a blocking task to allow doing other work while this is occurring
     let text_edits = deno_core::unsync::spawn_blocking({
-      let mut fmt_options = self
-        .config
-        .tree
-        .fmt_options_for_specifier(&specifier)
-        .options
-        .clone();
-      fmt_options.use_tabs = Some(!params.options.insert_spaces);
-      fmt_options.indent_width = Some(params.options.tab_size as u8);
+      let specifier_fmt_options =
+        self.config.tree.fmt_options_for_specifier(&specifier);
+      let mut fmt_options = specifier_fmt_options.options.clone();
+      let languages = specifier_fmt_options.languages.clone();
+      fmt_options.use_tabs = Some(!options.insert_spaces);
+      fmt_options.indent_width = Some(options.tab_size as u8);
       let document = document.clone();
       move || {
-        let format_result = match document.maybe_parsed_source() {
-          Some(Ok(parsed_source)) => {
+        // the file path is only used to determine what formatter should
+        // be used to format the file, so give the filepath an extension
+        // that matches what the user selected as the language
+        let file_path = document
+          .maybe_language_id()
+          .and_then(|id| id.as_extension())
+          .map(|ext| file_path.with_extension(ext))
+          .unwrap_or(file_path);
+        let format_result = match (document.maybe_parsed_source(), target) {
+          (Some(Ok(parsed_source)), FormatTarget::Document) => {
             format_parsed_source(parsed_source, &fmt_options)
           }
-          Some(Err(err)) => Err(anyhow!("{:#}", err)),
-          None => {
-            // the file path is only used to determine what formatter should
-            // be used to format the file, so give the filepath an extension
-            // that matches what the user selected as the language
-            let file_path = document
-              .maybe_language_id()
-              .and_then(|id| id.as_extension())
-              .map(|ext| file_path.with_extension(ext))
-              .unwrap_or(file_path);
+          (Some(Ok(parsed_source)), FormatTarget::Range(range)) => {
+            let range = SourceRange::new(
+              lsp_position_to_source_pos(
+                parsed_source,
+                &document,
+                range.start,
+              )?,
+              lsp_position_to_source_pos(parsed_source, &document, range.end)?,
+            );
+            format_statements(
+              parsed_source,
+              range,
+              &file_path,
+              &fmt_options,
+              &languages,
+            )
+          }
+          (Some(Ok(parsed_source)), FormatTarget::OnType(position, ch)) => {
+            let pos =
+              lsp_position_to_source_pos(parsed_source, &document, position)?;
+            let range = on_type_format_range(parsed_source, pos, &ch)?;
+            format_statements(
+              parsed_source,
+              range,
+              &file_path,
+              &fmt_options,
+              &languages,
+            )
+          }
+          (Some(Err(err)), _) => Err(anyhow!("{:#}", err)),
+          // range formatting is only supported for js/ts files
+          (None, FormatTarget::Range(_) | FormatTarget::OnType(..)) => Ok(None),
+          (None, FormatTarget::Document) => {
             // it's not a js/ts file, so attempt to format its contents
-            format_file(&file_path, document.content(), &fmt_options)
+            format_file(
+              &file_path,
+              document.content(),
+              &fmt_options,
+              &languages,
+            )
           }
         };
         match format_result {
@@ -1374,7 +1471,6 @@ impl Inner {
     .await
     .unwrap();
 
-    self.performance.measure(mark);
     if let Some(text_edits) = text_edits {
       if text_edits.is_empty() {
         Ok(None)
@@ -2370,6 +2466,191 @@ impl Inner {
     Ok(response)
   }
 
+  async fn document_link(
+    &self,
+    params: DocumentLinkParams,
+  ) -> LspResult<Option<Vec<DocumentLink>>> {
+    let specifier = self
+      .url_map
+      .normalize_url(&params.text_document.uri, LspUrlKind::File);
+    if !self.is_diagnosable(&specifier)
+      || !self.config.specifier_enabled(&specifier)
+    {
+      return Ok(None);
+    }
+    let Some(document) = self.documents.get(&specifier) else {
+      return Ok(None);
+    };
+
+    let mark = self
+      .performance
+      .mark_with_args("lsp.document_link", &params);
+    let mut links = Vec::<DocumentLink>::new();
+    let dependencies = document.dependencies();
+    let resolved = self.documents.resolve(
+      &dependencies.keys().cloned().collect::<Vec<_>>(),
+      &specifier,
+    );
+    for (dependency, resolved) in dependencies.values().zip(resolved) {
+      let Some((resolved, _)) = resolved else {
+        continue;
+      };
+      let Ok(target) = self
+        .url_map
+        .normalize_specifier(&resolved, document.file_referrer())
+      else {
+        continue;
+      };
+      for resolution in [&dependency.maybe_code, &dependency.maybe_type] {
+        let Resolution::Ok(resolution) = resolution else {
+          continue;
+        };
+        let range = to_lsp_range(&resolution.range);
+        if links.iter().any(|link| link.range == range) {
+          continue;
+        }
+        links.push(DocumentLink {
+          range,
+          target: Some(target.as_url().clone()),
+          tooltip: Some(resolved.to_string()),
+          data: None,
+        });
+      }
+    }
+    if let Some(Ok(parsed_source)) = document.maybe_parsed_source() {
+      for (range, url) in collect_comment_urls(parsed_source) {
+        links.push(DocumentLink {
+          range,
+          target: Some(url),
+          tooltip: None,
+          data: None,
+        });
+      }
+    }
+    self.performance.measure(mark);
+    Ok(Some(links))
+  }
+
+  async fn diagnostic(
+    &self,
+    params: DocumentDiagnosticParams,
+  ) -> LspResult<DocumentDiagnosticReportResult> {
+    let specifier = self
+      .url_map
+      .normalize_url(&params.text_document.uri, LspUrlKind::File);
+    let document = self.documents.get(&specifier).filter(|_| {
+      self.is_diagnosable(&specifier)
+        && self.config.specifier_enabled(&specifier)
+    });
+    let Some(document) = document else {
+      return Ok(DocumentDiagnosticReportResult::Report(
+        DocumentDiagnosticReport::Full(Default::default()),
+      ));
+    };
+
+    let mark = self.performance.mark_with_args("lsp.diagnostic", &params);
+    let pulled = self
+      .diagnostics_server
+      .pull(self.snapshot(), vec![document])
+      .await
+      .map_err(|err| {
+        error!("Failed to pull diagnostics: {:#}", err);
+        LspError::internal_error()
+      })?
+      .pop()
+      .unwrap();
+    let report =
+      if params.previous_result_id.as_ref() == Some(&pulled.result_id) {
+        DocumentDiagnosticReport::Unchanged(
+          RelatedUnchangedDocumentDiagnosticReport {
+            related_documents: None,
+            unchanged_document_diagnostic_report:
+              UnchangedDocumentDiagnosticReport {
+                result_id: pulled.result_id,
+              },
+          },
+        )
+      } else {
+        DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+          related_documents: None,
+          full_document_diagnostic_report: FullDocumentDiagnosticReport {
+            result_id: Some(pulled.result_id),
+            items: pulled.diagnostics,
+          },
+        })
+      };
+    self.performance.measure(mark);
+    Ok(DocumentDiagnosticReportResult::Report(report))
+  }
+
+  async fn workspace_diagnostic(
+    &self,
+    params: WorkspaceDiagnosticParams,
+  ) -> LspResult<WorkspaceDiagnosticReportResult> {
+    let mark = self
+      .performance
+      .mark_with_args("lsp.workspace_diagnostic", &params);
+    let documents = self
+      .documents
+      .documents(DocumentsFilter::AllDiagnosable)
+      .into_iter()
+      .filter(|d| self.config.specifier_enabled(d.specifier()))
+      .collect();
+    let pulled = self
+      .diagnostics_server
+      .pull(self.snapshot(), documents)
+      .await
+      .map_err(|err| {
+        error!("Failed to pull workspace diagnostics: {:#}", err);
+        LspError::internal_error()
+      })?;
+    let previous_result_ids = params
+      .previous_result_ids
+      .into_iter()
+      .map(|p| (p.uri, p.value))
+      .collect::<HashMap<_, _>>();
+    let mut items = Vec::with_capacity(pulled.len());
+    for pulled in pulled {
+      let file_referrer = self.documents.get_file_referrer(&pulled.specifier);
+      let Ok(uri) = self
+        .url_map
+        .normalize_specifier(&pulled.specifier, file_referrer.as_deref())
+      else {
+        continue;
+      };
+      let uri = uri.as_url().clone();
+      let version = pulled.version.map(|v| v as i64);
+      let item = if previous_result_ids.get(&uri) == Some(&pulled.result_id) {
+        WorkspaceDocumentDiagnosticReport::Unchanged(
+          WorkspaceUnchangedDocumentDiagnosticReport {
+            uri,
+            version,
+            unchanged_document_diagnostic_report:
+              UnchangedDocumentDiagnosticReport {
+                result_id: pulled.result_id,
+              },
+          },
+        )
+      } else {
+        WorkspaceDocumentDiagnosticReport::Full(
+          WorkspaceFullDocumentDiagnosticReport {
+            uri,
+            version,
+            full_document_diagnostic_report: FullDocumentDiagnosticReport {
+              result_id: Some(pulled.result_id),
+              items: pulled.diagnostics,
+            },
+          },
+        )
+      };
+      items.push(item);
+    }
+    self.performance.measure(mark);
+    Ok(WorkspaceDiagnosticReportResult::Report(
+      WorkspaceDiagnosticReport { items },
+    ))
+  }
+
   async fn incoming_calls(
     &self,
     params: CallHierarchyIncomingCallsParams,
@@ -2604,6 +2885,115 @@ impl Inner {
     Ok(Some(selection_ranges))
   }
 
+  async fn linked_editing_range(
+    &self,
+    params: LinkedEditingRangeParams,
+  ) -> LspResult<Option<LinkedEditingRanges>> {
+    let specifier = self.url_map.normalize_url(
+      &params.text_document_position_params.text_document.uri,
+      LspUrlKind::File,
+    );
+    if !self.is_diagnosable(&specifier)
+      || !self.config.specifier_enabled(&specifier)
+    {
+      return Ok(None);
+    }
+
+    let mark = self
+      .performance
+      .mark_with_args("lsp.linked_editing_range", &params);
+    let asset_or_doc = self.get_asset_or_document(&specifier)?;
+    let line_index = asset_or_doc.line_index();
+    let maybe_linked_editing_info = self
+      .ts_server
+      .get_linked_editing_range(
+        self.snapshot(),
+        specifier,
+        line_index.offset_tsc(params.text_document_position_params.position)?,
+      )
+      .await?;
+    let result = maybe_linked_editing_info
+      .map(|info| info.to_linked_editing_ranges(line_index));
+    self.performance.measure(mark);
+    Ok(result)
+  }
+
+  async fn moniker(
+    &self,
+    params: MonikerParams,
+  ) -> LspResult<Option<Vec<Moniker>>> {
+    let specifier = self.url_map.normalize_url(
+      &params.text_document_position_params.text_document.uri,
+      LspUrlKind::File,
+    );
+    if !self.is_diagnosable(&specifier)
+      || !self.config.specifier_enabled(&specifier)
+    {
+      return Ok(None);
+    }
+
+    let mark = self.performance.mark_with_args("lsp.moniker", &params);
+    let asset_or_doc = self.get_asset_or_document(&specifier)?;
+    let line_index = asset_or_doc.line_index();
+    let maybe_definition = self
+      .ts_server
+      .get_definition(
+        self.snapshot(),
+        specifier.clone(),
+        line_index.offset_tsc(params.text_document_position_params.position)?,
+      )
+      .await?;
+    let import_mapper = self.get_ts_response_import_mapper(&specifier);
+    let mut monikers = Vec::new();
+    for definition in maybe_definition
+      .and_then(|d| d.definitions)
+      .into_iter()
+      .flatten()
+    {
+      let Ok(definition_specifier) =
+        resolve_url(&definition.document_span.file_name)
+      else {
+        continue;
+      };
+      // module level declarations have the quoted module name as container
+      let symbol = match &definition.container_name {
+        Some(container_name)
+          if !container_name.is_empty() && !container_name.starts_with('"') =>
+        {
+          format!("{}.{}", container_name, definition.name)
+        }
+        _ => definition.name.clone(),
+      };
+      let moniker = if let Some(package_specifier) =
+        import_mapper.package_specifier(&definition_specifier, &self.config)
+      {
+        let (scheme, _) = package_specifier.split_once(':').unwrap();
+        Moniker {
+          scheme: scheme.to_string(),
+          identifier: format!("{}#{}", package_specifier, symbol),
+          unique: UniquenessLevel::Scheme,
+          kind: Some(if definition_specifier == specifier {
+            MonikerKind::Export
+          } else {
+            MonikerKind::Import
+          }),
+        }
+      } else {
+        Moniker {
+          scheme: "deno".to_string(),
+          identifier: format!("{}#{}", definition_specifier, symbol),
+          unique: UniquenessLevel::Project,
+          kind: Some(MonikerKind::Local),
+        }
+      };
+      if !monikers.contains(&moniker) {
+        monikers.push(moniker);
+      }
+    }
+    self.performance.measure(mark);
+    Ok((!monikers.is_empty()).then_some(monikers))
+  }
+
   async fn semantic_tokens_full(
     &self,
     params: SemanticTokensParams,
@@ -3195,6 +3585,56 @@ impl tower_lsp::LanguageServer for LanguageServer {
     self.inner.read().await.formatting(params).await
   }
 
+  async fn range_formatting(
+    &self,
+    params: DocumentRangeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    if !self.init_flag.is_raised() {
+      self.init_flag.wait_raised().await;
+    }
+    self.inner.read().await.range_formatting(params).await
+  }
+
+  async fn on_type_formatting(
+    &self,
+    params: DocumentOnTypeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    if !self.init_flag.is_raised() {
+      self.init_flag.wait_raised().await;
+    }
+    self.inner.read().await.on_type_formatting(params).await
+  }
+
+  async fn document_link(
+    &self,
+    params: DocumentLinkParams,
+  ) -> LspResult<Option<Vec<DocumentLink>>> {
+    if !self.init_flag.is_raised() {
+      self.init_flag.wait_raised().await;
+    }
+    self.inner.read().await.document_link(params).await
+  }
+
+  async fn diagnostic(
+    &self,
+    params: DocumentDiagnosticParams,
+  ) -> LspResult<DocumentDiagnosticReportResult> {
+    if !self.init_flag.is_raised() {
+      self.init_flag.wait_raised().await;
+    }
+    self.inner.read().await.diagnostic(params).await
+  }
+
+  async fn workspace_diagnostic(
+    &self,
+    params: WorkspaceDiagnosticParams,
+  ) -> LspResult<WorkspaceDiagnosticReportResult> {
+    if !self.init_flag.is_raised() {
+      self.init_flag.wait_raised().await;
+    }
+    self.inner.read().await.workspace_diagnostic(params).await
+  }
+
   async fn hover(&self, params: HoverParams) -> LspResult<Option<Hover>> {
     if !self.init_flag.is_raised() {
       self.init_flag.wait_raised().await;
@@ -3379,6 +3819,26 @@ impl tower_lsp::LanguageServer for LanguageServer {
     self.inner.read().await.selection_range(params).await
   }
 
+  async fn linked_editing_range(
+    &self,
+    params: LinkedEditingRangeParams,
+  ) -> LspResult<Option<LinkedEditingRanges>> {
+    if !self.init_flag.is_raised() {
+      self.init_flag.wait_raised().await;
+    }
+    self.inner.read().await.linked_editing_range(params).await
+  }
+
+  async fn moniker(
+    &self,
+    params: MonikerParams,
+  ) -> LspResult<Option<Vec<Moniker>>> {
+    if !self.init_flag.is_raised() {
+      self.init_flag.wait_raised().await;
+    }
+    self.inner.read().await.moniker(params).await
+  }
+
   async fn semantic_tokens_full(
     &self,
     params: SemanticTokensParams,
@@ -3430,6 +3890,56 @@ impl tower_lsp::LanguageServer for LanguageServer {
   }
 }
 
+/// The part of a document to format.
+enum FormatTarget {
+  Document,
+  Range(Range),
+  /// After the character was typed before the position.
+  OnType(Position, String),
+}
+
+fn lsp_position_to_source_pos(
+  parsed_source: &ParsedSource,
+  document: &Document,
+  position: Position,
+) -> Option<SourcePos> {
+  let offset = document.line_index().offset(position).ok()?;
+  Some(
+    parsed_source.text_info_lazy().range().start + u32::from(offset) as usize,
+  )
+}
+
+/// Formats only the top level statements around `range`, returning the new
+/// text of the whole module.
+fn format_statements(
+  parsed_source: &ParsedSource,
+  range: SourceRange,
+  file_path: &Path,
+  fmt_options: &FmtOptionsConfig,
+  languages: &FmtLanguagesConfig,
+) -> Result<Option<String>, AnyError> {
+  let Some(range) = statements_format_range(parsed_source, range) else {
+    return Ok(None);
+  };
+  let text_info = parsed_source.text_info_lazy();
+  let text = text_info.text_str();
+  let start = range.start.as_byte_index(text_info.range().start);
+  let end = range.end.as_byte_index(text_info.range().start);
+  let Some(formatted) =
+    format_file(file_path, &text[start..end], fmt_options, languages)?
+  else {
+    return Ok(None);
+  };
+  // a formatted file ends with a newline, which is already after the
+  // statements
+  Ok(Some(format!(
+    "{}{}{}",
+    &text[..start],
+    formatted.trim_end_matches(['\r', '\n']),
+    &text[end..]
+  )))
+}
+
 struct PrepareCacheResult {
   cli_options: CliOptions,
   roots: Vec<ModuleSpecifier>,
@@ -18,8 +18,12 @@ use tower_lsp::lsp_types::CompletionTriggerKind;
 use tower_lsp::lsp_types::DidChangeTextDocumentParams;
 use tower_lsp::lsp_types::DidCloseTextDocumentParams;
 use tower_lsp::lsp_types::DidOpenTextDocumentParams;
+use tower_lsp::lsp_types::HoverContents;
+use tower_lsp::lsp_types::HoverParams;
 use tower_lsp::lsp_types::InitializeParams;
 use tower_lsp::lsp_types::InitializedParams;
+use tower_lsp::lsp_types::InsertTextFormat;
+use tower_lsp::lsp_types::MarkedString;
 use tower_lsp::lsp_types::PartialResultParams;
 use tower_lsp::lsp_types::Position;
 use tower_lsp::lsp_types::Range;
@@ -167,16 +171,34 @@ impl ReplLanguageServer {
         item.label.clone()
       }
     });
+    let word_range = word_range_at(
+      text_info.text_str(),
+      position.as_byte_index(text_info.range().start),
+    );
     items
       .into_iter()
       .filter_map(|item| {
-        item.text_edit.and_then(|edit| match edit {
-          CompletionTextEdit::Edit(edit) => Some(ReplCompletionItem {
+        if item.insert_text_format == Some(InsertTextFormat::SNIPPET) {
+          return None;
+        }
+        match item.text_edit {
+          Some(CompletionTextEdit::Edit(edit)) => Some(ReplCompletionItem {
             new_text: edit.new_text,
             range: lsp_range_to_std_range(&text_info, &edit.range),
           }),
-          CompletionTextEdit::InsertAndReplace(_) => None,
-        })
+          Some(CompletionTextEdit::InsertAndReplace(edit)) => {
+            Some(ReplCompletionItem {
+              new_text: edit.new_text,
+              range: lsp_range_to_std_range(&text_info, &edit.insert),
+            })
+          }
+          // tsc completions usually don't have an edit and replace the
+          // identifier at the cursor
+          None => Some(ReplCompletionItem {
+            new_text: item.insert_text.unwrap_or(item.label),
+            range: word_range.clone(),
+          }),
+        }
       })
       .filter(|item| {
         // filter the results to only exact matches
@@ -192,6 +214,53 @@ impl ReplLanguageServer {
       .collect()
   }
 
+  /// Gets the type of an expression in the context of the code evaluated
+  /// so far, without committing the expression to the document.
+  pub async fn type_of(&mut self, expr: &str) -> Option<String> {
+    const TYPE_OF_IDENT: &str = "$deno$type";
+    let declaration = format!("const {TYPE_OF_IDENT} = ({expr});");
+    self.did_change(&declaration).await;
+    let text_info = deno_ast::SourceTextInfo::from_string(format!(
+      "{}{}",
+      self.document_text, self.pending_text
+    ));
+    let position =
+      text_info.range().start + self.document_text.len() + "const ".len();
+    let line_and_column = text_info.line_and_column_index(position);
+    let response = self
+      .language_server
+      .hover(HoverParams {
+        text_document_position_params: TextDocumentPositionParams {
+          text_document: TextDocumentIdentifier {
+            uri: self.get_document_specifier(),
+          },
+          position: Position {
+            line: line_and_column.line_index as u32,
+            character: line_and_column.column_index as u32,
+          },
+        },
+        work_done_progress_params: WorkDoneProgressParams {
+          work_done_token: None,
+        },
+      })
+      .await
+      .ok()
+      .flatten();
+    // remove the expression from the document again
+    self.did_change("").await;
+
+    let hover_text = match response?.contents {
+      HoverContents::Scalar(marked_string) => marked_string_text(marked_string),
+      HoverContents::Array(marked_strings) => marked_strings
+        .into_iter()
+        .map(marked_string_text)
+        .collect::<Vec<_>>()
+        .join("\n"),
+      HoverContents::Markup(markup) => markup.value,
+    };
+    extract_declared_type(&hover_text, TYPE_OF_IDENT)
+  }
+
   async fn did_change(&mut self, new_text: &str) {
     self.check_cwd_change().await;
     let new_text = if new_text.ends_with('\n') {
@@ -262,6 +331,39 @@ impl ReplLanguageServer {
   }
 }
 
+fn marked_string_text(marked_string: MarkedString) -> String {
+  match marked_string {
+    MarkedString::String(text) => text,
+    MarkedString::LanguageString(language_string) => language_string.value,
+  }
+}
+
+/// Extracts the type from hover text of the form `const name: Type`.
+fn extract_declared_type(hover_text: &str, name: &str) -> Option<String> {
+  let prefix = format!("const {name}: ");
+  let start = hover_text.find(&prefix)? + prefix.len();
+  let rest = &hover_text[start..];
+  let end = rest.find("\n```").unwrap_or(rest.len());
+  let ty = rest[..end].trim();
+  if ty.is_empty() {
+    None
+  } else {
+    Some(ty.to_string())
+  }
+}
+
+/// Gets the byte range of the identifier that ends at the given byte index.
+fn word_range_at(text: &str, index: usize) -> std::ops::Range<usize> {
+  let start = text[..index]
+    .char_indices()
+    .rev()
+    .take_while(|(_, c)| c.is_alphanumeric() || matches!(c, '_' | '$'))
+    .last()
+    .map(|(i, _)| i)
+    .unwrap_or(index);
+  start..index
+}
+
 fn lsp_range_to_std_range(
   text_info: &SourceTextInfo,
   range: &Range,
@@ -304,6 +406,7 @@ pub fn get_repl_workspace_settings() -> WorkspaceSettings {
     log_file: false,
     lint: false,
     document_preload_limit: 0, // don't pre-load any modules as it's expensive and not useful for the repl
+    pull_diagnostics: false,
     tls_certificate: None,
     unsafely_ignore_certificate_errors: None,
     unstable: false,
@@ -348,3 +451,30 @@ pub fn get_repl_workspace_settings() -> WorkspaceSettings {
     },
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_extract_declared_type() {
+    assert_eq!(
+      extract_declared_type(
+        "```typescript\nconst $deno$type: number\n```\n\n",
+        "$deno$type"
+      ),
+      Some("number".to_string())
+    );
+    assert_eq!(
+      extract_declared_type(
+        "```typescript\nconst $deno$type: {\n    a: string;\n}\n```",
+        "$deno$type"
+      ),
+      Some("{\n    a: string;\n}".to_string())
+    );
+    assert_eq!(
+      extract_declared_type("```typescript\n```", "$deno$type"),
+      None
+    );
+  }
+}
@@ -228,6 +228,7 @@ impl TestRun {
         .iter()
         .map(|s| (s.clone(), test::TestMode::Executable))
         .collect(),
+      false,
     )
     .await?;
 
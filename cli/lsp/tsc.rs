@@ -837,6 +837,22 @@ impl TsServer {
       })
   }
 
+  pub async fn get_linked_editing_range(
+    &self,
+    snapshot: Arc<StateSnapshot>,
+    specifier: ModuleSpecifier,
+    position: u32,
+  ) -> Result<Option<LinkedEditingInfo>, LspError> {
+    let req = TscRequest::GetLinkedEditingRangeAtPosition((
+      self.specifier_map.denormalize(&specifier),
+      position,
+    ));
+    self.request(snapshot, req).await.map_err(|err| {
+      log::error!("Failed to request to tsserver {}", err);
+      LspError::invalid_request()
+    })
+  }
+
   pub async fn get_outlining_spans(
     &self,
     snapshot: Arc<StateSnapshot>,
@@ -1616,6 +1632,29 @@ impl TextSpan {
   }
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedEditingInfo {
+  ranges: Vec<TextSpan>,
+  word_pattern: Option<String>,
+}
+
+impl LinkedEditingInfo {
+  pub fn to_linked_editing_ranges(
+    &self,
+    line_index: Arc<LineIndex>,
+  ) -> lsp::LinkedEditingRanges {
+    lsp::LinkedEditingRanges {
+      ranges: self
+        .ranges
+        .iter()
+        .map(|span| span.to_range(line_index.clone()))
+        .collect(),
+      word_pattern: self.word_pattern.clone(),
+    }
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SymbolDisplayPart {
@@ -2348,9 +2387,9 @@ pub struct HighlightSpan {
 #[serde(rename_all = "camelCase")]
 pub struct DefinitionInfo {
   // kind: ScriptElementKind,
-  // name: String,
+  pub name: String,
   // container_kind: Option<ScriptElementKind>,
-  // container_name: Option<String>,
+  pub container_name: Option<String>,
   #[serde(flatten)]
   pub document_span: DocumentSpan,
 }
@@ -5015,6 +5054,7 @@ pub enum TscRequest {
   GetNavigateToItems((String, Option<u32>, Option<String>)),
   // https://github.com/denoland/deno/blob/v1.37.1/cli/tsc/dts/typescript.d.ts#L6239
   ProvideInlayHints((String, TextSpan, UserPreferences)),
+  GetLinkedEditingRangeAtPosition((String, u32)),
 }
 
 impl TscRequest {
@@ -5114,6 +5154,10 @@ impl TscRequest {
       TscRequest::ProvideInlayHints(args) => {
         ("provideInlayHints", Some(serde_v8::to_v8(scope, args)?))
       }
+      TscRequest::GetLinkedEditingRangeAtPosition(args) => (
+        "getLinkedEditingRangeAtPosition",
+        Some(serde_v8::to_v8(scope, args)?),
+      ),
       TscRequest::CleanupSemanticCache => ("cleanupSemanticCache", None),
       TscRequest::GetAssets => ("$getAssets", None),
     };
@@ -5160,6 +5204,9 @@ impl TscRequest {
       TscRequest::GetSignatureHelpItems(_) => "getSignatureHelpItems",
       TscRequest::GetNavigateToItems(_) => "getNavigateToItems",
       TscRequest::ProvideInlayHints(_) => "provideInlayHints",
+      TscRequest::GetLinkedEditingRangeAtPosition(_) => {
+        "getLinkedEditingRangeAtPosition"
+      }
       TscRequest::GetAssets => "$getAssets",
     }
   }
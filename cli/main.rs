@@ -234,6 +234,9 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
     DenoSubcommand::Publish(publish_flags) => spawn_subcommand(async {
       tools::registry::publish(flags, publish_flags).await
     }),
+    DenoSubcommand::ApiDiff(api_diff_flags) => spawn_subcommand(async {
+      tools::registry::api_diff(flags, api_diff_flags).await
+    }),
   };
 
   handle.await?
@@ -332,7 +335,9 @@ pub fn main() {
     run_subcommand(flags).await
   };
 
-  match create_and_run_current_thread_with_maybe_metrics(future) {
+  let result = create_and_run_current_thread_with_maybe_metrics(future);
+  deno_runtime::deno_permissions::audit::print_allow_flags();
+  match result {
     Ok(exit_code) => std::process::exit(exit_code),
     Err(err) => exit_for_error(err),
   }
@@ -396,5 +401,21 @@ fn resolve_flags_and_init(
   deno_core::JsRuntime::init_platform(None);
   util::logger::init(flags.log_level);
 
+  if let Some(audit_log) = &flags.permissions.permission_audit {
+    init_permission_audit_log(audit_log)?;
+  }
+
   Ok(flags)
 }
+
+fn init_permission_audit_log(path: &str) -> Result<(), AnyError> {
+  let writer: Box<dyn std::io::Write + Send> = if path == "-" {
+    Box::new(std::io::stderr())
+  } else {
+    Box::new(std::fs::File::create(path).with_context(|| {
+      format!("Failed creating permission audit log '{}'", path)
+    })?)
+  };
+  deno_runtime::deno_permissions::audit::set_audit_log(writer);
+  Ok(())
+}
@@ -30,6 +30,7 @@ use crate::resolver::ModuleCodeStringSource;
 use crate::resolver::NpmModuleLoader;
 use crate::tools::check;
 use crate::tools::check::TypeChecker;
+use crate::tools::run::hmr::HmrModuleVersions;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::text_encoding::code_without_source_map;
 use crate::util::text_encoding::source_map_from_code;
@@ -226,6 +227,7 @@ struct SharedCliModuleLoaderState {
   is_repl: bool,
   code_cache: Option<Arc<CodeCache>>,
   emitter: Arc<Emitter>,
+  hmr_module_versions: Option<Arc<HmrModuleVersions>>,
   main_module_graph_container: Arc<MainModuleGraphContainer>,
   module_load_preparer: Arc<ModuleLoadPreparer>,
   node_resolver: Arc<CliNodeResolver>,
@@ -244,6 +246,7 @@ impl CliModuleLoaderFactory {
     options: &CliOptions,
     code_cache: Option<Arc<CodeCache>>,
     emitter: Arc<Emitter>,
+    hmr_module_versions: Option<Arc<HmrModuleVersions>>,
     main_module_graph_container: Arc<MainModuleGraphContainer>,
     module_load_preparer: Arc<ModuleLoadPreparer>,
     node_resolver: Arc<CliNodeResolver>,
@@ -264,6 +267,7 @@ impl CliModuleLoaderFactory {
         ),
         code_cache,
         emitter,
+        hmr_module_versions,
         main_module_graph_container,
         module_load_preparer,
         node_resolver,
@@ -359,6 +363,8 @@ impl<TGraphContainer: ModuleGraphContainer>
       .await
     {
       result?
+    } else if let Some(base_specifier) = self.hmr_base_specifier(specifier) {
+      self.load_hmr_module(specifier, &base_specifier).await?
     } else {
       self.load_prepared_module(specifier, maybe_referrer).await?
     };
@@ -415,6 +421,32 @@ impl<TGraphContainer: ModuleGraphContainer>
     ))
   }
 
+  /// Returns the original specifier if this is a module that was given a
+  /// fresh specifier by hot module replacement.
+  fn hmr_base_specifier(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<ModuleSpecifier> {
+    self.shared.hmr_module_versions.as_ref()?;
+    HmrModuleVersions::base_specifier(specifier)
+  }
+
+  /// Loads the current contents of a module invalidated by hot module
+  /// replacement. These modules are read straight from disk, because the
+  /// module graph still holds their previous source.
+  async fn load_hmr_module(
+    &self,
+    specifier: &ModuleSpecifier,
+    base_specifier: &ModuleSpecifier,
+  ) -> Result<ModuleCodeStringSource, AnyError> {
+    let code = self.emitter.load_and_emit_for_hmr(base_specifier).await?;
+    Ok(ModuleCodeStringSource {
+      code: ModuleSourceCode::String(code.into()),
+      found_url: specifier.clone(),
+      media_type: MediaType::from_specifier(base_specifier),
+    })
+  }
+
   fn resolve_referrer(
     &self,
     referrer: &str,
@@ -724,7 +756,10 @@ impl<TGraphContainer: ModuleGraphContainer> ModuleLoader
     let referrer = self.0.resolve_referrer(referrer)?;
     let specifier = self.0.inner_resolve(specifier, &referrer)?;
     ensure_not_jsr_non_jsr_remote_import(&specifier, &referrer)?;
-    Ok(specifier)
+    match &self.0.shared.hmr_module_versions {
+      Some(hmr_module_versions) => Ok(hmr_module_versions.resolve(specifier)),
+      None => Ok(specifier),
+    }
   }
 
   fn load(
@@ -761,6 +796,11 @@ impl<TGraphContainer: ModuleGraphContainer> ModuleLoader
       return Box::pin(deno_core::futures::future::ready(Ok(())));
     }
 
+    // modules invalidated by hot module replacement are loaded from disk
+    if self.0.hmr_base_specifier(specifier).is_some() {
+      return Box::pin(deno_core::futures::future::ready(Ok(())));
+    }
+
     let specifier = specifier.clone();
     let inner = self.0.clone();
 
@@ -1,6 +1,9 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -9,16 +12,26 @@ use jupyter_runtime::JupyterMessageContent;
 use jupyter_runtime::KernelIoPubConnection;
 use jupyter_runtime::StreamContent;
 
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op2;
+use deno_core::serde::Serialize;
 use deno_core::serde_json;
+use deno_core::serde_json::json;
 use deno_core::OpState;
+use deno_core::ToJsBuffer;
 use tokio::sync::mpsc;
 use tokio::sync::Mutex;
 
 deno_core::extension!(deno_jupyter,
   ops = [
     op_jupyter_broadcast,
+    op_jupyter_comm_register_target,
+    op_jupyter_comm_unregister_target,
+    op_jupyter_comm_open,
+    op_jupyter_comm_send,
+    op_jupyter_comm_close,
+    op_jupyter_comm_take_events,
   ],
   options = {
     sender: mpsc::UnboundedSender<StreamContent>,
@@ -32,13 +45,73 @@ deno_core::extension!(deno_jupyter,
   },
 );
 
-#[op2(async)]
-pub async fn op_jupyter_broadcast(
-  state: Rc<RefCell<OpState>>,
-  #[string] message_type: String,
-  #[serde] content: serde_json::Value,
-  #[serde] metadata: serde_json::Value,
-  #[serde] buffers: Vec<deno_core::JsBuffer>,
+/// An event received from the frontend for a comm, queued until the runtime
+/// dispatches it to the JS side.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommEvent {
+  /// One of "open", "msg" or "close".
+  pub kind: &'static str,
+  pub comm_id: String,
+  pub target_name: Option<String>,
+  pub data: serde_json::Value,
+  pub buffers: Vec<ToJsBuffer>,
+}
+
+/// Registry of the comm targets registered by the runtime and the comms that
+/// are currently open between the kernel and the frontend.
+///
+/// See https://jupyter-client.readthedocs.io/en/latest/messaging.html#custom-messages
+#[derive(Debug, Default)]
+pub struct CommContainer {
+  /// Open comms, keyed by comm id, with their target name.
+  comms: HashMap<String, String>,
+  targets: HashSet<String>,
+  pending_events: VecDeque<CommEvent>,
+}
+
+impl CommContainer {
+  pub fn has_target(&self, target_name: &str) -> bool {
+    self.targets.contains(target_name)
+  }
+
+  pub fn is_open(&self, comm_id: &str) -> bool {
+    self.comms.contains_key(comm_id)
+  }
+
+  /// Returns the open comms, optionally filtered by target name.
+  pub fn comms(
+    &self,
+    target_name: Option<&str>,
+  ) -> impl Iterator<Item = (&String, &String)> {
+    self.comms.iter().filter(move |(_, target)| {
+      target_name.map(|name| name == *target).unwrap_or(true)
+    })
+  }
+
+  pub fn open(&mut self, comm_id: String, target_name: String) {
+    self.comms.insert(comm_id, target_name);
+  }
+
+  pub fn close(&mut self, comm_id: &str) -> Option<String> {
+    self.comms.remove(comm_id)
+  }
+
+  pub fn queue_event(&mut self, event: CommEvent) {
+    self.pending_events.push_back(event);
+  }
+
+  pub fn has_pending_events(&self) -> bool {
+    !self.pending_events.is_empty()
+  }
+}
+
+async fn send_iopub_message(
+  state: &Rc<RefCell<OpState>>,
+  message_type: &str,
+  content: serde_json::Value,
+  metadata: serde_json::Value,
+  buffers: Vec<deno_core::JsBuffer>,
 ) -> Result<(), AnyError> {
   let (iopub_connection, last_execution_request) = {
     let s = state.borrow();
@@ -52,13 +125,13 @@ pub async fn op_jupyter_broadcast(
   let maybe_last_request = last_execution_request.borrow().clone();
   if let Some(last_request) = maybe_last_request {
     let content = JupyterMessageContent::from_type_and_content(
-      &message_type,
+      message_type,
       content.clone(),
     )
     .map_err(|err| {
       log::error!(
           "Error deserializing content from jupyter.broadcast, message_type: {}:\n\n{}\n\n{}",
-          &message_type,
+          message_type,
           content,
           err
       );
@@ -77,6 +150,125 @@ pub async fn op_jupyter_broadcast(
   Ok(())
 }
 
+#[op2(async)]
+pub async fn op_jupyter_broadcast(
+  state: Rc<RefCell<OpState>>,
+  #[string] message_type: String,
+  #[serde] content: serde_json::Value,
+  #[serde] metadata: serde_json::Value,
+  #[serde] buffers: Vec<deno_core::JsBuffer>,
+) -> Result<(), AnyError> {
+  send_iopub_message(&state, &message_type, content, metadata, buffers).await
+}
+
+#[op2(fast)]
+pub fn op_jupyter_comm_register_target(
+  state: &mut OpState,
+  #[string] target_name: String,
+) {
+  let comm_container = state.borrow::<Rc<RefCell<CommContainer>>>();
+  comm_container.borrow_mut().targets.insert(target_name);
+}
+
+#[op2(fast)]
+pub fn op_jupyter_comm_unregister_target(
+  state: &mut OpState,
+  #[string] target_name: &str,
+) {
+  let comm_container = state.borrow::<Rc<RefCell<CommContainer>>>();
+  comm_container.borrow_mut().targets.remove(target_name);
+}
+
+#[op2(async)]
+pub async fn op_jupyter_comm_open(
+  state: Rc<RefCell<OpState>>,
+  #[string] comm_id: String,
+  #[string] target_name: String,
+  #[serde] data: serde_json::Value,
+  #[serde] metadata: serde_json::Value,
+  #[serde] buffers: Vec<deno_core::JsBuffer>,
+) -> Result<(), AnyError> {
+  {
+    let s = state.borrow();
+    let mut comm_container =
+      s.borrow::<Rc<RefCell<CommContainer>>>().borrow_mut();
+    if comm_container.is_open(&comm_id) {
+      return Err(type_error(format!("Comm \"{comm_id}\" is already open")));
+    }
+    comm_container.open(comm_id.clone(), target_name.clone());
+  }
+
+  let content = json!({
+    "comm_id": comm_id,
+    "target_name": target_name,
+    "data": data,
+  });
+  let result =
+    send_iopub_message(&state, "comm_open", content, metadata, buffers).await;
+  if result.is_err() {
+    // the frontend never heard of the comm, so don't keep it registered
+    let s = state.borrow();
+    let mut comm_container =
+      s.borrow::<Rc<RefCell<CommContainer>>>().borrow_mut();
+    comm_container.close(&comm_id);
+  }
+  result
+}
+
+#[op2(async)]
+pub async fn op_jupyter_comm_send(
+  state: Rc<RefCell<OpState>>,
+  #[string] comm_id: String,
+  #[serde] data: serde_json::Value,
+  #[serde] metadata: serde_json::Value,
+  #[serde] buffers: Vec<deno_core::JsBuffer>,
+) -> Result<(), AnyError> {
+  {
+    let s = state.borrow();
+    let comm_container = s.borrow::<Rc<RefCell<CommContainer>>>().borrow();
+    if !comm_container.is_open(&comm_id) {
+      return Err(type_error(format!("Comm \"{comm_id}\" is not open")));
+    }
+  }
+
+  let content = json!({
+    "comm_id": comm_id,
+    "data": data,
+  });
+  send_iopub_message(&state, "comm_msg", content, metadata, buffers).await
+}
+
+#[op2(async)]
+pub async fn op_jupyter_comm_close(
+  state: Rc<RefCell<OpState>>,
+  #[string] comm_id: String,
+  #[serde] data: serde_json::Value,
+  #[serde] metadata: serde_json::Value,
+) -> Result<(), AnyError> {
+  {
+    let s = state.borrow();
+    let mut comm_container =
+      s.borrow::<Rc<RefCell<CommContainer>>>().borrow_mut();
+    if comm_container.close(&comm_id).is_none() {
+      return Ok(());
+    }
+  }
+
+  let content = json!({
+    "comm_id": comm_id,
+    "data": data,
+  });
+  send_iopub_message(&state, "comm_close", content, metadata, vec![]).await
+}
+
+#[op2]
+#[serde]
+pub fn op_jupyter_comm_take_events(state: &mut OpState) -> Vec<CommEvent> {
+  let comm_container = state.borrow::<Rc<RefCell<CommContainer>>>();
+  let mut comm_container = comm_container.borrow_mut();
+  Vec::from(std::mem::take(&mut comm_container.pending_events))
+}
+
 #[op2(fast)]
 pub fn op_print(
   state: &mut OpState,
@@ -15,6 +15,8 @@ use std::path::PathBuf;
 use std::process::Command;
 
 use deno_ast::ModuleSpecifier;
+use deno_config::glob::FilePatterns;
+use deno_config::glob::PathOrPatternSet;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
@@ -44,12 +46,15 @@ use crate::file_fetcher::FileFetcher;
 use crate::http_util::HttpClientProvider;
 use crate::npm::CliNpmResolver;
 use crate::npm::InnerCliNpmResolverRef;
+use crate::util::fs::canonicalize_path;
+use crate::util::fs::FileCollector;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
 
 use super::virtual_fs::FileBackedVfs;
 use super::virtual_fs::VfsBuilder;
 use super::virtual_fs::VfsRoot;
+use super::virtual_fs::VfsStats;
 use super::virtual_fs::VirtualDirectory;
 
 const MAGIC_TRAILER: &[u8; 8] = b"d3n0l4nd";
@@ -145,15 +150,43 @@ pub struct Metadata {
   pub node_modules: Option<NodeModules>,
   pub disable_deprecated_api_warning: bool,
   pub unstable_config: UnstableConfig,
+  pub assets: Option<AssetsVfs>,
 }
 
-pub fn load_npm_vfs(root_dir_path: PathBuf) -> Result<FileBackedVfs, AnyError> {
+/// The file system of the data assets included with `--include-assets`.
+/// Their file data is stored after the files of the npm vfs.
+#[derive(Deserialize, Serialize)]
+pub struct AssetsVfs {
+  /// The directory the assets were in when compiling, which is where they
+  /// are served from at runtime.
+  pub root_path: PathBuf,
+  pub dir: VirtualDirectory,
+  /// Offset of the asset files from the start of the npm files.
+  pub files_offset: u64,
+}
+
+fn open_current_exe() -> Result<(std::fs::File, Trailer), AnyError> {
   let file_path = current_exe().unwrap();
   let mut file = std::fs::File::open(file_path)?;
   file.seek(SeekFrom::End(-(TRAILER_SIZE as i64)))?;
   let mut trailer = [0; TRAILER_SIZE];
   file.read_exact(&mut trailer)?;
   let trailer = Trailer::parse(&trailer)?.unwrap();
+  Ok((file, trailer))
+}
+
+pub fn load_assets_vfs(assets: AssetsVfs) -> Result<FileBackedVfs, AnyError> {
+  let (file, trailer) = open_current_exe()?;
+  let fs_root = VfsRoot {
+    dir: assets.dir,
+    root_path: assets.root_path,
+    start_file_offset: trailer.npm_files_pos + assets.files_offset,
+  };
+  Ok(FileBackedVfs::new(file, fs_root))
+}
+
+pub fn load_npm_vfs(root_dir_path: PathBuf) -> Result<FileBackedVfs, AnyError> {
+  let (mut file, trailer) = open_current_exe()?;
   file.seek(SeekFrom::Start(trailer.npm_vfs_pos))?;
   let mut vfs_data = vec![0; trailer.npm_vfs_len() as usize];
   file.read_exact(&mut vfs_data)?;
@@ -180,7 +213,7 @@ fn write_binary_bytes(
   metadata: &Metadata,
   eszip: eszip::EszipV2,
   npm_vfs: Option<&VirtualDirectory>,
-  npm_files: &Vec<Vec<u8>>,
+  vfs_files: &Vec<Vec<u8>>,
 ) -> Result<(), AnyError> {
   let metadata = serde_json::to_string(metadata)?.as_bytes().to_vec();
   let npm_vfs = serde_json::to_string(&npm_vfs)?.as_bytes().to_vec();
@@ -190,7 +223,7 @@ fn write_binary_bytes(
   writer.write_all(&eszip_archive)?;
   writer.write_all(&metadata)?;
   writer.write_all(&npm_vfs)?;
-  for file in npm_files {
+  for file in vfs_files {
     writer.write_all(file)?;
   }
 
@@ -415,6 +448,58 @@ pub fn unpack_into_dir(
   fs::remove_file(&archive_path)?;
   Ok(exe_path)
 }
+/// Statistics about the files embedded in a standalone binary.
+#[derive(Debug, Default)]
+pub struct EmbeddedFilesStats {
+  pub node_modules: Option<VfsStats>,
+  pub assets: Option<VfsStats>,
+}
+
+/// Builds the file system of the data assets matched by `--include-assets`,
+/// rooted at the closest directory that contains all of them.
+fn build_assets_vfs(
+  compile_flags: &CompileFlags,
+  cli_options: &CliOptions,
+) -> Result<Option<(PathBuf, VfsBuilder)>, AnyError> {
+  if compile_flags.include_assets.is_empty() {
+    return Ok(None);
+  }
+  let initial_cwd = cli_options.initial_cwd();
+  let file_patterns = FilePatterns {
+    base: initial_cwd.to_path_buf(),
+    include: Some(PathOrPatternSet::from_include_relative_path_or_patterns(
+      initial_cwd,
+      &compile_flags.include_assets,
+    )?),
+    exclude: Default::default(),
+  };
+  let files = FileCollector::new(|_| true)
+    .ignore_git_folder()
+    .collect_file_patterns(file_patterns)?;
+  if files.is_empty() {
+    bail!(
+      "No files found matching --include-assets: {}",
+      compile_flags.include_assets.join(", ")
+    );
+  }
+
+  let mut root_path = files[0].parent().unwrap().to_path_buf();
+  for file in &files {
+    while !file.starts_with(&root_path) {
+      root_path = root_path.parent().unwrap().to_path_buf();
+    }
+  }
+  let mut builder = VfsBuilder::new(root_path.clone())?;
+  builder.set_compression_level(compile_flags.compress);
+  for file in &files {
+    // the builder works with canonicalized paths, but don't resolve
+    // symlinked files so they're included as regular files
+    let dir_path = canonicalize_path(file.parent().unwrap())?;
+    builder.add_file_at_path(&dir_path.join(file.file_name().unwrap()))?;
+  }
+  Ok(Some((root_path, builder)))
+}
+
 pub struct DenoCompileBinaryWriter<'a> {
   deno_dir: &'a DenoDir,
   file_fetcher: &'a FileFetcher,
@@ -451,7 +536,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     module_specifier: &ModuleSpecifier,
     compile_flags: &CompileFlags,
     cli_options: &CliOptions,
-  ) -> Result<(), AnyError> {
+  ) -> Result<EmbeddedFilesStats, AnyError> {
     // Select base binary based on target
     let mut original_binary = self.get_base_binary(compile_flags).await?;
 
@@ -493,24 +578,40 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     }
 
     let target = compile_flags.resolve_target();
-    let binary_name = format!("denort-{target}.zip");
-
-    let binary_path_suffix = if crate::version::is_canary() {
-      format!("canary/{}/{}", crate::version::GIT_COMMIT_HASH, binary_name)
-    } else {
-      format!("release/v{}/{}", env!("CARGO_PKG_VERSION"), binary_name)
-    };
-
-    let download_directory = self.deno_dir.dl_folder_path();
-    let binary_path = download_directory.join(&binary_path_suffix);
-
-    if !binary_path.exists() {
-      self
-        .download_base_binary(&download_directory, &binary_path_suffix)
-        .await?;
-    }
+    let binary_name = base_binary_archive_name(&target);
+    let binary_path_suffix = base_binary_path_suffix(&binary_name);
+
+    let binary_path =
+      match resolve_offline_base_binary(self.deno_dir, compile_flags, &target)?
+      {
+        Some(path) => path,
+        None => {
+          if let Some(base_binaries) = &compile_flags.base_binaries {
+            bail!(
+              concat!(
+                "Could not find the base binary for target '{}' in '{}'. ",
+                "Expected '{}' or '{}'.\n\n",
+                "Run `deno compile --list-targets --base-binaries {}` to see ",
+                "the targets available offline."
+              ),
+              target,
+              base_binaries,
+              binary_name,
+              binary_path_suffix,
+              base_binaries,
+            );
+          }
+          let download_directory = self.deno_dir.dl_folder_path();
+          self
+            .download_base_binary(&download_directory, &binary_path_suffix)
+            .await?
+        }
+      };
 
-    let archive_data = std::fs::read(binary_path)?;
+    let archive_data = std::fs::read(&binary_path).with_context(|| {
+      format!("Failed reading base binary '{}'", binary_path.display())
+    })?;
+    verify_base_binary_checksum(&binary_path, &archive_data)?;
     let temp_dir = tempfile::TempDir::new()?;
     let base_binary_path = unpack_into_dir(
       "denort",
@@ -528,17 +629,24 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     &self,
     output_directory: &Path,
     binary_path_suffix: &str,
-  ) -> Result<(), AnyError> {
+  ) -> Result<PathBuf, AnyError> {
     let download_url = format!("https://dl.deno.land/{binary_path_suffix}");
-    let maybe_bytes = {
+    // the checksum is fetched separately from the archive, so that a
+    // corrupted download is detected. It's served by the same host though,
+    // so it doesn't protect against a tampered archive.
+    let checksum_url = format!("{download_url}.sha256sum");
+    let (maybe_bytes, maybe_checksum) = {
+      let client = self.http_client_provider.get_or_create()?;
       let progress_bars = ProgressBar::new(ProgressBarStyle::DownloadBars);
       let progress = progress_bars.update(&download_url);
-
-      self
-        .http_client_provider
-        .get_or_create()?
-        .download_with_progress(download_url, None, &progress)
-        .await?
+      let maybe_bytes = client
+        .download_with_progress(download_url.clone(), None, &progress)
+        .await?;
+      let progress = progress_bars.update(&checksum_url);
+      let maybe_checksum = client
+        .download_with_progress(checksum_url.clone(), None, &progress)
+        .await?;
+      (maybe_bytes, maybe_checksum)
     };
     let bytes = match maybe_bytes {
       Some(bytes) => bytes,
@@ -551,8 +659,38 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     std::fs::create_dir_all(output_directory)?;
     let output_path = output_directory.join(binary_path_suffix);
     std::fs::create_dir_all(output_path.parent().unwrap())?;
-    tokio::fs::write(output_path, bytes).await?;
-    Ok(())
+    let file_name = output_path.file_name().unwrap().to_string_lossy();
+    match maybe_checksum {
+      Some(checksum_bytes) => {
+        let checksum_text = String::from_utf8_lossy(&checksum_bytes);
+        verify_checksum(&file_name, &bytes, &checksum_text, &checksum_url)?;
+        tokio::fs::write(&output_path, &bytes).await?;
+        // keep the published checksum next to the archive so that a
+        // corrupted cache is detected the next time it's used
+        tokio::fs::write(
+          checksum_sidecar_path(&output_path),
+          checksum_text.as_bytes(),
+        )
+        .await?;
+      }
+      None => {
+        log::warn!(
+          "{} No checksum is published at '{}', so the downloaded base binary could not be verified.",
+          crate::colors::yellow("Warning"),
+          checksum_url,
+        );
+        tokio::fs::write(&output_path, &bytes).await?;
+        // record the checksum of the download instead, so that the cached
+        // archive doesn't warn again and a later corruption is detected
+        let checksum = crate::util::checksum::gen(&[&bytes]);
+        tokio::fs::write(
+          checksum_sidecar_path(&output_path),
+          format!("{checksum}  {file_name}\n"),
+        )
+        .await?;
+      }
+    }
+    Ok(output_path)
   }
 
   /// This functions creates a standalone deno binary by appending a bundle
@@ -565,7 +703,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     entrypoint: &ModuleSpecifier,
     cli_options: &CliOptions,
     compile_flags: &CompileFlags,
-  ) -> Result<(), AnyError> {
+  ) -> Result<EmbeddedFilesStats, AnyError> {
     let ca_data = match cli_options.ca_data() {
       Some(CaData::File(ca_file)) => Some(
         std::fs::read(ca_file)
@@ -578,13 +716,16 @@ impl<'a> DenoCompileBinaryWriter<'a> {
       .resolve_import_map(self.file_fetcher)
       .await?
       .map(|import_map| (import_map.base_url().clone(), import_map.to_json()));
-    let (npm_vfs, npm_files, node_modules) =
+    let mut stats = EmbeddedFilesStats::default();
+    let (npm_vfs, mut vfs_files, node_modules) =
       match self.npm_resolver.as_inner() {
         InnerCliNpmResolverRef::Managed(managed) => {
           let snapshot =
             managed.serialized_valid_snapshot_for_system(&self.npm_system_info);
           if !snapshot.as_serialized().packages.is_empty() {
-            let (root_dir, files) = self.build_vfs()?.into_dir_and_files();
+            let builder = self.build_vfs(compile_flags)?;
+            stats.node_modules = Some(builder.stats());
+            let (root_dir, files) = builder.into_dir_and_files();
             eszip.add_npm_snapshot(snapshot);
             (
               Some(root_dir),
@@ -604,7 +745,9 @@ impl<'a> DenoCompileBinaryWriter<'a> {
           }
         }
         InnerCliNpmResolverRef::Byonm(_) => {
-          let (root_dir, files) = self.build_vfs()?.into_dir_and_files();
+          let builder = self.build_vfs(compile_flags)?;
+          stats.node_modules = Some(builder.stats());
+          let (root_dir, files) = builder.into_dir_and_files();
           (
             Some(root_dir),
             files,
@@ -616,6 +759,20 @@ impl<'a> DenoCompileBinaryWriter<'a> {
           )
         }
       };
+    let assets = match build_assets_vfs(compile_flags, cli_options)? {
+      Some((root_path, builder)) => {
+        stats.assets = Some(builder.stats());
+        let (dir, files) = builder.into_dir_and_files();
+        let files_offset = vfs_files.iter().map(|f| f.len() as u64).sum();
+        vfs_files.extend(files);
+        Some(AssetsVfs {
+          root_path,
+          dir,
+          files_offset,
+        })
+      }
+      None => None,
+    };
 
     let metadata = Metadata {
       argv: compile_flags.args.clone(),
@@ -641,6 +798,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
         sloppy_imports: cli_options.unstable_sloppy_imports(),
         features: cli_options.unstable_features(),
       },
+      assets,
     };
 
     write_binary_bytes(
@@ -649,11 +807,16 @@ impl<'a> DenoCompileBinaryWriter<'a> {
       &metadata,
       eszip,
       npm_vfs.as_ref(),
-      &npm_files,
-    )
+      &vfs_files,
+    )?;
+
+    Ok(stats)
   }
 
-  fn build_vfs(&self) -> Result<VfsBuilder, AnyError> {
+  fn build_vfs(
+    &self,
+    compile_flags: &CompileFlags,
+  ) -> Result<VfsBuilder, AnyError> {
     fn maybe_warn_different_system(system_info: &NpmSystemInfo) {
       if system_info != &NpmSystemInfo::default() {
         log::warn!("{} The node_modules directory may be incompatible with the target system.", crate::colors::yellow("Warning"));
@@ -665,6 +828,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
         if let Some(node_modules_path) = npm_resolver.root_node_modules_path() {
           maybe_warn_different_system(&self.npm_system_info);
           let mut builder = VfsBuilder::new(node_modules_path.clone())?;
+          builder.set_compression_level(compile_flags.compress);
           builder.add_dir_recursive(node_modules_path)?;
           Ok(builder)
         } else {
@@ -672,6 +836,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
           // but also don't make this dependent on the registry url
           let root_path = npm_resolver.global_cache_root_folder();
           let mut builder = VfsBuilder::new(root_path)?;
+          builder.set_compression_level(compile_flags.compress);
           for package in npm_resolver.all_system_packages(&self.npm_system_info)
           {
             let folder =
@@ -689,6 +854,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
         let node_modules_path = npm_resolver.root_node_modules_path().unwrap();
         let parent_path = node_modules_path.parent().unwrap();
         let mut builder = VfsBuilder::new(parent_path.to_path_buf())?;
+        builder.set_compression_level(compile_flags.compress);
         let package_json_path = parent_path.join("package.json");
         if package_json_path.exists() {
           builder.add_file_at_path(&package_json_path)?;
@@ -702,6 +868,178 @@ impl<'a> DenoCompileBinaryWriter<'a> {
   }
 }
 
+fn base_binary_archive_name(target: &str) -> String {
+  format!("denort-{target}.zip")
+}
+
+fn base_binary_path_suffix(binary_name: &str) -> String {
+  if crate::version::is_canary() {
+    format!("canary/{}/{}", crate::version::GIT_COMMIT_HASH, binary_name)
+  } else {
+    format!("release/v{}/{}", env!("CARGO_PKG_VERSION"), binary_name)
+  }
+}
+
+/// Resolves the archive of the base binary for the provided target
+/// without using the network.
+///
+/// When `--base-binaries` is provided, only that location is searched. It may
+/// be a single `denort-<target>.zip` archive or a directory containing them,
+/// either directly or in the same layout as dl.deno.land. Otherwise the
+/// archives previously downloaded to the DENO_DIR are used.
+pub fn resolve_offline_base_binary(
+  deno_dir: &DenoDir,
+  compile_flags: &CompileFlags,
+  target: &str,
+) -> Result<Option<PathBuf>, AnyError> {
+  let binary_name = base_binary_archive_name(target);
+  let binary_path_suffix = base_binary_path_suffix(&binary_name);
+  let Some(base_binaries) = &compile_flags.base_binaries else {
+    let binary_path = deno_dir.dl_folder_path().join(binary_path_suffix);
+    return Ok(binary_path.is_file().then_some(binary_path));
+  };
+
+  let base_binaries = PathBuf::from(base_binaries);
+  if base_binaries.is_file() {
+    let is_target_archive = base_binaries
+      .file_name()
+      .map(|name| name.to_string_lossy() == binary_name)
+      .unwrap_or(false);
+    return Ok(is_target_archive.then_some(base_binaries));
+  }
+  if !base_binaries.is_dir() {
+    bail!(
+      "Base binaries path '{}' does not exist.",
+      base_binaries.display()
+    );
+  }
+  Ok(
+    [
+      base_binaries.join(&binary_name),
+      base_binaries.join(&binary_path_suffix),
+    ]
+    .into_iter()
+    .find(|path| path.is_file()),
+  )
+}
+
+fn checksum_sidecar_path(archive_path: &Path) -> PathBuf {
+  let mut file_name = archive_path.file_name().unwrap().to_os_string();
+  file_name.push(".sha256sum");
+  archive_path.with_file_name(file_name)
+}
+
+/// Verifies the archive against the checksum in a `<archive>.sha256sum` file
+/// next to it or a `SHA256SUMS` file in the same directory, when present.
+fn verify_base_binary_checksum(
+  archive_path: &Path,
+  archive_data: &[u8],
+) -> Result<(), AnyError> {
+  let file_name = archive_path.file_name().unwrap().to_string_lossy();
+  let checksum_files = [
+    checksum_sidecar_path(archive_path),
+    archive_path.with_file_name("SHA256SUMS"),
+  ];
+  let mut expected = None;
+  for checksum_file in &checksum_files {
+    let Ok(text) = std::fs::read_to_string(checksum_file) else {
+      continue;
+    };
+    let Some(checksum) = find_checksum(&text, &file_name) else {
+      bail!(
+        "Could not find the checksum of '{}' in '{}'.",
+        file_name,
+        checksum_file.display()
+      );
+    };
+    expected = Some((checksum, checksum_file));
+    break;
+  }
+  let Some((expected, checksum_file)) = expected else {
+    log::warn!(
+      concat!(
+        "{} No checksum found for base binary '{}', so it could not be ",
+        "verified. Add a '{}' or 'SHA256SUMS' file next to it."
+      ),
+      crate::colors::yellow("Warning"),
+      archive_path.display(),
+      checksum_sidecar_path(archive_path)
+        .file_name()
+        .unwrap()
+        .to_string_lossy(),
+    );
+    return Ok(());
+  };
+  check_checksum(
+    &archive_path.display().to_string(),
+    archive_data,
+    &expected,
+    &checksum_file.display().to_string(),
+  )
+}
+
+/// Verifies data against its checksum in the text of a checksum file.
+fn verify_checksum(
+  file_name: &str,
+  data: &[u8],
+  checksum_text: &str,
+  checksum_source: &str,
+) -> Result<(), AnyError> {
+  let Some(expected) = find_checksum(checksum_text, file_name) else {
+    bail!(
+      "Could not find the checksum of '{}' in '{}'.",
+      file_name,
+      checksum_source
+    );
+  };
+  check_checksum(file_name, data, &expected, checksum_source)
+}
+
+fn check_checksum(
+  name: &str,
+  data: &[u8],
+  expected: &str,
+  checksum_source: &str,
+) -> Result<(), AnyError> {
+  let actual = crate::util::checksum::gen(&[data]);
+  if !actual.eq_ignore_ascii_case(expected) {
+    bail!(
+      concat!(
+        "Checksum mismatch for base binary '{}'.\n",
+        "  Expected: {} (from '{}')\n",
+        "  Actual: {}"
+      ),
+      name,
+      expected,
+      checksum_source,
+      actual,
+    );
+  }
+  Ok(())
+}
+
+/// Finds the checksum for a file in the text of a checksum file in the format
+/// output by `sha256sum`. A checksum without a file name only matches when
+/// it's the only checksum in the file.
+fn find_checksum(text: &str, file_name: &str) -> Option<String> {
+  let entries = text
+    .lines()
+    .filter_map(|line| {
+      let mut parts = line.split_whitespace();
+      let checksum = parts.next()?;
+      let is_valid =
+        checksum.len() == 64 && checksum.chars().all(|c| c.is_ascii_hexdigit());
+      is_valid.then(|| (checksum, parts.next()))
+    })
+    .collect::<Vec<_>>();
+  match entries.as_slice() {
+    [(checksum, None)] => Some(checksum.to_string()),
+    entries => entries.iter().find_map(|(checksum, name)| {
+      (name?.trim_start_matches('*') == file_name).then(|| checksum.to_string())
+    }),
+  }
+}
+
 /// This function sets the subsystem field in the PE header to 2 (GUI subsystem)
 /// For more information about the PE header: https://learn.microsoft.com/en-us/windows/win32/debug/pe-format
 fn set_windows_binary_to_gui(bin: &mut [u8]) -> Result<(), AnyError> {
@@ -740,3 +1078,46 @@ fn set_windows_binary_to_gui(bin: &mut [u8]) -> Result<(), AnyError> {
     .copy_from_slice(&subsystem.to_le_bytes());
   Ok(())
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_find_checksum() {
+    let checksum = "a".repeat(64);
+    let text = format!(
+      "{}  denort-x86_64-apple-darwin.zip\n{} *denort-x86_64-unknown-linux-gnu.zip\n",
+      "b".repeat(64),
+      checksum,
+    );
+    assert_eq!(
+      find_checksum(&text, "denort-x86_64-unknown-linux-gnu.zip"),
+      Some(checksum.clone())
+    );
+    assert_eq!(
+      find_checksum(&text, "denort-x86_64-pc-windows-msvc.zip"),
+      None
+    );
+    // sidecar file with only the checksum
+    assert_eq!(
+      find_checksum(
+        &format!("{checksum}\n"),
+        "denort-x86_64-pc-windows-msvc.zip"
+      ),
+      Some(checksum.clone())
+    );
+    // a checksum without a file name among others doesn't match any file
+    assert_eq!(
+      find_checksum(
+        &format!("{checksum}\n{}  denort.zip\n", "b".repeat(64)),
+        "denort-x86_64-pc-windows-msvc.zip"
+      ),
+      None
+    );
+    assert_eq!(
+      find_checksum("not-a-checksum  denort.zip", "denort.zip"),
+      None
+    );
+  }
+}
@@ -18,26 +18,83 @@ use deno_runtime::deno_io::fs::FsStat;
 
 use super::virtual_fs::FileBackedVfs;
 
+/// A file system that serves the paths within an embedded virtual file
+/// system and delegates everything else to another file system.
 #[derive(Debug, Clone)]
-pub struct DenoCompileFileSystem(Arc<FileBackedVfs>);
+pub struct DenoCompileFileSystem {
+  vfs: Arc<FileBackedVfs>,
+  fallback: Arc<dyn FileSystem>,
+  /// Whether only the entries of the vfs are served, rather than every path
+  /// within its root.
+  embedded_entries_only: bool,
+}
 
 impl DenoCompileFileSystem {
-  pub fn new(vfs: FileBackedVfs) -> Self {
-    Self(Arc::new(vfs))
+  pub fn with_fallback(
+    vfs: FileBackedVfs,
+    fallback: Arc<dyn FileSystem>,
+  ) -> Self {
+    Self {
+      vfs: Arc::new(vfs),
+      fallback,
+      embedded_entries_only: false,
+    }
+  }
+
+  /// Creates a file system that serves the entries of the vfs over the real
+  /// file system, which is used for all other paths, even within the root of
+  /// the vfs.
+  pub fn new_overlay(vfs: FileBackedVfs) -> Self {
+    Self {
+      vfs: Arc::new(vfs),
+      fallback: Arc::new(RealFs),
+      embedded_entries_only: true,
+    }
+  }
+
+  fn is_in_vfs(&self, path: &Path) -> bool {
+    if self.embedded_entries_only {
+      self.vfs.has_entry(path)
+    } else {
+      self.vfs.is_path_within(path)
+    }
   }
 
   fn error_if_in_vfs(&self, path: &Path) -> FsResult<()> {
-    if self.0.is_path_within(path) {
+    let is_in_vfs = if self.embedded_entries_only {
+      // directories of the vfs may be written to on the real file system
+      self.vfs.file_entry(path).is_ok()
+    } else {
+      self.vfs.is_path_within(path)
+    };
+    if is_in_vfs {
       Err(FsError::NotSupported)
     } else {
       Ok(())
     }
   }
 
+  /// Adds the entries of the directory on the fallback file system that
+  /// aren't in the vfs when only the entries of the vfs are served.
+  fn merge_dir_entries(
+    &self,
+    mut entries: Vec<FsDirEntry>,
+    fallback_entries: FsResult<Vec<FsDirEntry>>,
+  ) -> Vec<FsDirEntry> {
+    if let Ok(fallback_entries) = fallback_entries {
+      for entry in fallback_entries {
+        if !entries.iter().any(|e| e.name == entry.name) {
+          entries.push(entry);
+        }
+      }
+    }
+    entries
+  }
+
   fn copy_to_real_path(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
-    let old_file = self.0.file_entry(oldpath)?;
-    let old_file_bytes = self.0.read_file_all(old_file)?;
-    RealFs.write_file_sync(
+    let old_file = self.vfs.file_entry(oldpath)?;
+    let old_file_bytes = self.vfs.read_file_all(old_file)?;
+    self.fallback.write_file_sync(
       newpath,
       OpenOptions {
         read: false,
@@ -57,20 +114,20 @@ impl DenoCompileFileSystem {
 #[async_trait::async_trait(?Send)]
 impl FileSystem for DenoCompileFileSystem {
   fn cwd(&self) -> FsResult<PathBuf> {
-    RealFs.cwd()
+    self.fallback.cwd()
   }
 
   fn tmp_dir(&self) -> FsResult<PathBuf> {
-    RealFs.tmp_dir()
+    self.fallback.tmp_dir()
   }
 
   fn chdir(&self, path: &Path) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.chdir(path)
+    self.fallback.chdir(path)
   }
 
   fn umask(&self, mask: Option<u32>) -> FsResult<u32> {
-    RealFs.umask(mask)
+    self.fallback.umask(mask)
   }
 
   fn open_sync(
@@ -79,10 +136,10 @@ impl FileSystem for DenoCompileFileSystem {
     options: OpenOptions,
     access_check: Option<AccessCheckCb>,
   ) -> FsResult<Rc<dyn File>> {
-    if self.0.is_path_within(path) {
-      Ok(self.0.open_file(path)?)
+    if self.is_in_vfs(path) {
+      Ok(self.vfs.open_file(path)?)
     } else {
-      RealFs.open_sync(path, options, access_check)
+      self.fallback.open_sync(path, options, access_check)
     }
   }
   async fn open_async<'a>(
@@ -91,10 +148,10 @@ impl FileSystem for DenoCompileFileSystem {
     options: OpenOptions,
     access_check: Option<AccessCheckCb<'a>>,
   ) -> FsResult<Rc<dyn File>> {
-    if self.0.is_path_within(&path) {
-      Ok(self.0.open_file(&path)?)
+    if self.is_in_vfs(&path) {
+      Ok(self.vfs.open_file(&path)?)
     } else {
-      RealFs.open_async(path, options, access_check).await
+      self.fallback.open_async(path, options, access_check).await
     }
   }
 
@@ -105,7 +162,7 @@ impl FileSystem for DenoCompileFileSystem {
     mode: u32,
   ) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.mkdir_sync(path, recursive, mode)
+    self.fallback.mkdir_sync(path, recursive, mode)
   }
   async fn mkdir_async(
     &self,
@@ -114,16 +171,16 @@ impl FileSystem for DenoCompileFileSystem {
     mode: u32,
   ) -> FsResult<()> {
     self.error_if_in_vfs(&path)?;
-    RealFs.mkdir_async(path, recursive, mode).await
+    self.fallback.mkdir_async(path, recursive, mode).await
   }
 
   fn chmod_sync(&self, path: &Path, mode: u32) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.chmod_sync(path, mode)
+    self.fallback.chmod_sync(path, mode)
   }
   async fn chmod_async(&self, path: PathBuf, mode: u32) -> FsResult<()> {
     self.error_if_in_vfs(&path)?;
-    RealFs.chmod_async(path, mode).await
+    self.fallback.chmod_async(path, mode).await
   }
 
   fn chown_sync(
@@ -133,7 +190,7 @@ impl FileSystem for DenoCompileFileSystem {
     gid: Option<u32>,
   ) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.chown_sync(path, uid, gid)
+    self.fallback.chown_sync(path, uid, gid)
   }
   async fn chown_async(
     &self,
@@ -142,24 +199,24 @@ impl FileSystem for DenoCompileFileSystem {
     gid: Option<u32>,
   ) -> FsResult<()> {
     self.error_if_in_vfs(&path)?;
-    RealFs.chown_async(path, uid, gid).await
+    self.fallback.chown_async(path, uid, gid).await
   }
 
   fn remove_sync(&self, path: &Path, recursive: bool) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.remove_sync(path, recursive)
+    self.fallback.remove_sync(path, recursive)
   }
   async fn remove_async(&self, path: PathBuf, recursive: bool) -> FsResult<()> {
     self.error_if_in_vfs(&path)?;
-    RealFs.remove_async(path, recursive).await
+    self.fallback.remove_async(path, recursive).await
   }
 
   fn copy_file_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
     self.error_if_in_vfs(newpath)?;
-    if self.0.is_path_within(oldpath) {
+    if self.is_in_vfs(oldpath) {
       self.copy_to_real_path(oldpath, newpath)
     } else {
-      RealFs.copy_file_sync(oldpath, newpath)
+      self.fallback.copy_file_sync(oldpath, newpath)
     }
   }
   async fn copy_file_async(
@@ -168,92 +225,101 @@ impl FileSystem for DenoCompileFileSystem {
     newpath: PathBuf,
   ) -> FsResult<()> {
     self.error_if_in_vfs(&newpath)?;
-    if self.0.is_path_within(&oldpath) {
+    if self.is_in_vfs(&oldpath) {
       let fs = self.clone();
       tokio::task::spawn_blocking(move || {
         fs.copy_to_real_path(&oldpath, &newpath)
       })
       .await?
     } else {
-      RealFs.copy_file_async(oldpath, newpath).await
+      self.fallback.copy_file_async(oldpath, newpath).await
     }
   }
 
   fn cp_sync(&self, from: &Path, to: &Path) -> FsResult<()> {
     self.error_if_in_vfs(to)?;
 
-    RealFs.cp_sync(from, to)
+    self.fallback.cp_sync(from, to)
   }
   async fn cp_async(&self, from: PathBuf, to: PathBuf) -> FsResult<()> {
     self.error_if_in_vfs(&to)?;
 
-    RealFs.cp_async(from, to).await
+    self.fallback.cp_async(from, to).await
   }
 
   fn stat_sync(&self, path: &Path) -> FsResult<FsStat> {
-    if self.0.is_path_within(path) {
-      Ok(self.0.stat(path)?)
+    if self.is_in_vfs(path) {
+      Ok(self.vfs.stat(path)?)
     } else {
-      RealFs.stat_sync(path)
+      self.fallback.stat_sync(path)
     }
   }
   async fn stat_async(&self, path: PathBuf) -> FsResult<FsStat> {
-    if self.0.is_path_within(&path) {
-      Ok(self.0.stat(&path)?)
+    if self.is_in_vfs(&path) {
+      Ok(self.vfs.stat(&path)?)
     } else {
-      RealFs.stat_async(path).await
+      self.fallback.stat_async(path).await
     }
   }
 
   fn lstat_sync(&self, path: &Path) -> FsResult<FsStat> {
-    if self.0.is_path_within(path) {
-      Ok(self.0.lstat(path)?)
+    if self.is_in_vfs(path) {
+      Ok(self.vfs.lstat(path)?)
     } else {
-      RealFs.lstat_sync(path)
+      self.fallback.lstat_sync(path)
     }
   }
   async fn lstat_async(&self, path: PathBuf) -> FsResult<FsStat> {
-    if self.0.is_path_within(&path) {
-      Ok(self.0.lstat(&path)?)
+    if self.is_in_vfs(&path) {
+      Ok(self.vfs.lstat(&path)?)
     } else {
-      RealFs.lstat_async(path).await
+      self.fallback.lstat_async(path).await
     }
   }
 
   fn realpath_sync(&self, path: &Path) -> FsResult<PathBuf> {
-    if self.0.is_path_within(path) {
-      Ok(self.0.canonicalize(path)?)
+    if self.is_in_vfs(path) {
+      Ok(self.vfs.canonicalize(path)?)
     } else {
-      RealFs.realpath_sync(path)
+      self.fallback.realpath_sync(path)
     }
   }
   async fn realpath_async(&self, path: PathBuf) -> FsResult<PathBuf> {
-    if self.0.is_path_within(&path) {
-      Ok(self.0.canonicalize(&path)?)
+    if self.is_in_vfs(&path) {
+      Ok(self.vfs.canonicalize(&path)?)
     } else {
-      RealFs.realpath_async(path).await
+      self.fallback.realpath_async(path).await
     }
   }
 
   fn read_dir_sync(&self, path: &Path) -> FsResult<Vec<FsDirEntry>> {
-    if self.0.is_path_within(path) {
-      Ok(self.0.read_dir(path)?)
+    if self.is_in_vfs(path) {
+      let entries = self.vfs.read_dir(path)?;
+      if !self.embedded_entries_only {
+        return Ok(entries);
+      }
+      Ok(self.merge_dir_entries(entries, self.fallback.read_dir_sync(path)))
     } else {
-      RealFs.read_dir_sync(path)
+      self.fallback.read_dir_sync(path)
     }
   }
   async fn read_dir_async(&self, path: PathBuf) -> FsResult<Vec<FsDirEntry>> {
-    if self.0.is_path_within(&path) {
-      Ok(self.0.read_dir(&path)?)
+    if self.is_in_vfs(&path) {
+      let entries = self.vfs.read_dir(&path)?;
+      if !self.embedded_entries_only {
+        return Ok(entries);
+      }
+      let fallback_entries = self.fallback.read_dir_async(path).await;
+      Ok(self.merge_dir_entries(entries, fallback_entries))
     } else {
-      RealFs.read_dir_async(path).await
+      self.fallback.read_dir_async(path).await
     }
   }
 
   fn rename_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
     self.error_if_in_vfs(oldpath)?;
     self.error_if_in_vfs(newpath)?;
-    RealFs.rename_sync(oldpath, newpath)
+    self.fallback.rename_sync(oldpath, newpath)
   }
   async fn rename_async(
     &self,
@@ -262,13 +328,13 @@ impl FileSystem for DenoCompileFileSystem {
   ) -> FsResult<()> {
     self.error_if_in_vfs(&oldpath)?;
     self.error_if_in_vfs(&newpath)?;
-    RealFs.rename_async(oldpath, newpath).await
+    self.fallback.rename_async(oldpath, newpath).await
   }
 
   fn link_sync(&self, oldpath: &Path, newpath: &Path) -> FsResult<()> {
     self.error_if_in_vfs(oldpath)?;
     self.error_if_in_vfs(newpath)?;
-    RealFs.link_sync(oldpath, newpath)
+    self.fallback.link_sync(oldpath, newpath)
   }
   async fn link_async(
     &self,
@@ -277,7 +343,7 @@ impl FileSystem for DenoCompileFileSystem {
   ) -> FsResult<()> {
     self.error_if_in_vfs(&oldpath)?;
     self.error_if_in_vfs(&newpath)?;
-    RealFs.link_async(oldpath, newpath).await
+    self.fallback.link_async(oldpath, newpath).await
   }
 
   fn symlink_sync(
@@ -288,7 +354,7 @@ impl FileSystem for DenoCompileFileSystem {
   ) -> FsResult<()> {
     self.error_if_in_vfs(oldpath)?;
     self.error_if_in_vfs(newpath)?;
-    RealFs.symlink_sync(oldpath, newpath, file_type)
+    self.fallback.symlink_sync(oldpath, newpath, file_type)
   }
   async fn symlink_async(
     &self,
@@ -298,31 +364,34 @@ impl FileSystem for DenoCompileFileSystem {
   ) -> FsResult<()> {
     self.error_if_in_vfs(&oldpath)?;
     self.error_if_in_vfs(&newpath)?;
-    RealFs.symlink_async(oldpath, newpath, file_type).await
+    self
+      .fallback
+      .symlink_async(oldpath, newpath, file_type)
+      .await
   }
 
   fn read_link_sync(&self, path: &Path) -> FsResult<PathBuf> {
-    if self.0.is_path_within(path) {
-      Ok(self.0.read_link(path)?)
+    if self.is_in_vfs(path) {
+      Ok(self.vfs.read_link(path)?)
     } else {
-      RealFs.read_link_sync(path)
+      self.fallback.read_link_sync(path)
     }
   }
   async fn read_link_async(&self, path: PathBuf) -> FsResult<PathBuf> {
-    if self.0.is_path_within(&path) {
-      Ok(self.0.read_link(&path)?)
+    if self.is_in_vfs(&path) {
+      Ok(self.vfs.read_link(&path)?)
     } else {
-      RealFs.read_link_async(path).await
+      self.fallback.read_link_async(path).await
     }
   }
 
   fn truncate_sync(&self, path: &Path, len: u64) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.truncate_sync(path, len)
+    self.fallback.truncate_sync(path, len)
   }
   async fn truncate_async(&self, path: PathBuf, len: u64) -> FsResult<()> {
     self.error_if_in_vfs(&path)?;
-    RealFs.truncate_async(path, len).await
+    self.fallback.truncate_async(path, len).await
   }
 
   fn utime_sync(
@@ -334,7 +403,13 @@ impl FileSystem for DenoCompileFileSystem {
     mtime_nanos: u32,
   ) -> FsResult<()> {
     self.error_if_in_vfs(path)?;
-    RealFs.utime_sync(path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
+    self.fallback.utime_sync(
+      path,
+      atime_secs,
+      atime_nanos,
+      mtime_secs,
+      mtime_nanos,
+    )
   }
   async fn utime_async(
     &self,
@@ -345,7 +420,8 @@ impl FileSystem for DenoCompileFileSystem {
     mtime_nanos: u32,
   ) -> FsResult<()> {
     self.error_if_in_vfs(&path)?;
-    RealFs
+    self
+      .fallback
       .utime_async(path, atime_secs, atime_nanos, mtime_secs, mtime_nanos)
       .await
   }
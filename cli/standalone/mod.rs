@@ -65,12 +65,17 @@ use std::sync::Arc;
 
 pub mod binary;
 mod file_system;
+pub mod self_update;
 mod virtual_fs;
 
 pub use binary::extract_standalone;
 pub use binary::is_standalone_binary;
+pub use binary::resolve_offline_base_binary;
 pub use binary::DenoCompileBinaryWriter;
+pub use binary::EmbeddedFilesStats;
+pub use virtual_fs::VfsStats;
 
+use self::binary::load_assets_vfs;
 use self::binary::load_npm_vfs;
 use self::binary::Metadata;
 use self::file_system::DenoCompileFileSystem;
@@ -348,6 +353,19 @@ pub async fn run(
     NpmCacheDir::new(root_path.clone(), vec![npm_registry_url.clone()]);
   let npm_global_cache_dir = npm_cache_dir.get_cache_location();
   let cache_setting = CacheSetting::Only;
+  let mut vfs_roots = Vec::new();
+  let base_fs = match metadata.assets {
+    Some(assets) => {
+      let vfs =
+        load_assets_vfs(assets).context("Failed to load assets vfs.")?;
+      // only the embedded assets are readable without a permission, not the
+      // real files next to them
+      vfs_roots.extend(vfs.file_paths());
+      Arc::new(DenoCompileFileSystem::new_overlay(vfs))
+        as Arc<dyn deno_fs::FileSystem>
+    }
+    None => Arc::new(deno_fs::RealFs) as Arc<dyn deno_fs::FileSystem>,
+  };
   let (package_json_deps_provider, fs, npm_resolver, maybe_vfs_root) =
     match metadata.node_modules {
       Some(binary::NodeModules::Managed {
@@ -372,8 +390,9 @@ pub async fn run(
           Arc::new(PackageJsonDepsProvider::new(
             package_json_deps.map(|serialized| serialized.into_deps()),
           ));
-        let fs = Arc::new(DenoCompileFileSystem::new(vfs))
-          as Arc<dyn deno_fs::FileSystem>;
+        let fs =
+          Arc::new(DenoCompileFileSystem::with_fallback(vfs, base_fs.clone()))
+            as Arc<dyn deno_fs::FileSystem>;
         let npm_resolver =
           create_cli_npm_resolver(CliNpmResolverCreateOptions::Managed(
             CliNpmResolverManagedCreateOptions {
@@ -411,8 +430,9 @@ pub async fn run(
           Arc::new(PackageJsonDepsProvider::new(
             package_json_deps.map(|serialized| serialized.into_deps()),
           ));
-        let fs = Arc::new(DenoCompileFileSystem::new(vfs))
-          as Arc<dyn deno_fs::FileSystem>;
+        let fs =
+          Arc::new(DenoCompileFileSystem::with_fallback(vfs, base_fs.clone()))
+            as Arc<dyn deno_fs::FileSystem>;
         let npm_resolver =
           create_cli_npm_resolver(CliNpmResolverCreateOptions::Byonm(
             CliNpmResolverByonmCreateOptions {
@@ -431,7 +451,7 @@ pub async fn run(
       None => {
         let package_json_deps_provider =
           Arc::new(PackageJsonDepsProvider::new(None));
-        let fs = Arc::new(deno_fs::RealFs) as Arc<dyn deno_fs::FileSystem>;
+        let fs = base_fs.clone();
         let npm_resolver =
           create_cli_npm_resolver(CliNpmResolverCreateOptions::Managed(
             CliNpmResolverManagedCreateOptions {
@@ -501,8 +521,9 @@ pub async fn run(
     let maybe_cwd = std::env::current_dir().ok();
     let mut permissions =
       metadata.permissions.to_options(maybe_cwd.as_deref())?;
-    // if running with an npm vfs, grant read access to it
-    if let Some(vfs_root) = maybe_vfs_root {
+    // if running with an npm or assets vfs, grant read access to it
+    vfs_roots.extend(maybe_vfs_root);
+    for vfs_root in vfs_roots {
       match &mut permissions.allow_read {
         Some(vec) if vec.is_empty() => {
           // do nothing, already granted
@@ -518,6 +539,12 @@ pub async fn run(
 
     PermissionsContainer::new(Permissions::from_options(&permissions)?)
   };
+  let enable_self_update = metadata.unstable_config.legacy_flag_enabled
+    || metadata
+      .unstable_config
+      .features
+      .iter()
+      .any(|feature| feature == self_update::UNSTABLE_FEATURE_NAME);
   let feature_checker = Arc::new({
     let mut checker = FeatureChecker::default();
     checker.set_exit_cb(Box::new(crate::unstable_exit_cb));
@@ -587,17 +614,36 @@ pub async fn run(
     None,
   );
 
+  // The executable is being checked by `Deno.selfUpdate()` before it's
+  // installed, so stop now that the embedded program could be loaded.
+  if let Some(token) = self_update::check_token() {
+    self_update::confirm_check(&token);
+    return Ok(0);
+  }
+
   // Initialize v8 once from the main thread.
   v8_set_flags(construct_v8_flags(&[], &metadata.v8_flags, vec![]));
   deno_core::JsRuntime::init_platform(None);
 
+  let custom_extensions = if enable_self_update {
+    vec![self_update::deno_self_update::init_ops(
+      http_client_provider,
+    )]
+  } else {
+    vec![]
+  };
   let mut worker = worker_factory
-    .create_main_worker(
+    .create_custom_worker(
       WorkerExecutionMode::Run,
       main_module.clone(),
       permissions,
+      custom_extensions,
+      Default::default(),
     )
     .await?;
+  if enable_self_update {
+    worker.enable_self_update()?;
+  }
 
   let exit_code = worker.run().await?;
   Ok(exit_code)
@@ -0,0 +1,347 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Replacing the executable of a standalone binary with a new build of it.
+
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::op2;
+use deno_core::url::Url;
+use deno_core::OpState;
+use deno_runtime::permissions::PermissionsContainer;
+use serde::Deserialize;
+
+use crate::http_util::HttpClientProvider;
+use crate::util::checksum;
+
+use super::binary::is_standalone_binary;
+
+pub const UNSTABLE_FEATURE_NAME: &str = "self-update";
+
+/// Set to a random token when running a downloaded update to check that it
+/// starts, in which case the executable prints the token and exits right
+/// after loading the embedded program.
+pub const CHECK_ENV_VAR_NAME: &str = "DENO_SELF_UPDATE_CHECK";
+
+/// How long a downloaded update may take to start before it's rejected.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Returns the token of the check this executable is run for, if any. Only
+/// tokens in the format of the generated ones are accepted, so that an
+/// unrelated value in the environment doesn't stop the program.
+pub fn check_token() -> Option<String> {
+  let token = std::env::var(CHECK_ENV_VAR_NAME).ok()?;
+  let is_valid =
+    token.len() == 32 && token.chars().all(|c| c.is_ascii_hexdigit());
+  is_valid.then_some(token)
+}
+
+/// Prints the token of the check, which shows that the executable started.
+#[allow(clippy::print_stdout)]
+pub fn confirm_check(token: &str) {
+  println!("{token}");
+}
+
+fn new_check_token() -> String {
+  (0..16)
+    .map(|_| format!("{:02x}", rand::random::<u8>()))
+    .collect()
+}
+
+deno_core::extension!(deno_self_update,
+  ops = [op_self_update],
+  options = {
+    http_client_provider: Arc<HttpClientProvider>,
+  },
+  state = |state, options| {
+    state.put(options.http_client_provider);
+  },
+);
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfUpdateOptions {
+  /// The expected SHA-256 checksum of the new executable, as hex.
+  sha256: Option<String>,
+  /// A base64 encoded Ed25519 signature of the new executable.
+  signature: Option<String>,
+  /// The base64 encoded Ed25519 public key to verify the signature with.
+  public_key: Option<String>,
+}
+
+#[op2(async)]
+pub async fn op_self_update(
+  state: Rc<RefCell<OpState>>,
+  #[string] source: String,
+  #[serde] options: SelfUpdateOptions,
+) -> Result<(), AnyError> {
+  const API_NAME: &str = "Deno.selfUpdate()";
+
+  let current_exe_path = std::env::current_exe()?;
+  let source = resolve_source(&source)?;
+  let http_client_provider = {
+    let mut state = state.borrow_mut();
+    let permissions = state.borrow_mut::<PermissionsContainer>();
+    permissions.check_write(&current_exe_path, API_NAME)?;
+    match &source {
+      UpdateSource::Url(url) => permissions.check_net_url(url, API_NAME)?,
+      UpdateSource::Path(path) => permissions.check_read(path, API_NAME)?,
+    }
+    state.borrow::<Arc<HttpClientProvider>>().clone()
+  };
+
+  let data = match source {
+    UpdateSource::Url(url) => {
+      let client = http_client_provider.get_or_create()?;
+      client
+        .download(url.clone())
+        .await
+        .with_context(|| format!("Failed downloading {url}"))?
+    }
+    UpdateSource::Path(path) => tokio::fs::read(&path)
+      .await
+      .with_context(|| format!("Failed reading {}", path.display()))?,
+  };
+
+  tokio::task::spawn_blocking(move || {
+    verify_executable(&data, &options)?;
+    install_executable(&data, &current_exe_path)
+  })
+  .await?
+}
+
+enum UpdateSource {
+  Url(Url),
+  Path(PathBuf),
+}
+
+fn resolve_source(source: &str) -> Result<UpdateSource, AnyError> {
+  match Url::parse(source) {
+    Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {
+      Ok(UpdateSource::Url(url))
+    }
+    Ok(url) if url.scheme() == "file" => match url.to_file_path() {
+      Ok(path) => Ok(UpdateSource::Path(path)),
+      Err(()) => bail!("Invalid file URL: {}", url),
+    },
+    // single letters are windows drive letters
+    Ok(url) if url.scheme().len() > 1 => {
+      bail!(
+        "Unsupported scheme \"{}\" for the update source",
+        url.scheme()
+      )
+    }
+    _ => Ok(UpdateSource::Path(std::env::current_dir()?.join(source))),
+  }
+}
+
+fn verify_executable(
+  data: &[u8],
+  options: &SelfUpdateOptions,
+) -> Result<(), AnyError> {
+  if options.sha256.is_none() && options.signature.is_none() {
+    bail!("Either a sha256 checksum or a signature must be provided to verify the update.");
+  }
+  if let Some(expected) = &options.sha256 {
+    let actual = checksum::gen(&[data]);
+    if !actual.eq_ignore_ascii_case(expected) {
+      bail!(
+        "Checksum mismatch for the update.\n  Expected: {}\n  Actual: {}",
+        expected,
+        actual
+      );
+    }
+  }
+  if let Some(signature) = &options.signature {
+    let Some(public_key) = &options.public_key else {
+      bail!("A publicKey must be provided to verify the signature.");
+    };
+    let signature = BASE64_STANDARD
+      .decode(signature)
+      .context("Invalid base64 signature")?;
+    let public_key = BASE64_STANDARD
+      .decode(public_key)
+      .context("Invalid base64 public key")?;
+    ring::signature::UnparsedPublicKey::new(
+      &ring::signature::ED25519,
+      public_key,
+    )
+    .verify(data, &signature)
+    .map_err(|_| {
+      deno_core::anyhow::anyhow!("Signature verification of the update failed.")
+    })?;
+  }
+  Ok(())
+}
+
+/// Writes the new executable next to the current one, checks that it's a
+/// standalone binary and then swaps it in.
+fn install_executable(
+  data: &[u8],
+  current_exe_path: &Path,
+) -> Result<(), AnyError> {
+  let mut file_name = current_exe_path.file_name().unwrap().to_os_string();
+  file_name.push(".new");
+  let new_exe_path = current_exe_path.with_file_name(file_name);
+  fs::write(&new_exe_path, data).with_context(|| {
+    format!("Failed writing update to {}", new_exe_path.display())
+  })?;
+  let result = (|| {
+    if !is_standalone_binary(&new_exe_path) {
+      bail!("The update is not an executable created by deno compile.");
+    }
+    fs::set_permissions(
+      &new_exe_path,
+      fs::metadata(current_exe_path)?.permissions(),
+    )?;
+    check_exe(&new_exe_path)?;
+    replace_exe(&new_exe_path, current_exe_path).with_context(|| {
+      format!("Failed replacing {}", current_exe_path.display())
+    })
+  })();
+  if result.is_err() {
+    let _ = fs::remove_file(&new_exe_path);
+  }
+  result
+}
+
+/// Runs the new executable to make sure it starts before it's installed.
+fn check_exe(exe_path: &Path) -> Result<(), AnyError> {
+  let token = new_check_token();
+  let mut child = std::process::Command::new(exe_path)
+    .env(CHECK_ENV_VAR_NAME, &token)
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .with_context(|| format!("Failed running {}", exe_path.display()))?;
+  let started = Instant::now();
+  while child.try_wait()?.is_none() {
+    if started.elapsed() > CHECK_TIMEOUT {
+      let _ = child.kill();
+      let _ = child.wait();
+      bail!(
+        "The update did not start within {} seconds.",
+        CHECK_TIMEOUT.as_secs()
+      );
+    }
+    std::thread::sleep(Duration::from_millis(50));
+  }
+  let output = child.wait_with_output()?;
+  if !output.status.success() {
+    bail!(
+      "The update failed to start: {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+  }
+  if String::from_utf8_lossy(&output.stdout).trim() != token {
+    bail!("The update did not confirm that it started.");
+  }
+  Ok(())
+}
+
+/// Replaces the executable at `to` with the one at `from`, restoring the
+/// original executable when that fails.
+fn replace_exe(from: &Path, to: &Path) -> Result<(), std::io::Error> {
+  let backup_path = if cfg!(windows) {
+    to.with_extension("old.exe")
+  } else {
+    let mut file_name = to.file_name().unwrap().to_os_string();
+    file_name.push(".old");
+    to.with_file_name(file_name)
+  };
+  // On windows you cannot replace the currently running executable,
+  // so first we rename it to the backup path.
+  fs::rename(to, &backup_path)?;
+  // Windows cannot rename files across device boundaries, so if rename fails,
+  // we try again with copy.
+  if let Err(err) =
+    fs::rename(from, to).or_else(|_| fs::copy(from, to).map(|_| ()))
+  {
+    let _ = fs::remove_file(to);
+    fs::rename(&backup_path, to)?;
+    return Err(err);
+  }
+  // the running executable can't be removed on windows
+  if !cfg!(windows) {
+    let _ = fs::remove_file(&backup_path);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn verify_executable_checksum() {
+    let data = b"executable";
+    let err = verify_executable(data, &SelfUpdateOptions::default())
+      .unwrap_err()
+      .to_string();
+    assert!(err.starts_with("Either a sha256 checksum"));
+
+    let options = SelfUpdateOptions {
+      sha256: Some(checksum::gen(&[data]).to_uppercase()),
+      ..Default::default()
+    };
+    verify_executable(data, &options).unwrap();
+
+    let options = SelfUpdateOptions {
+      sha256: Some(checksum::gen(&[b"other"])),
+      ..Default::default()
+    };
+    let err = verify_executable(data, &options).unwrap_err().to_string();
+    assert!(err.starts_with("Checksum mismatch for the update."));
+  }
+
+  #[test]
+  fn verify_executable_signature() {
+    use ring::signature::KeyPair;
+
+    let rng = ring::rand::SystemRandom::new();
+    let pkcs8 = ring::signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    let key_pair =
+      ring::signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    let data = b"executable";
+    let options = SelfUpdateOptions {
+      sha256: None,
+      signature: Some(BASE64_STANDARD.encode(key_pair.sign(data))),
+      public_key: Some(BASE64_STANDARD.encode(key_pair.public_key())),
+    };
+    verify_executable(data, &options).unwrap();
+    let err = verify_executable(b"tampered", &options)
+      .unwrap_err()
+      .to_string();
+    assert_eq!(err, "Signature verification of the update failed.");
+  }
+
+  #[test]
+  fn replace_exe_restores_on_failure() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let exe_path = temp_dir.path().join("app");
+    fs::write(&exe_path, "old").unwrap();
+
+    let missing_path = temp_dir.path().join("missing");
+    assert!(replace_exe(&missing_path, &exe_path).is_err());
+    assert_eq!(fs::read_to_string(&exe_path).unwrap(), "old");
+
+    let new_path = temp_dir.path().join("app.new");
+    fs::write(&new_path, "new").unwrap();
+    replace_exe(&new_path, &exe_path).unwrap();
+    assert_eq!(fs::read_to_string(&exe_path).unwrap(), "new");
+    assert!(!new_path.exists());
+  }
+}
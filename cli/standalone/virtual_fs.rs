@@ -23,6 +23,7 @@ use deno_runtime::deno_io;
 use deno_runtime::deno_io::fs::FsError;
 use deno_runtime::deno_io::fs::FsResult;
 use deno_runtime::deno_io::fs::FsStat;
+use once_cell::sync::OnceCell;
 use serde::Deserialize;
 use serde::Serialize;
 use thiserror::Error;
@@ -39,12 +40,33 @@ pub struct StripRootError {
   target: PathBuf,
 }
 
+/// Statistics about the files included in a virtual file system.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VfsStats {
+  /// Number of files in the file system.
+  pub files: usize,
+  /// Number of files whose content was already stored for another file.
+  pub duplicate_files: usize,
+  /// Total size of all files, including duplicates.
+  pub total_size: u64,
+  /// Size of the file data that is stored in the binary.
+  pub stored_size: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct StoredFile {
+  offset: u64,
+  compressed_len: Option<u64>,
+}
+
 pub struct VfsBuilder {
   root_path: PathBuf,
   root_dir: VirtualDirectory,
   files: Vec<Vec<u8>>,
   current_offset: u64,
-  file_offsets: HashMap<String, u64>,
+  file_offsets: HashMap<String, StoredFile>,
+  compression_level: Option<i32>,
+  stats: VfsStats,
 }
 
 impl VfsBuilder {
@@ -64,6 +86,8 @@ impl VfsBuilder {
       files: Vec::new(),
       current_offset: 0,
       file_offsets: Default::default(),
+      compression_level: None,
+      stats: Default::default(),
     })
   }
 
@@ -71,6 +95,16 @@ impl VfsBuilder {
     self.root_dir.name = name;
   }
 
+  /// Compress the data of files added after this call with the given zstd
+  /// level, when that makes them smaller.
+  pub fn set_compression_level(&mut self, level: Option<i32>) {
+    self.compression_level = level;
+  }
+
+  pub fn stats(&self) -> VfsStats {
+    self.stats
+  }
+
   pub fn add_dir_recursive(&mut self, path: &Path) -> Result<(), AnyError> {
     let path = canonicalize_path(path)?;
     self.add_dir_recursive_internal(&path)
@@ -177,23 +211,62 @@ impl VfsBuilder {
   pub fn add_file_at_path(&mut self, path: &Path) -> Result<(), AnyError> {
     let file_bytes = std::fs::read(path)
       .with_context(|| format!("Reading {}", path.display()))?;
-    self.add_file(path, file_bytes)
+    let metadata = std::fs::metadata(path).ok();
+    let to_millis = |time: std::io::Result<std::time::SystemTime>| {
+      time
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64)
+    };
+    let mtime = metadata.as_ref().and_then(|m| to_millis(m.modified()));
+    // not available on all platforms and file systems
+    let birthtime = metadata.as_ref().and_then(|m| to_millis(m.created()));
+    self.add_file_with_times(path, file_bytes, mtime, birthtime)
   }
 
   fn add_file(&mut self, path: &Path, data: Vec<u8>) -> Result<(), AnyError> {
+    self.add_file_with_times(path, data, None, None)
+  }
+
+  fn add_file_with_times(
+    &mut self,
+    path: &Path,
+    data: Vec<u8>,
+    mtime: Option<u64>,
+    birthtime: Option<u64>,
+  ) -> Result<(), AnyError> {
     log::debug!("Adding file '{}'", path.display());
     let checksum = util::checksum::gen(&[&data]);
-    let offset = if let Some(offset) = self.file_offsets.get(&checksum) {
+    let data_len = data.len() as u64;
+    let (stored_file, new_data) = if let Some(stored_file) =
+      self.file_offsets.get(&checksum)
+    {
       // duplicate file, reuse an old offset
-      *offset
+      self.stats.duplicate_files += 1;
+      (*stored_file, None)
     } else {
-      self.file_offsets.insert(checksum, self.current_offset);
-      self.current_offset
+      let (data, compressed_len) = if let Some(level) = self.compression_level {
+        let compressed = zstd::bulk::compress(&data, level)
+          .with_context(|| format!("Compressing {}", path.display()))?;
+        if compressed.len() < data.len() {
+          let compressed_len = compressed.len() as u64;
+          (compressed, Some(compressed_len))
+        } else {
+          (data, None)
+        }
+      } else {
+        (data, None)
+      };
+      let stored_file = StoredFile {
+        offset: self.current_offset,
+        compressed_len,
+      };
+      self.file_offsets.insert(checksum, stored_file);
+      (stored_file, Some(data))
     };
 
     let dir = self.add_dir(path.parent().unwrap())?;
     let name = path.file_name().unwrap().to_string_lossy();
-    let data_len = data.len();
     match dir.entries.binary_search_by(|e| e.name().cmp(&name)) {
       Ok(_) => unreachable!(),
       Err(insert_index) => {
@@ -201,17 +274,23 @@ impl VfsBuilder {
           insert_index,
           VfsEntry::File(VirtualFile {
             name: name.to_string(),
-            offset,
-            len: data.len() as u64,
+            offset: stored_file.offset,
+            len: data_len,
+            compressed_len: stored_file.compressed_len,
+            mtime,
+            birthtime,
           }),
         );
       }
     }
 
+    self.stats.files += 1;
+    self.stats.total_size += data_len;
     // new file, update the list of files
-    if self.current_offset == offset {
+    if let Some(data) = new_data {
+      self.current_offset += data.len() as u64;
+      self.stats.stored_size += data.len() as u64;
       self.files.push(data);
-      self.current_offset += data_len as u64;
     }
 
     Ok(())
@@ -299,15 +378,17 @@ impl<'a> VfsEntryRef<'a> {
         is_directory: false,
         is_file: true,
         is_symlink: false,
+        // the access time of the original file isn't meaningful once it is
+        // embedded
         atime: None,
-        birthtime: None,
-        mtime: None,
+        birthtime: file.birthtime,
+        mtime: file.mtime,
         blksize: 0,
         size: file.len,
         dev: 0,
         ino: 0,
         mode: 0,
-        nlink: 0,
+        nlink: 1,
         uid: 0,
         gid: 0,
         rdev: 0,
@@ -381,6 +462,17 @@ pub struct VirtualFile {
   pub name: String,
   pub offset: u64,
   pub len: u64,
+  /// Length of the zstd compressed data when the file is stored compressed.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub compressed_len: Option<u64>,
+  /// Modification time of the original file in milliseconds since the
+  /// unix epoch.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub mtime: Option<u64>,
+  /// Creation time of the original file in milliseconds since the unix
+  /// epoch, when the platform reports it.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub birthtime: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -519,6 +611,9 @@ struct FileBackedVfsFile {
   file: VirtualFile,
   pos: Arc<Mutex<u64>>,
   vfs: Arc<FileBackedVfs>,
+  /// The data of a compressed file, decompressed on the first read and
+  /// dropped with the last handle to the open file.
+  decompressed: Arc<OnceCell<Vec<u8>>>,
 }
 
 impl FileBackedVfsFile {
@@ -567,10 +662,7 @@ impl FileBackedVfsFile {
       *pos = std::cmp::min(self.file.len, *pos + buf.len() as u64);
       read_pos
     };
-    self
-      .vfs
-      .read_file(&self.file, pos, buf)
-      .map_err(|err| err.into())
+    self.read_at(pos, buf).map_err(|err| err.into())
   }
 
   fn read_to_end(&self) -> FsResult<Vec<u8>> {
@@ -589,9 +681,19 @@ impl FileBackedVfsFile {
     }
     let size = (self.file.len - pos) as usize;
     let mut buf = vec![0; size];
-    self.vfs.read_file(&self.file, pos, &mut buf)?;
+    self.read_at(pos, &mut buf)?;
     Ok(buf)
   }
+
+  fn read_at(&self, pos: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+    if self.file.compressed_len.is_some() {
+      let data = self
+        .decompressed
+        .get_or_try_init(|| self.vfs.decompress(&self.file))?;
+      return Ok(copy_from_pos(data, pos, buf));
+    }
+    self.vfs.read_file(&self.file, pos, buf)
+  }
 }
 
 #[async_trait::async_trait(?Send)]
@@ -665,10 +767,10 @@ impl deno_io::fs::File for FileBackedVfsFile {
   }
 
   fn stat_sync(self: Rc<Self>) -> FsResult<FsStat> {
-    Err(FsError::NotSupported)
+    Ok(VfsEntryRef::File(&self.file).as_fs_stat())
   }
   async fn stat_async(self: Rc<Self>) -> FsResult<FsStat> {
-    Err(FsError::NotSupported)
+    Ok(VfsEntryRef::File(&self.file).as_fs_stat())
   }
 
   fn lock_sync(self: Rc<Self>, _exclusive: bool) -> FsResult<()> {
@@ -745,6 +847,32 @@ impl FileBackedVfs {
     path.starts_with(&self.fs_root.root_path)
   }
 
+  /// Whether the path is a file, directory or symlink of the file system.
+  pub fn has_entry(&self, path: &Path) -> bool {
+    self.fs_root.find_entry_no_follow(path).is_ok()
+  }
+
+  /// Returns the paths of all the files and symlinks in the file system.
+  pub fn file_paths(&self) -> Vec<PathBuf> {
+    fn collect(
+      dir: &VirtualDirectory,
+      dir_path: &Path,
+      paths: &mut Vec<PathBuf>,
+    ) {
+      for entry in &dir.entries {
+        let path = dir_path.join(entry.name());
+        match entry {
+          VfsEntry::Dir(dir) => collect(dir, &path, paths),
+          VfsEntry::File(_) | VfsEntry::Symlink(_) => paths.push(path),
+        }
+      }
+    }
+
+    let mut paths = Vec::new();
+    collect(&self.fs_root.dir, &self.fs_root.root_path, &mut paths);
+    paths
+  }
+
   pub fn open_file(
     self: &Arc<Self>,
     path: &Path,
@@ -754,6 +882,7 @@ impl FileBackedVfs {
       file: file.clone(),
       vfs: self.clone(),
       pos: Default::default(),
+      decompressed: Default::default(),
     }))
   }
 
@@ -803,11 +932,24 @@ impl FileBackedVfs {
   }
 
   pub fn read_file_all(&self, file: &VirtualFile) -> std::io::Result<Vec<u8>> {
+    if file.compressed_len.is_some() {
+      return self.decompress(file);
+    }
     let mut buf = vec![0; file.len as usize];
     self.read_file(file, 0, &mut buf)?;
     Ok(buf)
   }
 
+  /// Returns the decompressed data of a compressed file.
+  fn decompress(&self, file: &VirtualFile) -> std::io::Result<Vec<u8>> {
+    let compressed_len = file.compressed_len.unwrap_or(file.len);
+    let mut compressed = vec![0; compressed_len as usize];
+    self.read_file(file, 0, &mut compressed)?;
+    zstd::bulk::decompress(&compressed, file.len as usize)
+  }
+
+  /// Reads the data of the file as it is stored in the binary, which is
+  /// compressed when the file has a `compressed_len`.
   pub fn read_file(
     &self,
     file: &VirtualFile,
@@ -846,6 +988,15 @@ impl FileBackedVfs {
   }
 }
 
+/// Copies the data starting at `pos` into the buffer, returning the number
+/// of copied bytes.
+fn copy_from_pos(data: &[u8], pos: u64, buf: &mut [u8]) -> usize {
+  let start = std::cmp::min(pos as usize, data.len());
+  let len = std::cmp::min(buf.len(), data.len() - start);
+  buf[..len].copy_from_slice(&data[start..start + len]);
+  len
+}
+
 #[cfg(test)]
 mod test {
   use std::io::Write;
@@ -1044,6 +1195,82 @@ mod test {
     );
   }
 
+  #[test]
+  fn file_stat_metadata() {
+    let temp_dir = TempDir::new();
+    let temp_dir_path = temp_dir.path().canonicalize();
+    temp_dir.create_dir_all("src/templates");
+    temp_dir.write("src/templates/a.html", "<p>a</p>");
+    let src_path = temp_dir_path.join("src").to_path_buf();
+    let mut builder = VfsBuilder::new(src_path.clone()).unwrap();
+    builder.add_dir_recursive(&src_path).unwrap();
+    let (dest_path, virtual_fs) = into_virtual_fs(builder, &temp_dir);
+
+    let file_path = dest_path.join("templates").join("a.html");
+    let stat = virtual_fs.stat(&file_path).unwrap();
+    assert!(stat.is_file);
+    assert_eq!(stat.size, 8);
+    let expected_mtime =
+      std::fs::metadata(temp_dir_path.join("src/templates/a.html").as_path())
+        .unwrap()
+        .modified()
+        .unwrap()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    assert_eq!(stat.mtime, Some(expected_mtime));
+    assert_eq!(stat.atime, None);
+
+    let virtual_fs = Arc::new(virtual_fs);
+    let file = virtual_fs.open_file(&file_path).unwrap();
+    let file_stat = file.stat_sync().unwrap();
+    assert_eq!(file_stat.size, 8);
+    assert_eq!(file_stat.mtime, stat.mtime);
+  }
+
+  #[test]
+  fn compressed_virtual_fs() {
+    let temp_dir = TempDir::new();
+    let src_path = temp_dir.path().canonicalize().join("src");
+    src_path.create_dir_all();
+    let src_path = src_path.to_path_buf();
+    let mut builder = VfsBuilder::new(src_path.clone()).unwrap();
+    builder.set_compression_level(Some(19));
+    let large_text = "0123456789".repeat(1000);
+    builder
+      .add_file(&src_path.join("a.txt"), large_text.clone().into())
+      .unwrap();
+    builder
+      .add_file(&src_path.join("b.txt"), large_text.clone().into())
+      .unwrap();
+    // not worth compressing, so stored as is
+    builder
+      .add_file(&src_path.join("c.txt"), "c".into())
+      .unwrap();
+    let stats = builder.stats();
+    assert_eq!(stats.files, 3);
+    assert_eq!(stats.duplicate_files, 1);
+    assert_eq!(stats.total_size, 20_001);
+    assert!(stats.stored_size < 1_000);
+
+    let (dest_path, virtual_fs) = into_virtual_fs(builder, &temp_dir);
+    assert_eq!(read_file(&virtual_fs, &dest_path.join("a.txt")), large_text);
+    assert_eq!(read_file(&virtual_fs, &dest_path.join("b.txt")), large_text);
+    assert_eq!(read_file(&virtual_fs, &dest_path.join("c.txt")), "c");
+    assert_eq!(
+      virtual_fs.stat(&dest_path.join("a.txt")).unwrap().size,
+      10_000
+    );
+
+    let virtual_fs = Arc::new(virtual_fs);
+    let file = virtual_fs.open_file(&dest_path.join("a.txt")).unwrap();
+    file.clone().seek_sync(SeekFrom::Start(9_995)).unwrap();
+    let mut buf = vec![0; 3];
+    assert_eq!(file.clone().read_sync(&mut buf).unwrap(), 3);
+    assert_eq!(buf, b"567");
+    assert_eq!(file.clone().read_all_sync().unwrap(), b"89");
+  }
+
   #[tokio::test]
   async fn test_open_file() {
     let temp_dir = TempDir::new();
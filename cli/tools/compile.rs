@@ -2,19 +2,42 @@
 
 use crate::args::CompileFlags;
 use crate::args::Flags;
+use crate::args::COMPILE_TARGETS;
+use crate::cache::DenoDir;
+use crate::cache::ParsedSourceCache;
 use crate::factory::CliFactory;
+use crate::graph_util::CreateGraphOptions;
 use crate::http_util::HttpClientProvider;
 use crate::standalone::is_standalone_binary;
+use crate::standalone::resolve_offline_base_binary;
+use crate::standalone::VfsStats;
+use crate::util::display::human_size;
+use deno_ast::swc::ast::CallExpr;
+use deno_ast::swc::ast::Callee;
+use deno_ast::swc::ast::Expr;
+use deno_ast::swc::ast::Lit;
+use deno_ast::swc::ast::MemberProp;
+use deno_ast::swc::ast::MetaPropExpr;
+use deno_ast::swc::ast::MetaPropKind;
+use deno_ast::swc::ast::NewExpr;
+use deno_ast::swc::ast::Program;
+use deno_ast::swc::visit::noop_visit_type;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::ParsedSource;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
 use deno_graph::GraphKind;
+use deno_graph::Module;
+use deno_graph::ModuleGraph;
+use deno_graph::ModuleSpecifier;
 use deno_terminal::colors;
+use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
-use std::sync::Arc;
 
 use super::installer::infer_name_from_url;
 
@@ -23,13 +46,17 @@ pub async fn compile(
   compile_flags: CompileFlags,
 ) -> Result<(), AnyError> {
   let factory = CliFactory::from_flags(flags)?;
+  if compile_flags.list_targets {
+    return list_targets(factory.deno_dir()?, &compile_flags);
+  }
   let cli_options = factory.cli_options();
   let module_graph_creator = factory.module_graph_creator().await?;
+  let module_graph_builder = factory.module_graph_builder().await?;
   let parsed_source_cache = factory.parsed_source_cache();
   let binary_writer = factory.create_compile_binary_writer().await?;
   let http_client = factory.http_client_provider();
   let module_specifier = cli_options.resolve_main_module()?;
-  let module_roots = {
+  let mut module_roots = {
     let mut vec = Vec::with_capacity(compile_flags.include.len() + 1);
     vec.push(module_specifier.clone());
     for side_module in &compile_flags.include {
@@ -57,22 +84,46 @@ pub async fn compile(
   )
   .await?;
 
-  let graph = Arc::try_unwrap(
-    module_graph_creator
-      .create_graph_and_maybe_check(module_roots.clone())
-      .await?,
-  )
-  .unwrap();
-  let graph = if cli_options.type_check_mode().is_true() {
-    // In this case, the previous graph creation did type checking, which will
-    // create a module graph with types information in it. We don't want to
-    // store that in the eszip so create a code only module graph from scratch.
+  // include the entrypoints of web workers, which aren't part of the graph,
+  // by adding them as roots of the graph until no new ones are found
+  let mut graph = ModuleGraph::new(GraphKind::CodeOnly);
+  let mut scanned_modules = HashSet::new();
+  let mut pending_roots = module_roots.clone();
+  while !pending_roots.is_empty() {
+    module_graph_builder
+      .build_graph_with_npm_resolution(
+        &mut graph,
+        CreateGraphOptions {
+          graph_kind: GraphKind::CodeOnly,
+          roots: pending_roots,
+          is_dynamic: false,
+          loader: None,
+        },
+      )
+      .await?;
+    pending_roots = collect_worker_specifiers(
+      &graph,
+      parsed_source_cache,
+      &mut scanned_modules,
+    )
+    .into_iter()
+    .filter(|specifier| !module_roots.contains(specifier))
+    .collect::<Vec<_>>();
+    for specifier in &pending_roots {
+      log::debug!("Including worker entrypoint '{}'", specifier);
+    }
+    module_roots.extend(pending_roots.iter().cloned());
+  }
+
+  if cli_options.type_check_mode().is_true() {
+    // Type checking needs a module graph with types information in it. We
+    // don't want to store that in the eszip, so keep the code only graph.
     module_graph_creator
-      .create_graph(GraphKind::CodeOnly, module_roots)
-      .await?
+      .create_graph_and_maybe_check(module_roots)
+      .await?;
   } else {
-    graph
-  };
+    module_graph_creator.graph_valid(&graph)?;
+  }
 
   let ts_config_for_emit =
     cli_options.resolve_ts_config_for_emit(deno_config::TsConfigType::Emit)?;
@@ -80,6 +131,7 @@ pub async fn compile(
     crate::args::ts_config_to_transpile_and_emit_options(
       ts_config_for_emit.ts_config,
     )?;
+  let (module_count, modules_size) = graph_modules_size(&graph);
   let parser = parsed_source_cache.as_capturing_parser();
   let eszip = eszip::EszipV2::from_graph(
     graph,
@@ -109,11 +161,14 @@ pub async fn compile(
     .await
     .with_context(|| format!("Writing {}", output_path.display()));
   drop(file);
-  if let Err(err) = write_result {
-    // errored, so attempt to remove the output path
-    let _ = std::fs::remove_file(output_path);
-    return Err(err);
-  }
+  let stats = match write_result {
+    Ok(stats) => stats,
+    Err(err) => {
+      // errored, so attempt to remove the output path
+      let _ = std::fs::remove_file(output_path);
+      return Err(err);
+    }
+  };
 
   // set it as executable
   #[cfg(unix)]
@@ -123,9 +178,195 @@ pub async fn compile(
     std::fs::set_permissions(output_path, perms)?;
   }
 
+  log::debug!(
+    "{} {} modules, {}",
+    colors::green("Embedded"),
+    module_count,
+    human_size(modules_size as f64),
+  );
+  if let Some(stats) = &stats.node_modules {
+    log_vfs_stats("node_modules", stats, compile_flags.compress.is_some());
+  }
+  if let Some(stats) = &stats.assets {
+    log_vfs_stats("assets", stats, compile_flags.compress.is_some());
+  }
+
   Ok(())
 }
 
+/// Outputs the targets that can be compiled for without network access.
+#[allow(clippy::print_stdout)]
+fn list_targets(
+  deno_dir: &DenoDir,
+  compile_flags: &CompileFlags,
+) -> Result<(), AnyError> {
+  let width = COMPILE_TARGETS.iter().map(|t| t.len()).max().unwrap_or(0);
+  for target in COMPILE_TARGETS {
+    match resolve_offline_base_binary(deno_dir, compile_flags, target)? {
+      Some(path) => println!(
+        "{:<width$}  {} {}",
+        target,
+        colors::green("available"),
+        colors::gray(path.display().to_string()),
+      ),
+      None if compile_flags.base_binaries.is_some() => {
+        println!("{:<width$}  {}", target, colors::red("not available"))
+      }
+      None => println!(
+        "{:<width$}  {}",
+        target,
+        colors::gray("not available (will be downloaded)"),
+      ),
+    }
+  }
+  Ok(())
+}
+
+/// Returns the number of modules in the graph and the size of their source.
+fn graph_modules_size(graph: &ModuleGraph) -> (usize, u64) {
+  let mut count = 0;
+  let mut size = 0;
+  for module in graph.modules() {
+    let source_len = match module {
+      Module::Js(module) => module.source.len(),
+      Module::Json(module) => module.source.len(),
+      _ => continue,
+    };
+    count += 1;
+    size += source_len as u64;
+  }
+  (count, size)
+}
+
+fn log_vfs_stats(name: &str, stats: &VfsStats, compress: bool) {
+  log::info!(
+    "{} {} files ({} duplicates) from {}, {} stored as {}{}",
+    colors::green("Embedded"),
+    stats.files,
+    stats.duplicate_files,
+    name,
+    human_size(stats.total_size as f64),
+    human_size(stats.stored_size as f64),
+    if compress { " compressed" } else { "" },
+  );
+}
+
+/// Collects the entrypoints of the web workers spawned by the modules in the
+/// graph that are not in the graph yet.
+fn collect_worker_specifiers(
+  graph: &ModuleGraph,
+  parsed_source_cache: &ParsedSourceCache,
+  scanned_modules: &mut HashSet<ModuleSpecifier>,
+) -> Vec<ModuleSpecifier> {
+  let mut specifiers = Vec::new();
+  for module in graph.modules() {
+    let Module::Js(module) = module else {
+      continue;
+    };
+    // modules of a previous pass were already scanned
+    if !scanned_modules.insert(module.specifier.clone()) {
+      continue;
+    }
+    let Ok(parsed_source) =
+      parsed_source_cache.get_parsed_source_from_js_module(module)
+    else {
+      continue;
+    };
+    for specifier in analyze_worker_specifiers(&parsed_source) {
+      if graph.get(&specifier).is_none() && !specifiers.contains(&specifier) {
+        specifiers.push(specifier);
+      }
+    }
+  }
+  specifiers
+}
+
+/// Finds the web workers a module spawns in the form of
+/// `new Worker(new URL("./worker.ts", import.meta.url))` or
+/// `new Worker(import.meta.resolve("./worker.ts"))`.
+fn analyze_worker_specifiers(
+  parsed_source: &ParsedSource,
+) -> Vec<ModuleSpecifier> {
+  let mut visitor = WorkerSpecifierFinder {
+    specifiers: Vec::new(),
+  };
+  let program = parsed_source.program();
+  let program: &Program = &program;
+  program.visit_with(&mut visitor);
+  visitor
+    .specifiers
+    .into_iter()
+    .filter_map(|specifier| parsed_source.specifier().join(&specifier).ok())
+    .collect()
+}
+
+struct WorkerSpecifierFinder {
+  specifiers: Vec<String>,
+}
+
+impl Visit for WorkerSpecifierFinder {
+  noop_visit_type!();
+
+  fn visit_new_expr(&mut self, new_expr: &NewExpr) {
+    new_expr.visit_children_with(self);
+    if !is_ident(&new_expr.callee, "Worker") {
+      return;
+    }
+    let Some(arg) = new_expr.args.as_ref().and_then(|args| args.first()) else {
+      return;
+    };
+    let specifier = match &*arg.expr {
+      // new URL("./worker.ts", import.meta.url)
+      Expr::New(url_expr) if is_ident(&url_expr.callee, "URL") => {
+        match url_expr.args.as_deref() {
+          Some([specifier, base]) if is_import_meta_prop(&base.expr, "url") => {
+            str_lit(&specifier.expr)
+          }
+          _ => None,
+        }
+      }
+      // import.meta.resolve("./worker.ts")
+      Expr::Call(CallExpr {
+        callee: Callee::Expr(callee),
+        args,
+        ..
+      }) if is_import_meta_prop(callee, "resolve") => {
+        args.first().and_then(|arg| str_lit(&arg.expr))
+      }
+      _ => None,
+    };
+    if let Some(specifier) = specifier {
+      self.specifiers.push(specifier);
+    }
+  }
+}
+
+fn is_ident(expr: &Expr, name: &str) -> bool {
+  matches!(expr, Expr::Ident(ident) if &*ident.sym == name)
+}
+
+fn is_import_meta_prop(expr: &Expr, prop_name: &str) -> bool {
+  match expr {
+    Expr::Member(member) => {
+      matches!(
+        &*member.obj,
+        Expr::MetaProp(MetaPropExpr {
+          kind: MetaPropKind::ImportMeta,
+          ..
+        })
+      ) && matches!(&member.prop, MemberProp::Ident(ident) if &*ident.sym == prop_name)
+    }
+    _ => false,
+  }
+}
+
+fn str_lit(expr: &Expr) -> Option<String> {
+  match expr {
+    Expr::Lit(Lit::Str(str)) => Some(str.value.to_string()),
+    _ => None,
+  }
+}
+
 /// This function writes out a final binary to specified path. If output path
 /// is not already standalone binary it will return error instead.
 fn validate_output_path(output_path: &Path) -> Result<(), AnyError> {
@@ -252,6 +493,10 @@ mod test {
         target: Some("x86_64-unknown-linux-gnu".to_string()),
         no_terminal: false,
         include: vec![],
+        include_assets: vec![],
+        compress: None,
+        base_binaries: None,
+        list_targets: false,
       },
       &std::env::current_dir().unwrap(),
     )
@@ -275,7 +520,11 @@ mod test {
         args: Vec::new(),
         target: Some("x86_64-pc-windows-msvc".to_string()),
         include: vec![],
+        include_assets: vec![],
         no_terminal: false,
+        compress: None,
+        base_binaries: None,
+        list_targets: false,
       },
       &std::env::current_dir().unwrap(),
     )
@@ -310,4 +559,32 @@ mod test {
     run_test("C:\\my-exe.0.1.2", Some("windows"), "C:\\my-exe.0.1.2.exe");
     run_test("my-exe-0.1.2", Some("linux"), "my-exe-0.1.2");
   }
+
+  #[test]
+  fn analyze_worker_specifiers_in_module() {
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: ModuleSpecifier::parse("file:///project/main.ts").unwrap(),
+      capture_tokens: false,
+      maybe_syntax: None,
+      media_type: deno_ast::MediaType::TypeScript,
+      scope_analysis: false,
+      text: r#"
+new Worker(new URL("./worker.ts", import.meta.url), { type: "module" });
+new Worker(import.meta.resolve("./workers/other.js"), { type: "module" });
+const url = "./dynamic.ts";
+new Worker(new URL(url, import.meta.url), { type: "module" });
+new Worker(new URL("./relative_to_location.ts"));
+new SharedWorker(new URL("./shared.ts", import.meta.url));
+"#
+      .into(),
+    })
+    .unwrap();
+    assert_eq!(
+      analyze_worker_specifiers(&parsed_source),
+      vec![
+        ModuleSpecifier::parse("file:///project/worker.ts").unwrap(),
+        ModuleSpecifier::parse("file:///project/workers/other.js").unwrap(),
+      ]
+    );
+  }
 }
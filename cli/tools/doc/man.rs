@@ -0,0 +1,197 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Generates a roff man page from the module documentation of a CLI's
+//! main module. The first line of the module doc is used as the summary,
+//! headings become sections, and `@example` and `@see` tags get their own
+//! sections.
+
+use std::fmt::Write;
+
+use deno_doc::js_doc::JsDocTag;
+use deno_doc::DocNode;
+use deno_doc::DocNodeKind;
+
+/// Generates a man page in section 1 (user commands).
+pub fn generate(name: &str, nodes: &[DocNode]) -> String {
+  let js_doc = nodes
+    .iter()
+    .find(|node| node.kind == DocNodeKind::ModuleDoc)
+    .map(|node| &node.js_doc);
+  let doc = js_doc
+    .and_then(|js_doc| js_doc.doc.as_deref())
+    .unwrap_or_default()
+    .trim();
+  let (summary, description) = match doc.split_once('\n') {
+    Some((summary, description)) => (summary.trim(), description.trim()),
+    None => (doc, ""),
+  };
+
+  let mut text = String::new();
+  writeln!(
+    text,
+    ".TH \"{}\" \"1\" \"\" \"{}\" \"User Commands\"",
+    escape(&name.to_uppercase()),
+    escape(name)
+  )
+  .unwrap();
+  writeln!(text, ".SH NAME").unwrap();
+  if summary.is_empty() {
+    writeln!(text, "{}", escape(name)).unwrap();
+  } else {
+    writeln!(text, "{} \\- {}", escape(name), inline(summary)).unwrap();
+  }
+  if !description.is_empty() {
+    if !description.starts_with('#') {
+      writeln!(text, ".SH DESCRIPTION").unwrap();
+    }
+    write_markdown(&mut text, description);
+  }
+
+  let mut examples = Vec::new();
+  let mut see = Vec::new();
+  for tag in js_doc.iter().flat_map(|js_doc| js_doc.tags.iter()) {
+    match tag {
+      JsDocTag::Example { doc } => examples.push(&doc[..]),
+      JsDocTag::See { doc } => see.push(&doc[..]),
+      _ => {}
+    }
+  }
+  if !examples.is_empty() {
+    writeln!(text, ".SH EXAMPLES").unwrap();
+    for example in examples {
+      write_markdown(&mut text, example.trim());
+    }
+  }
+  if !see.is_empty() {
+    writeln!(text, ".SH SEE ALSO").unwrap();
+    writeln!(
+      text,
+      "{}",
+      see
+        .iter()
+        .map(|doc| inline(doc.trim()))
+        .collect::<Vec<_>>()
+        .join(", ")
+    )
+    .unwrap();
+  }
+  text
+}
+
+/// Converts the subset of Markdown used in doc comments to roff.
+fn write_markdown(text: &mut String, markdown: &str) {
+  let mut in_code_block = false;
+  let mut needs_paragraph = false;
+  for line in markdown.lines() {
+    let trimmed = line.trim();
+    if trimmed.starts_with("```") {
+      if in_code_block {
+        writeln!(text, ".fi\n.RE").unwrap();
+      } else {
+        writeln!(text, ".PP\n.RS 4\n.nf").unwrap();
+      }
+      in_code_block = !in_code_block;
+      needs_paragraph = false;
+      continue;
+    }
+    if in_code_block {
+      writeln!(text, "{}", escape(line)).unwrap();
+      continue;
+    }
+    if trimmed.is_empty() {
+      needs_paragraph = true;
+      continue;
+    }
+    if let Some(heading) = trimmed.strip_prefix("# ") {
+      writeln!(text, ".SH {}", escape(&heading.trim().to_uppercase())).unwrap();
+    } else if let Some(heading) = trimmed
+      .strip_prefix("## ")
+      .or_else(|| trimmed.strip_prefix("### "))
+    {
+      writeln!(text, ".SS {}", escape(heading.trim())).unwrap();
+    } else if let Some(item) = trimmed
+      .strip_prefix("- ")
+      .or_else(|| trimmed.strip_prefix("* "))
+    {
+      writeln!(text, ".IP \\(bu 2\n{}", inline(item)).unwrap();
+    } else {
+      if needs_paragraph {
+        writeln!(text, ".PP").unwrap();
+      }
+      writeln!(text, "{}", inline(trimmed)).unwrap();
+    }
+    needs_paragraph = false;
+  }
+  if in_code_block {
+    writeln!(text, ".fi\n.RE").unwrap();
+  }
+}
+
+/// Escapes the text and converts inline code and bold text to bold.
+fn inline(text: &str) -> String {
+  let mut output = String::new();
+  let mut bold = false;
+  let mut rest = text;
+  while !rest.is_empty() {
+    let (marker_len, index) = match (rest.find('`'), rest.find("**")) {
+      (Some(code), Some(strong)) if strong < code => (2, strong),
+      (Some(code), _) => (1, code),
+      (None, Some(strong)) => (2, strong),
+      (None, None) => {
+        output.push_str(&escape(rest));
+        break;
+      }
+    };
+    output.push_str(&escape(&rest[..index]));
+    output.push_str(if bold { "\\fR" } else { "\\fB" });
+    bold = !bold;
+    rest = &rest[index + marker_len..];
+  }
+  if bold {
+    output.push_str("\\fR");
+  }
+  if output.starts_with('.') || output.starts_with('\'') {
+    output.insert_str(0, "\\&");
+  }
+  output
+}
+
+fn escape(text: &str) -> String {
+  let text = text.replace('\\', "\\e").replace('-', "\\-");
+  if text.starts_with('.') || text.starts_with('\'') {
+    format!("\\&{text}")
+  } else {
+    text
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn converts_markdown() {
+    let mut text = String::new();
+    write_markdown(
+      &mut text,
+      "Greets the `--name` **user**.\n\nMore text.\n\n## Options\n\n- `-v` verbose\n\n```sh\n.hidden -x\n```",
+    );
+    assert_eq!(
+      text,
+      concat!(
+        "Greets the \\fB\\-\\-name\\fR \\fBuser\\fR.\n",
+        ".PP\n",
+        "More text.\n",
+        ".SS Options\n",
+        ".IP \\(bu 2\n",
+        "\\fB\\-v\\fR verbose\n",
+        ".PP\n",
+        ".RS 4\n",
+        ".nf\n",
+        "\\&.hidden \\-x\n",
+        ".fi\n",
+        ".RE\n",
+      )
+    );
+  }
+}
@@ -0,0 +1,773 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Generates Markdown documentation with an index file per module and a
+//! file per symbol, for example `mod.ts/index.md` and `mod.ts/MyClass.md`.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use deno_ast::swc::ast::VarDeclKind;
+use deno_core::serde_json;
+use deno_doc::function::FunctionDef;
+use deno_doc::html::HrefResolver;
+use deno_doc::js_doc::JsDoc;
+use deno_doc::js_doc::JsDocTag;
+use deno_doc::params::ParamDef;
+use deno_doc::params::ParamPatternDef;
+use deno_doc::ts_type::TsTypeDef;
+use deno_doc::ts_type_param::TsTypeParamDef;
+use deno_doc::DocNode;
+use deno_doc::DocNodeKind;
+use indexmap::IndexMap;
+
+use super::DocResolver;
+
+pub struct MarkdownModule<'a> {
+  /// The path of the module relative to the current directory.
+  pub path: String,
+  pub nodes: &'a [DocNode],
+}
+
+/// Generates the Markdown files, keyed by their path relative to the
+/// output directory.
+pub fn generate(
+  package_name: Option<&str>,
+  modules: &[MarkdownModule],
+  resolver: &DocResolver,
+) -> BTreeMap<String, String> {
+  let mut symbols = Vec::new();
+  for module in modules {
+    collect_symbols(&module.path, None, module.nodes, &mut symbols);
+  }
+  let generator = Generator {
+    resolver,
+    symbol_files: symbols
+      .iter()
+      .map(|symbol| (symbol.name.clone(), symbol.file.clone()))
+      .collect(),
+  };
+
+  let mut files = BTreeMap::new();
+  files.insert("index.md".to_string(), render_index(package_name, modules));
+  for module in modules {
+    let file = module_file(&module.path);
+    let text = generator.render_module(&file, module, &symbols);
+    files.insert(file, text);
+  }
+  for symbol in &symbols {
+    files.insert(symbol.file.clone(), generator.render_symbol(symbol));
+  }
+  files
+}
+
+struct Symbol<'a> {
+  /// The dot separated name, including the namespaces.
+  name: String,
+  module_path: String,
+  file: String,
+  /// Overloads and declaration merging lead to multiple nodes.
+  nodes: Vec<&'a DocNode>,
+}
+
+fn module_file(module_path: &str) -> String {
+  format!("{module_path}/index.md")
+}
+
+fn collect_symbols<'a>(
+  module_path: &str,
+  namespace: Option<&str>,
+  nodes: impl IntoIterator<Item = &'a DocNode>,
+  symbols: &mut Vec<Symbol<'a>>,
+) {
+  let mut nodes_by_name = IndexMap::<&str, Vec<&DocNode>>::new();
+  for node in nodes {
+    if matches!(node.kind, DocNodeKind::Import | DocNodeKind::ModuleDoc) {
+      continue;
+    }
+    nodes_by_name.entry(&node.name).or_default().push(node);
+  }
+  for (name, nodes) in nodes_by_name {
+    let name = match namespace {
+      Some(namespace) => format!("{namespace}.{name}"),
+      None => name.to_string(),
+    };
+    for node in &nodes {
+      if let Some(namespace_def) = &node.namespace_def {
+        collect_symbols(
+          module_path,
+          Some(&name),
+          namespace_def.elements.iter().map(|element| &**element),
+          symbols,
+        );
+      }
+    }
+    symbols.push(Symbol {
+      file: format!("{module_path}/{name}.md"),
+      name,
+      module_path: module_path.to_string(),
+      nodes,
+    });
+  }
+}
+
+fn render_index(
+  package_name: Option<&str>,
+  modules: &[MarkdownModule],
+) -> String {
+  let mut text = String::new();
+  writeln!(text, "# {}\n", package_name.unwrap_or("Documentation")).unwrap();
+  writeln!(text, "## Modules\n").unwrap();
+  for module in modules {
+    let summary = module_doc(module.nodes)
+      .and_then(|doc| doc.doc.as_deref())
+      .and_then(|doc| doc.lines().find(|line| !line.trim().is_empty()))
+      .map(|line| format!(" - {}", line.trim()))
+      .unwrap_or_default();
+    writeln!(
+      text,
+      "- [`{}`]({}){}",
+      module.path,
+      module_file(&module.path),
+      summary
+    )
+    .unwrap();
+  }
+  text
+}
+
+fn module_doc(nodes: &[DocNode]) -> Option<&JsDoc> {
+  nodes
+    .iter()
+    .find(|node| node.kind == DocNodeKind::ModuleDoc)
+    .map(|node| &node.js_doc)
+}
+
+struct Generator<'a> {
+  resolver: &'a DocResolver,
+  /// Symbol name to the file documenting it.
+  symbol_files: HashMap<String, String>,
+}
+
+impl<'a> Generator<'a> {
+  fn render_module(
+    &self,
+    file: &str,
+    module: &MarkdownModule,
+    symbols: &[Symbol],
+  ) -> String {
+    let mut text = String::new();
+    writeln!(text, "# `{}`\n", module.path).unwrap();
+    if let Some(js_doc) = module_doc(module.nodes) {
+      self.write_js_doc(&mut text, file, js_doc);
+    }
+
+    let mut symbols_by_kind = BTreeMap::<&str, Vec<&Symbol>>::new();
+    for symbol in symbols {
+      if symbol.module_path == module.path && !symbol.name.contains('.') {
+        symbols_by_kind
+          .entry(kind_title(symbol.nodes[0].kind))
+          .or_default()
+          .push(symbol);
+      }
+    }
+    for (kind, symbols) in symbols_by_kind {
+      writeln!(text, "## {kind}\n").unwrap();
+      for symbol in symbols {
+        writeln!(
+          text,
+          "- [`{}`]({}){}",
+          symbol.name,
+          relative_href(file, &symbol.file),
+          summary(&symbol.nodes[0].js_doc)
+            .map(|summary| format!(" - {}", self.resolve_links(file, summary)))
+            .unwrap_or_default()
+        )
+        .unwrap();
+      }
+      text.push('\n');
+    }
+    trim_trailing_newlines(text)
+  }
+
+  fn render_symbol(&self, symbol: &Symbol) -> String {
+    let file = &symbol.file;
+    let mut text = String::new();
+    writeln!(text, "# {}\n", symbol.name).unwrap();
+    writeln!(
+      text,
+      "{} in [`{}`]({})\n",
+      kind_name(symbol.nodes[0].kind),
+      symbol.module_path,
+      relative_href(file, &module_file(&symbol.module_path))
+    )
+    .unwrap();
+
+    for node in &symbol.nodes {
+      writeln!(text, "```ts\n{}\n```\n", signature(node)).unwrap();
+      self.write_js_doc(&mut text, file, &node.js_doc);
+
+      let members = members(node);
+      for section in ["Properties", "Methods", "Members"] {
+        let members = members
+          .iter()
+          .filter(|member| member.section == section)
+          .collect::<Vec<_>>();
+        self.write_members(&mut text, file, section, &members);
+      }
+      if node.namespace_def.is_some() {
+        let prefix = format!("{}.", symbol.name);
+        let mut elements = self
+          .symbol_files
+          .iter()
+          .filter(|(name, target)| {
+            name
+              .strip_prefix(&prefix)
+              .is_some_and(|rest| !rest.contains('.'))
+              && target.starts_with(&format!("{}/", symbol.module_path))
+          })
+          .collect::<Vec<_>>();
+        elements.sort();
+        if !elements.is_empty() {
+          writeln!(text, "## Members\n").unwrap();
+          for (name, target) in elements {
+            writeln!(text, "- [`{}`]({})", name, relative_href(file, target))
+              .unwrap();
+          }
+          text.push('\n');
+        }
+      }
+    }
+    trim_trailing_newlines(text)
+  }
+
+  fn write_members(
+    &self,
+    text: &mut String,
+    file: &str,
+    title: &str,
+    members: &[&Member],
+  ) {
+    if members.is_empty() {
+      return;
+    }
+    writeln!(text, "## {title}\n").unwrap();
+    for member in members {
+      writeln!(
+        text,
+        "### `{}`\n\n```ts\n{}\n```\n",
+        member.name, member.signature
+      )
+      .unwrap();
+      self.write_js_doc(text, file, member.js_doc);
+    }
+  }
+
+  fn write_js_doc(&self, text: &mut String, file: &str, js_doc: &JsDoc) {
+    let mut params = Vec::new();
+    let mut returns = None;
+    let mut examples = Vec::new();
+    let mut see = Vec::new();
+    for tag in js_doc.tags.iter() {
+      match tag {
+        JsDocTag::Deprecated { doc } => {
+          let doc = doc
+            .as_deref()
+            .map(|doc| format!(" {}", self.resolve_links(file, doc)))
+            .unwrap_or_default();
+          writeln!(text, "> **Deprecated**{doc}\n").unwrap();
+        }
+        JsDocTag::Param { name, doc, .. } => params.push((name, doc)),
+        JsDocTag::Return { doc, .. } => returns = doc.as_deref(),
+        JsDocTag::Example { doc } => examples.push(&doc[..]),
+        JsDocTag::See { doc } => see.push(&doc[..]),
+        _ => {}
+      }
+    }
+    if let Some(doc) = js_doc.doc.as_deref() {
+      writeln!(text, "{}\n", self.resolve_links(file, doc.trim())).unwrap();
+    }
+    if !params.is_empty() {
+      writeln!(text, "#### Parameters\n").unwrap();
+      for (name, doc) in params {
+        let doc = doc
+          .as_deref()
+          .map(|doc| format!(" - {}", self.resolve_links(file, doc)))
+          .unwrap_or_default();
+        writeln!(text, "- `{name}`{doc}").unwrap();
+      }
+      text.push('\n');
+    }
+    if let Some(doc) = returns {
+      writeln!(text, "#### Returns\n\n{}\n", self.resolve_links(file, doc))
+        .unwrap();
+    }
+    for example in examples {
+      writeln!(text, "#### Example\n\n{}\n", example.trim()).unwrap();
+    }
+    if !see.is_empty() {
+      writeln!(text, "#### See\n").unwrap();
+      for doc in see {
+        writeln!(text, "- {}", self.resolve_links(file, doc)).unwrap();
+      }
+      text.push('\n');
+    }
+  }
+
+  /// Replaces the `{@link Symbol}`, `{@linkcode Symbol}` and
+  /// `{@link Symbol | text}` tags with Markdown links.
+  fn resolve_links(&self, file: &str, doc: &str) -> String {
+    let mut text = String::with_capacity(doc.len());
+    let mut rest = doc;
+    while let Some(start) = rest.find("{@link") {
+      let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+        break;
+      };
+      text.push_str(&rest[..start]);
+      let tag = &rest[start + 1..end];
+      let (is_code, target) =
+        if let Some(target) = tag.strip_prefix("@linkcode") {
+          (true, target)
+        } else if let Some(target) = tag.strip_prefix("@linkplain") {
+          (false, target)
+        } else {
+          (false, &tag["@link".len()..])
+        };
+      let (target, label) = match target.split_once('|') {
+        Some((target, label)) => (target.trim(), label.trim()),
+        None => match target.trim().split_once(' ') {
+          Some((target, label)) => (target, label.trim()),
+          None => (target.trim(), target.trim()),
+        },
+      };
+      let label = if is_code {
+        format!("`{label}`")
+      } else {
+        label.to_string()
+      };
+      match self.resolve_href(file, target) {
+        Some(href) => write!(text, "[{label}]({href})").unwrap(),
+        None if is_code => text.push_str(&label),
+        None => write!(text, "`{label}`").unwrap(),
+      }
+      rest = &rest[end + 1..];
+    }
+    text.push_str(rest);
+    text
+  }
+
+  fn resolve_href(&self, file: &str, target: &str) -> Option<String> {
+    if target.starts_with("http://") || target.starts_with("https://") {
+      return Some(target.to_string());
+    }
+    if let Some(target_file) = self.symbol_files.get(target) {
+      return Some(relative_href(file, target_file));
+    }
+    let symbol = target
+      .split('.')
+      .map(ToString::to_string)
+      .collect::<Vec<_>>();
+    self.resolver.resolve_global_symbol(&symbol)
+  }
+}
+
+/// The path of `to` relative to the directory of `from`, where both are
+/// relative to the output directory.
+fn relative_href(from: &str, to: &str) -> String {
+  let from_dir = from.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+  let from_parts = from_dir
+    .split('/')
+    .filter(|part| !part.is_empty())
+    .collect::<Vec<_>>();
+  let to_parts = to.split('/').collect::<Vec<_>>();
+  let common = from_parts
+    .iter()
+    .zip(&to_parts)
+    .take_while(|(a, b)| a == b)
+    .count();
+  let mut parts = vec![".."; from_parts.len() - common];
+  parts.extend(&to_parts[common..]);
+  parts.join("/")
+}
+
+fn summary(js_doc: &JsDoc) -> Option<&str> {
+  js_doc
+    .doc
+    .as_deref()?
+    .split("\n\n")
+    .next()
+    .map(|paragraph| paragraph.trim())
+    .filter(|paragraph| !paragraph.is_empty())
+}
+
+fn trim_trailing_newlines(mut text: String) -> String {
+  text.truncate(text.trim_end().len());
+  text.push('\n');
+  text
+}
+
+fn kind_name(kind: DocNodeKind) -> &'static str {
+  match kind {
+    DocNodeKind::Function => "Function",
+    DocNodeKind::Variable => "Variable",
+    DocNodeKind::Class => "Class",
+    DocNodeKind::Enum => "Enum",
+    DocNodeKind::Interface => "Interface",
+    DocNodeKind::TypeAlias => "Type alias",
+    DocNodeKind::Namespace => "Namespace",
+    DocNodeKind::ModuleDoc | DocNodeKind::Import => "Module",
+  }
+}
+
+fn kind_title(kind: DocNodeKind) -> &'static str {
+  match kind {
+    DocNodeKind::Function => "Functions",
+    DocNodeKind::Variable => "Variables",
+    DocNodeKind::Class => "Classes",
+    DocNodeKind::Enum => "Enums",
+    DocNodeKind::Interface => "Interfaces",
+    DocNodeKind::TypeAlias => "Type aliases",
+    DocNodeKind::Namespace => "Namespaces",
+    DocNodeKind::ModuleDoc | DocNodeKind::Import => "Modules",
+  }
+}
+
+fn type_params(type_params: &[TsTypeParamDef]) -> String {
+  if type_params.is_empty() {
+    return String::new();
+  }
+  format!(
+    "<{}>",
+    type_params
+      .iter()
+      .map(ToString::to_string)
+      .collect::<Vec<_>>()
+      .join(", ")
+  )
+}
+
+fn params(params: &[ParamDef]) -> String {
+  params
+    .iter()
+    .map(ToString::to_string)
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// Whether callers can leave out the parameter, because it's optional, has a
+/// default value or is a rest parameter.
+fn is_optional_param(param: &ParamDef) -> bool {
+  match &param.pattern {
+    ParamPatternDef::Assign { .. } | ParamPatternDef::Rest { .. } => true,
+    ParamPatternDef::Array { optional, .. }
+    | ParamPatternDef::Identifier { optional, .. }
+    | ParamPatternDef::Object { optional, .. } => *optional,
+  }
+}
+
+/// The declarations with the trailing optional parameters left out one by
+/// one, so adding optional parameters to a declaration can be detected.
+pub fn shorter_declarations<T>(
+  params: &[ParamDef],
+  declaration: impl Fn(&[ParamDef]) -> T,
+) -> Vec<T> {
+  let mut declarations = Vec::new();
+  let mut len = params.len();
+  while len > 0 && is_optional_param(&params[len - 1]) {
+    len -= 1;
+    declarations.push(declaration(&params[..len]));
+  }
+  declarations
+}
+
+fn return_type(return_type: Option<&TsTypeDef>) -> String {
+  return_type
+    .map(|return_type| format!(": {return_type}"))
+    .unwrap_or_default()
+}
+
+pub struct Member<'a> {
+  /// The title of the section listing the member.
+  pub section: &'static str,
+  pub name: &'a str,
+  pub signature: String,
+  /// The serialized definition, for comparing members structurally.
+  pub def: serde_json::Value,
+  /// The serialized definitions of a method without its trailing optional
+  /// parameters.
+  pub shorter_defs: Vec<serde_json::Value>,
+  pub optional: bool,
+  pub js_doc: &'a JsDoc,
+}
+
+/// The properties and methods of classes and interfaces, and the members of
+/// enums.
+pub fn members(node: &DocNode) -> Vec<Member> {
+  let mut members = Vec::new();
+  if let Some(class_def) = &node.class_def {
+    for property in &class_def.properties {
+      let mut signature = String::new();
+      if property.is_static {
+        signature.push_str("static ");
+      }
+      if property.readonly {
+        signature.push_str("readonly ");
+      }
+      signature.push_str(&property.name);
+      if property.optional {
+        signature.push('?');
+      }
+      if let Some(ts_type) = &property.ts_type {
+        write!(signature, ": {ts_type}").unwrap();
+      }
+      members.push(Member {
+        section: "Properties",
+        name: &property.name,
+        signature,
+        def: serde_json::to_value(property).unwrap_or_default(),
+        shorter_defs: Vec::new(),
+        optional: property.optional,
+        js_doc: &property.js_doc,
+      });
+    }
+    for method in &class_def.methods {
+      let function_def = &method.function_def;
+      let declaration = |method_params: &[ParamDef]| {
+        format!(
+          "{}{}{}({}){}",
+          if method.is_static { "static " } else { "" },
+          method.name,
+          type_params(&function_def.type_params),
+          params(method_params),
+          return_type(function_def.return_type.as_ref()),
+        )
+      };
+      members.push(Member {
+        section: "Methods",
+        name: &method.name,
+        signature: declaration(&function_def.params),
+        def: serde_json::to_value(method).unwrap_or_default(),
+        shorter_defs: shorter_declarations(&function_def.params, |params| {
+          let mut method = method.clone();
+          method.function_def.params = params.to_vec();
+          serde_json::to_value(method).unwrap_or_default()
+        }),
+        optional: method.optional,
+        js_doc: &method.js_doc,
+      });
+    }
+  }
+  if let Some(interface_def) = &node.interface_def {
+    for property in &interface_def.properties {
+      let mut signature = String::new();
+      if property.readonly {
+        signature.push_str("readonly ");
+      }
+      signature.push_str(&property.name);
+      if property.optional {
+        signature.push('?');
+      }
+      if let Some(ts_type) = &property.ts_type {
+        write!(signature, ": {ts_type}").unwrap();
+      }
+      members.push(Member {
+        section: "Properties",
+        name: &property.name,
+        signature,
+        def: serde_json::to_value(property).unwrap_or_default(),
+        shorter_defs: Vec::new(),
+        optional: property.optional,
+        js_doc: &property.js_doc,
+      });
+    }
+    for method in &interface_def.methods {
+      let declaration = |method_params: &[ParamDef]| {
+        format!(
+          "{}{}{}({}){}",
+          method.name,
+          if method.optional { "?" } else { "" },
+          type_params(&method.type_params),
+          params(method_params),
+          return_type(method.return_type.as_ref()),
+        )
+      };
+      members.push(Member {
+        section: "Methods",
+        name: &method.name,
+        signature: declaration(&method.params),
+        def: serde_json::to_value(method).unwrap_or_default(),
+        shorter_defs: shorter_declarations(&method.params, |params| {
+          let mut method = method.clone();
+          method.params = params.to_vec();
+          serde_json::to_value(method).unwrap_or_default()
+        }),
+        optional: method.optional,
+        js_doc: &method.js_doc,
+      });
+    }
+  }
+  if let Some(enum_def) = &node.enum_def {
+    for member in &enum_def.members {
+      let signature = match &member.init {
+        Some(init) => format!("{} = {}", member.name, init),
+        None => member.name.to_string(),
+      };
+      members.push(Member {
+        section: "Members",
+        name: &member.name,
+        signature,
+        def: serde_json::to_value(member).unwrap_or_default(),
+        shorter_defs: Vec::new(),
+        optional: false,
+        js_doc: &member.js_doc,
+      });
+    }
+  }
+  members
+}
+
+fn function_signature(
+  node: &DocNode,
+  def: &FunctionDef,
+  function_params: &[ParamDef],
+) -> String {
+  format!(
+    "{}function{} {}{}({}){}",
+    if def.is_async { "async " } else { "" },
+    if def.is_generator { "*" } else { "" },
+    node.name,
+    type_params(&def.type_params),
+    params(function_params),
+    return_type(def.return_type.as_ref()),
+  )
+}
+
+/// The TypeScript declaration of the node, without its body.
+pub fn signature(node: &DocNode) -> String {
+  let name = &node.name;
+  if let Some(def) = &node.function_def {
+    function_signature(node, def, &def.params)
+  } else if let Some(def) = &node.variable_def {
+    let kind = match def.kind {
+      VarDeclKind::Const => "const",
+      VarDeclKind::Let => "let",
+      VarDeclKind::Var => "var",
+    };
+    format!("{kind} {name}{}", return_type(def.ts_type.as_ref()))
+  } else if let Some(def) = &node.class_def {
+    let mut signature = format!(
+      "{}class {}{}",
+      if def.is_abstract { "abstract " } else { "" },
+      name,
+      type_params(&def.type_params)
+    );
+    if let Some(extends) = &def.extends {
+      write!(signature, " extends {extends}").unwrap();
+      if !def.super_type_params.is_empty() {
+        write!(
+          signature,
+          "<{}>",
+          def
+            .super_type_params
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+        )
+        .unwrap();
+      }
+    }
+    if !def.implements.is_empty() {
+      write!(
+        signature,
+        " implements {}",
+        def
+          .implements
+          .iter()
+          .map(ToString::to_string)
+          .collect::<Vec<_>>()
+          .join(", ")
+      )
+      .unwrap();
+    }
+    signature
+  } else if let Some(def) = &node.interface_def {
+    let mut signature =
+      format!("interface {}{}", name, type_params(&def.type_params));
+    if !def.extends.is_empty() {
+      write!(
+        signature,
+        " extends {}",
+        def
+          .extends
+          .iter()
+          .map(ToString::to_string)
+          .collect::<Vec<_>>()
+          .join(", ")
+      )
+      .unwrap();
+    }
+    signature
+  } else if let Some(def) = &node.type_alias_def {
+    format!(
+      "type {}{} = {}",
+      name,
+      type_params(&def.type_params),
+      def.ts_type
+    )
+  } else if node.enum_def.is_some() {
+    format!("enum {name}")
+  } else if node.namespace_def.is_some() {
+    format!("namespace {name}")
+  } else {
+    name.to_string()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn relative_hrefs() {
+    assert_eq!(
+      relative_href("index.md", "mod.ts/index.md"),
+      "mod.ts/index.md"
+    );
+    assert_eq!(relative_href("mod.ts/index.md", "mod.ts/Foo.md"), "Foo.md");
+    assert_eq!(
+      relative_href("src/a.ts/Foo.md", "src/b.ts/Bar.md"),
+      "../b.ts/Bar.md"
+    );
+    assert_eq!(
+      relative_href("src/a.ts/Foo.md", "mod.ts/Bar.md"),
+      "../../mod.ts/Bar.md"
+    );
+  }
+
+  #[test]
+  fn resolves_links() {
+    let resolver = DocResolver {
+      deno_ns: [vec!["Deno".to_string(), "Listener".to_string()]]
+        .into_iter()
+        .collect(),
+    };
+    let generator = Generator {
+      resolver: &resolver,
+      symbol_files: HashMap::from([(
+        "Foo".to_string(),
+        "mod.ts/Foo.md".to_string(),
+      )]),
+    };
+    assert_eq!(
+      generator.resolve_links(
+        "mod.ts/Bar.md",
+        "See {@link Foo}, {@linkcode Foo | the foo}, {@link Unknown} and {@link Deno.Listener}."
+      ),
+      format!(
+        "See [Foo](Foo.md), [`the foo`](Foo.md), `Unknown` and [Deno.Listener](https://deno.land/api@v{}?s=Deno.Listener).",
+        env!("CARGO_PKG_VERSION")
+      )
+    );
+  }
+}
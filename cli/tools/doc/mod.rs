@@ -2,6 +2,8 @@
 
 use crate::args::DocFlags;
 use crate::args::DocHtmlFlag;
+use crate::args::DocManFlag;
+use crate::args::DocMarkdownFlag;
 use crate::args::DocSourceFileFlag;
 use crate::args::Flags;
 use crate::colors;
@@ -28,8 +30,19 @@ use doc::html::ShortPath;
 use doc::DocDiagnostic;
 use indexmap::IndexMap;
 use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
 use std::rc::Rc;
 
+mod man;
+mod markdown;
+
+pub use markdown::members;
+pub use markdown::shorter_declarations;
+pub use markdown::signature;
+
 async fn generate_doc_nodes_for_builtin_types(
   doc_flags: DocFlags,
   parser: &dyn ModuleParser,
@@ -153,40 +166,20 @@ pub async fn doc(flags: Flags, doc_flags: DocFlags) -> Result<(), AnyError> {
   };
 
   if let Some(html_options) = &doc_flags.html {
-    let deno_ns = if doc_flags.source_files != DocSourceFileFlag::Builtin {
-      let deno_ns = generate_doc_nodes_for_builtin_types(
-        doc_flags.clone(),
-        &capturing_parser,
-        &analyzer,
-      )
-      .await?;
-      let (_, deno_ns) = deno_ns.into_iter().next().unwrap();
-
-      let short_path = Rc::new(ShortPath::new(
-        ModuleSpecifier::parse("file:///lib.deno.d.ts").unwrap(),
-        None,
-        None,
-        None,
-      ));
-
-      deno_doc::html::compute_namespaced_symbols(
-        &deno_ns
-          .into_iter()
-          .map(|node| deno_doc::html::DocNodeWithContext {
-            origin: short_path.clone(),
-            ns_qualifiers: Rc::new(vec![]),
-            kind_with_drilldown:
-              deno_doc::html::DocNodeKindWithDrilldown::Other(node.kind),
-            inner: std::sync::Arc::new(node),
-            drilldown_parent_kind: None,
-          })
-          .collect::<Vec<_>>(),
-      )
-    } else {
-      Default::default()
-    };
-
+    let deno_ns =
+      resolve_deno_ns(&doc_flags, &capturing_parser, &analyzer).await?;
     generate_docs_directory(doc_nodes_by_url, html_options, deno_ns)
+  } else if let Some(markdown_options) = &doc_flags.markdown {
+    let deno_ns =
+      resolve_deno_ns(&doc_flags, &capturing_parser, &analyzer).await?;
+    generate_markdown_directory(
+      &doc_nodes_by_url,
+      markdown_options,
+      deno_ns,
+      cli_options.initial_cwd(),
+    )
+  } else if let Some(man_options) = &doc_flags.man {
+    print_man_page_to_stdout(&doc_nodes_by_url, man_options)
   } else {
     let modules_len = doc_nodes_by_url.len();
     let doc_nodes =
@@ -208,8 +201,48 @@ pub async fn doc(flags: Flags, doc_flags: DocFlags) -> Result<(), AnyError> {
   }
 }
 
+/// The symbols of the `Deno` namespace, which are linked to the online
+/// documentation.
+async fn resolve_deno_ns(
+  doc_flags: &DocFlags,
+  parser: &dyn ModuleParser,
+  analyzer: &dyn ModuleAnalyzer,
+) -> Result<HashSet<Vec<String>>, AnyError> {
+  let deno_ns = if doc_flags.source_files != DocSourceFileFlag::Builtin {
+    let deno_ns =
+      generate_doc_nodes_for_builtin_types(doc_flags.clone(), parser, analyzer)
+        .await?;
+    let (_, deno_ns) = deno_ns.into_iter().next().unwrap();
+
+    let short_path = Rc::new(ShortPath::new(
+      ModuleSpecifier::parse("file:///lib.deno.d.ts").unwrap(),
+      None,
+      None,
+      None,
+    ));
+
+    deno_doc::html::compute_namespaced_symbols(
+      &deno_ns
+        .into_iter()
+        .map(|node| deno_doc::html::DocNodeWithContext {
+          origin: short_path.clone(),
+          ns_qualifiers: Rc::new(vec![]),
+          kind_with_drilldown: deno_doc::html::DocNodeKindWithDrilldown::Other(
+            node.kind,
+          ),
+          inner: std::sync::Arc::new(node),
+          drilldown_parent_kind: None,
+        })
+        .collect::<Vec<_>>(),
+    )
+  } else {
+    Default::default()
+  };
+  Ok(deno_ns)
+}
+
 struct DocResolver {
-  deno_ns: std::collections::HashSet<Vec<String>>,
+  deno_ns: HashSet<Vec<String>>,
 }
 
 impl deno_doc::html::HrefResolver for DocResolver {
@@ -260,7 +293,7 @@ impl deno_doc::html::HrefResolver for DocResolver {
 fn generate_docs_directory(
   doc_nodes_by_url: IndexMap<ModuleSpecifier, Vec<doc::DocNode>>,
   html_options: &DocHtmlFlag,
-  deno_ns: std::collections::HashSet<Vec<String>>,
+  deno_ns: HashSet<Vec<String>>,
 ) -> Result<(), AnyError> {
   let cwd = std::env::current_dir().context("Failed to get CWD")?;
   let output_dir_resolved = cwd.join(&html_options.output);
@@ -304,6 +337,96 @@ fn generate_docs_directory(
   Ok(())
 }
 
+fn generate_markdown_directory(
+  doc_nodes_by_url: &IndexMap<ModuleSpecifier, Vec<doc::DocNode>>,
+  markdown_options: &DocMarkdownFlag,
+  deno_ns: HashSet<Vec<String>>,
+  cwd: &Path,
+) -> Result<(), AnyError> {
+  let output_dir_resolved = cwd.join(&markdown_options.output);
+  let modules = doc_nodes_by_url
+    .iter()
+    .map(|(specifier, nodes)| markdown::MarkdownModule {
+      path: module_path(specifier, cwd),
+      nodes,
+    })
+    .collect::<Vec<_>>();
+  let files = markdown::generate(
+    markdown_options.name.as_deref(),
+    &modules,
+    &DocResolver { deno_ns },
+  );
+
+  // the files are written next to any existing ones, since the output
+  // directory might contain files that aren't part of the docs
+  let path = &output_dir_resolved;
+  let no_of_files = files.len();
+  for (name, content) in files {
+    let this_path = path.join(name);
+    let prefix = this_path.parent().with_context(|| {
+      format!("Failed to get parent path for {:?}", this_path)
+    })?;
+    std::fs::create_dir_all(prefix)
+      .with_context(|| format!("Failed to create directory {:?}", prefix))?;
+    std::fs::write(&this_path, content)
+      .with_context(|| format!("Failed to write file {:?}", this_path))?;
+  }
+
+  log::info!(
+    "{}",
+    colors::green(format!(
+      "Written {} files to {:?}",
+      no_of_files, markdown_options.output
+    ))
+  );
+  Ok(())
+}
+
+/// The path of the module relative to the current directory, or its host
+/// and path for remote modules.
+fn module_path(specifier: &ModuleSpecifier, cwd: &Path) -> String {
+  let path = match specifier.to_file_path() {
+    Ok(path) => path
+      .strip_prefix(cwd)
+      .map(ToOwned::to_owned)
+      .unwrap_or(path),
+    Err(_) => PathBuf::from(format!(
+      "{}{}",
+      specifier.host_str().unwrap_or_default(),
+      specifier.path()
+    )),
+  };
+  path
+    .components()
+    .filter_map(|component| match component {
+      Component::Normal(part) => Some(part.to_string_lossy()),
+      _ => None,
+    })
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+fn print_man_page_to_stdout(
+  doc_nodes_by_url: &IndexMap<ModuleSpecifier, Vec<doc::DocNode>>,
+  man_options: &DocManFlag,
+) -> Result<(), AnyError> {
+  // the first module is the entrypoint of the tool
+  let Some((specifier, nodes)) = doc_nodes_by_url.first() else {
+    bail!("No module to generate a man page for.");
+  };
+  let name = match &man_options.name {
+    Some(name) => name.clone(),
+    None => specifier
+      .path_segments()
+      .and_then(|mut segments| segments.next_back())
+      .and_then(|file_name| file_name.split('.').next())
+      .unwrap_or("main")
+      .to_string(),
+  };
+  let page = man::generate(&name, nodes);
+  write_to_stdout_ignore_sigpipe(page.as_bytes()).map_err(AnyError::from)
+}
+
 fn print_docs_to_stdout(
   doc_flags: DocFlags,
   mut doc_nodes: Vec<deno_doc::DocNode>,
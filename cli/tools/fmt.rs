@@ -1,7 +1,11 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 //! This module provides file formatting utilities using
-//! [`dprint-plugin-typescript`](https://github.com/dprint/dprint-plugin-typescript).
+//! [`dprint-plugin-typescript`](https://github.com/dprint/dprint-plugin-typescript),
+//! [`malva`](https://github.com/g-plane/malva) for stylesheets,
+//! [`markup_fmt`](https://github.com/g-plane/markup_fmt) for HTML,
+//! [`pretty_yaml`](https://github.com/g-plane/pretty_yaml) for YAML and
+//! [`sqlformat`](https://github.com/shssoichiro/sqlformat-rs) for SQL.
 //!
 //! At the moment it is only consumed using CLI but in
 //! the future it can be easily extended to provide
@@ -10,8 +14,10 @@
 use crate::args::CliOptions;
 use crate::args::Flags;
 use crate::args::FmtFlags;
+use crate::args::FmtLanguagesConfig;
 use crate::args::FmtOptions;
 use crate::args::FmtOptionsConfig;
+use crate::args::LanguageFmtOptionsConfig;
 use crate::args::ProseWrap;
 use crate::colors;
 use crate::factory::CliFactory;
@@ -19,8 +25,18 @@ use crate::util::diff::diff;
 use crate::util::file_watcher;
 use crate::util::fs::canonicalize_path;
 use crate::util::fs::FileCollector;
+use crate::util::git;
+use crate::util::git::ChangedLines;
+use crate::util::git::GitDiffTarget;
 use crate::util::path::get_extension;
+use deno_ast::swc::ast::Expr;
+use deno_ast::swc::ast::TaggedTpl;
+use deno_ast::swc::visit::noop_visit_type;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::MediaType;
 use deno_ast::ParsedSource;
+use deno_ast::SourceRangedForSpanned;
 use deno_config::glob::FilePatterns;
 use deno_core::anyhow::anyhow;
 use deno_core::anyhow::bail;
@@ -33,11 +49,13 @@ use deno_core::unsync::spawn_blocking;
 use log::debug;
 use log::info;
 use log::warn;
+use std::borrow::Cow;
 use std::fs;
 use std::io::stdin;
 use std::io::stdout;
 use std::io::Read;
 use std::io::Write;
+use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicUsize;
@@ -71,14 +89,18 @@ pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
           let factory = CliFactory::from_flags(flags)?;
           let cli_options = factory.cli_options();
           let fmt_options = cli_options.resolve_fmt_options(fmt_flags)?;
-          let files = collect_fmt_files(cli_options, fmt_options.files.clone())
-            .and_then(|files| {
-              if files.is_empty() {
-                Err(generic_error("No target files found."))
-              } else {
-                Ok(files)
-              }
-            })?;
+          let files = collect_fmt_files(
+            cli_options,
+            fmt_options.files.clone(),
+            &fmt_options.languages,
+          )
+          .and_then(|files| {
+            if files.is_empty() {
+              Err(generic_error("No target files found."))
+            } else {
+              Ok(files)
+            }
+          })?;
           let _ = watcher_communicator.watch_paths(files.clone());
           let refmt_files = if let Some(paths) = changed_paths {
             if fmt_options.check {
@@ -105,7 +127,7 @@ pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
           } else {
             files
           };
-          format_files(factory, fmt_options, refmt_files).await?;
+          format_files(factory, fmt_options, refmt_files, None).await?;
 
           Ok(())
         })
@@ -115,39 +137,86 @@ pub async fn format(flags: Flags, fmt_flags: FmtFlags) -> Result<(), AnyError> {
   } else {
     let factory = CliFactory::from_flags(flags)?;
     let cli_options = factory.cli_options();
+    let maybe_diff_target = match &fmt_flags.changed {
+      Some(revision) => Some(GitDiffTarget::Revision(revision.clone())),
+      None => fmt_flags.staged.then_some(GitDiffTarget::Staged),
+    };
     let fmt_options = cli_options.resolve_fmt_options(fmt_flags)?;
-    let files = collect_fmt_files(cli_options, fmt_options.files.clone())
-      .and_then(|files| {
-        if files.is_empty() {
-          Err(generic_error("No target files found."))
-        } else {
-          Ok(files)
-        }
-      })?;
-    format_files(factory, fmt_options, files).await?;
+    let files = collect_fmt_files(
+      cli_options,
+      fmt_options.files.clone(),
+      &fmt_options.languages,
+    )
+    .and_then(|files| {
+      if files.is_empty() {
+        Err(generic_error("No target files found."))
+      } else {
+        Ok(files)
+      }
+    })?;
+    let maybe_changed_lines = match maybe_diff_target {
+      Some(target) => Some(
+        git::changed_lines(cli_options.initial_cwd(), &target)
+          .context("Failed getting the changed lines from git.")?,
+      ),
+      None => None,
+    };
+    format_files(factory, fmt_options, files, maybe_changed_lines).await?;
   }
 
   Ok(())
 }
 
+/// Formats the files. When `maybe_changed_lines` is provided, only the
+/// files and lines in it are formatted.
 async fn format_files(
   factory: CliFactory,
   fmt_options: FmtOptions,
-  paths: Vec<PathBuf>,
+  mut paths: Vec<PathBuf>,
+  maybe_changed_lines: Option<ChangedLines>,
 ) -> Result<(), AnyError> {
+  let maybe_changed_lines = maybe_changed_lines.map(|changed_lines| {
+    // git provides canonicalized paths, so key the lines by the collected
+    // paths instead
+    let changed_lines = paths
+      .iter()
+      .filter_map(|path| {
+        let lines = changed_lines.get(&canonicalize_path(path).ok()?)?;
+        Some((path.clone(), lines.clone()))
+      })
+      .collect::<ChangedLines>();
+    paths.retain(|path| changed_lines.contains_key(path));
+    Arc::new(changed_lines)
+  });
   let caches = factory.caches()?;
   let check = fmt_options.check;
   let incremental_cache = Arc::new(IncrementalCache::new(
     caches.fmt_incremental_cache_db(),
-    &fmt_options.options,
+    &(
+      &fmt_options.options,
+      &fmt_options.languages,
+      maybe_changed_lines.as_deref(),
+    ),
     &paths,
   ));
   if check {
-    check_source_files(paths, fmt_options.options, incremental_cache.clone())
-      .await?;
+    check_source_files(
+      paths,
+      fmt_options.options,
+      fmt_options.languages,
+      maybe_changed_lines,
+      incremental_cache.clone(),
+    )
+    .await?;
   } else {
-    format_source_files(paths, fmt_options.options, incremental_cache.clone())
-      .await?;
+    format_source_files(
+      paths,
+      fmt_options.options,
+      fmt_options.languages,
+      maybe_changed_lines,
+      incremental_cache.clone(),
+    )
+    .await?;
   }
   incremental_cache.wait_completion().await;
   Ok(())
@@ -156,8 +225,9 @@ async fn format_files(
 fn collect_fmt_files(
   cli_options: &CliOptions,
   files: FilePatterns,
+  languages: &FmtLanguagesConfig,
 ) -> Result<Vec<PathBuf>, AnyError> {
-  FileCollector::new(|e| is_supported_ext_fmt(e.path))
+  FileCollector::new(|e| is_supported_ext_fmt(e.path, languages))
     .ignore_git_folder()
     .ignore_node_modules()
     .set_vendor_folder(cli_options.vendor_dir_path().map(ToOwned::to_owned))
@@ -165,10 +235,11 @@ fn collect_fmt_files(
 }
 
 /// Formats markdown (using <https://github.com/dprint/dprint-plugin-markdown>) and its code blocks
-/// (ts/tsx, js/jsx).
+/// (ts/tsx, js/jsx, json, css/scss/sass/less, html/vue/svelte/astro, yaml and sql).
 fn format_markdown(
   file_text: &str,
   fmt_options: &FmtOptionsConfig,
+  languages: &FmtLanguagesConfig,
 ) -> Result<Option<String>, AnyError> {
   let markdown_config = get_resolved_markdown_config(fmt_options);
   dprint_plugin_markdown::format_text(
@@ -176,6 +247,54 @@ fn format_markdown(
     &markdown_config,
     move |tag, text, line_width| {
       let tag = tag.to_lowercase();
+      let with_line_width = |options: &LanguageFmtOptionsConfig| {
+        let mut options = options.resolve(fmt_options);
+        options.line_width = Some(line_width);
+        options
+      };
+      match tag.as_str() {
+        "css" | "scss" | "sass" | "less" => {
+          let Some(css) = &languages.css else {
+            return Ok(None);
+          };
+          return format_css(
+            &PathBuf::from(format!("deno_fmt_stdin.{tag}")),
+            text,
+            &with_line_width(css),
+          );
+        }
+        "html" | "vue" | "svelte" | "astro" => {
+          let Some(html) = &languages.html else {
+            return Ok(None);
+          };
+          let languages = FmtLanguagesConfig {
+            html: Some(LanguageFmtOptionsConfig {
+              line_width: Some(line_width),
+              ..html.clone()
+            }),
+            ..languages.clone()
+          };
+          return format_html(
+            &PathBuf::from(format!("deno_fmt_stdin.{tag}")),
+            text,
+            fmt_options,
+            &languages,
+          );
+        }
+        "yaml" | "yml" => {
+          let Some(yaml) = &languages.yaml else {
+            return Ok(None);
+          };
+          return format_yaml(text, &with_line_width(yaml));
+        }
+        "sql" => {
+          let Some(sql) = &languages.sql else {
+            return Ok(None);
+          };
+          return format_sql(text, fmt_options, sql);
+        }
+        _ => {}
+      }
       if matches!(
         tag.as_str(),
         "ts"
@@ -234,32 +353,384 @@ pub fn format_json(
   dprint_plugin_json::format_text(file_path, file_text, &config)
 }
 
-/// Formats a single TS, TSX, JS, JSX, JSONC, JSON, MD, or IPYNB file.
+/// Formats CSS, SCSS, Sass and Less.
+pub fn format_css(
+  file_path: &Path,
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let formatted_str = malva::format_text(
+    file_text,
+    malva::detect_syntax(file_path).unwrap_or(malva::Syntax::Css),
+    &get_resolved_malva_config(fmt_options),
+  )
+  .map_err(AnyError::from)?;
+
+  Ok(if formatted_str == file_text {
+    None
+  } else {
+    Some(formatted_str)
+  })
+}
+
+/// Formats HTML, Vue, Svelte and Astro along with the scripts and styles
+/// they contain.
+pub fn format_html(
+  file_path: &Path,
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+  languages: &FmtLanguagesConfig,
+) -> Result<Option<String>, AnyError> {
+  let html_options = resolve_language_options(&languages.html, fmt_options);
+  let language = match get_extension(file_path).as_deref() {
+    Some("vue") => markup_fmt::Language::Vue,
+    Some("svelte") => markup_fmt::Language::Svelte,
+    Some("astro") => markup_fmt::Language::Astro,
+    _ => markup_fmt::Language::Html,
+  };
+  let formatted_str = markup_fmt::format_text(
+    file_text,
+    language,
+    &get_resolved_markup_fmt_config(&html_options),
+    |text, hints| {
+      // give the formatters a file name with the extension of the embedded
+      // language, since that's how they determine the syntax
+      let file_name = file_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "deno_fmt_stdin".to_string());
+      let path = PathBuf::from(format!("{}.{}", file_name, hints.ext));
+      // attribute values are quoted by the HTML, so use the other quote
+      let prefers_single_quote =
+        hints.attr != html_options.single_quote.unwrap_or(false);
+      match hints.ext {
+        "css" | "scss" | "sass" | "less" => {
+          let mut malva_config = get_resolved_malva_config(
+            &resolve_language_options(&languages.css, fmt_options),
+          );
+          malva_config.layout.print_width = hints.print_width;
+          malva_config.language.quotes = if prefers_single_quote {
+            malva::config::Quotes::AlwaysSingle
+          } else {
+            malva::config::Quotes::AlwaysDouble
+          };
+          malva::format_text(
+            text,
+            malva::detect_syntax(&path).unwrap_or(malva::Syntax::Css),
+            &malva_config,
+          )
+          .map(Cow::from)
+          .map_err(AnyError::from)
+        }
+        "json" => {
+          let mut json_config = get_resolved_json_config(fmt_options);
+          json_config.line_width = hints.print_width as u32;
+          dprint_plugin_json::format_text(&path, text, &json_config).map(
+            |formatted| formatted.map(Cow::from).unwrap_or(Cow::Borrowed(text)),
+          )
+        }
+        _ => {
+          let mut typescript_config =
+            get_resolved_typescript_config(fmt_options);
+          typescript_config.line_width = hints.print_width as u32;
+          typescript_config.quote_style = if prefers_single_quote {
+            dprint_plugin_typescript::configuration::QuoteStyle::PreferSingle
+          } else {
+            dprint_plugin_typescript::configuration::QuoteStyle::PreferDouble
+          };
+          dprint_plugin_typescript::format_text(
+            &path,
+            text.to_string(),
+            &typescript_config,
+          )
+          .map(|formatted| {
+            formatted.map(Cow::from).unwrap_or(Cow::Borrowed(text))
+          })
+        }
+      }
+    },
+  )
+  .map_err(|error| match error {
+    markup_fmt::FormatError::Syntax(error) => generic_error(error.to_string()),
+    markup_fmt::FormatError::External(errors) => generic_error(
+      errors
+        .into_iter()
+        .map(|error| format!("{error:#}"))
+        .collect::<Vec<_>>()
+        .join("\n\n"),
+    ),
+  })?;
+
+  Ok(if formatted_str == file_text {
+    None
+  } else {
+    Some(formatted_str)
+  })
+}
+
+/// Formats YAML.
+pub fn format_yaml(
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let formatted_str =
+    pretty_yaml::format_text(file_text, &get_resolved_yaml_config(fmt_options))
+      .map_err(AnyError::from)?;
+
+  Ok(if formatted_str == file_text {
+    None
+  } else {
+    Some(formatted_str)
+  })
+}
+
+/// Formats SQL. The line width isn't taken into account.
+pub fn format_sql(
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+  sql_options: &LanguageFmtOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let mut formatted_str = format_sql_text(
+    file_text,
+    &sql_options.resolve(fmt_options),
+    sql_options.uppercase_keywords.unwrap_or(false),
+  );
+  if !formatted_str.is_empty() {
+    formatted_str.push('\n');
+  }
+
+  Ok(if formatted_str == file_text {
+    None
+  } else {
+    Some(formatted_str)
+  })
+}
+
+fn format_sql_text(
+  text: &str,
+  options: &FmtOptionsConfig,
+  uppercase_keywords: bool,
+) -> String {
+  let indent = if options.use_tabs.unwrap_or(false) {
+    sqlformat::Indent::Tabs
+  } else {
+    sqlformat::Indent::Spaces(options.indent_width.unwrap_or(2))
+  };
+  sqlformat::format(
+    text,
+    &sqlformat::QueryParams::None,
+    sqlformat::FormatOptions {
+      indent,
+      uppercase: uppercase_keywords,
+      lines_between_queries: 2,
+    },
+  )
+}
+
+/// Formats the `css`, `html` and `sql` tagged templates of a JS or TS file
+/// with the formatter of their language, when its `fmt` config section is
+/// present. Templates with substitutions or escapes, and the ones that fail
+/// to format, are left as is.
+fn format_embedded_templates(
+  file_path: &Path,
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+  languages: &FmtLanguagesConfig,
+) -> Result<Option<String>, AnyError> {
+  if languages.css.is_none()
+    && languages.html.is_none()
+    && languages.sql.is_none()
+  {
+    return Ok(None);
+  }
+  let specifier = deno_core::resolve_path(
+    &file_path.to_string_lossy(),
+    &std::env::current_dir()?,
+  )?;
+  let Ok(parsed_source) = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier,
+    text: file_text.into(),
+    media_type: MediaType::from_path(file_path),
+    capture_tokens: false,
+    maybe_syntax: None,
+    scope_analysis: false,
+  }) else {
+    return Ok(None);
+  };
+  let mut collector = EmbeddedTemplateCollector::default();
+  parsed_source.module().visit_with(&mut collector);
+  if collector.templates.is_empty() {
+    return Ok(None);
+  }
+
+  let source_start = parsed_source.text_info_lazy().range().start;
+  let indent_unit = if fmt_options.use_tabs.unwrap_or(false) {
+    "\t".to_string()
+  } else {
+    " ".repeat(fmt_options.indent_width.unwrap_or(2) as usize)
+  };
+  let mut text = file_text.to_string();
+  // replace from the end, so the byte ranges of the others stay valid
+  collector.templates.sort_by_key(|(_, range)| range.start);
+  for (tag, range) in collector.templates.into_iter().rev() {
+    // skip the backticks
+    let start = range.start.as_byte_index(source_start) + 1;
+    let end = range.end.as_byte_index(source_start) - 1;
+    let content = &text[start..end];
+    if content.trim().is_empty() || content.contains('\\') {
+      continue;
+    }
+    let formatted = match tag {
+      EmbeddedLanguage::Css => languages.css.as_ref().map(|css| {
+        format_css(
+          Path::new("embedded.css"),
+          content,
+          &css.resolve(fmt_options),
+        )
+      }),
+      EmbeddedLanguage::Html => languages.html.as_ref().map(|_| {
+        format_html(Path::new("embedded.html"), content, fmt_options, languages)
+      }),
+      EmbeddedLanguage::Sql => languages
+        .sql
+        .as_ref()
+        .map(|sql| format_sql(content, fmt_options, sql)),
+    };
+    let Some(Ok(formatted)) = formatted else {
+      continue;
+    };
+    let formatted = formatted.as_deref().unwrap_or(content).trim();
+    if formatted.contains('`') || formatted.contains("${") {
+      continue;
+    }
+    let replacement = if !content.contains('\n') && !formatted.contains('\n') {
+      formatted.to_string()
+    } else {
+      // put the content on its own lines, indented one level deeper than
+      // the line the template starts on
+      let line_start = text[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+      let base_indent: String = text[line_start..start]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect();
+      let content_indent = format!("{base_indent}{indent_unit}");
+      let mut replacement = String::from("\n");
+      for line in formatted.lines() {
+        if !line.is_empty() {
+          replacement.push_str(&content_indent);
+          replacement.push_str(line);
+        }
+        replacement.push('\n');
+      }
+      replacement.push_str(&base_indent);
+      replacement
+    };
+    text.replace_range(start..end, &replacement);
+  }
+
+  Ok(if text == file_text { None } else { Some(text) })
+}
+
+#[derive(Clone, Copy)]
+enum EmbeddedLanguage {
+  Css,
+  Html,
+  Sql,
+}
+
+#[derive(Default)]
+struct EmbeddedTemplateCollector {
+  templates: Vec<(EmbeddedLanguage, deno_ast::SourceRange)>,
+}
+
+impl Visit for EmbeddedTemplateCollector {
+  noop_visit_type!();
+
+  fn visit_tagged_tpl(&mut self, tagged_tpl: &TaggedTpl) {
+    tagged_tpl.visit_children_with(self);
+    let Expr::Ident(ident) = &*tagged_tpl.tag else {
+      return;
+    };
+    let language = match &*ident.sym {
+      "css" => EmbeddedLanguage::Css,
+      "html" => EmbeddedLanguage::Html,
+      "sql" => EmbeddedLanguage::Sql,
+      _ => return,
+    };
+    if tagged_tpl.tpl.exprs.is_empty() {
+      self.templates.push((language, tagged_tpl.tpl.range()));
+    }
+  }
+}
+
+/// The options of a language section, or the general options when the
+/// section isn't present.
+fn resolve_language_options(
+  language: &Option<LanguageFmtOptionsConfig>,
+  fmt_options: &FmtOptionsConfig,
+) -> FmtOptionsConfig {
+  language
+    .as_ref()
+    .map(|language| language.resolve(fmt_options))
+    .unwrap_or_else(|| fmt_options.clone())
+}
+
+/// Formats a single TS, TSX, JS, JSX, JSONC, JSON, MD, IPYNB, CSS, SCSS,
+/// SASS, LESS, HTML, VUE, SVELTE, ASTRO, YAML or SQL file. Files of a
+/// language that isn't enabled by its `fmt` config section are left as is.
 pub fn format_file(
   file_path: &Path,
   file_text: &str,
   fmt_options: &FmtOptionsConfig,
+  languages: &FmtLanguagesConfig,
 ) -> Result<Option<String>, AnyError> {
   let ext = get_extension(file_path).unwrap_or_default();
 
   match ext.as_str() {
     "md" | "mkd" | "mkdn" | "mdwn" | "mdown" | "markdown" => {
-      format_markdown(file_text, fmt_options)
+      format_markdown(file_text, fmt_options, languages)
     }
     "json" | "jsonc" => format_json(file_path, file_text, fmt_options),
+    "css" | "scss" | "sass" | "less" => match &languages.css {
+      Some(css) => format_css(file_path, file_text, &css.resolve(fmt_options)),
+      None => Ok(None),
+    },
+    "html" | "htm" | "vue" | "svelte" | "astro" => match &languages.html {
+      Some(_) => format_html(file_path, file_text, fmt_options, languages),
+      None => Ok(None),
+    },
+    "yml" | "yaml" => match &languages.yaml {
+      Some(yaml) => format_yaml(file_text, &yaml.resolve(fmt_options)),
+      None => Ok(None),
+    },
+    "sql" => match &languages.sql {
+      Some(sql) => format_sql(file_text, fmt_options, sql),
+      None => Ok(None),
+    },
     "ipynb" => dprint_plugin_jupyter::format_text(
       file_text,
       |file_path: &Path, file_text: String| {
-        format_file(file_path, &file_text, fmt_options)
+        format_file(file_path, &file_text, fmt_options, languages)
       },
     ),
     _ => {
       let config = get_resolved_typescript_config(fmt_options);
-      dprint_plugin_typescript::format_text(
+      let formatted_text = dprint_plugin_typescript::format_text(
         file_path,
         file_text.to_string(),
         &config,
-      )
+      )?;
+      let templates_text = format_embedded_templates(
+        file_path,
+        formatted_text.as_deref().unwrap_or(file_text),
+        fmt_options,
+        languages,
+      )?;
+      Ok(match templates_text {
+        Some(text) if text == file_text => None,
+        Some(text) => Some(text),
+        None => formatted_text,
+      })
     }
   }
 }
@@ -277,6 +748,8 @@ pub fn format_parsed_source(
 async fn check_source_files(
   paths: Vec<PathBuf>,
   fmt_options: FmtOptionsConfig,
+  languages: FmtLanguagesConfig,
+  maybe_changed_lines: Option<Arc<ChangedLines>>,
   incremental_cache: Arc<IncrementalCache>,
 ) -> Result<(), AnyError> {
   let not_formatted_files_count = Arc::new(AtomicUsize::new(0));
@@ -297,7 +770,20 @@ async fn check_source_files(
         return Ok(());
       }
 
-      match format_file(&file_path, &file_text, &fmt_options) {
+      let format_result = match &maybe_changed_lines {
+        Some(changed_lines) => format_file_lines(
+          &file_path,
+          &file_text,
+          &fmt_options,
+          &languages,
+          changed_lines
+            .get(&file_path)
+            .map(Vec::as_slice)
+            .unwrap_or(&[]),
+        ),
+        None => format_file(&file_path, &file_text, &fmt_options, &languages),
+      };
+      match format_result {
         Ok(Some(formatted_text)) => {
           not_formatted_files_count.fetch_add(1, Ordering::Relaxed);
           let _g = output_lock.lock();
@@ -358,6 +844,8 @@ async fn check_source_files(
 async fn format_source_files(
   paths: Vec<PathBuf>,
   fmt_options: FmtOptionsConfig,
+  languages: FmtLanguagesConfig,
+  maybe_changed_lines: Option<Arc<ChangedLines>>,
   incremental_cache: Arc<IncrementalCache>,
 ) -> Result<(), AnyError> {
   let formatted_files_count = Arc::new(AtomicUsize::new(0));
@@ -376,12 +864,28 @@ async fn format_source_files(
         return Ok(());
       }
 
-      match format_ensure_stable(
-        &file_path,
-        &file_contents.text,
-        &fmt_options,
-        format_file,
-      ) {
+      let format_result = match &maybe_changed_lines {
+        // the line numbers only apply to the original text, so it
+        // can't be formatted again to ensure it's stable
+        Some(changed_lines) => format_file_lines(
+          &file_path,
+          &file_contents.text,
+          &fmt_options,
+          &languages,
+          changed_lines
+            .get(&file_path)
+            .map(Vec::as_slice)
+            .unwrap_or(&[]),
+        ),
+        None => format_ensure_stable(
+          &file_path,
+          &file_contents.text,
+          &fmt_options,
+          &languages,
+          format_file,
+        ),
+      };
+      match format_result {
         Ok(Some(formatted_text)) => {
           incremental_cache.update_file(&file_path, &formatted_text);
           write_file_contents(
@@ -426,6 +930,85 @@ async fn format_source_files(
   Ok(())
 }
 
+/// Formats the file, but only keeps the changes of the formatter that touch
+/// the provided lines (1-indexed, exclusive end). Errors when the kept
+/// changes don't result in a file that can be parsed.
+fn format_file_lines(
+  file_path: &Path,
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+  languages: &FmtLanguagesConfig,
+  lines: &[Range<usize>],
+) -> Result<Option<String>, AnyError> {
+  if lines.is_empty() {
+    return Ok(None);
+  }
+  let Some(formatted_text) =
+    format_file(file_path, file_text, fmt_options, languages)?
+  else {
+    return Ok(None);
+  };
+  let text = apply_changes_in_lines(file_text, &formatted_text, lines);
+  if text == file_text {
+    return Ok(None);
+  }
+  // the changes that were left out might be needed for the file to stay
+  // valid, so parse it again before it gets written
+  format_file(file_path, &text, fmt_options, languages).context(
+    "Only formatting the changed lines would break the file. Format the whole file instead.",
+  )?;
+  Ok(Some(text))
+}
+
+fn apply_changes_in_lines(
+  original: &str,
+  formatted: &str,
+  lines: &[Range<usize>],
+) -> String {
+  let line_starts = std::iter::once(0)
+    .chain(original.match_indices('\n').map(|(index, _)| index + 1))
+    .collect::<Vec<_>>();
+  let line_at = |offset: usize| line_starts.partition_point(|s| *s <= offset);
+  let touches_lines = |start: usize, end: usize| {
+    let start_line = line_at(start);
+    let end_line = line_at(end);
+    lines
+      .iter()
+      .any(|range| range.start <= end_line && start_line < range.end)
+  };
+
+  let chunks = dissimilar::diff(original, formatted);
+  let mut text = String::with_capacity(formatted.len());
+  let mut offset = 0;
+  let mut chunks = chunks.into_iter().peekable();
+  while let Some(chunk) = chunks.next() {
+    let dissimilar::Chunk::Equal(equal) = chunk else {
+      // group the consecutive insertions and deletions into a single change
+      let start = offset;
+      let mut inserted = String::new();
+      let mut next = Some(chunk);
+      while let Some(chunk) = next {
+        match chunk {
+          dissimilar::Chunk::Delete(deleted) => offset += deleted.len(),
+          dissimilar::Chunk::Insert(insert) => inserted.push_str(insert),
+          dissimilar::Chunk::Equal(_) => unreachable!(),
+        }
+        next =
+          chunks.next_if(|chunk| !matches!(chunk, dissimilar::Chunk::Equal(_)));
+      }
+      if touches_lines(start, offset) {
+        text.push_str(&inserted);
+      } else {
+        text.push_str(&original[start..offset]);
+      }
+      continue;
+    };
+    text.push_str(equal);
+    offset += equal.len();
+  }
+  text
+}
+
 /// When storing any formatted text in the incremental cache, we want
 /// to ensure that anything stored when formatted will have itself as
 /// the output as well. This is to prevent "double format" issues where
@@ -434,19 +1017,21 @@ fn format_ensure_stable(
   file_path: &Path,
   file_text: &str,
   fmt_options: &FmtOptionsConfig,
+  languages: &FmtLanguagesConfig,
   fmt_func: impl Fn(
     &Path,
     &str,
     &FmtOptionsConfig,
+    &FmtLanguagesConfig,
   ) -> Result<Option<String>, AnyError>,
 ) -> Result<Option<String>, AnyError> {
-  let formatted_text = fmt_func(file_path, file_text, fmt_options)?;
+  let formatted_text = fmt_func(file_path, file_text, fmt_options, languages)?;
 
   match formatted_text {
     Some(mut current_text) => {
       let mut count = 0;
       loop {
-        match fmt_func(file_path, &current_text, fmt_options) {
+        match fmt_func(file_path, &current_text, fmt_options, languages) {
           Ok(Some(next_pass_text)) => {
             // just in case
             if next_pass_text == current_text {
@@ -497,7 +1082,21 @@ fn format_stdin(fmt_options: FmtOptions, ext: &str) -> Result<(), AnyError> {
     bail!("Failed to read from stdin");
   }
   let file_path = PathBuf::from(format!("_stdin.{ext}"));
-  let formatted_text = format_file(&file_path, &source, &fmt_options.options)?;
+  // the language was asked for explicitly, so format it even without its
+  // `fmt` config section
+  let mut languages = fmt_options.languages.clone();
+  let language = match ext {
+    "css" | "scss" | "sass" | "less" => Some(&mut languages.css),
+    "html" | "vue" | "svelte" | "astro" => Some(&mut languages.html),
+    "yml" | "yaml" => Some(&mut languages.yaml),
+    "sql" => Some(&mut languages.sql),
+    _ => None,
+  };
+  if let Some(language) = language {
+    language.get_or_insert_with(Default::default);
+  }
+  let formatted_text =
+    format_file(&file_path, &source, &fmt_options.options, &languages)?;
   if fmt_options.check {
     #[allow(clippy::print_stdout)]
     if formatted_text.is_some() {
@@ -606,6 +1205,76 @@ fn get_resolved_json_config(
   builder.build()
 }
 
+fn get_resolved_malva_config(
+  options: &FmtOptionsConfig,
+) -> malva::config::FormatOptions {
+  use malva::config::*;
+
+  FormatOptions {
+    layout: LayoutOptions {
+      print_width: options.line_width.unwrap_or(80) as usize,
+      use_tabs: options.use_tabs.unwrap_or(false),
+      indent_width: options.indent_width.unwrap_or(2) as usize,
+      line_break: LineBreak::Lf,
+    },
+    language: LanguageOptions {
+      hex_case: HexCase::Lower,
+      quotes: if options.single_quote.unwrap_or(false) {
+        Quotes::AlwaysSingle
+      } else {
+        Quotes::AlwaysDouble
+      },
+      ..Default::default()
+    },
+  }
+}
+
+fn get_resolved_markup_fmt_config(
+  options: &FmtOptionsConfig,
+) -> markup_fmt::config::FormatOptions {
+  use markup_fmt::config::*;
+
+  FormatOptions {
+    layout: LayoutOptions {
+      print_width: options.line_width.unwrap_or(80) as usize,
+      use_tabs: options.use_tabs.unwrap_or(false),
+      indent_width: options.indent_width.unwrap_or(2) as usize,
+      line_break: LineBreak::Lf,
+    },
+    language: LanguageOptions {
+      quotes: if options.single_quote.unwrap_or(false) {
+        Quotes::Single
+      } else {
+        Quotes::Double
+      },
+      ..Default::default()
+    },
+  }
+}
+
+fn get_resolved_yaml_config(
+  options: &FmtOptionsConfig,
+) -> pretty_yaml::config::FormatOptions {
+  use pretty_yaml::config::*;
+
+  // YAML doesn't allow tabs for indentation
+  FormatOptions {
+    layout: LayoutOptions {
+      print_width: options.line_width.unwrap_or(80) as usize,
+      indent_width: options.indent_width.unwrap_or(2) as usize,
+      line_break: LineBreak::Lf,
+    },
+    language: LanguageOptions {
+      quotes: if options.single_quote.unwrap_or(false) {
+        Quotes::PreferSingle
+      } else {
+        Quotes::PreferDouble
+      },
+      ..Default::default()
+    },
+  }
+}
+
 struct FileContents {
   text: String,
   had_bom: bool,
@@ -683,11 +1352,16 @@ where
 }
 
 /// This function is similar to is_supported_ext but adds additional extensions
-/// supported by `deno fmt`.
-fn is_supported_ext_fmt(path: &Path) -> bool {
-  get_extension(path).is_some_and(|ext| {
-    matches!(
-      ext.as_str(),
+/// supported by `deno fmt`, including the ones of the languages enabled by
+/// their `fmt` config section.
+fn is_supported_ext_fmt(path: &Path, languages: &FmtLanguagesConfig) -> bool {
+  get_extension(path).is_some_and(|ext| match ext.as_str() {
+    "css" | "scss" | "sass" | "less" => languages.css.is_some(),
+    "html" | "htm" | "vue" | "svelte" | "astro" => languages.html.is_some(),
+    "yml" | "yaml" => languages.yaml.is_some(),
+    "sql" => languages.sql.is_some(),
+    ext => matches!(
+      ext,
       "ts"
         | "tsx"
         | "js"
@@ -705,7 +1379,7 @@ fn is_supported_ext_fmt(path: &Path) -> bool {
         | "mdown"
         | "markdown"
         | "ipynb"
-    )
+    ),
   })
 }
 
@@ -715,30 +1389,78 @@ mod test {
 
   #[test]
   fn test_is_supported_ext_fmt() {
-    assert!(!is_supported_ext_fmt(Path::new("tests/subdir/redirects")));
-    assert!(is_supported_ext_fmt(Path::new("README.md")));
-    assert!(is_supported_ext_fmt(Path::new("readme.MD")));
-    assert!(is_supported_ext_fmt(Path::new("readme.mkd")));
-    assert!(is_supported_ext_fmt(Path::new("readme.mkdn")));
-    assert!(is_supported_ext_fmt(Path::new("readme.mdwn")));
-    assert!(is_supported_ext_fmt(Path::new("readme.mdown")));
-    assert!(is_supported_ext_fmt(Path::new("readme.markdown")));
-    assert!(is_supported_ext_fmt(Path::new("lib/typescript.d.ts")));
-    assert!(is_supported_ext_fmt(Path::new("testdata/run/001_hello.js")));
-    assert!(is_supported_ext_fmt(Path::new("testdata/run/002_hello.ts")));
-    assert!(is_supported_ext_fmt(Path::new("foo.jsx")));
-    assert!(is_supported_ext_fmt(Path::new("foo.tsx")));
-    assert!(is_supported_ext_fmt(Path::new("foo.TS")));
-    assert!(is_supported_ext_fmt(Path::new("foo.TSX")));
-    assert!(is_supported_ext_fmt(Path::new("foo.JS")));
-    assert!(is_supported_ext_fmt(Path::new("foo.JSX")));
-    assert!(is_supported_ext_fmt(Path::new("foo.mjs")));
-    assert!(!is_supported_ext_fmt(Path::new("foo.mjsx")));
-    assert!(is_supported_ext_fmt(Path::new("foo.jsonc")));
-    assert!(is_supported_ext_fmt(Path::new("foo.JSONC")));
-    assert!(is_supported_ext_fmt(Path::new("foo.json")));
-    assert!(is_supported_ext_fmt(Path::new("foo.JsON")));
-    assert!(is_supported_ext_fmt(Path::new("foo.ipynb")));
+    let languages = FmtLanguagesConfig::default();
+    assert!(!is_supported_ext_fmt(
+      Path::new("tests/subdir/redirects"),
+      &languages
+    ));
+    assert!(is_supported_ext_fmt(Path::new("README.md"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("readme.MD"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("readme.mkd"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("readme.mkdn"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("readme.mdwn"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("readme.mdown"), &languages));
+    assert!(is_supported_ext_fmt(
+      Path::new("readme.markdown"),
+      &languages
+    ));
+    assert!(is_supported_ext_fmt(
+      Path::new("lib/typescript.d.ts"),
+      &languages
+    ));
+    assert!(is_supported_ext_fmt(
+      Path::new("testdata/run/001_hello.js"),
+      &languages
+    ));
+    assert!(is_supported_ext_fmt(
+      Path::new("testdata/run/002_hello.ts"),
+      &languages
+    ));
+    assert!(is_supported_ext_fmt(Path::new("foo.jsx"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.tsx"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.TS"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.TSX"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.JS"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.JSX"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.mjs"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.mjsx"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.jsonc"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.JSONC"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.json"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.JsON"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.ipynb"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.css"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.SCSS"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.sass"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.less"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.html"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.htm"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.yml"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.yaml"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.sql"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.vue"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.svelte"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.astro"), &languages));
+
+    let languages = FmtLanguagesConfig {
+      css: Some(Default::default()),
+      html: Some(Default::default()),
+      yaml: Some(Default::default()),
+      sql: Some(Default::default()),
+    };
+    assert!(is_supported_ext_fmt(Path::new("foo.css"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.SCSS"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.sass"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.less"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.html"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.htm"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.yml"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.yaml"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.sql"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.vue"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.svelte"), &languages));
+    assert!(is_supported_ext_fmt(Path::new("foo.astro"), &languages));
+    assert!(!is_supported_ext_fmt(Path::new("foo.mdx"), &languages));
   }
 
   #[test]
@@ -748,7 +1470,8 @@ mod test {
       &PathBuf::from("mod.ts"),
       "1",
       &Default::default(),
-      |_, file_text, _| Ok(Some(format!("1{file_text}"))),
+      &Default::default(),
+      |_, file_text, _, _| Ok(Some(format!("1{file_text}"))),
     )
     .unwrap();
   }
@@ -759,7 +1482,8 @@ mod test {
       &PathBuf::from("mod.ts"),
       "1",
       &Default::default(),
-      |_, _, _| bail!("Error formatting."),
+      &Default::default(),
+      |_, _, _, _| bail!("Error formatting."),
     )
     .unwrap_err();
 
@@ -773,7 +1497,8 @@ mod test {
       &PathBuf::from("mod.ts"),
       "1",
       &Default::default(),
-      |_, file_text, _| {
+      &Default::default(),
+      |_, file_text, _, _| {
         if file_text == "1" {
           Ok(Some("11".to_string()))
         } else {
@@ -790,7 +1515,8 @@ mod test {
       &PathBuf::from("mod.ts"),
       "1",
       &Default::default(),
-      |_, file_text, _| {
+      &Default::default(),
+      |_, file_text, _, _| {
         if file_text == "1" {
           Ok(Some("11".to_string()))
         } else if file_text == "11" {
@@ -814,6 +1540,7 @@ mod test {
         single_quote: Some(true),
         ..Default::default()
       },
+      &Default::default(),
     )
     .unwrap()
     .unwrap();
@@ -823,4 +1550,141 @@ mod test {
       "console.log(\"there's\");\nconsole.log('hi');\nconsole.log('bye');\n",
     );
   }
+
+  #[test]
+  fn test_format_css() {
+    // not enabled by a "fmt.css" section
+    let file_text = format_file(
+      &PathBuf::from("test.css"),
+      "a{color:RED;background:#FFF}",
+      &Default::default(),
+      &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(file_text, None);
+
+    let file_text = format_file(
+      &PathBuf::from("test.css"),
+      "a{color:RED;background:#FFF}",
+      &Default::default(),
+      &FmtLanguagesConfig {
+        css: Some(Default::default()),
+        ..Default::default()
+      },
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(file_text, "a {\n  color: RED;\n  background: #fff;\n}\n");
+  }
+
+  #[test]
+  fn test_format_yaml_language_options() {
+    let languages = FmtLanguagesConfig {
+      yaml: Some(LanguageFmtOptionsConfig {
+        indent_width: Some(4),
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+    let file_text = format_file(
+      &PathBuf::from("test.yml"),
+      "a:\n  b: 1\n",
+      &Default::default(),
+      &languages,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(file_text, "a:\n    b: 1\n");
+  }
+
+  #[test]
+  fn test_format_sql_uppercase_keywords() {
+    let file_text = format_file(
+      &PathBuf::from("test.sql"),
+      "select a from b",
+      &Default::default(),
+      &FmtLanguagesConfig {
+        sql: Some(LanguageFmtOptionsConfig {
+          uppercase_keywords: Some(true),
+          ..Default::default()
+        }),
+        ..Default::default()
+      },
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(file_text, "SELECT\n  a\nFROM\n  b\n");
+  }
+
+  #[test]
+  fn test_format_markdown_code_blocks() {
+    let file_text = format_file(
+      &PathBuf::from("README.md"),
+      "# Title\n\n```css\na{color:red}\n```\n",
+      &Default::default(),
+      &FmtLanguagesConfig {
+        css: Some(Default::default()),
+        ..Default::default()
+      },
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(file_text, "# Title\n\n```css\na {\n  color: red;\n}\n```\n");
+  }
+
+  #[test]
+  fn test_format_embedded_templates() {
+    let file_text = "const style = css`a{color:red}`;\nconst page = html`\n<p>hi</p>\n`;\nconst other = css`a{color:${color}}`;\n";
+    // not enabled by a "fmt.css" or "fmt.html" section
+    let formatted_text = format_file(
+      &PathBuf::from("test.ts"),
+      file_text,
+      &Default::default(),
+      &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(formatted_text, None);
+
+    let formatted_text = format_file(
+      &PathBuf::from("test.ts"),
+      file_text,
+      &Default::default(),
+      &FmtLanguagesConfig {
+        css: Some(Default::default()),
+        html: Some(Default::default()),
+        ..Default::default()
+      },
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+      formatted_text,
+      "const style = css`\n  a {\n    color: red;\n  }\n`;\nconst page = html`\n  <p>hi</p>\n`;\nconst other = css`a{color:${color}}`;\n",
+    );
+  }
+
+  #[test]
+  fn test_format_file_lines() {
+    let file_text = "const a=1;\nconst b=2;\n\nconst c=3;\n";
+    let formatted_text = format_file_lines(
+      &PathBuf::from("test.ts"),
+      file_text,
+      &Default::default(),
+      &Default::default(),
+      &[2..3],
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(formatted_text, "const a=1;\nconst b = 2;\n\nconst c=3;\n");
+
+    let formatted_text = format_file_lines(
+      &PathBuf::from("test.ts"),
+      file_text,
+      &Default::default(),
+      &Default::default(),
+      &[],
+    )
+    .unwrap();
+    assert_eq!(formatted_text, None);
+  }
 }
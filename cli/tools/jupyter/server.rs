@@ -9,6 +9,8 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::cdp;
+use crate::ops::jupyter::CommContainer;
+use crate::ops::jupyter::CommEvent;
 use crate::tools::repl;
 use deno_core::error::AnyError;
 use deno_core::futures;
@@ -38,6 +40,7 @@ pub struct JupyterServer {
   // This is Arc<Mutex<>>, so we don't hold RefCell borrows across await
   // points.
   iopub_connection: Arc<Mutex<KernelIoPubConnection>>,
+  comm_container: Rc<RefCell<CommContainer>>,
   repl_session: repl::ReplSession,
 }
 
@@ -60,6 +63,7 @@ impl JupyterServer {
 
     let iopub_connection = Arc::new(Mutex::new(iopub_connection));
     let last_execution_request = Rc::new(RefCell::new(None));
+    let comm_container = Rc::new(RefCell::new(CommContainer::default()));
 
     // Store `iopub_connection` in the op state so it's accessible to the runtime API.
     {
@@ -67,6 +71,7 @@ impl JupyterServer {
       let mut op_state = op_state_rc.borrow_mut();
       op_state.put(iopub_connection.clone());
       op_state.put(last_execution_request.clone());
+      op_state.put(comm_container.clone());
     }
 
     let cancel_handle = CancelHandle::new_rc();
@@ -75,6 +80,7 @@ impl JupyterServer {
       execution_count: 0,
       iopub_connection: iopub_connection.clone(),
       last_execution_request: last_execution_request.clone(),
+      comm_container,
       repl_session,
     };
 
@@ -352,15 +358,35 @@ impl JupyterServer {
         connection.send(kernel_info().as_child_of(parent)).await?;
       }
       JupyterMessageContent::CommOpen(comm) => {
-        connection
-          .send(
-            messaging::CommClose {
-              comm_id: comm.comm_id,
-              data: Default::default(),
-            }
-            .as_child_of(parent),
-          )
-          .await?;
+        let comm_id = comm.comm_id.0.clone();
+        let has_target =
+          self.comm_container.borrow().has_target(&comm.target_name);
+        if has_target {
+          {
+            let mut comm_container = self.comm_container.borrow_mut();
+            comm_container.open(comm_id.clone(), comm.target_name.clone());
+            comm_container.queue_event(CommEvent {
+              kind: "open",
+              comm_id,
+              target_name: Some(comm.target_name.clone()),
+              data: serde_json::to_value(&comm.data)?,
+              buffers: msg.buffers.iter().map(|b| b.to_vec().into()).collect(),
+            });
+          }
+          self.dispatch_comm_events(parent).await?;
+        } else {
+          // Nobody registered this target, so let the frontend know that the
+          // comm is dead straight away.
+          connection
+            .send(
+              messaging::CommClose {
+                comm_id: comm.comm_id,
+                data: Default::default(),
+              }
+              .as_child_of(parent),
+            )
+            .await?;
+        }
       }
       JupyterMessageContent::HistoryRequest(_req) => {
         connection
@@ -378,11 +404,24 @@ impl JupyterServer {
         // TODO(@zph): implement input reply from https://github.com/denoland/deno/pull/23592
         // NOTE: This will belong on the stdin channel, not the shell channel
       }
-      JupyterMessageContent::CommInfoRequest(_req) => {
+      JupyterMessageContent::CommInfoRequest(req) => {
+        let comms = self
+          .comm_container
+          .borrow()
+          .comms(req.target_name.as_deref())
+          .map(|(comm_id, target_name)| {
+            (
+              messaging::CommId(comm_id.clone()),
+              messaging::CommInfo {
+                target_name: target_name.clone(),
+              },
+            )
+          })
+          .collect();
         connection
           .send(
             messaging::CommInfoReply {
-              comms: Default::default(),
+              comms,
               status: ReplyStatus::Ok,
               error: None,
             }
@@ -390,9 +429,33 @@ impl JupyterServer {
           )
           .await?;
       }
-      JupyterMessageContent::CommMsg(_)
-      | JupyterMessageContent::CommClose(_) => {
-        // Do nothing with regular comm messages
+      JupyterMessageContent::CommMsg(comm) => {
+        let comm_id = comm.comm_id.0.clone();
+        let is_open = self.comm_container.borrow().is_open(&comm_id);
+        if is_open {
+          self.comm_container.borrow_mut().queue_event(CommEvent {
+            kind: "msg",
+            comm_id,
+            target_name: None,
+            data: serde_json::to_value(&comm.data)?,
+            buffers: msg.buffers.iter().map(|b| b.to_vec().into()).collect(),
+          });
+          self.dispatch_comm_events(parent).await?;
+        }
+      }
+      JupyterMessageContent::CommClose(comm) => {
+        let comm_id = comm.comm_id.0.clone();
+        let target_name = self.comm_container.borrow_mut().close(&comm_id);
+        if target_name.is_some() {
+          self.comm_container.borrow_mut().queue_event(CommEvent {
+            kind: "close",
+            comm_id,
+            target_name,
+            data: serde_json::to_value(&comm.data)?,
+            buffers: vec![],
+          });
+          self.dispatch_comm_events(parent).await?;
+        }
       }
       // Any unknown message type is ignored
       _ => {
@@ -595,6 +658,42 @@ impl JupyterServer {
     Ok(())
   }
 
+  /// Hands the queued comm events over to the JS side. Messages sent by the
+  /// comm handlers are parented to the comm message that triggered them.
+  async fn dispatch_comm_events(
+    &mut self,
+    parent_message: &JupyterMessage,
+  ) -> Result<(), AnyError> {
+    if !self.comm_container.borrow().has_pending_events() {
+      return Ok(());
+    }
+
+    let previous_request = self
+      .last_execution_request
+      .replace(Some(parent_message.clone()));
+
+    let result = self
+      .repl_session
+      .post_message_with_event_loop(
+        "Runtime.evaluate",
+        Some(json!({
+          "expression": "Deno[Deno.internal].jupyter.dispatchCommEvents()",
+          "contextId": self.repl_session.context_id,
+          "awaitPromise": true,
+        })),
+      )
+      .await;
+
+    *self.last_execution_request.borrow_mut() = previous_request;
+
+    let response: cdp::EvaluateResponse = serde_json::from_value(result?)?;
+    if let Some(exception_details) = &response.exception_details {
+      log::error!("Error handling comm message: {}", exception_details.text);
+    }
+
+    Ok(())
+  }
+
   async fn send_iopub(
     &mut self,
     message: JupyterMessage,
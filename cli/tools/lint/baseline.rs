@@ -0,0 +1,284 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! A baseline file records the lint diagnostics that already exist in a
+//! project, so only new violations are reported. This allows enabling new
+//! rules on a large code base and fixing the existing violations over time.
+//!
+//! Diagnostics are keyed by file, rule and a fingerprint of the diagnostic
+//! that doesn't depend on its position, so unrelated edits to a file don't
+//! invalidate the baseline.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use deno_lint::diagnostic::LintDiagnostic;
+
+use crate::util::checksum;
+use crate::util::fs::atomic_write_file_with_retries;
+
+const BASELINE_VERSION: u32 = 1;
+
+/// File path relative to the baseline file -> rule code -> fingerprints.
+type BaselineEntries = BTreeMap<String, BTreeMap<String, Vec<String>>>;
+
+#[derive(Serialize, Deserialize)]
+struct BaselineFile {
+  version: u32,
+  diagnostics: BaselineEntries,
+}
+
+#[derive(Debug)]
+pub struct LintBaseline {
+  path: PathBuf,
+  base_dir: PathBuf,
+  /// Record the diagnostics instead of filtering them out.
+  write: bool,
+  entries: Mutex<BaselineEntries>,
+}
+
+impl LintBaseline {
+  /// Loads the baseline file at the provided path. A missing file is
+  /// treated as an empty baseline.
+  pub fn load(path: PathBuf, write: bool) -> Result<Self, AnyError> {
+    let entries = match std::fs::read_to_string(&path) {
+      Ok(text) => {
+        let file: BaselineFile =
+          serde_json::from_str(&text).with_context(|| {
+            format!("Failed parsing lint baseline '{}'.", path.display())
+          })?;
+        if file.version != BASELINE_VERSION {
+          bail!(
+            "Unsupported lint baseline version {} in '{}'. Regenerate it with `deno lint --write-baseline`.",
+            file.version,
+            path.display()
+          );
+        }
+        file.diagnostics
+      }
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+        Default::default()
+      }
+      Err(err) => {
+        return Err(err).with_context(|| {
+          format!("Failed reading lint baseline '{}'.", path.display())
+        })
+      }
+    };
+    let base_dir = path
+      .parent()
+      .map(ToOwned::to_owned)
+      .unwrap_or_else(|| PathBuf::from("/"));
+    Ok(Self {
+      path,
+      base_dir,
+      write,
+      entries: Mutex::new(entries),
+    })
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  pub fn is_write(&self) -> bool {
+    self.write
+  }
+
+  /// Removes the diagnostics of the file that are in the baseline. When
+  /// writing the baseline, the diagnostics are recorded for the file
+  /// instead and none are returned.
+  pub fn apply(
+    &self,
+    specifier: &ModuleSpecifier,
+    mut diagnostics: Vec<LintDiagnostic>,
+  ) -> Vec<LintDiagnostic> {
+    let Some(key) = self.file_key(specifier) else {
+      return diagnostics;
+    };
+    let mut entries = self.entries.lock();
+    if self.write {
+      let mut rules = BTreeMap::<String, Vec<String>>::new();
+      for diagnostic in &diagnostics {
+        rules
+          .entry(diagnostic.code.clone())
+          .or_default()
+          .push(fingerprint(diagnostic));
+      }
+      if rules.is_empty() {
+        entries.remove(&key);
+      } else {
+        for fingerprints in rules.values_mut() {
+          fingerprints.sort();
+        }
+        entries.insert(key, rules);
+      }
+      return Vec::new();
+    }
+
+    let Some(rules) = entries.get(&key) else {
+      return diagnostics;
+    };
+    // each baseline entry only suppresses a single occurrence
+    let mut remaining = HashMap::<(String, String), usize>::new();
+    for (code, fingerprints) in rules {
+      for fingerprint in fingerprints {
+        *remaining
+          .entry((code.clone(), fingerprint.clone()))
+          .or_default() += 1;
+      }
+    }
+    drop(entries);
+    diagnostics.retain(|diagnostic| {
+      let key = (diagnostic.code.clone(), fingerprint(diagnostic));
+      match remaining.get_mut(&key) {
+        Some(count) if *count > 0 => {
+          *count -= 1;
+          false
+        }
+        _ => true,
+      }
+    });
+    diagnostics
+  }
+
+  /// Writes the recorded diagnostics to the baseline file, returning the
+  /// number of diagnostics in the baseline.
+  pub fn write(&self) -> Result<usize, AnyError> {
+    let entries = self.entries.lock();
+    let count = entries
+      .values()
+      .flat_map(|rules| rules.values())
+      .map(|fingerprints| fingerprints.len())
+      .sum();
+    let mut text = serde_json::to_string_pretty(&BaselineFile {
+      version: BASELINE_VERSION,
+      diagnostics: entries.clone(),
+    })?;
+    text.push('\n');
+    atomic_write_file_with_retries(&self.path, text, 0o644).with_context(
+      || format!("Failed writing lint baseline '{}'.", self.path.display()),
+    )?;
+    Ok(count)
+  }
+
+  fn file_key(&self, specifier: &ModuleSpecifier) -> Option<String> {
+    let path = specifier.to_file_path().ok()?;
+    let path = path.strip_prefix(&self.base_dir).unwrap_or(&path);
+    Some(
+      path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"),
+    )
+  }
+}
+
+/// A hash of the rule, message and the line of the diagnostic with
+/// normalized whitespace, so it stays the same when code moves around.
+fn fingerprint(diagnostic: &LintDiagnostic) -> String {
+  let text = diagnostic.text_info.text_str();
+  let range = diagnostic
+    .range
+    .as_byte_range(diagnostic.text_info.range().start);
+  let line_start = text[..range.start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+  let line_end = text[range.end..]
+    .find('\n')
+    .map(|i| range.end + i)
+    .unwrap_or(text.len());
+  let context = text[line_start..line_end]
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join(" ");
+  let mut hash = checksum::gen(&[
+    diagnostic.code.as_bytes(),
+    diagnostic.message.as_bytes(),
+    context.as_bytes(),
+  ]);
+  hash.truncate(16);
+  hash
+}
+
+#[cfg(test)]
+mod test {
+  use deno_ast::SourceRange;
+  use deno_ast::SourceTextInfo;
+  use test_util::TempDir;
+
+  use super::*;
+
+  fn diagnostic(
+    specifier: &ModuleSpecifier,
+    text_info: &SourceTextInfo,
+    code: &str,
+    start: usize,
+    end: usize,
+  ) -> LintDiagnostic {
+    let file_start = text_info.range().start;
+    LintDiagnostic {
+      specifier: specifier.clone(),
+      range: SourceRange::new(file_start + start, file_start + end),
+      text_info: text_info.clone(),
+      message: format!("{code} message"),
+      code: code.to_string(),
+      hint: None,
+      fixes: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn write_and_apply() {
+    let temp_dir = TempDir::new();
+    let baseline_path = temp_dir.path().join("baseline.json").to_path_buf();
+    let specifier =
+      ModuleSpecifier::from_file_path(temp_dir.path().join("src/a.ts"))
+        .unwrap();
+    let text_info = SourceTextInfo::from_string(
+      "const a = 1;\nconst b = 2;\nconst c = 3;\n".to_string(),
+    );
+
+    let baseline = LintBaseline::load(baseline_path.clone(), true).unwrap();
+    let remaining = baseline.apply(
+      &specifier,
+      vec![
+        diagnostic(&specifier, &text_info, "no-unused-vars", 6, 7),
+        diagnostic(&specifier, &text_info, "no-unused-vars", 19, 20),
+      ],
+    );
+    assert!(remaining.is_empty());
+    assert_eq!(baseline.write().unwrap(), 2);
+    let file: BaselineFile =
+      serde_json::from_str(&std::fs::read_to_string(&baseline_path).unwrap())
+        .unwrap();
+    assert_eq!(
+      file.diagnostics.keys().collect::<Vec<_>>(),
+      vec!["src/a.ts"]
+    );
+
+    // the code moved down a line, which shouldn't matter
+    let text_info = SourceTextInfo::from_string(
+      "\nconst a = 1;\nconst b = 2;\nconst c = 3;\n".to_string(),
+    );
+    let baseline = LintBaseline::load(baseline_path, false).unwrap();
+    let remaining = baseline.apply(
+      &specifier,
+      vec![
+        diagnostic(&specifier, &text_info, "no-unused-vars", 7, 8),
+        diagnostic(&specifier, &text_info, "no-unused-vars", 20, 21),
+        diagnostic(&specifier, &text_info, "no-unused-vars", 33, 34),
+      ],
+    );
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].range.start, text_info.range().start + 33);
+  }
+}
@@ -24,8 +24,6 @@ use deno_lint::linter::LinterBuilder;
 use deno_lint::rules;
 use deno_lint::rules::LintRule;
 use log::debug;
-use log::info;
-use serde::Serialize;
 use std::borrow::Cow;
 use std::collections::HashSet;
 use std::fs;
@@ -52,17 +50,17 @@ use crate::util::fs::FileCollector;
 use crate::util::path::is_script_ext;
 use crate::util::sync::AtomicFlag;
 
+mod baseline;
 pub mod no_slow_types;
+mod plugins;
+mod reporters;
 
-static STDIN_FILE_NAME: &str = "$deno$stdin.ts";
+pub use baseline::LintBaseline;
+pub use plugins::LintPluginRunner;
+use reporters::create_reporter;
+use reporters::LintReporter;
 
-fn create_reporter(kind: LintReporterKind) -> Box<dyn LintReporter + Send> {
-  match kind {
-    LintReporterKind::Pretty => Box::new(PrettyLintReporter::new()),
-    LintReporterKind::Json => Box::new(JsonLintReporter::new()),
-    LintReporterKind::Compact => Box::new(CompactLintReporter::new()),
-  }
-}
+static STDIN_FILE_NAME: &str = "$deno$stdin.ts";
 
 pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
   if let Some(watch_flags) = &lint_flags.watch {
@@ -122,17 +120,25 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
     let files = &lint_options.files;
     let success = if is_stdin {
       let reporter_kind = lint_options.reporter_kind;
-      let reporter_lock = Arc::new(Mutex::new(create_reporter(reporter_kind)));
+      let reporter_lock = Arc::new(Mutex::new(create_reporter(
+        reporter_kind,
+        cwd_url(cli_options.initial_cwd()),
+      )));
       let lint_rules = get_config_rules_err_empty(
-        lint_options.rules,
+        lint_options.rules.clone(),
         cli_options.maybe_config_file().as_ref(),
       )?;
+      let linter = create_linter(
+        lint_rules.rules,
+        create_plugin_runner(&lint_options.plugins, &lint_options.rules),
+      );
       let file_path = cli_options.initial_cwd().join(STDIN_FILE_NAME);
-      let r = lint_stdin(&file_path, lint_rules.rules, lint_config);
+      let r = lint_stdin(&file_path, &linter, lint_config);
       let success = handle_lint_result(
         &file_path.to_string_lossy(),
         r,
         reporter_lock.clone(),
+        None,
       );
       reporter_lock.lock().close(1);
       success
@@ -165,17 +171,32 @@ async fn lint_files(
   let caches = factory.caches()?;
   let maybe_config_file = factory.cli_options().maybe_config_file().as_ref();
   let lint_rules =
-    get_config_rules_err_empty(lint_options.rules, maybe_config_file)?;
+    get_config_rules_err_empty(lint_options.rules.clone(), maybe_config_file)?;
+  let plugin_runner =
+    create_plugin_runner(&lint_options.plugins, &lint_options.rules);
   let incremental_cache = Arc::new(IncrementalCache::new(
     caches.lint_incremental_cache_db(),
-    &lint_rules.incremental_cache_state(),
+    &(
+      lint_rules.incremental_cache_state(),
+      plugin_runner
+        .as_ref()
+        .map(|runner| runner.incremental_cache_state()),
+    ),
     &paths,
   ));
+  let baseline = lint_options
+    .baseline
+    .clone()
+    .map(|path| LintBaseline::load(path, lint_options.write_baseline))
+    .transpose()?
+    .map(Arc::new);
   let target_files_len = paths.len();
   let reporter_kind = lint_options.reporter_kind;
   // todo(dsherret): abstract away this lock behind a performant interface
-  let reporter_lock =
-    Arc::new(Mutex::new(create_reporter(reporter_kind.clone())));
+  let reporter_lock = Arc::new(Mutex::new(create_reporter(
+    reporter_kind.clone(),
+    cwd_url(factory.cli_options().initial_cwd()),
+  )));
   let has_error = Arc::new(AtomicFlag::default());
 
   let mut futures = Vec::with_capacity(2);
@@ -222,18 +243,24 @@ async fn lint_files(
 
   futures.push({
     let has_error = has_error.clone();
-    let linter = create_linter(lint_rules.rules);
+    let linter = create_linter(lint_rules.rules, plugin_runner);
     let reporter_lock = reporter_lock.clone();
     let incremental_cache = incremental_cache.clone();
     let lint_config = lint_config.clone();
     let fix = lint_options.fix;
+    let baseline = baseline.clone();
     deno_core::unsync::spawn(async move {
       run_parallelized(paths, {
         move |file_path| {
           let file_text = deno_ast::strip_bom(fs::read_to_string(&file_path)?);
 
-          // don't bother rechecking this file if it didn't have any diagnostics before
-          if incremental_cache.is_file_same(&file_path, &file_text) {
+          // don't bother rechecking this file if it didn't have any diagnostics
+          // before, unless its entry in the baseline is being rewritten
+          let is_writing_baseline =
+            baseline.as_ref().is_some_and(|b| b.is_write());
+          if !is_writing_baseline
+            && incremental_cache.is_file_same(&file_path, &file_text)
+          {
             return Ok(());
           }
 
@@ -253,6 +280,7 @@ async fn lint_files(
             &file_path.to_string_lossy(),
             r,
             reporter_lock.clone(),
+            baseline.as_deref(),
           );
           if !success {
             has_error.raise();
@@ -268,11 +296,28 @@ async fn lint_files(
   deno_core::futures::future::try_join_all(futures).await?;
 
   incremental_cache.wait_completion().await;
+  if let Some(baseline) = baseline.filter(|b| b.is_write()) {
+    let count = baseline.write()?;
+    log::info!(
+      "Wrote {} {} to {}",
+      count,
+      if count == 1 {
+        "diagnostic"
+      } else {
+        "diagnostics"
+      },
+      baseline.path().display()
+    );
+  }
   reporter_lock.lock().close(target_files_len);
 
   Ok(!has_error.is_raised())
 }
 
+fn cwd_url(cwd: &Path) -> ModuleSpecifier {
+  ModuleSpecifier::from_directory_path(cwd).unwrap()
+}
+
 fn collect_lint_files(
   cli_options: &CliOptions,
   files: FilePatterns,
@@ -328,16 +373,76 @@ pub fn print_rules_list(json: bool, maybe_rules_tags: Option<Vec<String>>) {
   }
 }
 
-pub fn create_linter(rules: Vec<&'static dyn LintRule>) -> Linter {
-  LinterBuilder::default()
-    .ignore_file_directive("deno-lint-ignore-file")
-    .ignore_diagnostic_directive("deno-lint-ignore")
-    .rules(rules)
-    .build()
+/// The built-in linter along with the rules of any configured lint plugins.
+pub struct CliLinter {
+  linter: Linter,
+  plugin_runner: Option<Arc<LintPluginRunner>>,
+}
+
+impl CliLinter {
+  pub fn lint_file(
+    &self,
+    options: LintFileOptions,
+  ) -> Result<(ParsedSource, Vec<LintDiagnostic>), AnyError> {
+    let (source, diagnostics) = self.linter.lint_file(options)?;
+    let diagnostics = self.with_plugin_diagnostics(&source, diagnostics)?;
+    Ok((source, diagnostics))
+  }
+
+  pub fn lint_with_ast(
+    &self,
+    parsed_source: &ParsedSource,
+    config: LintConfig,
+  ) -> Result<Vec<LintDiagnostic>, AnyError> {
+    let diagnostics = self.linter.lint_with_ast(parsed_source, config);
+    self.with_plugin_diagnostics(parsed_source, diagnostics)
+  }
+
+  fn with_plugin_diagnostics(
+    &self,
+    parsed_source: &ParsedSource,
+    mut diagnostics: Vec<LintDiagnostic>,
+  ) -> Result<Vec<LintDiagnostic>, AnyError> {
+    let Some(plugin_runner) = &self.plugin_runner else {
+      return Ok(diagnostics);
+    };
+    let ignores = plugins::IgnoreDirectives::collect(parsed_source);
+    diagnostics.retain(|d| !ignores.is_plugin_code_diagnostic(d));
+    diagnostics.extend(plugin_runner.lint(parsed_source, &ignores)?);
+    Ok(diagnostics)
+  }
+}
+
+pub fn create_linter(
+  rules: Vec<&'static dyn LintRule>,
+  plugin_runner: Option<Arc<LintPluginRunner>>,
+) -> CliLinter {
+  CliLinter {
+    linter: LinterBuilder::default()
+      .ignore_file_directive("deno-lint-ignore-file")
+      .ignore_diagnostic_directive("deno-lint-ignore")
+      .rules(rules)
+      .build(),
+    plugin_runner,
+  }
+}
+
+/// Starts the lint plugins, unless none are configured.
+pub fn create_plugin_runner(
+  plugins: &[ModuleSpecifier],
+  rules: &LintRulesConfig,
+) -> Option<Arc<LintPluginRunner>> {
+  if plugins.is_empty() {
+    return None;
+  }
+  Some(Arc::new(LintPluginRunner::new(
+    plugins.to_vec(),
+    rules.exclude.clone().unwrap_or_default(),
+  )))
 }
 
 fn lint_file(
-  linter: &Linter,
+  linter: &CliLinter,
   file_path: &Path,
   source_code: String,
   config: LintConfig,
@@ -356,19 +461,17 @@ fn lint_file(
       config,
     )
   } else {
-    linter
-      .lint_file(LintFileOptions {
-        specifier,
-        media_type,
-        source_code,
-        config,
-      })
-      .map_err(AnyError::from)
+    linter.lint_file(LintFileOptions {
+      specifier,
+      media_type,
+      source_code,
+      config,
+    })
   }
 }
 
 fn lint_file_and_fix(
-  linter: &Linter,
+  linter: &CliLinter,
   specifier: &ModuleSpecifier,
   media_type: MediaType,
   source_code: String,
@@ -433,7 +536,7 @@ fn lint_file_and_fix(
 fn apply_lint_fixes_and_relint(
   specifier: &ModuleSpecifier,
   media_type: MediaType,
-  linter: &Linter,
+  linter: &CliLinter,
   config: LintConfig,
   text_info: &SourceTextInfo,
   diagnostics: &[LintDiagnostic],
@@ -497,7 +600,7 @@ fn apply_lint_fixes(
 /// Compatible with `--json` flag.
 fn lint_stdin(
   file_path: &Path,
-  lint_rules: Vec<&'static dyn LintRule>,
+  linter: &CliLinter,
   config: LintConfig,
 ) -> Result<(ParsedSource, Vec<LintDiagnostic>), AnyError> {
   let mut source_code = String::new();
@@ -505,27 +608,28 @@ fn lint_stdin(
     return Err(generic_error("Failed to read from stdin"));
   }
 
-  let linter = create_linter(lint_rules);
-
-  linter
-    .lint_file(LintFileOptions {
-      specifier: specifier_from_file_path(file_path)?,
-      source_code: deno_ast::strip_bom(source_code),
-      media_type: MediaType::TypeScript,
-      config,
-    })
-    .map_err(AnyError::from)
+  linter.lint_file(LintFileOptions {
+    specifier: specifier_from_file_path(file_path)?,
+    source_code: deno_ast::strip_bom(source_code),
+    media_type: MediaType::TypeScript,
+    config,
+  })
 }
 
 fn handle_lint_result(
   file_path: &str,
   result: Result<(ParsedSource, Vec<LintDiagnostic>), AnyError>,
   reporter_lock: Arc<Mutex<Box<dyn LintReporter + Send>>>,
+  maybe_baseline: Option<&LintBaseline>,
 ) -> bool {
   let mut reporter = reporter_lock.lock();
 
   match result {
-    Ok((_source, mut file_diagnostics)) => {
+    Ok((source, file_diagnostics)) => {
+      let mut file_diagnostics = match maybe_baseline {
+        Some(baseline) => baseline.apply(source.specifier(), file_diagnostics),
+        None => file_diagnostics,
+      };
       file_diagnostics.sort_by(|a, b| match a.specifier.cmp(&b.specifier) {
         std::cmp::Ordering::Equal => a.range.start.cmp(&b.range.start),
         file_order => file_order,
@@ -627,247 +731,14 @@ impl<'a> deno_ast::diagnostics::Diagnostic for LintOrCliDiagnostic<'a> {
 
   fn docs_url(&self) -> Option<Cow<'_, str>> {
     match self {
+      // plugin rules aren't documented on the lint website
+      LintOrCliDiagnostic::Lint(d) if plugins::is_plugin_code(&d.code) => None,
       LintOrCliDiagnostic::Lint(d) => d.docs_url(),
       LintOrCliDiagnostic::FastCheck(d) => d.docs_url(),
     }
   }
 }
 
-trait LintReporter {
-  fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic);
-  fn visit_error(&mut self, file_path: &str, err: &AnyError);
-  fn close(&mut self, check_count: usize);
-}
-
-#[derive(Serialize)]
-struct LintError {
-  file_path: String,
-  message: String,
-}
-
-struct PrettyLintReporter {
-  lint_count: u32,
-  fixable_diagnostics: u32,
-}
-
-impl PrettyLintReporter {
-  fn new() -> PrettyLintReporter {
-    PrettyLintReporter {
-      lint_count: 0,
-      fixable_diagnostics: 0,
-    }
-  }
-}
-
-impl LintReporter for PrettyLintReporter {
-  fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic) {
-    self.lint_count += 1;
-    if let LintOrCliDiagnostic::Lint(d) = d {
-      if !d.fixes.is_empty() {
-        self.fixable_diagnostics += 1;
-      }
-    }
-
-    log::error!("{}\n", d.display());
-  }
-
-  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
-    log::error!("Error linting: {file_path}");
-    log::error!("   {err}");
-  }
-
-  fn close(&mut self, check_count: usize) {
-    let fixable_suffix = if self.fixable_diagnostics > 0 {
-      colors::gray(format!(" ({} fixable via --fix)", self.fixable_diagnostics))
-        .to_string()
-    } else {
-      "".to_string()
-    };
-    match self.lint_count {
-      1 => info!("Found 1 problem{}", fixable_suffix),
-      n if n > 1 => {
-        info!("Found {} problems{}", self.lint_count, fixable_suffix)
-      }
-      _ => (),
-    }
-
-    match check_count {
-      n if n <= 1 => info!("Checked {} file", n),
-      n if n > 1 => info!("Checked {} files", n),
-      _ => unreachable!(),
-    }
-  }
-}
-
-struct CompactLintReporter {
-  lint_count: u32,
-}
-
-impl CompactLintReporter {
-  fn new() -> CompactLintReporter {
-    CompactLintReporter { lint_count: 0 }
-  }
-}
-
-impl LintReporter for CompactLintReporter {
-  fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic) {
-    self.lint_count += 1;
-
-    match d.range() {
-      Some((text_info, range)) => {
-        let line_and_column = text_info.line_and_column_display(range.start);
-        log::error!(
-          "{}: line {}, col {} - {} ({})",
-          d.specifier(),
-          line_and_column.line_number,
-          line_and_column.column_number,
-          d.message(),
-          d.code(),
-        )
-      }
-      None => {
-        log::error!("{}: {} ({})", d.specifier(), d.message(), d.code())
-      }
-    }
-  }
-
-  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
-    log::error!("Error linting: {file_path}");
-    log::error!("   {err}");
-  }
-
-  fn close(&mut self, check_count: usize) {
-    match self.lint_count {
-      1 => info!("Found 1 problem"),
-      n if n > 1 => info!("Found {} problems", self.lint_count),
-      _ => (),
-    }
-
-    match check_count {
-      n if n <= 1 => info!("Checked {} file", n),
-      n if n > 1 => info!("Checked {} files", n),
-      _ => unreachable!(),
-    }
-  }
-}
-
-// WARNING: Ensure doesn't change because it's used in the JSON output
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct JsonDiagnosticLintPosition {
-  /// The 1-indexed line number.
-  pub line: usize,
-  /// The 0-indexed column index.
-  pub col: usize,
-  pub byte_pos: usize,
-}
-
-impl JsonDiagnosticLintPosition {
-  pub fn new(byte_index: usize, loc: deno_ast::LineAndColumnIndex) -> Self {
-    JsonDiagnosticLintPosition {
-      line: loc.line_index + 1,
-      col: loc.column_index,
-      byte_pos: byte_index,
-    }
-  }
-}
-
-// WARNING: Ensure doesn't change because it's used in the JSON output
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
-struct JsonLintDiagnosticRange {
-  pub start: JsonDiagnosticLintPosition,
-  pub end: JsonDiagnosticLintPosition,
-}
-
-// WARNING: Ensure doesn't change because it's used in the JSON output
-#[derive(Clone, Serialize)]
-struct JsonLintDiagnostic {
-  pub filename: String,
-  pub range: Option<JsonLintDiagnosticRange>,
-  pub message: String,
-  pub code: String,
-  pub hint: Option<String>,
-}
-
-#[derive(Serialize)]
-struct JsonLintReporter {
-  diagnostics: Vec<JsonLintDiagnostic>,
-  errors: Vec<LintError>,
-}
-
-impl JsonLintReporter {
-  fn new() -> JsonLintReporter {
-    JsonLintReporter {
-      diagnostics: Vec::new(),
-      errors: Vec::new(),
-    }
-  }
-}
-
-impl LintReporter for JsonLintReporter {
-  fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic) {
-    self.diagnostics.push(JsonLintDiagnostic {
-      filename: d.specifier().to_string(),
-      range: d.range().map(|(text_info, range)| JsonLintDiagnosticRange {
-        start: JsonDiagnosticLintPosition::new(
-          range.start.as_byte_index(text_info.range().start),
-          text_info.line_and_column_index(range.start),
-        ),
-        end: JsonDiagnosticLintPosition::new(
-          range.end.as_byte_index(text_info.range().start),
-          text_info.line_and_column_index(range.end),
-        ),
-      }),
-      message: d.message().to_string(),
-      code: d.code().to_string(),
-      hint: d.hint().map(|h| h.to_string()),
-    });
-  }
-
-  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
-    self.errors.push(LintError {
-      file_path: file_path.to_string(),
-      message: err.to_string(),
-    });
-  }
-
-  fn close(&mut self, _check_count: usize) {
-    sort_diagnostics(&mut self.diagnostics);
-    let json = serde_json::to_string_pretty(&self);
-    #[allow(clippy::print_stdout)]
-    {
-      println!("{}", json.unwrap());
-    }
-  }
-}
-
-fn sort_diagnostics(diagnostics: &mut [JsonLintDiagnostic]) {
-  // Sort so that we guarantee a deterministic output which is useful for tests
-  diagnostics.sort_by(|a, b| {
-    use std::cmp::Ordering;
-    let file_order = a.filename.cmp(&b.filename);
-    match file_order {
-      Ordering::Equal => match &a.range {
-        Some(a_range) => match &b.range {
-          Some(b_range) => {
-            let line_order = a_range.start.line.cmp(&b_range.start.line);
-            match line_order {
-              Ordering::Equal => a_range.start.col.cmp(&b_range.start.col),
-              _ => line_order,
-            }
-          }
-          None => Ordering::Less,
-        },
-        None => match &b.range {
-          Some(_) => Ordering::Greater,
-          None => Ordering::Equal,
-        },
-      },
-      _ => file_order,
-    }
-  });
-}
-
 fn get_config_rules_err_empty(
   rules: LintRulesConfig,
   maybe_config_file: Option<&deno_config::ConfigFile>,
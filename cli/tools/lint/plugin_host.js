@@ -0,0 +1,190 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+// Runs the rules of the lint plugins listed in the `lint.plugins` config.
+//
+// A plugin module default exports an object of the following shape:
+//
+//   export default {
+//     name: "my-plugin",
+//     rules: {
+//       "no-foo": {
+//         create(context) {
+//           return {
+//             Identifier(node) {
+//               if (node.value === "foo") {
+//                 context.report({ node, message: "Don't use foo" });
+//               }
+//             },
+//           };
+//         },
+//       },
+//     },
+//   };
+//
+// The AST handed to the visitors is the swc AST serialized to JSON. Every
+// node has a `type` and a `span` holding the byte offsets of the node.
+
+((globalThis) => {
+  const core = globalThis.Deno.core;
+
+  /** @type {{ name: string, rules: Record<string, any> }[]} */
+  const plugins = [];
+
+  function register(entries) {
+    for (const [specifier, plugin] of entries) {
+      if (
+        plugin === null || typeof plugin !== "object" ||
+        typeof plugin.name !== "string" || plugin.rules === null ||
+        typeof plugin.rules !== "object"
+      ) {
+        throw new TypeError(
+          `Lint plugin "${specifier}" must default export an object with a "name" and "rules".`,
+        );
+      }
+      if (plugin.name.includes("/")) {
+        throw new TypeError(
+          `Lint plugin name "${plugin.name}" in "${specifier}" must not contain a "/".`,
+        );
+      }
+      for (const [ruleName, rule] of Object.entries(plugin.rules)) {
+        if (rule === null || typeof rule?.create !== "function") {
+          throw new TypeError(
+            `Lint rule "${plugin.name}/${ruleName}" must have a "create" function.`,
+          );
+        }
+      }
+      plugins.push(plugin);
+    }
+  }
+
+  function isNode(value) {
+    return value !== null && typeof value === "object" &&
+      typeof value.type === "string" && value.span !== undefined;
+  }
+
+  function visit(value, parent, visitors) {
+    if (Array.isArray(value)) {
+      for (const item of value) {
+        visit(item, parent, visitors);
+      }
+    } else if (isNode(value)) {
+      Object.defineProperty(value, "parent", { value: parent });
+      callVisitors(visitors, value.type, value);
+      for (const key in value) {
+        if (key !== "span") {
+          visit(value[key], value, visitors);
+        }
+      }
+      callVisitors(visitors, `${value.type}:exit`, value);
+    } else if (value !== null && typeof value === "object") {
+      // wrapper objects without a type, for example `ExprOrSpread`
+      for (const key in value) {
+        visit(value[key], parent, visitors);
+      }
+    }
+  }
+
+  function callVisitors(visitors, key, node) {
+    const fns = visitors.get(key);
+    if (fns !== undefined) {
+      for (const fn of fns) {
+        fn(node);
+      }
+    }
+  }
+
+  function toSpan(nodeOrSpan) {
+    const span = nodeOrSpan?.span ?? nodeOrSpan;
+    if (
+      span === null || typeof span !== "object" ||
+      typeof span.start !== "number" || typeof span.end !== "number"
+    ) {
+      throw new TypeError("Expected a node or a span with a start and end.");
+    }
+    return { start: span.start, end: span.end };
+  }
+
+  const fixer = {
+    replaceText(nodeOrSpan, text) {
+      const { start, end } = toSpan(nodeOrSpan);
+      return { start, end, newText: String(text) };
+    },
+    insertTextBefore(nodeOrSpan, text) {
+      const { start } = toSpan(nodeOrSpan);
+      return { start, end: start, newText: String(text) };
+    },
+    insertTextAfter(nodeOrSpan, text) {
+      const { end } = toSpan(nodeOrSpan);
+      return { start: end, end, newText: String(text) };
+    },
+    remove(nodeOrSpan) {
+      const { start, end } = toSpan(nodeOrSpan);
+      return { start, end, newText: "" };
+    },
+  };
+
+  function run(request) {
+    const { specifier, text, offset, ast, exclude } = request;
+    const bytes = core.encode(text);
+    const diagnostics = [];
+    const visitors = new Map();
+
+    for (const plugin of plugins) {
+      for (const [ruleName, rule] of Object.entries(plugin.rules)) {
+        const code = `${plugin.name}/${ruleName}`;
+        if (exclude.includes(code)) {
+          continue;
+        }
+        const context = {
+          id: code,
+          filename: specifier,
+          source: text,
+          getSourceText(nodeOrSpan) {
+            const { start, end } = toSpan(nodeOrSpan);
+            return core.decode(bytes.subarray(start - offset, end - offset));
+          },
+          report(report) {
+            const { start, end } = toSpan(report.span ?? report.node);
+            let fixes = [];
+            if (typeof report.fix === "function") {
+              const changes = report.fix(fixer);
+              if (changes != null) {
+                fixes = [{
+                  description: report.fixDescription ?? `Fix ${code}`,
+                  changes: Array.isArray(changes) ? changes : [changes],
+                }];
+              }
+            }
+            diagnostics.push({
+              code,
+              message: String(report.message),
+              hint: report.hint == null ? null : String(report.hint),
+              start,
+              end,
+              fixes,
+            });
+          },
+        };
+        const ruleVisitors = rule.create(context) ?? {};
+        for (const [key, fn] of Object.entries(ruleVisitors)) {
+          if (typeof fn !== "function") {
+            continue;
+          }
+          let fns = visitors.get(key);
+          if (fns === undefined) {
+            fns = [];
+            visitors.set(key, fns);
+          }
+          fns.push(fn);
+        }
+      }
+    }
+
+    if (visitors.size > 0) {
+      visit(ast, null, visitors);
+    }
+    return diagnostics;
+  }
+
+  globalThis[Symbol.for("deno.lint.host")] = { register, run };
+})(globalThis);
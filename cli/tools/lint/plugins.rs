@@ -0,0 +1,616 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Lint rules provided by JavaScript or TypeScript plugin modules listed in
+//! the `lint.plugins` config.
+//!
+//! The plugins are run in a separate isolate without any Deno APIs, owned by
+//! a dedicated thread. Callers send the parsed source of a file to that thread
+//! and block until the diagnostics of the plugin rules come back.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use deno_ast::swc::ast::Program;
+use deno_ast::swc::common::comments::CommentKind;
+use deno_ast::swc::common::BytePos;
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::ParsedSource;
+use deno_ast::SourcePos;
+use deno_ast::SourceRange;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::ascii_str_include;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use deno_core::serde_v8;
+use deno_core::v8;
+use deno_core::JsRuntime;
+use deno_core::ModuleLoadResponse;
+use deno_core::ModuleLoader;
+use deno_core::ModuleSource;
+use deno_core::ModuleSourceCode;
+use deno_core::ModuleType;
+use deno_core::RequestedModuleType;
+use deno_core::ResolutionKind;
+use deno_core::RuntimeOptions;
+use deno_lint::diagnostic::LintDiagnostic;
+use deno_lint::diagnostic::LintFix;
+use deno_lint::diagnostic::LintFixChange;
+use deno_runtime::tokio_util::create_basic_runtime;
+use once_cell::sync::OnceCell;
+use tokio::sync::mpsc;
+
+use crate::util::checksum;
+
+const PLUGINS_ENTRY_SPECIFIER: &str = "file:///$deno$lint_plugins.js";
+
+/// Handle to the thread running the lint plugins.
+#[derive(Debug)]
+pub struct LintPluginRunner {
+  specifiers: Vec<ModuleSpecifier>,
+  /// The plugin modules and the local modules they import, which are only
+  /// known once the plugins are loaded.
+  modules: OnceCell<Vec<ModuleSpecifier>>,
+  modules_receiver: Mutex<std::sync::mpsc::Receiver<Vec<ModuleSpecifier>>>,
+  sender: mpsc::UnboundedSender<PluginRequest>,
+}
+
+impl LintPluginRunner {
+  /// Starts the plugin thread. Errors loading the plugins are surfaced
+  /// when linting the first file.
+  pub fn new(
+    specifiers: Vec<ModuleSpecifier>,
+    excluded_rules: Vec<String>,
+  ) -> Self {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let (modules_sender, modules_receiver) = std::sync::mpsc::channel();
+    let thread_specifiers = specifiers.clone();
+    std::thread::Builder::new()
+      .name("lint-plugins".to_string())
+      .spawn(move || {
+        let runtime = create_basic_runtime();
+        runtime.block_on(run_plugin_host(
+          thread_specifiers,
+          excluded_rules,
+          modules_sender,
+          receiver,
+        ))
+      })
+      .unwrap();
+    Self {
+      specifiers,
+      modules: OnceCell::new(),
+      modules_receiver: Mutex::new(modules_receiver),
+      sender,
+    }
+  }
+
+  pub fn specifiers(&self) -> &[ModuleSpecifier] {
+    &self.specifiers
+  }
+
+  /// State that should bust the lint incremental cache when a plugin or a
+  /// module it imports changes. This blocks until the plugins are loaded.
+  pub fn incremental_cache_state(&self) -> Vec<String> {
+    let modules = self.modules.get_or_init(|| {
+      self
+        .modules_receiver
+        .lock()
+        .recv()
+        .unwrap_or_else(|_| self.specifiers.clone())
+    });
+    modules
+      .iter()
+      .map(|specifier| {
+        let hash = specifier
+          .to_file_path()
+          .ok()
+          .and_then(|path| std::fs::read(path).ok())
+          .map(|bytes| checksum::gen(&[&bytes]))
+          .unwrap_or_default();
+        format!("{specifier}#{hash}")
+      })
+      .collect()
+  }
+
+  /// Runs the plugin rules on the provided source. This blocks the current
+  /// thread, so it should not be called from an async context.
+  pub fn lint(
+    &self,
+    parsed_source: &ParsedSource,
+    ignores: &IgnoreDirectives,
+  ) -> Result<Vec<LintDiagnostic>, AnyError> {
+    let text_info = parsed_source.text_info_lazy();
+    let offset = text_info.range().start.as_byte_pos().0;
+    let program = parsed_source.program();
+    let request = serde_json::to_string(&PluginRunArgs {
+      specifier: parsed_source.specifier(),
+      text: text_info.text_str(),
+      offset,
+      ast: &program,
+    })?;
+    let (response_tx, response_rx) = std::sync::mpsc::channel();
+    self
+      .sender
+      .send(PluginRequest {
+        args: request,
+        response_tx,
+      })
+      .map_err(|_| generic_error("The lint plugin thread has stopped."))?;
+    let results = response_rx
+      .recv()
+      .map_err(|_| generic_error("The lint plugin thread has stopped."))??;
+
+    let file_len = text_info.text_str().len() as u32;
+    let to_range = |start: u32, end: u32| -> Result<SourceRange, AnyError> {
+      if start < offset || start > end || end - offset > file_len {
+        bail!(
+          "Lint plugin reported an invalid range {}..{} for {}",
+          start,
+          end,
+          parsed_source.specifier()
+        );
+      }
+      Ok(SourceRange::new(
+        SourcePos::unsafely_from_byte_pos(BytePos(start)),
+        SourcePos::unsafely_from_byte_pos(BytePos(end)),
+      ))
+    };
+    let mut diagnostics = Vec::with_capacity(results.len());
+    for result in results {
+      let range = to_range(result.start, result.end)?;
+      let line_index = text_info.line_index(range.start);
+      if ignores.is_ignored(&result.code, line_index) {
+        continue;
+      }
+      let fixes = result
+        .fixes
+        .into_iter()
+        .map(|fix| {
+          Ok(LintFix {
+            description: Cow::Owned(fix.description),
+            changes: fix
+              .changes
+              .into_iter()
+              .map(|change| {
+                Ok(LintFixChange {
+                  new_text: Cow::Owned(change.new_text),
+                  range: to_range(change.start, change.end)?,
+                })
+              })
+              .collect::<Result<Vec<_>, AnyError>>()?,
+          })
+        })
+        .collect::<Result<Vec<_>, AnyError>>()?;
+      diagnostics.push(LintDiagnostic {
+        specifier: parsed_source.specifier().clone(),
+        range,
+        text_info: text_info.clone(),
+        message: result.message,
+        code: result.code,
+        hint: result.hint,
+        fixes,
+      });
+    }
+    Ok(diagnostics)
+  }
+}
+
+/// Gets if the diagnostic code belongs to a plugin rule.
+pub fn is_plugin_code(code: &str) -> bool {
+  code.contains('/')
+}
+
+struct PluginRequest {
+  args: String,
+  response_tx: std::sync::mpsc::Sender<Result<Vec<PluginDiagnostic>, AnyError>>,
+}
+
+#[derive(Serialize)]
+struct PluginRunArgs<'a> {
+  specifier: &'a ModuleSpecifier,
+  text: &'a str,
+  offset: u32,
+  ast: &'a Program,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginDiagnostic {
+  code: String,
+  message: String,
+  hint: Option<String>,
+  start: u32,
+  end: u32,
+  fixes: Vec<PluginFix>,
+}
+
+#[derive(Deserialize)]
+struct PluginFix {
+  description: String,
+  changes: Vec<PluginFixChange>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginFixChange {
+  start: u32,
+  end: u32,
+  new_text: String,
+}
+
+async fn run_plugin_host(
+  specifiers: Vec<ModuleSpecifier>,
+  excluded_rules: Vec<String>,
+  modules_sender: std::sync::mpsc::Sender<Vec<ModuleSpecifier>>,
+  mut receiver: mpsc::UnboundedReceiver<PluginRequest>,
+) {
+  let mut host = PluginHost::load(&specifiers, excluded_rules)
+    .await
+    .map_err(|err| format!("{err:#}"));
+  // the error loading the plugins is surfaced when linting
+  let modules = match &host {
+    Ok(host) => host.modules.clone(),
+    Err(_) => specifiers.clone(),
+  };
+  // the receiver may have gone away
+  let _ = modules_sender.send(modules);
+  while let Some(request) = receiver.recv().await {
+    let result = match &mut host {
+      Ok(host) => host.run(request.args),
+      Err(message) => Err(generic_error(format!(
+        "Failed loading lint plugins: {message}"
+      ))),
+    };
+    // the receiver may have gone away
+    let _ = request.response_tx.send(result);
+  }
+}
+
+struct PluginHost {
+  runtime: JsRuntime,
+  exclude: String,
+  modules: Vec<ModuleSpecifier>,
+}
+
+impl PluginHost {
+  async fn load(
+    specifiers: &[ModuleSpecifier],
+    excluded_rules: Vec<String>,
+  ) -> Result<Self, AnyError> {
+    let module_loader = Rc::new(PluginModuleLoader::default());
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      module_loader: Some(module_loader.clone()),
+      ..Default::default()
+    });
+    runtime.execute_script(
+      "ext:deno_lint/plugin_host.js",
+      ascii_str_include!("plugin_host.js"),
+    )?;
+
+    let mut code = String::new();
+    for (i, specifier) in specifiers.iter().enumerate() {
+      code.push_str(&format!(
+        "import plugin{i} from {};\n",
+        serde_json::to_string(specifier.as_str())?
+      ));
+    }
+    let entries = specifiers
+      .iter()
+      .enumerate()
+      .map(|(i, specifier)| {
+        Ok(format!(
+          "[{}, plugin{i}]",
+          serde_json::to_string(specifier.as_str())?
+        ))
+      })
+      .collect::<Result<Vec<_>, AnyError>>()?;
+    code.push_str(&format!(
+      "globalThis[Symbol.for(\"deno.lint.host\")].register([{}]);\n",
+      entries.join(", ")
+    ));
+
+    let entry_specifier = ModuleSpecifier::parse(PLUGINS_ENTRY_SPECIFIER)?;
+    let id = runtime
+      .load_main_es_module_from_code(&entry_specifier, code)
+      .await?;
+    let receiver = runtime.mod_evaluate(id);
+    runtime.run_event_loop(Default::default()).await?;
+    receiver.await?;
+
+    let modules = module_loader.loaded.take();
+    Ok(Self {
+      runtime,
+      exclude: serde_json::to_string(&excluded_rules)?,
+      modules,
+    })
+  }
+
+  fn run(&mut self, args: String) -> Result<Vec<PluginDiagnostic>, AnyError> {
+    let script = format!(
+      "globalThis[Symbol.for(\"deno.lint.host\")].run({{ ...{args}, exclude: {} }})",
+      self.exclude
+    );
+    let global = self
+      .runtime
+      .execute_script("ext:deno_lint/run_plugins.js", script)?;
+    let scope = &mut self.runtime.handle_scope();
+    let local = v8::Local::new(scope, global);
+    Ok(serde_v8::from_v8::<Vec<PluginDiagnostic>>(scope, local)?)
+  }
+}
+
+/// Loads local plugin modules, transpiling TypeScript and JSX.
+#[derive(Default)]
+struct PluginModuleLoader {
+  loaded: RefCell<Vec<ModuleSpecifier>>,
+}
+
+impl ModuleLoader for PluginModuleLoader {
+  fn resolve(
+    &self,
+    specifier: &str,
+    referrer: &str,
+    _kind: ResolutionKind,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    Ok(deno_core::resolve_import(specifier, referrer)?)
+  }
+
+  fn load(
+    &self,
+    specifier: &ModuleSpecifier,
+    _maybe_referrer: Option<&ModuleSpecifier>,
+    _is_dynamic: bool,
+    _requested_module_type: RequestedModuleType,
+  ) -> ModuleLoadResponse {
+    self.loaded.borrow_mut().push(specifier.clone());
+    ModuleLoadResponse::Sync(load_plugin_module(specifier))
+  }
+}
+
+fn load_plugin_module(
+  specifier: &ModuleSpecifier,
+) -> Result<ModuleSource, AnyError> {
+  if specifier.scheme() != "file" {
+    bail!(
+      "Lint plugins can only import local modules. Attempted to import: {}",
+      specifier
+    );
+  }
+  let path = specifier
+    .to_file_path()
+    .map_err(|_| generic_error(format!("Invalid file path: {specifier}")))?;
+  let text = std::fs::read_to_string(&path)
+    .with_context(|| format!("Failed reading lint plugin '{specifier}'."))?;
+  let media_type = MediaType::from_specifier(specifier);
+  let code = match media_type {
+    MediaType::JavaScript | MediaType::Mjs => text,
+    MediaType::TypeScript
+    | MediaType::Mts
+    | MediaType::Jsx
+    | MediaType::Tsx => {
+      let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+        specifier: specifier.clone(),
+        text: deno_ast::strip_bom(text).into(),
+        media_type,
+        capture_tokens: false,
+        maybe_syntax: None,
+        scope_analysis: false,
+      })?;
+      parsed_source
+        .transpile(&Default::default(), &Default::default())?
+        .into_source()
+        .into_string()?
+        .text
+    }
+    _ => bail!(
+      "Unsupported lint plugin module type {}: {}",
+      media_type,
+      specifier
+    ),
+  };
+  Ok(ModuleSource::new(
+    ModuleType::JavaScript,
+    ModuleSourceCode::String(code.into()),
+    specifier,
+    None,
+  ))
+}
+
+/// `deno-lint-ignore-file` and `deno-lint-ignore` comments, which the plugin
+/// rules respect the same way as the built-in rules.
+pub struct IgnoreDirectives {
+  /// `None` when the whole file is ignored.
+  file_codes: Option<HashSet<String>>,
+  /// Codes ignored on the line following the comment, keyed by the line
+  /// index of the comment. An empty set ignores all codes.
+  line_codes: Vec<(usize, HashSet<String>)>,
+  /// The plugin codes of each directive, keyed by the line index of the
+  /// comment.
+  plugin_codes_by_line: Vec<(usize, Vec<String>)>,
+}
+
+impl IgnoreDirectives {
+  pub fn collect(parsed_source: &ParsedSource) -> Self {
+    let text_info = parsed_source.text_info_lazy();
+    let mut file_codes = Some(HashSet::new());
+    let mut line_codes = Vec::new();
+    let mut plugin_codes_by_line = Vec::new();
+    for comment in parsed_source.comments().get_vec() {
+      let text = match comment.kind {
+        CommentKind::Line => comment.text.trim(),
+        CommentKind::Block => {
+          comment.text.trim().trim_start_matches('*').trim()
+        }
+      };
+      let comment_line = text_info
+        .line_index(SourcePos::unsafely_from_byte_pos(comment.span.lo));
+      if let Some(rest) = text.strip_prefix("deno-lint-ignore-file") {
+        let codes = parse_codes(rest);
+        plugin_codes_by_line.push((comment_line, filter_plugin_codes(&codes)));
+        if codes.is_empty() {
+          file_codes = None;
+        } else if let Some(file_codes) = &mut file_codes {
+          file_codes.extend(codes);
+        }
+      } else if let Some(rest) = text.strip_prefix("deno-lint-ignore") {
+        if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+          let line_index = text_info
+            .line_index(SourcePos::unsafely_from_byte_pos(comment.span.hi));
+          let codes = parse_codes(rest);
+          plugin_codes_by_line
+            .push((comment_line, filter_plugin_codes(&codes)));
+          line_codes.push((line_index, codes));
+        }
+      }
+    }
+    Self {
+      file_codes,
+      line_codes,
+      plugin_codes_by_line,
+    }
+  }
+
+  /// Gets if the diagnostic is the built-in linter reporting a plugin code
+  /// of an ignore directive as unknown or unused, because it doesn't know
+  /// about the plugin rules.
+  pub fn is_plugin_code_diagnostic(&self, diagnostic: &LintDiagnostic) -> bool {
+    if !matches!(
+      diagnostic.code.as_str(),
+      "ban-unknown-rule-code" | "ban-unused-ignore"
+    ) {
+      return false;
+    }
+    let line_index = diagnostic.text_info.line_index(diagnostic.range.start);
+    self
+      .plugin_codes_by_line
+      .iter()
+      .filter(|(comment_line, _)| *comment_line == line_index)
+      .flat_map(|(_, codes)| codes)
+      .any(|code| diagnostic.message.contains(&format!("\"{code}\"")))
+  }
+
+  fn is_ignored(&self, code: &str, line_index: usize) -> bool {
+    match &self.file_codes {
+      None => return true,
+      Some(codes) if codes.contains(code) => return true,
+      Some(_) => {}
+    }
+    self.line_codes.iter().any(|(comment_line, codes)| {
+      comment_line + 1 == line_index
+        && (codes.is_empty() || codes.contains(code))
+    })
+  }
+}
+
+fn filter_plugin_codes(codes: &HashSet<String>) -> Vec<String> {
+  codes
+    .iter()
+    .filter(|code| is_plugin_code(code))
+    .cloned()
+    .collect()
+}
+
+fn parse_codes(text: &str) -> HashSet<String> {
+  // allow for an explanation after the codes, ex. `-- reason`
+  let text = text.split("--").next().unwrap_or_default();
+  text
+    .split(|c: char| c.is_whitespace() || c == ',')
+    .filter(|c| !c.is_empty())
+    .map(ToOwned::to_owned)
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn parse(text: &str) -> ParsedSource {
+    deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: ModuleSpecifier::parse("file:///mod.ts").unwrap(),
+      text: text.into(),
+      media_type: MediaType::TypeScript,
+      capture_tokens: true,
+      maybe_syntax: None,
+      scope_analysis: false,
+    })
+    .unwrap()
+  }
+
+  #[test]
+  fn ignore_directives() {
+    let parsed_source = parse(
+      r#"// deno-lint-ignore-file my-plugin/a
+// deno-lint-ignore my-plugin/b -- reason
+const b = 1;
+// deno-lint-ignore
+const c = 2;
+"#,
+    );
+    let ignores = IgnoreDirectives::collect(&parsed_source);
+    assert!(ignores.is_ignored("my-plugin/a", 4));
+    assert!(ignores.is_ignored("my-plugin/b", 2));
+    assert!(!ignores.is_ignored("my-plugin/b", 3));
+    assert!(!ignores.is_ignored("my-plugin/c", 2));
+    assert!(ignores.is_ignored("my-plugin/c", 4));
+
+    let parsed_source = parse("// deno-lint-ignore-file\nconst a = 1;\n");
+    let ignores = IgnoreDirectives::collect(&parsed_source);
+    assert!(ignores.is_ignored("my-plugin/a", 1));
+  }
+
+  #[test]
+  fn plugin_code_diagnostics() {
+    let parsed_source = parse(
+      "// deno-lint-ignore my-plugin/a no-explicit-any\nconst a: any = 1;\n",
+    );
+    let ignores = IgnoreDirectives::collect(&parsed_source);
+    let text_info = parsed_source.text_info_lazy();
+    let diagnostic = |code: &str, message: &str, line: usize| LintDiagnostic {
+      specifier: parsed_source.specifier().clone(),
+      range: SourceRange::new(
+        text_info.line_start(line),
+        text_info.line_end(line),
+      ),
+      text_info: text_info.clone(),
+      message: message.to_string(),
+      code: code.to_string(),
+      hint: None,
+      fixes: Vec::new(),
+    };
+    assert!(ignores.is_plugin_code_diagnostic(&diagnostic(
+      "ban-unknown-rule-code",
+      "Unknown rule for code \"my-plugin/a\"",
+      0,
+    )));
+    assert!(ignores.is_plugin_code_diagnostic(&diagnostic(
+      "ban-unused-ignore",
+      "Ignore for code \"my-plugin/a\" was not used.",
+      0,
+    )));
+    assert!(!ignores.is_plugin_code_diagnostic(&diagnostic(
+      "ban-unused-ignore",
+      "Ignore for code \"no-explicit-any\" was not used.",
+      0,
+    )));
+    // not reported on the directive
+    assert!(!ignores.is_plugin_code_diagnostic(&diagnostic(
+      "ban-unused-ignore",
+      "Ignore for code \"my-plugin/a\" was not used.",
+      1,
+    )));
+  }
+
+  #[test]
+  fn plugin_codes() {
+    assert!(is_plugin_code("my-plugin/no-foo"));
+    assert!(!is_plugin_code("no-unused-vars"));
+  }
+}
@@ -0,0 +1,777 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use deno_ast::diagnostics::Diagnostic;
+use deno_ast::diagnostics::DiagnosticLevel;
+use deno_ast::ModuleSpecifier;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use indexmap::IndexMap;
+use log::info;
+use serde::Serialize;
+
+use crate::args::LintReporterKind;
+use crate::colors;
+use crate::util::path::to_percent_decoded_str;
+
+use super::LintOrCliDiagnostic;
+
+pub fn create_reporter(
+  kind: LintReporterKind,
+  cwd: Url,
+) -> Box<dyn LintReporter + Send> {
+  match kind {
+    LintReporterKind::Pretty => Box::new(PrettyLintReporter::new()),
+    LintReporterKind::Json => Box::new(JsonLintReporter::new()),
+    LintReporterKind::Compact => Box::new(CompactLintReporter::new()),
+    LintReporterKind::Sarif => Box::new(SarifLintReporter::new(cwd)),
+    LintReporterKind::Github => Box::new(GithubLintReporter::new(cwd)),
+    LintReporterKind::Junit => Box::new(JunitLintReporter::new(cwd)),
+  }
+}
+
+pub trait LintReporter {
+  fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic);
+  fn visit_error(&mut self, file_path: &str, err: &AnyError);
+  fn close(&mut self, check_count: usize);
+}
+
+#[derive(Serialize)]
+struct LintError {
+  file_path: String,
+  message: String,
+}
+
+struct PrettyLintReporter {
+  lint_count: u32,
+  fixable_diagnostics: u32,
+}
+
+impl PrettyLintReporter {
+  fn new() -> PrettyLintReporter {
+    PrettyLintReporter {
+      lint_count: 0,
+      fixable_diagnostics: 0,
+    }
+  }
+}
+
+impl LintReporter for PrettyLintReporter {
+  fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic) {
+    self.lint_count += 1;
+    if let LintOrCliDiagnostic::Lint(d) = d {
+      if !d.fixes.is_empty() {
+        self.fixable_diagnostics += 1;
+      }
+    }
+
+    log::error!("{}\n", d.display());
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    log::error!("Error linting: {file_path}");
+    log::error!("   {err}");
+  }
+
+  fn close(&mut self, check_count: usize) {
+    let fixable_suffix = if self.fixable_diagnostics > 0 {
+      colors::gray(format!(" ({} fixable via --fix)", self.fixable_diagnostics))
+        .to_string()
+    } else {
+      "".to_string()
+    };
+    match self.lint_count {
+      1 => info!("Found 1 problem{}", fixable_suffix),
+      n if n > 1 => {
+        info!("Found {} problems{}", self.lint_count, fixable_suffix)
+      }
+      _ => (),
+    }
+
+    match check_count {
+      n if n <= 1 => info!("Checked {} file", n),
+      n if n > 1 => info!("Checked {} files", n),
+      _ => unreachable!(),
+    }
+  }
+}
+
+struct CompactLintReporter {
+  lint_count: u32,
+}
+
+impl CompactLintReporter {
+  fn new() -> CompactLintReporter {
+    CompactLintReporter { lint_count: 0 }
+  }
+}
+
+impl LintReporter for CompactLintReporter {
+  fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic) {
+    self.lint_count += 1;
+
+    match d.range() {
+      Some((text_info, range)) => {
+        let line_and_column = text_info.line_and_column_display(range.start);
+        log::error!(
+          "{}: line {}, col {} - {} ({})",
+          d.specifier(),
+          line_and_column.line_number,
+          line_and_column.column_number,
+          d.message(),
+          d.code(),
+        )
+      }
+      None => {
+        log::error!("{}: {} ({})", d.specifier(), d.message(), d.code())
+      }
+    }
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    log::error!("Error linting: {file_path}");
+    log::error!("   {err}");
+  }
+
+  fn close(&mut self, check_count: usize) {
+    match self.lint_count {
+      1 => info!("Found 1 problem"),
+      n if n > 1 => info!("Found {} problems", self.lint_count),
+      _ => (),
+    }
+
+    match check_count {
+      n if n <= 1 => info!("Checked {} file", n),
+      n if n > 1 => info!("Checked {} files", n),
+      _ => unreachable!(),
+    }
+  }
+}
+
+// WARNING: Ensure doesn't change because it's used in the JSON output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonDiagnosticLintPosition {
+  /// The 1-indexed line number.
+  pub line: usize,
+  /// The 0-indexed column index.
+  pub col: usize,
+  pub byte_pos: usize,
+}
+
+impl JsonDiagnosticLintPosition {
+  pub fn new(byte_index: usize, loc: deno_ast::LineAndColumnIndex) -> Self {
+    JsonDiagnosticLintPosition {
+      line: loc.line_index + 1,
+      col: loc.column_index,
+      byte_pos: byte_index,
+    }
+  }
+}
+
+// WARNING: Ensure doesn't change because it's used in the JSON output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct JsonLintDiagnosticRange {
+  pub start: JsonDiagnosticLintPosition,
+  pub end: JsonDiagnosticLintPosition,
+}
+
+// WARNING: Ensure doesn't change because it's used in the JSON output
+#[derive(Clone, Serialize)]
+struct JsonLintDiagnostic {
+  pub filename: String,
+  pub range: Option<JsonLintDiagnosticRange>,
+  pub message: String,
+  pub code: String,
+  pub hint: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonLintReporter {
+  diagnostics: Vec<JsonLintDiagnostic>,
+  errors: Vec<LintError>,
+}
+
+impl JsonLintReporter {
+  fn new() -> JsonLintReporter {
+    JsonLintReporter {
+      diagnostics: Vec::new(),
+      errors: Vec::new(),
+    }
+  }
+}
+
+impl LintReporter for JsonLintReporter {
+  fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic) {
+    self.diagnostics.push(JsonLintDiagnostic {
+      filename: d.specifier().to_string(),
+      range: d.range().map(|(text_info, range)| JsonLintDiagnosticRange {
+        start: JsonDiagnosticLintPosition::new(
+          range.start.as_byte_index(text_info.range().start),
+          text_info.line_and_column_index(range.start),
+        ),
+        end: JsonDiagnosticLintPosition::new(
+          range.end.as_byte_index(text_info.range().start),
+          text_info.line_and_column_index(range.end),
+        ),
+      }),
+      message: d.message().to_string(),
+      code: d.code().to_string(),
+      hint: d.hint().map(|h| h.to_string()),
+    });
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    self.errors.push(LintError {
+      file_path: file_path.to_string(),
+      message: err.to_string(),
+    });
+  }
+
+  fn close(&mut self, _check_count: usize) {
+    sort_diagnostics(&mut self.diagnostics);
+    let json = serde_json::to_string_pretty(&self);
+    #[allow(clippy::print_stdout)]
+    {
+      println!("{}", json.unwrap());
+    }
+  }
+}
+
+fn sort_diagnostics(diagnostics: &mut [JsonLintDiagnostic]) {
+  // Sort so that we guarantee a deterministic output which is useful for tests
+  diagnostics.sort_by(|a, b| {
+    use std::cmp::Ordering;
+    let file_order = a.filename.cmp(&b.filename);
+    match file_order {
+      Ordering::Equal => match &a.range {
+        Some(a_range) => match &b.range {
+          Some(b_range) => {
+            let line_order = a_range.start.line.cmp(&b_range.start.line);
+            match line_order {
+              Ordering::Equal => a_range.start.col.cmp(&b_range.start.col),
+              _ => line_order,
+            }
+          }
+          None => Ordering::Less,
+        },
+        None => match &b.range {
+          Some(_) => Ordering::Greater,
+          None => Ordering::Equal,
+        },
+      },
+      _ => file_order,
+    }
+  });
+}
+
+/// Gets the path of a linted file for display, relative to the current
+/// directory when possible.
+fn display_path(cwd: &Url, specifier: &ModuleSpecifier) -> String {
+  if specifier.scheme() == "file" {
+    if let Some(relative) = cwd.make_relative(specifier) {
+      if !relative.starts_with("../") {
+        return to_percent_decoded_str(&relative);
+      }
+    }
+    if let Ok(path) = specifier.to_file_path() {
+      return path.display().to_string();
+    }
+  }
+  specifier.to_string()
+}
+
+fn display_file_path(cwd: &Url, file_path: &str) -> String {
+  match ModuleSpecifier::from_file_path(file_path) {
+    Ok(specifier) => display_path(cwd, &specifier),
+    Err(()) => file_path.to_string(),
+  }
+}
+
+/// The 1-indexed start and end of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct DiagnosticRegion {
+  start_line: usize,
+  start_column: usize,
+  end_line: usize,
+  end_column: usize,
+}
+
+impl DiagnosticRegion {
+  fn from_diagnostic(d: &LintOrCliDiagnostic) -> Option<Self> {
+    let (text_info, range) = d.range()?;
+    let start = text_info.line_and_column_display(range.start);
+    let end = text_info.line_and_column_display(range.end);
+    Some(DiagnosticRegion {
+      start_line: start.line_number,
+      start_column: start.column_number,
+      end_line: end.line_number,
+      end_column: end.column_number,
+    })
+  }
+}
+
+fn level_name(level: DiagnosticLevel) -> &'static str {
+  match level {
+    DiagnosticLevel::Error => "error",
+    DiagnosticLevel::Warning => "warning",
+  }
+}
+
+fn message_with_hint(d: &LintOrCliDiagnostic) -> String {
+  match d.hint() {
+    Some(hint) => format!("{}\nhint: {}", d.message(), hint),
+    None => d.message().to_string(),
+  }
+}
+
+// WARNING: Ensure doesn't change because it's used in the SARIF output
+// https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLog {
+  #[serde(rename = "$schema")]
+  schema: &'static str,
+  version: &'static str,
+  runs: Vec<SarifRun>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRun {
+  tool: SarifTool,
+  original_uri_base_ids: HashMap<&'static str, SarifArtifactLocation>,
+  results: Vec<SarifResult>,
+  invocations: Vec<SarifInvocation>,
+}
+
+#[derive(Serialize)]
+struct SarifTool {
+  driver: SarifToolComponent,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifToolComponent {
+  name: &'static str,
+  information_uri: &'static str,
+  version: &'static str,
+  rules: Vec<SarifReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifReportingDescriptor {
+  id: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  help_uri: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+  rule_id: String,
+  rule_index: usize,
+  level: &'static str,
+  message: SarifMessage,
+  locations: Vec<SarifLocation>,
+}
+
+#[derive(Serialize)]
+struct SarifMessage {
+  text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+  physical_location: SarifPhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+  artifact_location: SarifArtifactLocation,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  region: Option<SarifRegion>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactLocation {
+  uri: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  uri_base_id: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+  start_line: usize,
+  start_column: usize,
+  end_line: usize,
+  end_column: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifInvocation {
+  execution_successful: bool,
+  tool_execution_notifications: Vec<SarifNotification>,
+}
+
+#[derive(Serialize)]
+struct SarifNotification {
+  level: &'static str,
+  message: SarifMessage,
+  locations: Vec<SarifLocation>,
+}
+
+const SARIF_SRCROOT: &str = "%SRCROOT%";
+
+struct SarifLintReporter {
+  cwd: Url,
+  rules: IndexMap<String, SarifReportingDescriptor>,
+  results: Vec<(SarifResult, Option<DiagnosticRegion>)>,
+  notifications: Vec<SarifNotification>,
+}
+
+impl SarifLintReporter {
+  fn new(cwd: Url) -> SarifLintReporter {
+    SarifLintReporter {
+      cwd,
+      rules: IndexMap::new(),
+      results: Vec::new(),
+      notifications: Vec::new(),
+    }
+  }
+
+  /// Files in the current directory are referenced relative to the
+  /// `%SRCROOT%` base so that code scanning can map them to the repository.
+  fn artifact_location(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> SarifArtifactLocation {
+    if specifier.scheme() == "file" {
+      if let Some(relative) = self.cwd.make_relative(specifier) {
+        if !relative.starts_with("../") {
+          return SarifArtifactLocation {
+            uri: relative,
+            uri_base_id: Some(SARIF_SRCROOT),
+          };
+        }
+      }
+    }
+    SarifArtifactLocation {
+      uri: specifier.to_string(),
+      uri_base_id: None,
+    }
+  }
+}
+
+impl LintReporter for SarifLintReporter {
+  fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic) {
+    let code = d.code().to_string();
+    let rule_index = match self.rules.get_index_of(&code) {
+      Some(index) => index,
+      None => {
+        let help_uri = d.docs_url().map(|url| url.to_string());
+        self
+          .rules
+          .insert_full(
+            code.clone(),
+            SarifReportingDescriptor {
+              id: code.clone(),
+              help_uri,
+            },
+          )
+          .0
+      }
+    };
+    let region = DiagnosticRegion::from_diagnostic(&d);
+    let result = SarifResult {
+      rule_id: code,
+      rule_index,
+      level: level_name(d.level()),
+      message: SarifMessage {
+        text: message_with_hint(&d),
+      },
+      locations: vec![SarifLocation {
+        physical_location: SarifPhysicalLocation {
+          artifact_location: self.artifact_location(d.specifier()),
+          region: region.map(|r| SarifRegion {
+            start_line: r.start_line,
+            start_column: r.start_column,
+            end_line: r.end_line,
+            end_column: r.end_column,
+          }),
+        },
+      }],
+    };
+    self.results.push((result, region));
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    let locations = match ModuleSpecifier::from_file_path(file_path) {
+      Ok(specifier) => vec![SarifLocation {
+        physical_location: SarifPhysicalLocation {
+          artifact_location: self.artifact_location(&specifier),
+          region: None,
+        },
+      }],
+      Err(()) => Vec::new(),
+    };
+    self.notifications.push(SarifNotification {
+      level: "error",
+      message: SarifMessage {
+        text: err.to_string(),
+      },
+      locations,
+    });
+  }
+
+  fn close(&mut self, _check_count: usize) {
+    let mut results = std::mem::take(&mut self.results);
+    // Sort so that we guarantee a deterministic output
+    results.sort_by(|(a, a_region), (b, b_region)| {
+      let a_uri = &a.locations[0].physical_location.artifact_location.uri;
+      let b_uri = &b.locations[0].physical_location.artifact_location.uri;
+      a_uri.cmp(b_uri).then_with(|| a_region.cmp(b_region))
+    });
+    let log = SarifLog {
+      schema: "https://json.schemastore.org/sarif-2.1.0.json",
+      version: "2.1.0",
+      runs: vec![SarifRun {
+        tool: SarifTool {
+          driver: SarifToolComponent {
+            name: "deno lint",
+            information_uri:
+              "https://docs.deno.com/runtime/manual/tools/linter",
+            version: crate::version::deno(),
+            rules: std::mem::take(&mut self.rules).into_values().collect(),
+          },
+        },
+        original_uri_base_ids: HashMap::from([(
+          SARIF_SRCROOT,
+          SarifArtifactLocation {
+            uri: self.cwd.to_string(),
+            uri_base_id: None,
+          },
+        )]),
+        results: results.into_iter().map(|(result, _)| result).collect(),
+        invocations: vec![SarifInvocation {
+          execution_successful: self.notifications.is_empty(),
+          tool_execution_notifications: std::mem::take(&mut self.notifications),
+        }],
+      }],
+    };
+    let json = serde_json::to_string_pretty(&log);
+    #[allow(clippy::print_stdout)]
+    {
+      println!("{}", json.unwrap());
+    }
+  }
+}
+
+/// Reports the diagnostics as GitHub Actions workflow commands, which show
+/// up as annotations on the affected lines.
+/// https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions
+struct GithubLintReporter {
+  cwd: Url,
+  lint_count: u32,
+}
+
+impl GithubLintReporter {
+  fn new(cwd: Url) -> GithubLintReporter {
+    GithubLintReporter { cwd, lint_count: 0 }
+  }
+}
+
+fn escape_github_data(value: &str) -> String {
+  value
+    .replace('%', "%25")
+    .replace('\r', "%0D")
+    .replace('\n', "%0A")
+}
+
+fn escape_github_property(value: &str) -> String {
+  escape_github_data(value)
+    .replace(':', "%3A")
+    .replace(',', "%2C")
+}
+
+#[allow(clippy::print_stdout)]
+fn print_github_command(
+  command: &str,
+  properties: &[(&str, String)],
+  message: &str,
+) {
+  let properties = properties
+    .iter()
+    .map(|(name, value)| format!("{}={}", name, escape_github_property(value)))
+    .collect::<Vec<_>>()
+    .join(",");
+  println!(
+    "::{} {}::{}",
+    command,
+    properties,
+    escape_github_data(message)
+  );
+}
+
+impl LintReporter for GithubLintReporter {
+  fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic) {
+    self.lint_count += 1;
+
+    let mut properties = vec![("file", display_path(&self.cwd, d.specifier()))];
+    if let Some(region) = DiagnosticRegion::from_diagnostic(&d) {
+      properties.push(("line", region.start_line.to_string()));
+      properties.push(("col", region.start_column.to_string()));
+      properties.push(("endLine", region.end_line.to_string()));
+      properties.push(("endColumn", region.end_column.to_string()));
+    }
+    properties.push(("title", d.code().to_string()));
+    let mut message = message_with_hint(&d);
+    if let Some(docs_url) = d.docs_url() {
+      message.push_str(&format!("\ndocs: {}", docs_url));
+    }
+    print_github_command(level_name(d.level()), &properties, &message);
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    print_github_command(
+      "error",
+      &[("file", display_file_path(&self.cwd, file_path))],
+      &format!("Error linting: {err}"),
+    );
+  }
+
+  fn close(&mut self, check_count: usize) {
+    match self.lint_count {
+      1 => info!("Found 1 problem"),
+      n if n > 1 => info!("Found {} problems", self.lint_count),
+      _ => (),
+    }
+
+    match check_count {
+      n if n <= 1 => info!("Checked {} file", n),
+      n if n > 1 => info!("Checked {} files", n),
+      _ => unreachable!(),
+    }
+  }
+}
+
+/// Reports each diagnostic as a failed test case of the test suite of its
+/// file.
+struct JunitLintReporter {
+  cwd: Url,
+  suites: BTreeMap<String, quick_junit::TestSuite>,
+}
+
+impl JunitLintReporter {
+  fn new(cwd: Url) -> JunitLintReporter {
+    JunitLintReporter {
+      cwd,
+      suites: BTreeMap::new(),
+    }
+  }
+
+  fn add_test_case(&mut self, filename: String, case: quick_junit::TestCase) {
+    self
+      .suites
+      .entry(filename.clone())
+      .or_insert_with(|| quick_junit::TestSuite::new(filename))
+      .add_test_case(case);
+  }
+}
+
+impl LintReporter for JunitLintReporter {
+  fn visit_diagnostic(&mut self, d: LintOrCliDiagnostic) {
+    let filename = display_path(&self.cwd, d.specifier());
+    let region = DiagnosticRegion::from_diagnostic(&d);
+    let name = match region {
+      Some(region) => format!(
+        "{} at line {}, col {}",
+        d.code(),
+        region.start_line,
+        region.start_column
+      ),
+      None => d.code().to_string(),
+    };
+    let mut description = format!("{}: {}", filename, message_with_hint(&d));
+    if let Some(docs_url) = d.docs_url() {
+      description.push_str(&format!("\ndocs: {}", docs_url));
+    }
+    let mut case = quick_junit::TestCase::new(
+      name,
+      quick_junit::TestCaseStatus::NonSuccess {
+        kind: quick_junit::NonSuccessKind::Failure,
+        message: Some(d.message().to_string()),
+        ty: Some(d.code().to_string()),
+        description: Some(description),
+        reruns: vec![],
+      },
+    );
+    case.classname = Some(filename.clone());
+    if let Some(region) = region {
+      case
+        .extra
+        .insert(String::from("line"), region.start_line.to_string());
+      case
+        .extra
+        .insert(String::from("col"), region.start_column.to_string());
+    }
+    self.add_test_case(filename, case);
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    let filename = display_file_path(&self.cwd, file_path);
+    let mut case = quick_junit::TestCase::new(
+      "lint",
+      quick_junit::TestCaseStatus::NonSuccess {
+        kind: quick_junit::NonSuccessKind::Error,
+        message: Some(format!("Error linting: {filename}")),
+        ty: None,
+        description: Some(err.to_string()),
+        reruns: vec![],
+      },
+    );
+    case.classname = Some(filename.clone());
+    self.add_test_case(filename, case);
+  }
+
+  fn close(&mut self, _check_count: usize) {
+    let mut report = quick_junit::Report::new("deno lint");
+    report.add_test_suites(std::mem::take(&mut self.suites).into_values());
+    if let Err(err) = report.serialize(std::io::stdout()) {
+      log::error!("Failed to write JUnit report to stdout: {err}");
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_escape_github_property() {
+    assert_eq!(escape_github_property("a,b:c%d\ne"), "a%2Cb%3Ac%25d%0Ae");
+    assert_eq!(escape_github_data("a,b:c\r\n"), "a,b:c%0D%0A");
+  }
+
+  #[test]
+  fn test_display_path() {
+    let cwd = Url::parse("file:///project/").unwrap();
+    assert_eq!(
+      display_path(&cwd, &Url::parse("file:///project/src/a%20b.ts").unwrap()),
+      "src/a b.ts"
+    );
+    assert_eq!(
+      display_path(&cwd, &Url::parse("https://deno.land/x/mod.ts").unwrap()),
+      "https://deno.land/x/mod.ts"
+    );
+  }
+}
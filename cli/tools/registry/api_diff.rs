@@ -0,0 +1,1027 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Compares the public API of a package to a previous version, to find the
+//! version bump the changes require. The API is read from the documentation
+//! of the exports, so a symbol is identified by its export and name, and
+//! compared by the structure of its declaration.
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fmt::Write;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+
+use deno_config::ConfigFile;
+use deno_config::WorkspaceMemberConfig;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_doc::js_doc::JsDoc;
+use deno_doc::DocNode;
+use deno_doc::DocNodeKind;
+use deno_doc::DocParser;
+use deno_doc::DocParserOptions;
+use deno_graph::GraphKind;
+use deno_graph::ModuleSpecifier;
+use deno_semver::package::PackageNv;
+use deno_semver::Version;
+use deno_terminal::colors;
+use serde::Serialize;
+
+use crate::args::ApiDiffFlags;
+use crate::args::CacheSetting;
+use crate::args::Flags;
+use crate::cache::ParsedSourceCache;
+use crate::factory::CliFactory;
+use crate::file_fetcher::FileFetcher;
+use crate::graph_util::ModuleGraphCreator;
+use crate::jsr::JsrFetchResolver;
+use crate::tools::doc::members;
+use crate::tools::doc::shorter_declarations;
+use crate::tools::doc::signature;
+use crate::util::display::write_json_to_stdout;
+use crate::util::git;
+
+const CONFIG_FILE_NAMES: [&str; 4] =
+  ["deno.json", "deno.jsonc", "jsr.json", "jsr.jsonc"];
+
+pub async fn api_diff(
+  flags: Flags,
+  api_diff_flags: ApiDiffFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags)?;
+  let cli_options = factory.cli_options();
+  let Some(config_file) = cli_options.maybe_config_file() else {
+    bail!(
+      "Couldn't find a deno.json, deno.jsonc, jsr.json or jsr.jsonc configuration file in {}.",
+      cli_options.initial_cwd().display()
+    );
+  };
+  let Some(package_name) = &config_file.json.name else {
+    bail!("Missing 'name' field in {}.", config_file.specifier);
+  };
+
+  let differ = ApiDiffer::new(&factory).await?;
+  let base = differ
+    .resolve_base(api_diff_flags.base.as_deref(), package_name)
+    .await?;
+  let changes = differ.diff(&base, config_file).await?;
+  let bump = required_bump(&changes);
+
+  if api_diff_flags.json {
+    return write_json_to_stdout(&json!({
+      "base": base.to_string(),
+      "bump": bump,
+      "changes": changes,
+    }));
+  }
+
+  if changes.is_empty() {
+    log::info!("No changes to the public API since {}.", base);
+  } else {
+    log::info!("Changes to the public API since {}:\n", base);
+    for change in &changes {
+      log::info!("{}", change);
+    }
+    log::info!("");
+  }
+  log::info!(
+    "Required version bump: {}",
+    colors::bold(
+      bump
+        .map(|bump| bump.to_string())
+        .unwrap_or_else(|| "none".to_string())
+    )
+  );
+  Ok(())
+}
+
+/// Fails when the version of a package is bumped less than the changes to
+/// its public API since the latest published version require.
+pub async fn check_semver(
+  factory: &CliFactory,
+  packages: &[WorkspaceMemberConfig],
+) -> Result<(), AnyError> {
+  let differ = ApiDiffer::new(factory).await?;
+  let mut errors = Vec::new();
+  for package in packages {
+    let Some(version) = &package.config_file.json.version else {
+      continue;
+    };
+    let version = Version::parse_standard(version)?;
+    let Some(published) = differ.latest_version(&package.package_name).await
+    else {
+      log::debug!("No published version of {}.", package.package_name);
+      continue;
+    };
+    let Some(actual) = Bump::between(&published.version, &version) else {
+      // the registry rejects versions that aren't newer
+      continue;
+    };
+    let published_version = published.version.clone();
+    let changes = differ
+      .diff(&ApiBase::Jsr(published), &package.config_file)
+      .await?;
+    let Some(required) = required_bump(&changes) else {
+      continue;
+    };
+    if actual < required {
+      let mut error = format!(
+        "{}@{} is a {} version bump from {}, but the public API changes require a {} version bump:\n",
+        package.package_name, version, actual, published_version, required
+      );
+      for change in changes.iter().filter(|change| change.bump > actual) {
+        write!(error, "\n{}", change).unwrap();
+      }
+      errors.push(error);
+    }
+  }
+  if !errors.is_empty() {
+    bail!(
+      "{}\n\nBump the version or run without --check-semver.",
+      errors.join("\n\n")
+    );
+  }
+  Ok(())
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Bump {
+  Patch,
+  Minor,
+  Major,
+}
+
+impl Bump {
+  /// The bump from one version to the next, or `None` if the next version
+  /// isn't newer. Like for semver ranges, bumping the minor version of a
+  /// `0.x` version is a major bump and bumping its patch version a minor
+  /// bump.
+  pub fn between(from: &Version, to: &Version) -> Option<Bump> {
+    if to <= from {
+      return None;
+    }
+    let bump = if to.major != from.major {
+      Bump::Major
+    } else if to.minor != from.minor {
+      Bump::Minor
+    } else {
+      Bump::Patch
+    };
+    if to.major == 0 {
+      Some(match bump {
+        Bump::Major | Bump::Minor => Bump::Major,
+        Bump::Patch => Bump::Minor,
+      })
+    } else {
+      Some(bump)
+    }
+  }
+}
+
+impl fmt::Display for Bump {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Bump::Patch => write!(f, "patch"),
+      Bump::Minor => write!(f, "minor"),
+      Bump::Major => write!(f, "major"),
+    }
+  }
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiChangeKind {
+  Added,
+  Removed,
+  Changed,
+  DocChanged,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiChange {
+  pub export: String,
+  /// The dot separated name of the symbol, or `None` when the whole export
+  /// was added or removed.
+  pub symbol: Option<String>,
+  pub kind: ApiChangeKind,
+  pub bump: Bump,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub old_signature: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub new_signature: Option<String>,
+}
+
+impl fmt::Display for ApiChange {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let target = match &self.symbol {
+      Some(symbol) => format!("`{}` in {}", symbol, self.export),
+      None => format!("export {}", self.export),
+    };
+    let bump = colors::gray(format!("({})", self.bump));
+    match self.kind {
+      ApiChangeKind::Added => {
+        write!(f, "{} added {} {}", colors::green("+"), target, bump)
+      }
+      ApiChangeKind::Removed => {
+        write!(f, "{} removed {} {}", colors::red("-"), target, bump)
+      }
+      ApiChangeKind::Changed => {
+        write!(f, "{} changed {} {}", colors::yellow("~"), target, bump)?;
+        for line in self.old_signature.iter().flat_map(|s| s.lines()) {
+          write!(f, "\n    {}", colors::red(format!("- {line}")))?;
+        }
+        for line in self.new_signature.iter().flat_map(|s| s.lines()) {
+          write!(f, "\n    {}", colors::green(format!("+ {line}")))?;
+        }
+        Ok(())
+      }
+      ApiChangeKind::DocChanged => write!(
+        f,
+        "{} changed the documentation of {} {}",
+        colors::yellow("~"),
+        target,
+        bump
+      ),
+    }
+  }
+}
+
+/// The largest bump of the changes, or `None` when nothing changed.
+pub fn required_bump(changes: &[ApiChange]) -> Option<Bump> {
+  changes.iter().map(|change| change.bump).max()
+}
+
+/// The public API of a package.
+#[derive(Debug, Default)]
+pub struct ApiSurface {
+  /// The symbols of each export, keyed by the dot separated symbol name.
+  /// Members of classes, interfaces and enums are separate symbols.
+  exports: BTreeMap<String, BTreeMap<String, ApiSymbol>>,
+}
+
+#[derive(Debug)]
+struct ApiSymbol {
+  /// The declarations of the symbol, several for overloads and declaration
+  /// merging.
+  declarations: Vec<ApiDeclaration>,
+  doc: String,
+  /// The bump required when the symbol is added.
+  addition: Bump,
+}
+
+#[derive(Debug)]
+struct ApiDeclaration {
+  /// The TypeScript declaration, to display the change.
+  signature: String,
+  /// The serialized doc node of the declaration, without its location,
+  /// documentation and the members that are separate symbols.
+  def: serde_json::Value,
+  /// The definitions without their trailing optional parameters.
+  shorter_defs: Vec<serde_json::Value>,
+}
+
+impl ApiSymbol {
+  /// The declarations, one per line.
+  fn signature(&self) -> String {
+    self
+      .declarations
+      .iter()
+      .map(|declaration| declaration.signature.as_str())
+      .collect::<Vec<_>>()
+      .join("\n")
+  }
+
+  fn is_changed(&self, old: &ApiSymbol) -> bool {
+    self.declarations.len() != old.declarations.len()
+      || self
+        .declarations
+        .iter()
+        .zip(&old.declarations)
+        .any(|(new, old)| new.def != old.def)
+  }
+
+  /// The bump required when the declarations change from `old` to the ones
+  /// of this symbol. Adding overloads or optional parameters keeps the old
+  /// declarations working, so it's the same as adding the symbol.
+  fn change_bump(&self, old: &ApiSymbol) -> Bump {
+    let is_compatible = old.declarations.iter().all(|old| {
+      self
+        .declarations
+        .iter()
+        .any(|new| new.def == old.def || new.shorter_defs.contains(&old.def))
+    });
+    if is_compatible {
+      self.addition
+    } else {
+      Bump::Major
+    }
+  }
+}
+
+impl ApiSurface {
+  pub fn add_export(&mut self, export: &str, nodes: &[DocNode]) {
+    let symbols = self.exports.entry(export.to_string()).or_default();
+    collect_symbols(symbols, None, nodes);
+  }
+
+  /// The changes from `old` to this API.
+  pub fn diff(&self, old: &ApiSurface) -> Vec<ApiChange> {
+    let mut changes = Vec::new();
+    let exports = old.exports.keys().chain(self.exports.keys());
+    for export in exports.collect::<BTreeSet<_>>() {
+      let (old_symbols, new_symbols) =
+        match (old.exports.get(export), self.exports.get(export)) {
+          (Some(old_symbols), Some(new_symbols)) => (old_symbols, new_symbols),
+          (old_symbols, _) => {
+            changes.push(ApiChange {
+              export: export.clone(),
+              symbol: None,
+              kind: if old_symbols.is_some() {
+                ApiChangeKind::Removed
+              } else {
+                ApiChangeKind::Added
+              },
+              bump: if old_symbols.is_some() {
+                Bump::Major
+              } else {
+                Bump::Minor
+              },
+              old_signature: None,
+              new_signature: None,
+            });
+            continue;
+          }
+        };
+      let names = old_symbols.keys().chain(new_symbols.keys());
+      for name in names.collect::<BTreeSet<_>>() {
+        // members come and go with their parent
+        let parent = name.rsplit_once('.').map(|(parent, _)| parent);
+        let (kind, bump) = match (old_symbols.get(name), new_symbols.get(name))
+        {
+          (Some(old_symbol), Some(new_symbol)) => {
+            if new_symbol.is_changed(old_symbol) {
+              (ApiChangeKind::Changed, new_symbol.change_bump(old_symbol))
+            } else if old_symbol.doc != new_symbol.doc {
+              (ApiChangeKind::DocChanged, Bump::Patch)
+            } else {
+              continue;
+            }
+          }
+          (Some(_), None) => {
+            if parent.is_some_and(|parent| !new_symbols.contains_key(parent)) {
+              continue;
+            }
+            (ApiChangeKind::Removed, Bump::Major)
+          }
+          (None, Some(new_symbol)) => {
+            if parent.is_some_and(|parent| !old_symbols.contains_key(parent)) {
+              continue;
+            }
+            (ApiChangeKind::Added, new_symbol.addition)
+          }
+          (None, None) => unreachable!(),
+        };
+        let signature = |symbols: &BTreeMap<String, ApiSymbol>| {
+          symbols.get(name).map(|symbol| symbol.signature())
+        };
+        changes.push(ApiChange {
+          export: export.clone(),
+          symbol: Some(name.clone()),
+          old_signature: if kind == ApiChangeKind::Changed {
+            signature(old_symbols)
+          } else {
+            None
+          },
+          new_signature: if kind == ApiChangeKind::Changed {
+            signature(new_symbols)
+          } else {
+            None
+          },
+          kind,
+          bump,
+        });
+      }
+    }
+    changes
+  }
+}
+
+fn collect_symbols<'a>(
+  symbols: &mut BTreeMap<String, ApiSymbol>,
+  namespace: Option<&str>,
+  nodes: impl IntoIterator<Item = &'a DocNode>,
+) {
+  for node in nodes {
+    if matches!(node.kind, DocNodeKind::Import | DocNodeKind::ModuleDoc) {
+      continue;
+    }
+    let name = match namespace {
+      Some(namespace) => format!("{namespace}.{}", node.name),
+      None => node.name.to_string(),
+    };
+    add_symbol(
+      symbols,
+      name.clone(),
+      ApiDeclaration {
+        signature: signature(node),
+        def: comparable_def(node),
+        shorter_defs: match &node.function_def {
+          Some(def) => shorter_declarations(&def.params, |params| {
+            let mut node = node.clone();
+            if let Some(def) = &mut node.function_def {
+              def.params = params.to_vec();
+            }
+            comparable_def(&node)
+          }),
+          None => Vec::new(),
+        },
+      },
+      &node.js_doc,
+      Bump::Minor,
+    );
+    for member in members(node) {
+      // implementers of an interface have to add its new required members
+      let addition = if node.interface_def.is_some() && !member.optional {
+        Bump::Major
+      } else {
+        Bump::Minor
+      };
+      add_symbol(
+        symbols,
+        format!("{name}.{}", member.name),
+        ApiDeclaration {
+          signature: member.signature,
+          def: without_locations_and_docs(member.def),
+          shorter_defs: member
+            .shorter_defs
+            .into_iter()
+            .map(without_locations_and_docs)
+            .collect(),
+        },
+        member.js_doc,
+        addition,
+      );
+    }
+    if let Some(namespace_def) = &node.namespace_def {
+      collect_symbols(
+        symbols,
+        Some(&name),
+        namespace_def.elements.iter().map(|element| &**element),
+      );
+    }
+  }
+}
+
+/// The doc node without the members that are separate symbols, in a form
+/// that only changes with the declaration.
+fn comparable_def(node: &DocNode) -> serde_json::Value {
+  let mut value =
+    without_locations_and_docs(serde_json::to_value(node).unwrap_or_default());
+  if let Some(object) = value.as_object_mut() {
+    let member_fields = [
+      ("classDef", ["properties", "methods"].as_slice()),
+      ("interfaceDef", ["properties", "methods"].as_slice()),
+      ("enumDef", ["members"].as_slice()),
+      ("namespaceDef", ["elements"].as_slice()),
+    ];
+    for (def, fields) in member_fields {
+      if let Some(def) = object.get_mut(def).and_then(|def| def.as_object_mut())
+      {
+        for field in fields {
+          def.remove(*field);
+        }
+      }
+    }
+  }
+  value
+}
+
+/// Removes the locations and the documentation, which don't affect the API,
+/// from the serialized doc node.
+fn without_locations_and_docs(
+  mut value: serde_json::Value,
+) -> serde_json::Value {
+  fn strip(value: &mut serde_json::Value) {
+    match value {
+      serde_json::Value::Object(object) => {
+        object.remove("location");
+        object.remove("jsDoc");
+        object.values_mut().for_each(strip);
+      }
+      serde_json::Value::Array(values) => values.iter_mut().for_each(strip),
+      _ => {}
+    }
+  }
+  strip(&mut value);
+  value
+}
+
+fn add_symbol(
+  symbols: &mut BTreeMap<String, ApiSymbol>,
+  name: String,
+  declaration: ApiDeclaration,
+  js_doc: &JsDoc,
+  addition: Bump,
+) {
+  let doc = serde_json::to_string(js_doc).unwrap_or_default();
+  match symbols.entry(name) {
+    Entry::Occupied(mut entry) => {
+      // overloads and declaration merging
+      let symbol = entry.get_mut();
+      symbol.declarations.push(declaration);
+      symbol.doc.push_str(&doc);
+      symbol.addition = symbol.addition.max(addition);
+    }
+    Entry::Vacant(entry) => {
+      entry.insert(ApiSymbol {
+        declarations: vec![declaration],
+        doc,
+        addition,
+      });
+    }
+  }
+}
+
+/// The version of a package to compare against.
+pub enum ApiBase {
+  /// A gzipped or plain tarball of the package.
+  Tarball(String),
+  GitRevision(String),
+  Jsr(PackageNv),
+}
+
+impl fmt::Display for ApiBase {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ApiBase::Tarball(path) => write!(f, "{}", path),
+      ApiBase::GitRevision(revision) => write!(f, "git revision {}", revision),
+      ApiBase::Jsr(nv) => write!(f, "jsr:{}", nv),
+    }
+  }
+}
+
+struct ApiDiffer {
+  module_graph_creator: Arc<ModuleGraphCreator>,
+  parsed_source_cache: Arc<ParsedSourceCache>,
+  jsr_resolver: JsrFetchResolver,
+}
+
+impl ApiDiffer {
+  async fn new(factory: &CliFactory) -> Result<Self, AnyError> {
+    // always get the latest versions of packages
+    let mut file_fetcher = FileFetcher::new(
+      factory.global_http_cache()?.clone(),
+      CacheSetting::ReloadAll,
+      true,
+      factory.http_client_provider().clone(),
+      Default::default(),
+      None,
+    );
+    file_fetcher.set_download_log_level(log::Level::Trace);
+    Ok(Self {
+      module_graph_creator: factory.module_graph_creator().await?.clone(),
+      parsed_source_cache: factory.parsed_source_cache().clone(),
+      jsr_resolver: JsrFetchResolver::new(Arc::new(file_fetcher)),
+    })
+  }
+
+  /// The latest version of the package that isn't yanked.
+  async fn latest_version(&self, package_name: &str) -> Option<PackageNv> {
+    let package_info = self.jsr_resolver.package_info(package_name).await?;
+    let version = package_info
+      .versions
+      .iter()
+      .filter(|(_, info)| !info.yanked)
+      .map(|(version, _)| version)
+      .max()?;
+    Some(PackageNv {
+      name: package_name.to_string(),
+      version: version.clone(),
+    })
+  }
+
+  async fn resolve_base(
+    &self,
+    base: Option<&str>,
+    package_name: &str,
+  ) -> Result<ApiBase, AnyError> {
+    let Some(base) = base else {
+      let Some(nv) = self.latest_version(package_name).await else {
+        bail!("Couldn't find a published version of {}.", package_name);
+      };
+      return Ok(ApiBase::Jsr(nv));
+    };
+    if let Some(revision) = base.strip_prefix("git:") {
+      Ok(ApiBase::GitRevision(revision.to_string()))
+    } else if let Some(nv) = base.strip_prefix("jsr:") {
+      let nv = PackageNv::from_str(nv)
+        .with_context(|| format!("Invalid package version '{}'.", base))?;
+      Ok(ApiBase::Jsr(nv))
+    } else if let Ok(version) = Version::parse_standard(base) {
+      Ok(ApiBase::Jsr(PackageNv {
+        name: package_name.to_string(),
+        version,
+      }))
+    } else if [".tgz", ".tar.gz", ".tar"]
+      .iter()
+      .any(|ext| base.ends_with(ext))
+    {
+      Ok(ApiBase::Tarball(base.to_string()))
+    } else {
+      bail!(
+        "Invalid version to compare against '{}'. Use a version, jsr:<package>@<version>, git:<revision> or a path to a tarball.",
+        base
+      );
+    }
+  }
+
+  /// The changes to the public API of the package since the base version.
+  async fn diff(
+    &self,
+    base: &ApiBase,
+    config_file: &ConfigFile,
+  ) -> Result<Vec<ApiChange>, AnyError> {
+    let config_path = config_file.specifier.to_file_path().unwrap();
+    let root_dir = config_path.parent().unwrap();
+    let old = match base {
+      ApiBase::Jsr(nv) => {
+        let Some(version_info) =
+          self.jsr_resolver.package_version_info(nv).await
+        else {
+          bail!("Couldn't find {}.", base);
+        };
+        let exports = version_info
+          .exports
+          .as_object()
+          .map(|exports| {
+            exports
+              .keys()
+              .map(|name| {
+                let specifier = match name.strip_prefix("./") {
+                  Some(path) => format!("jsr:{}/{}", nv, path),
+                  None => format!("jsr:{}", nv),
+                };
+                Ok((name.clone(), ModuleSpecifier::parse(&specifier)?))
+              })
+              .collect::<Result<Vec<_>, AnyError>>()
+          })
+          .transpose()?
+          .unwrap_or_default();
+        self.api_surface(exports).await?
+      }
+      ApiBase::Tarball(path) => {
+        let path = root_dir.join(path);
+        let bytes = std::fs::read(&path)
+          .with_context(|| format!("Failed reading {}.", path.display()))?;
+        self.api_surface_of_tarball(&bytes).await?
+      }
+      ApiBase::GitRevision(revision) => {
+        let bytes = git::archive(root_dir, revision)?;
+        self.api_surface_of_tarball(&bytes).await?
+      }
+    };
+    let new = self.api_surface(config_exports(config_file)?).await?;
+    Ok(new.diff(&old))
+  }
+
+  async fn api_surface_of_tarball(
+    &self,
+    bytes: &[u8],
+  ) -> Result<ApiSurface, AnyError> {
+    let dir = tempfile::tempdir()?;
+    let mut tar_bytes = Vec::new();
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+      flate2::read::GzDecoder::new(bytes)
+        .read_to_end(&mut tar_bytes)
+        .context("Failed decompressing the tarball.")?;
+    } else {
+      tar_bytes.extend_from_slice(bytes);
+    }
+    tar::Archive::new(tar_bytes.as_slice())
+      .unpack(dir.path())
+      .context("Failed extracting the tarball.")?;
+    let config_file = read_config_file(dir.path())?;
+    self.api_surface(config_exports(&config_file)?).await
+  }
+
+  /// Generates the public API of the exports, which are mapped to the
+  /// specifiers of their modules.
+  async fn api_surface(
+    &self,
+    exports: Vec<(String, ModuleSpecifier)>,
+  ) -> Result<ApiSurface, AnyError> {
+    let graph = self
+      .module_graph_creator
+      .create_graph(
+        GraphKind::TypesOnly,
+        exports
+          .iter()
+          .map(|(_, specifier)| specifier.clone())
+          .collect(),
+      )
+      .await?;
+    let parser = self.parsed_source_cache.as_capturing_parser();
+    let doc_parser = DocParser::new(
+      &graph,
+      &parser,
+      DocParserOptions {
+        diagnostics: false,
+        private: false,
+      },
+    )?;
+    let mut surface = ApiSurface::default();
+    for (name, specifier) in exports {
+      let specifier = graph.resolve(&specifier);
+      let nodes = doc_parser.parse_with_reexports(&specifier)?;
+      surface.add_export(&name, &nodes);
+    }
+    Ok(surface)
+  }
+}
+
+fn read_config_file(dir: &Path) -> Result<ConfigFile, AnyError> {
+  let Some(path) = CONFIG_FILE_NAMES
+    .iter()
+    .map(|name| dir.join(name))
+    .find(|path| path.is_file())
+  else {
+    bail!("Couldn't find a deno.json, deno.jsonc, jsr.json or jsr.jsonc configuration file in the version to compare against.");
+  };
+  let text = std::fs::read_to_string(&path)?;
+  ConfigFile::new(
+    &text,
+    ModuleSpecifier::from_file_path(&path).unwrap(),
+    &deno_config::ParseOptions::default(),
+  )
+}
+
+/// The exports of the package, resolved relative to its config file.
+fn config_exports(
+  config_file: &ConfigFile,
+) -> Result<Vec<(String, ModuleSpecifier)>, AnyError> {
+  config_file
+    .to_exports_config()?
+    .into_map()
+    .into_iter()
+    .map(|(name, path)| Ok((name, config_file.specifier.join(&path)?)))
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn surface(exports: &[(&str, &[(&str, &str, Bump)])]) -> ApiSurface {
+    ApiSurface {
+      exports: exports
+        .iter()
+        .map(|(export, symbols)| {
+          let symbols = symbols
+            .iter()
+            .map(|(name, signature, addition)| {
+              (
+                name.to_string(),
+                ApiSymbol {
+                  declarations: signature
+                    .lines()
+                    .map(|signature| ApiDeclaration {
+                      signature: signature.to_string(),
+                      def: json!(signature),
+                      shorter_defs: Vec::new(),
+                    })
+                    .collect(),
+                  doc: String::new(),
+                  addition: *addition,
+                },
+              )
+            })
+            .collect();
+          (export.to_string(), symbols)
+        })
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn bump_between() {
+    let version = |text| Version::parse_standard(text).unwrap();
+    assert_eq!(
+      Bump::between(&version("1.2.3"), &version("2.0.0")),
+      Some(Bump::Major)
+    );
+    assert_eq!(
+      Bump::between(&version("1.2.3"), &version("1.3.0")),
+      Some(Bump::Minor)
+    );
+    assert_eq!(
+      Bump::between(&version("1.2.3"), &version("1.2.4")),
+      Some(Bump::Patch)
+    );
+    assert_eq!(
+      Bump::between(&version("0.2.3"), &version("0.3.0")),
+      Some(Bump::Major)
+    );
+    assert_eq!(
+      Bump::between(&version("0.2.3"), &version("0.2.4")),
+      Some(Bump::Minor)
+    );
+    assert_eq!(Bump::between(&version("1.2.3"), &version("1.2.3")), None);
+  }
+
+  #[test]
+  fn diffs_surfaces() {
+    let old = surface(&[
+      (
+        ".",
+        &[
+          (
+            "add",
+            "function add(a: number, b: number): number",
+            Bump::Minor,
+          ),
+          ("Options", "interface Options", Bump::Minor),
+          ("Options.verbose", "verbose?: boolean", Bump::Minor),
+          ("Removed", "class Removed", Bump::Minor),
+          ("Removed.run", "run(): void", Bump::Minor),
+        ],
+      ),
+      ("./old", &[]),
+    ]);
+    let new = surface(&[
+      (
+        ".",
+        &[
+          (
+            "add",
+            "function add(a: bigint, b: bigint): bigint",
+            Bump::Minor,
+          ),
+          ("Options", "interface Options", Bump::Minor),
+          ("Options.verbose", "verbose?: boolean", Bump::Minor),
+          ("Options.name", "name: string", Bump::Major),
+          (
+            "sub",
+            "function sub(a: number, b: number): number",
+            Bump::Minor,
+          ),
+        ],
+      ),
+      ("./new", &[]),
+    ]);
+    let changes = new.diff(&old);
+    assert_eq!(
+      changes,
+      vec![
+        ApiChange {
+          export: ".".to_string(),
+          symbol: Some("Options.name".to_string()),
+          kind: ApiChangeKind::Added,
+          bump: Bump::Major,
+          old_signature: None,
+          new_signature: None,
+        },
+        ApiChange {
+          export: ".".to_string(),
+          symbol: Some("Removed".to_string()),
+          kind: ApiChangeKind::Removed,
+          bump: Bump::Major,
+          old_signature: None,
+          new_signature: None,
+        },
+        ApiChange {
+          export: ".".to_string(),
+          symbol: Some("add".to_string()),
+          kind: ApiChangeKind::Changed,
+          bump: Bump::Major,
+          old_signature: Some(
+            "function add(a: number, b: number): number".to_string()
+          ),
+          new_signature: Some(
+            "function add(a: bigint, b: bigint): bigint".to_string()
+          ),
+        },
+        ApiChange {
+          export: ".".to_string(),
+          symbol: Some("sub".to_string()),
+          kind: ApiChangeKind::Added,
+          bump: Bump::Minor,
+          old_signature: None,
+          new_signature: None,
+        },
+        ApiChange {
+          export: "./new".to_string(),
+          symbol: None,
+          kind: ApiChangeKind::Added,
+          bump: Bump::Minor,
+          old_signature: None,
+          new_signature: None,
+        },
+        ApiChange {
+          export: "./old".to_string(),
+          symbol: None,
+          kind: ApiChangeKind::Removed,
+          bump: Bump::Major,
+          old_signature: None,
+          new_signature: None,
+        },
+      ]
+    );
+    assert_eq!(required_bump(&changes), Some(Bump::Major));
+    assert_eq!(required_bump(&new.diff(&new)), None);
+  }
+
+  #[test]
+  fn diffs_compatible_declarations() {
+    let declaration =
+      |signature: &str, shorter_signatures: &[&str]| ApiDeclaration {
+        signature: signature.to_string(),
+        def: json!(signature),
+        shorter_defs: shorter_signatures
+          .iter()
+          .map(|signature| json!(signature))
+          .collect(),
+      };
+    let symbol = |declarations, addition| ApiSymbol {
+      declarations,
+      doc: String::new(),
+      addition,
+    };
+    let old = symbol(
+      vec![declaration("function add(a: number): number", &[])],
+      Bump::Minor,
+    );
+
+    // an added optional parameter
+    let new = symbol(
+      vec![declaration(
+        "function add(a: number, b?: number): number",
+        &["function add(a: number): number"],
+      )],
+      Bump::Minor,
+    );
+    assert_eq!(new.change_bump(&old), Bump::Minor);
+
+    // an added overload
+    let new = symbol(
+      vec![
+        declaration("function add(a: number): number", &[]),
+        declaration("function add(a: bigint): bigint", &[]),
+      ],
+      Bump::Minor,
+    );
+    assert_eq!(new.change_bump(&old), Bump::Minor);
+
+    // an added required parameter
+    let new = symbol(
+      vec![declaration(
+        "function add(a: number, b: number): number",
+        &[],
+      )],
+      Bump::Minor,
+    );
+    assert_eq!(new.change_bump(&old), Bump::Major);
+
+    // implementers of an interface have to implement the new overload
+    let old = symbol(
+      vec![declaration("add(a: number): number", &[])],
+      Bump::Major,
+    );
+    let new = symbol(
+      vec![
+        declaration("add(a: number): number", &[]),
+        declaration("add(a: bigint): bigint", &[]),
+      ],
+      Bump::Major,
+    );
+    assert_eq!(new.change_bump(&old), Bump::Major);
+  }
+
+  #[test]
+  fn strips_locations_and_docs() {
+    let value = without_locations_and_docs(json!({
+      "name": "add",
+      "location": { "filename": "file:///mod.ts", "line": 1, "col": 0 },
+      "jsDoc": { "doc": "Adds." },
+      "functionDef": {
+        "params": [{ "kind": "identifier", "name": "a", "jsDoc": {} }],
+      },
+    }));
+    assert_eq!(
+      value,
+      json!({
+        "name": "add",
+        "functionDef": {
+          "params": [{ "kind": "identifier", "name": "a" }],
+        },
+      })
+    );
+  }
+}
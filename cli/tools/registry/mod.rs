@@ -53,6 +53,7 @@ use crate::tools::registry::diagnostics::PublishDiagnosticsCollector;
 use crate::util::display::human_size;
 
 mod api;
+mod api_diff;
 mod auth;
 mod diagnostics;
 mod graph;
@@ -63,6 +64,7 @@ mod publish_order;
 mod tar;
 mod unfurl;
 
+pub use api_diff::api_diff;
 use auth::get_auth_method;
 use auth::AuthMethod;
 pub use pm::add;
@@ -131,6 +133,11 @@ pub async fn publish(
     bail!("No packages to publish");
   }
 
+  if publish_flags.check_semver {
+    api_diff::check_semver(&cli_factory, &config_file.to_workspace_members()?)
+      .await?;
+  }
+
   if std::env::var("DENO_TESTING_DISABLE_GIT_CHECK")
     .ok()
     .is_none()
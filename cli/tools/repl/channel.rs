@@ -43,11 +43,15 @@ pub enum RustylineSyncMessage {
     line_text: String,
     position: usize,
   },
+  LspTypeHint {
+    line_text: String,
+  },
 }
 
 pub enum RustylineSyncResponse {
   PostMessage(Result<Value, AnyError>),
   LspCompletions(Vec<ReplCompletionItem>),
+  LspTypeHint(Option<String>),
 }
 
 pub struct RustylineSyncMessageSender {
@@ -75,7 +79,8 @@ impl RustylineSyncMessageSender {
     } else {
       match self.response_rx.borrow_mut().blocking_recv().unwrap() {
         RustylineSyncResponse::PostMessage(result) => result,
-        RustylineSyncResponse::LspCompletions(_) => unreachable!(),
+        RustylineSyncResponse::LspCompletions(_)
+        | RustylineSyncResponse::LspTypeHint(_) => unreachable!(),
       }
     }
   }
@@ -97,7 +102,26 @@ impl RustylineSyncMessageSender {
     } else {
       match self.response_rx.borrow_mut().blocking_recv().unwrap() {
         RustylineSyncResponse::LspCompletions(result) => result,
-        RustylineSyncResponse::PostMessage(_) => unreachable!(),
+        RustylineSyncResponse::PostMessage(_)
+        | RustylineSyncResponse::LspTypeHint(_) => unreachable!(),
+      }
+    }
+  }
+
+  pub fn lsp_type_hint(&self, line_text: &str) -> Option<String> {
+    if self
+      .message_tx
+      .blocking_send(RustylineSyncMessage::LspTypeHint {
+        line_text: line_text.to_string(),
+      })
+      .is_err()
+    {
+      None
+    } else {
+      match self.response_rx.borrow_mut().blocking_recv().unwrap() {
+        RustylineSyncResponse::LspTypeHint(result) => result,
+        RustylineSyncResponse::PostMessage(_)
+        | RustylineSyncResponse::LspCompletions(_) => unreachable!(),
       }
     }
   }
@@ -0,0 +1,112 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+/// A REPL meta command, entered as a line starting with a `.`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReplCommand {
+  /// Clear the screen and the session transcript.
+  Clear,
+  /// Enter multi-line editor mode.
+  Editor,
+  /// Print the help text.
+  Help,
+  /// Toggle inline type hints.
+  Hints,
+  /// Evaluate the contents of a file in the session.
+  Load(String),
+  /// Save the code evaluated in the session to a file.
+  Save(String),
+  /// Print the type of an expression.
+  Type(String),
+}
+
+pub const HELP_TEXT: &str = "\
+.clear    Clear the screen and the session transcript
+.editor   Enter editor mode (ctrl+d to evaluate, ctrl+c to cancel)
+.help     Print this help message
+.hints    Toggle inline type hints while typing
+.load     Evaluate a file in the current session: .load <file>
+.save     Save the evaluated code of the session to a file: .save <file>
+.type     Print the type of an expression: .type <expression>";
+
+impl ReplCommand {
+  /// Parses a line of input as a meta command. Returns `None` if the line
+  /// should be evaluated as code, and an error message if it looks like a
+  /// command but is not a valid one.
+  pub fn parse(line: &str) -> Option<Result<Self, String>> {
+    let line = line.trim();
+    let rest = line.strip_prefix('.')?;
+    // `.5 + 1` is a valid expression, but no statement can start with a
+    // dot followed by a letter
+    if !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+      return None;
+    }
+
+    let (name, arg) = match rest.split_once(char::is_whitespace) {
+      Some((name, arg)) => (name, arg.trim()),
+      None => (rest, ""),
+    };
+    let require_arg = |usage: &str| {
+      if arg.is_empty() {
+        Err(format!("Missing argument. Usage: {usage}"))
+      } else {
+        Ok(arg.to_string())
+      }
+    };
+    let command = match name {
+      "clear" => Ok(ReplCommand::Clear),
+      "editor" => Ok(ReplCommand::Editor),
+      "help" => Ok(ReplCommand::Help),
+      "hints" => Ok(ReplCommand::Hints),
+      "load" => require_arg(".load <file>").map(ReplCommand::Load),
+      "save" => require_arg(".save <file>").map(ReplCommand::Save),
+      "type" => require_arg(".type <expression>").map(ReplCommand::Type),
+      _ => Err(format!(
+        "Invalid REPL keyword \".{name}\". Type \".help\" for a list of commands."
+      )),
+    };
+    Some(command)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn parse_commands() {
+    assert_eq!(ReplCommand::parse(".help"), Some(Ok(ReplCommand::Help)));
+    assert_eq!(
+      ReplCommand::parse("  .clear "),
+      Some(Ok(ReplCommand::Clear))
+    );
+    assert_eq!(ReplCommand::parse(".editor"), Some(Ok(ReplCommand::Editor)));
+    assert_eq!(ReplCommand::parse(".hints"), Some(Ok(ReplCommand::Hints)));
+    assert_eq!(
+      ReplCommand::parse(".save session.ts"),
+      Some(Ok(ReplCommand::Save("session.ts".to_string())))
+    );
+    assert_eq!(
+      ReplCommand::parse(".load  ./a b.ts"),
+      Some(Ok(ReplCommand::Load("./a b.ts".to_string())))
+    );
+    assert_eq!(
+      ReplCommand::parse(".type [1, 'a']"),
+      Some(Ok(ReplCommand::Type("[1, 'a']".to_string())))
+    );
+  }
+
+  #[test]
+  fn parse_invalid_commands() {
+    assert!(matches!(ReplCommand::parse(".save"), Some(Err(_))));
+    assert!(matches!(ReplCommand::parse(".type  "), Some(Err(_))));
+    assert!(matches!(ReplCommand::parse(".foo"), Some(Err(_))));
+  }
+
+  #[test]
+  fn parse_code() {
+    assert_eq!(ReplCommand::parse("1 + 1"), None);
+    assert_eq!(ReplCommand::parse(".5 + 1"), None);
+    assert_eq!(ReplCommand::parse("[1].map(String)"), None);
+    assert_eq!(ReplCommand::parse(""), None);
+  }
+}
@@ -14,6 +14,8 @@ use deno_core::serde_json;
 use rustyline::completion::Completer;
 use rustyline::error::ReadlineError;
 use rustyline::highlight::Highlighter;
+use rustyline::hint::Hint;
+use rustyline::hint::Hinter;
 use rustyline::validate::ValidationContext;
 use rustyline::validate::ValidationResult;
 use rustyline::validate::Validator;
@@ -31,7 +33,6 @@ use rustyline::KeyEvent;
 use rustyline::Modifiers;
 use rustyline::RepeatCount;
 use rustyline_derive::Helper;
-use rustyline_derive::Hinter;
 use std::borrow::Cow;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
@@ -43,13 +44,30 @@ use super::session::REPL_INTERNALS_NAME;
 
 // Provides helpers to the editor like validation for multi-line edits, completion candidates for
 // tab completion.
-#[derive(Helper, Hinter)]
+#[derive(Helper)]
 pub struct EditorHelper {
   pub context_id: u64,
   pub sync_sender: RustylineSyncMessageSender,
+  /// Whether inline type hints are shown, toggled with `.hints`.
+  pub type_hints_enabled: Arc<AtomicBool>,
+  /// The last expression a type hint was requested for and its result, so
+  /// redrawing the same input doesn't query the language server again.
+  pub type_hint_cache: Mutex<Option<(String, Option<String>)>>,
 }
 
 impl EditorHelper {
+  fn type_hint(&self, expr: &str) -> Option<String> {
+    let mut cache = self.type_hint_cache.lock();
+    if let Some((cached_expr, ty)) = cache.as_ref() {
+      if cached_expr == expr {
+        return ty.clone();
+      }
+    }
+    let ty = self.sync_sender.lsp_type_hint(expr);
+    *cache = Some((expr.to_string(), ty.clone()));
+    ty
+  }
+
   pub fn get_global_lexical_scope_names(&self) -> Vec<String> {
     let evaluate_response = self
       .sync_sender
@@ -191,14 +209,33 @@ impl Completer for EditorHelper {
     _ctx: &Context<'_>,
   ) -> Result<(usize, Vec<String>), ReadlineError> {
     let lsp_completions = self.sync_sender.lsp_completions(line, pos);
+    let (start, mut candidates) = self.get_runtime_completions(line, pos);
     if !lsp_completions.is_empty() {
-      // assumes all lsp completions have the same start position
-      return Ok((
-        lsp_completions[0].range.start,
-        lsp_completions.into_iter().map(|c| c.new_text).collect(),
-      ));
+      let lsp_start = lsp_completions[0].range.start;
+      let lsp_candidates = lsp_completions
+        .into_iter()
+        .filter(|c| c.range.start == lsp_start)
+        .map(|c| c.new_text);
+      if lsp_start != start {
+        return Ok((lsp_start, lsp_candidates.collect()));
+      }
+      // combine the type aware completions with the ones of the runtime
+      // values, which might not be known to the type checker
+      candidates.extend(lsp_candidates);
+      candidates.sort();
+      candidates.dedup(); // make sure to sort first
     }
 
+    Ok((start, candidates))
+  }
+}
+
+impl EditorHelper {
+  fn get_runtime_completions(
+    &self,
+    line: &str,
+    pos: usize,
+  ) -> (usize, Vec<String>) {
     let expr = get_expr_from_line_at_pos(line, pos);
 
     // check if the expression is in the form `obj.prop`
@@ -215,7 +252,7 @@ impl Completer for EditorHelper {
         })
         .collect();
 
-      Ok((pos - prop_name.len(), candidates))
+      (pos - prop_name.len(), candidates)
     } else {
       // combine results of declarations and globalThis properties
       let mut candidates = self
@@ -229,8 +266,60 @@ impl Completer for EditorHelper {
       candidates.sort();
       candidates.dedup(); // make sure to sort first
 
-      Ok((pos - expr.len(), candidates))
+      (pos - expr.len(), candidates)
+    }
+  }
+}
+
+/// The type of the expression being typed, shown after the cursor.
+pub struct TypeHint {
+  display: String,
+}
+
+impl Hint for TypeHint {
+  fn display(&self) -> &str {
+    &self.display
+  }
+
+  fn completion(&self) -> Option<&str> {
+    // the hint is informational and can't be accepted into the input
+    None
+  }
+}
+
+impl Hinter for EditorHelper {
+  type Hint = TypeHint;
+
+  fn hint(
+    &self,
+    line: &str,
+    pos: usize,
+    _ctx: &Context<'_>,
+  ) -> Option<Self::Hint> {
+    // without colors the hint could not be told apart from the input
+    if !self.type_hints_enabled.load(Relaxed)
+      || !colors::use_color()
+      || pos != line.len()
+    {
+      return None;
+    }
+    let expr = line.trim_end();
+    if expr.is_empty()
+      || expr.ends_with(';')
+      || expr.contains('\n')
+      || expr.starts_with('.')
+      || !matches!(validate(expr), ValidationResult::Valid(_))
+    {
+      return None;
+    }
+    let ty = self.type_hint(expr)?;
+    // `any` is also the type of expressions that don't type check
+    if ty == "any" || ty.contains('\n') {
+      return None;
     }
+    Some(TypeHint {
+      display: format!(" // {ty}"),
+    })
   }
 }
 
@@ -326,7 +415,7 @@ fn validate(input: &str) -> ValidationResult {
 
 impl Highlighter for EditorHelper {
   fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
-    hint.into()
+    colors::gray(hint).to_string().into()
   }
 
   fn highlight_candidate<'c>(
@@ -477,8 +566,13 @@ impl ReplEditor {
     })
   }
 
-  pub fn readline(&self) -> Result<String, ReadlineError> {
-    self.inner.lock().readline("> ")
+  pub fn readline(&self, prompt: &str) -> Result<String, ReadlineError> {
+    let mut editor = self.inner.lock();
+    // the previous input may have changed the types of expressions
+    if let Some(helper) = editor.helper() {
+      *helper.type_hint_cache.lock() = None;
+    }
+    editor.readline(prompt)
   }
 
   pub fn update_history(&self, entry: String) {
@@ -502,6 +596,15 @@ impl ReplEditor {
   pub fn set_should_exit_on_interrupt(&self, yes: bool) {
     self.should_exit_on_interrupt.store(yes, Relaxed);
   }
+
+  /// Toggles inline type hints, returning whether they are now enabled.
+  pub fn toggle_type_hints(&self) -> bool {
+    let editor = self.inner.lock();
+    let Some(helper) = editor.helper() else {
+      return false;
+    };
+    !helper.type_hints_enabled.fetch_xor(true, Relaxed)
+  }
 }
 
 /// Command to reverse search history , same as rustyline default C-R but that resets repl should_exit flag to false
@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::args::CliOptions;
@@ -19,6 +20,7 @@ use deno_runtime::WorkerExecutionMode;
 use rustyline::error::ReadlineError;
 
 mod channel;
+mod commands;
 mod editor;
 mod session;
 
@@ -26,6 +28,7 @@ use channel::rustyline_channel;
 use channel::RustylineSyncMessage;
 use channel::RustylineSyncMessageHandler;
 use channel::RustylineSyncResponse;
+use commands::ReplCommand;
 use editor::EditorHelper;
 use editor::ReplEditor;
 pub use session::EvaluationOutput;
@@ -38,6 +41,8 @@ struct Repl {
   session: ReplSession,
   editor: ReplEditor,
   message_handler: RustylineSyncMessageHandler,
+  /// Code that was evaluated successfully, written out by `.save`.
+  transcript: Vec<String>,
 }
 
 #[allow(clippy::print_stdout)]
@@ -48,21 +53,25 @@ impl Repl {
         &mut self.session,
         &mut self.message_handler,
         self.editor.clone(),
+        "> ",
       )
       .await;
       match line {
         Ok(line) => {
           self.editor.set_should_exit_on_interrupt(false);
           self.editor.update_history(line.clone());
-          let output = self.session.evaluate_line_and_get_output(&line).await;
+          let should_close = match ReplCommand::parse(&line) {
+            Some(Ok(command)) => self.run_command(command).await?,
+            Some(Err(message)) => {
+              println!("{}", message);
+              false
+            }
+            None => self.evaluate_and_print(line).await?,
+          };
 
-          // We check for close and break here instead of making it a loop condition to get
-          // consistent behavior in when the user evaluates a call to close().
-          if self.session.closing().await? {
+          if should_close {
             break;
           }
-
-          println!("{}", output);
         }
         Err(ReadlineError::Interrupted) => {
           if self.editor.should_exit_on_interrupt() {
@@ -84,6 +93,107 @@ impl Repl {
 
     Ok(())
   }
+
+  /// Evaluates the code and prints the result. Returns `true` if the
+  /// code closed the REPL.
+  async fn evaluate_and_print(
+    &mut self,
+    code: String,
+  ) -> Result<bool, AnyError> {
+    let output = self.session.evaluate_line_and_get_output(&code).await;
+
+    // We check for close and break here instead of making it a loop condition to get
+    // consistent behavior in when the user evaluates a call to close().
+    if self.session.closing().await? {
+      return Ok(true);
+    }
+
+    if let EvaluationOutput::Value(_) = output {
+      self.transcript.push(code);
+    }
+    println!("{}", output);
+    Ok(false)
+  }
+
+  /// Runs a meta command. Returns `true` if the command closed the REPL.
+  async fn run_command(
+    &mut self,
+    command: ReplCommand,
+  ) -> Result<bool, AnyError> {
+    match command {
+      ReplCommand::Clear => {
+        self.transcript.clear();
+        print!("\x1B[2J\x1B[1;1H");
+        println!("Session transcript cleared.");
+      }
+      ReplCommand::Editor => {
+        println!(
+          "// Entering editor mode (ctrl+d to evaluate, ctrl+c to cancel)"
+        );
+        let mut lines = Vec::new();
+        loop {
+          let line = read_line_and_poll(
+            &mut self.session,
+            &mut self.message_handler,
+            self.editor.clone(),
+            "",
+          )
+          .await;
+          match line {
+            Ok(line) => lines.push(line),
+            Err(ReadlineError::Eof) => break,
+            Err(ReadlineError::Interrupted) => return Ok(false),
+            Err(err) => return Err(err.into()),
+          }
+        }
+        let code = lines.join("\n");
+        if code.trim().is_empty() {
+          return Ok(false);
+        }
+        self.editor.update_history(code.clone());
+        return self.evaluate_and_print(code).await;
+      }
+      ReplCommand::Help => {
+        println!("{}", commands::HELP_TEXT);
+        println!();
+        println!("Press ctrl+c twice, ctrl+d or call close() to exit.");
+      }
+      ReplCommand::Load(path) => {
+        let path = PathBuf::from(path);
+        match std::fs::read_to_string(&path) {
+          Ok(code) => return self.evaluate_and_print(code).await,
+          Err(err) => {
+            println!("Failed to load \"{}\": {}", path.display(), err)
+          }
+        }
+      }
+      ReplCommand::Save(path) => {
+        let path = PathBuf::from(path);
+        let mut contents = self.transcript.join("\n");
+        contents.push('\n');
+        match std::fs::write(&path, contents) {
+          Ok(()) => println!("Session saved to: {}", path.display()),
+          Err(err) => {
+            println!("Failed to save \"{}\": {}", path.display(), err)
+          }
+        }
+      }
+      ReplCommand::Hints => {
+        if self.editor.toggle_type_hints() {
+          println!("Inline type hints enabled.");
+        } else {
+          println!("Inline type hints disabled.");
+        }
+      }
+      ReplCommand::Type(expr) => {
+        match self.session.language_server.type_of(&expr).await {
+          Some(ty) => println!("{}", ty),
+          None => println!("Unable to determine the type of {}", expr),
+        }
+      }
+    }
+    Ok(false)
+  }
 }
 
 #[allow(clippy::print_stdout)]
@@ -91,8 +201,9 @@ async fn read_line_and_poll(
   repl_session: &mut ReplSession,
   message_handler: &mut RustylineSyncMessageHandler,
   editor: ReplEditor,
+  prompt: &'static str,
 ) -> Result<String, ReadlineError> {
-  let mut line_fut = spawn_blocking(move || editor.readline());
+  let mut line_fut = spawn_blocking(move || editor.readline(prompt));
   let mut poll_worker = true;
   let notifications_rc = repl_session.notifications.clone();
   let mut notifications = notifications_rc.lock().await;
@@ -117,6 +228,10 @@ async fn read_line_and_poll(
             let result = repl_session.language_server.completions(&line_text, position).await;
             message_handler.send(RustylineSyncResponse::LspCompletions(result)).unwrap();
           }
+          Some(RustylineSyncMessage::LspTypeHint { line_text }) => {
+            let result = repl_session.language_server.type_of(&line_text).await;
+            message_handler.send(RustylineSyncResponse::LspTypeHint(result)).unwrap();
+          }
           None => {}, // channel closed
         }
 
@@ -197,6 +312,8 @@ pub async fn run(flags: Flags, repl_flags: ReplFlags) -> Result<i32, AnyError> {
   let helper = EditorHelper {
     context_id: session.context_id,
     sync_sender: rustyline_channel.0,
+    type_hints_enabled: Default::default(),
+    type_hint_cache: Default::default(),
   };
 
   let editor = ReplEditor::new(helper, history_file_path)?;
@@ -205,6 +322,7 @@ pub async fn run(flags: Flags, repl_flags: ReplFlags) -> Result<i32, AnyError> {
     session,
     editor,
     message_handler: rustyline_channel.1,
+    transcript: Vec::new(),
   };
 
   if let Some(eval_files) = repl_flags.eval_files {
@@ -2,21 +2,133 @@
 
 use crate::cdp;
 use crate::emit::Emitter;
+use crate::graph_container::MainModuleGraphContainer;
+use crate::graph_container::ModuleGraphContainer;
 use crate::util::file_watcher::WatcherCommunicator;
 use crate::util::file_watcher::WatcherRestartMode;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::futures::StreamExt;
+use deno_core::parking_lot::Mutex;
 use deno_core::serde_json::json;
 use deno_core::serde_json::{self};
 use deno_core::url::Url;
 use deno_core::LocalInspectorSession;
+use deno_core::ModuleSpecifier;
+use deno_graph::ModuleGraph;
 use deno_terminal::colors;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::path::Path;
 use std::sync::Arc;
 use tokio::select;
 
+// NOTE: keep in sync with `HMR_VERSION_QUERY_PARAM` in `runtime/js/40_hmr.js`
+const HMR_VERSION_QUERY_PARAM: &str = "__hmr";
+
+/// Tracks the modules that were invalidated by hot module replacement.
+///
+/// V8 never evaluates a module twice for the same specifier, so invalidated
+/// modules are given a fresh specifier with a version query parameter. The
+/// module loader consults this map when resolving, so that the re-evaluated
+/// importers of a changed module pick up its new instance.
+#[derive(Debug, Default)]
+pub struct HmrModuleVersions {
+  inner: Mutex<HmrModuleVersionsInner>,
+}
+
+#[derive(Debug, Default)]
+struct HmrModuleVersionsInner {
+  current_version: usize,
+  versions: HashMap<ModuleSpecifier, usize>,
+}
+
+impl HmrModuleVersions {
+  /// Bumps the version of the given modules, returning the new version.
+  pub fn invalidate(
+    &self,
+    specifiers: impl IntoIterator<Item = ModuleSpecifier>,
+  ) -> usize {
+    let mut inner = self.inner.lock();
+    inner.current_version += 1;
+    let version = inner.current_version;
+    for specifier in specifiers {
+      inner.versions.insert(specifier, version);
+    }
+    version
+  }
+
+  /// Maps a resolved specifier to the latest version of the module.
+  pub fn resolve(&self, specifier: ModuleSpecifier) -> ModuleSpecifier {
+    if specifier.query().is_some() {
+      return specifier;
+    }
+    let inner = self.inner.lock();
+    match inner.versions.get(&specifier) {
+      Some(version) => {
+        let mut specifier = specifier;
+        specifier
+          .set_query(Some(&format!("{HMR_VERSION_QUERY_PARAM}={version}")));
+        specifier
+      }
+      None => specifier,
+    }
+  }
+
+  /// Returns the original specifier of a versioned module, or `None` if the
+  /// specifier wasn't produced by `resolve`.
+  pub fn base_specifier(
+    specifier: &ModuleSpecifier,
+  ) -> Option<ModuleSpecifier> {
+    let version = specifier
+      .query()?
+      .strip_prefix(HMR_VERSION_QUERY_PARAM)?
+      .strip_prefix('=')?;
+    if version.is_empty() || !version.bytes().all(|b| b.is_ascii_digit()) {
+      return None;
+    }
+    let mut base = specifier.clone();
+    base.set_query(None);
+    Some(base)
+  }
+}
+
+/// Collects the transitive importers of `specifier`, keyed by module.
+fn collect_importers(
+  graph: &ModuleGraph,
+  specifier: &ModuleSpecifier,
+) -> HashMap<String, Vec<String>> {
+  let mut dependents: HashMap<&ModuleSpecifier, Vec<&ModuleSpecifier>> =
+    HashMap::new();
+  for module in graph.modules() {
+    let Some(module) = module.js() else {
+      continue;
+    };
+    for dependency in module.dependencies.values() {
+      if let Some(dep) = dependency.maybe_code.maybe_specifier() {
+        dependents.entry(dep).or_default().push(&module.specifier);
+      }
+    }
+  }
+
+  let mut importers = HashMap::new();
+  let mut seen = HashSet::new();
+  let mut pending = VecDeque::from([specifier]);
+  while let Some(specifier) = pending.pop_front() {
+    if !seen.insert(specifier) {
+      continue;
+    }
+    let parents = dependents.get(specifier).cloned().unwrap_or_default();
+    importers.insert(
+      specifier.to_string(),
+      parents.iter().map(|s| s.to_string()).collect(),
+    );
+    pending.extend(parents);
+  }
+  importers
+}
+
 fn explain(status: &cdp::Status) -> &'static str {
   match status {
     cdp::Status::Ok => "OK",
@@ -52,13 +164,19 @@ fn should_retry(status: &cdp::Status) -> bool {
 ///
 /// Even if the runner decides that a path will be hot-replaced, the V8 isolate
 /// can refuse to perform hot replacement, eg. a top-level variable/function
-/// of an ES module cannot be hot-replaced. In such situation the runner will
-/// force a full restart of a program by notifying the `FileWatcher`.
+/// of an ES module cannot be hot-replaced. In such situation, and for modules
+/// that aren't scripts (eg. JSON modules), the runner evaluates the changed
+/// module and its importers again, up to the modules that accept the update
+/// through `hot(import.meta).accept()`. Changed text files are passed to the
+/// modules that accept them. If no module accepts an update, the runner
+/// will force a full restart of a program by notifying the `FileWatcher`.
 pub struct HmrRunner {
   session: LocalInspectorSession,
   watcher_communicator: Arc<WatcherCommunicator>,
   script_ids: HashMap<String, String>,
   emitter: Arc<Emitter>,
+  graph_container: Arc<MainModuleGraphContainer>,
+  module_versions: Arc<HmrModuleVersions>,
 }
 
 #[async_trait::async_trait(?Send)]
@@ -92,14 +210,7 @@ impl crate::worker::HmrRunner for HmrRunner {
             break Err(generic_error(format!("{} {}", message, description)));
           } else if notification.method == "Debugger.scriptParsed" {
             let params = serde_json::from_value::<cdp::ScriptParsed>(notification.params)?;
-            if params.url.starts_with("file://") {
-              let file_url = Url::parse(&params.url).unwrap();
-              let file_path = file_url.to_file_path().unwrap();
-              if let Ok(canonicalized_file_path) = file_path.canonicalize() {
-                let canonicalized_file_url = Url::from_file_path(canonicalized_file_path).unwrap();
-                self.script_ids.insert(canonicalized_file_url.to_string(), params.script_id);
-              }
-            }
+            self.register_script(&params.url, params.script_id);
           }
         }
         changed_paths = self.watcher_communicator.watch_for_changed_paths() => {
@@ -110,55 +221,8 @@ impl crate::worker::HmrRunner for HmrRunner {
             continue;
           };
 
-          let filtered_paths: Vec<PathBuf> = changed_paths.into_iter().filter(|p| p.extension().map_or(false, |ext| {
-            let ext_str = ext.to_str().unwrap();
-            matches!(ext_str, "js" | "ts" | "jsx" | "tsx")
-          })).collect();
-
-          // If after filtering there are no paths it means it's either a file
-          // we can't HMR or an external file that was passed explicitly to
-          // `--unstable-hmr=<file>` path.
-          if filtered_paths.is_empty() {
-            let _ = self.watcher_communicator.force_restart();
-            continue;
-          }
-
-          for path in filtered_paths {
-            let Some(path_str) = path.to_str() else {
-              let _ = self.watcher_communicator.force_restart();
-              continue;
-            };
-            let Ok(module_url) = Url::from_file_path(path_str) else {
-              let _ = self.watcher_communicator.force_restart();
-              continue;
-            };
-
-            let Some(id) = self.script_ids.get(module_url.as_str()).cloned() else {
-              let _ = self.watcher_communicator.force_restart();
-              continue;
-            };
-
-            let source_code = self.emitter.load_and_emit_for_hmr(
-              &module_url
-            ).await?;
-
-            let mut tries = 1;
-            loop {
-              let result = self.set_script_source(&id, source_code.as_str()).await?;
-
-              if matches!(result.status, cdp::Status::Ok) {
-                self.dispatch_hmr_event(module_url.as_str()).await?;
-                self.watcher_communicator.print(format!("Replaced changed module {}", module_url.as_str()));
-                break;
-              }
-
-              self.watcher_communicator.print(format!("Failed to reload module {}: {}.", module_url, colors::gray(explain(&result.status))));
-              if should_retry(&result.status) && tries <= 2 {
-                tries += 1;
-                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-                continue;
-              }
-
+          for path in changed_paths {
+            if !self.handle_changed_path(&path).await? {
               let _ = self.watcher_communicator.force_restart();
               break;
             }
@@ -173,17 +237,207 @@ impl crate::worker::HmrRunner for HmrRunner {
 impl HmrRunner {
   pub fn new(
     emitter: Arc<Emitter>,
+    graph_container: Arc<MainModuleGraphContainer>,
+    module_versions: Arc<HmrModuleVersions>,
     session: LocalInspectorSession,
     watcher_communicator: Arc<WatcherCommunicator>,
   ) -> Self {
     Self {
       session,
       emitter,
+      graph_container,
+      module_versions,
       watcher_communicator,
       script_ids: HashMap::new(),
     }
   }
 
+  /// Records the script id of a parsed module, keyed by its canonicalized
+  /// file URL. A module evaluated again by hot module replacement replaces
+  /// the entry of its previous instance, so later changes are applied to the
+  /// instance that is in use.
+  fn register_script(&mut self, url: &str, script_id: String) {
+    if !url.starts_with("file://") {
+      return;
+    }
+    let Ok(file_url) = Url::parse(url) else {
+      return;
+    };
+    let file_url = match HmrModuleVersions::base_specifier(&file_url) {
+      Some(base) => base,
+      // a module imported with its own query string is a separate instance
+      None if file_url.query().is_some() => return,
+      None => file_url,
+    };
+    let Ok(file_path) = file_url.to_file_path() else {
+      return;
+    };
+    if let Ok(canonicalized_file_path) = file_path.canonicalize() {
+      let canonicalized_file_url =
+        Url::from_file_path(canonicalized_file_path).unwrap();
+      self
+        .script_ids
+        .insert(canonicalized_file_url.to_string(), script_id);
+    }
+  }
+
+  /// Tries to apply the change of a single file to the running program.
+  /// Returns `false` if a full restart is needed instead.
+  async fn handle_changed_path(
+    &mut self,
+    path: &Path,
+  ) -> Result<bool, AnyError> {
+    let Ok(module_url) = Url::from_file_path(path) else {
+      return Ok(false);
+    };
+
+    let is_script = path
+      .extension()
+      .and_then(|ext| ext.to_str())
+      .map_or(false, |ext| matches!(ext, "js" | "ts" | "jsx" | "tsx"));
+    let script_id = if is_script {
+      self.script_ids.get(module_url.as_str()).cloned()
+    } else {
+      None
+    };
+
+    if let Some(id) = script_id {
+      let source_code = self.emitter.load_and_emit_for_hmr(&module_url).await?;
+
+      let mut tries = 1;
+      loop {
+        let result = self.set_script_source(&id, source_code.as_str()).await?;
+
+        if matches!(result.status, cdp::Status::Ok) {
+          self.dispatch_hmr_event(module_url.as_str()).await?;
+          self
+            .watcher_communicator
+            .print(format!("Replaced changed module {}", module_url.as_str()));
+          return Ok(true);
+        }
+
+        self.watcher_communicator.print(format!(
+          "Failed to reload module {}: {}.",
+          module_url,
+          colors::gray(explain(&result.status))
+        ));
+        if should_retry(&result.status) && tries <= 2 {
+          tries += 1;
+          tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+          continue;
+        }
+
+        // The module body changed in a way V8 can't patch in place, try to
+        // evaluate it again instead.
+        if !matches!(
+          result.status,
+          cdp::Status::BlockedByTopLevelEsModuleChange
+        ) {
+          return Ok(false);
+        }
+        break;
+      }
+    }
+
+    self.invalidate_module(&module_url).await
+  }
+
+  /// Evaluates the changed module and its importers again, up to the modules
+  /// that accept the update through `hot(import.meta).accept()`.
+  ///
+  /// Files that aren't modules of the program, eg. text files passed to
+  /// `--unstable-hmr`, are passed as text to the modules that accept them.
+  async fn invalidate_module(
+    &mut self,
+    module_url: &ModuleSpecifier,
+  ) -> Result<bool, AnyError> {
+    let graph = self.graph_container.graph();
+    let (importers, text) = if graph.get(module_url).is_some() {
+      (collect_importers(&graph, module_url), None)
+    } else {
+      let Ok(path) = module_url.to_file_path() else {
+        return Ok(false);
+      };
+      let Ok(text) = std::fs::read_to_string(path) else {
+        return Ok(false);
+      };
+      (HashMap::new(), Some(text))
+    };
+
+    let result = self
+      .evaluate_internal(format!(
+        "system[system.internal].hmr.prepareUpdate({}, {})",
+        serde_json::to_string(module_url.as_str())?,
+        serde_json::to_string(&importers)?,
+      ))
+      .await?;
+    let Some(invalidated) = result
+      .and_then(|value| serde_json::from_value::<Vec<String>>(value).ok())
+    else {
+      return Ok(false);
+    };
+
+    let version = self.module_versions.invalidate(
+      invalidated
+        .iter()
+        .filter_map(|specifier| ModuleSpecifier::parse(specifier).ok()),
+    );
+    let result = self
+      .evaluate_internal(format!(
+        "system[system.internal].hmr.applyUpdate({version}, {})",
+        serde_json::to_string(&text)?,
+      ))
+      .await?;
+    if result != Some(serde_json::Value::Bool(true)) {
+      return Ok(false);
+    }
+
+    self.dispatch_hmr_event(module_url.as_str()).await?;
+    if text.is_some() {
+      self
+        .watcher_communicator
+        .print(format!("Updated changed file {}", module_url.as_str()));
+    } else {
+      self.watcher_communicator.print(format!(
+        "Re-evaluated changed module {} ({} module(s) invalidated)",
+        module_url.as_str(),
+        invalidated.len()
+      ));
+    }
+    Ok(true)
+  }
+
+  /// Evaluates an expression in the main realm, awaiting its result. Returns
+  /// `None` if the expression threw.
+  async fn evaluate_internal(
+    &mut self,
+    expression: String,
+  ) -> Result<Option<serde_json::Value>, AnyError> {
+    let result = self
+      .session
+      .post_message(
+        "Runtime.evaluate",
+        Some(json!({
+          "expression": expression,
+          "contextId": Some(1),
+          "awaitPromise": true,
+          "returnByValue": true,
+        })),
+      )
+      .await?;
+    let response = serde_json::from_value::<cdp::EvaluateResponse>(result)?;
+    if let Some(exception_details) = response.exception_details {
+      let (message, description) =
+        exception_details.get_message_and_description();
+      self.watcher_communicator.print(format!(
+        "Failed to apply update: {} {}",
+        message, description
+      ));
+      return Ok(None);
+    }
+    Ok(response.result.value)
+  }
+
   // TODO(bartlomieju): this code is duplicated in `cli/tools/coverage/mod.rs`
   async fn enable_debugger(&mut self) -> Result<(), AnyError> {
     self
@@ -255,3 +509,44 @@ impl HmrRunner {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn hmr_module_versions_resolve() {
+    let versions = HmrModuleVersions::default();
+    let a = ModuleSpecifier::parse("file:///a.ts").unwrap();
+    let b = ModuleSpecifier::parse("file:///b.json").unwrap();
+    assert_eq!(versions.resolve(a.clone()), a);
+
+    assert_eq!(versions.invalidate([a.clone(), b.clone()]), 1);
+    assert_eq!(versions.resolve(a.clone()).as_str(), "file:///a.ts?__hmr=1");
+    assert_eq!(versions.invalidate([b.clone()]), 2);
+    assert_eq!(versions.resolve(a).as_str(), "file:///a.ts?__hmr=1");
+    assert_eq!(versions.resolve(b).as_str(), "file:///b.json?__hmr=2");
+
+    // specifiers with a query are left alone
+    let c = ModuleSpecifier::parse("file:///a.ts?foo=bar").unwrap();
+    assert_eq!(versions.resolve(c.clone()), c);
+  }
+
+  #[test]
+  fn hmr_module_versions_base_specifier() {
+    let run = |specifier: &str| {
+      HmrModuleVersions::base_specifier(
+        &ModuleSpecifier::parse(specifier).unwrap(),
+      )
+      .map(|s| s.to_string())
+    };
+    assert_eq!(
+      run("file:///a.ts?__hmr=3"),
+      Some("file:///a.ts".to_string())
+    );
+    assert_eq!(run("file:///a.ts"), None);
+    assert_eq!(run("file:///a.ts?__hmr="), None);
+    assert_eq!(run("file:///a.ts?__hmr=1&foo=bar"), None);
+    assert_eq!(run("file:///a.ts?foo=bar"), None);
+  }
+}
@@ -31,6 +31,10 @@ use tokio::task::LocalSet;
 // WARNING: Do not depend on this env var in user code. It's not stable API.
 const USE_PKG_JSON_HIDDEN_ENV_VAR_NAME: &str =
   "DENO_INTERNAL_TASK_USE_PKG_JSON";
+/// The subcommands that take a `--permission-set` flag.
+const PERMISSION_SET_SUBCOMMANDS: [&str; 7] = [
+  "bench", "compile", "install", "repl", "run", "serve", "test",
+];
 
 pub async fn execute_script(
   flags: Flags,
@@ -59,6 +63,7 @@ pub async fn execute_script(
   let npm_resolver = factory.npm_resolver().await?;
   let node_resolver = factory.node_resolver().await?;
   let env_vars = real_env_vars();
+  let permission_set = cli_options.permission_flags().permission_set.as_ref();
   let force_use_pkg_json = std::env::var_os(USE_PKG_JSON_HIDDEN_ENV_VAR_NAME)
     .map(|v| {
       // always remove so sub processes don't inherit this env var
@@ -86,8 +91,11 @@ pub async fn execute_script(
       None => config_file_path.parent().unwrap().to_owned(),
     };
 
-    let custom_commands =
-      resolve_custom_commands(npm_resolver.as_ref(), node_resolver)?;
+    let custom_commands = resolve_custom_commands(
+      npm_resolver.as_ref(),
+      node_resolver,
+      permission_set,
+    )?;
     run_task(RunTaskOptions {
       task_name,
       script,
@@ -144,8 +152,11 @@ pub async fn execute_script(
       task_name.clone(),
       format!("post{}", task_name),
     ];
-    let custom_commands =
-      resolve_custom_commands(npm_resolver.as_ref(), node_resolver)?;
+    let custom_commands = resolve_custom_commands(
+      npm_resolver.as_ref(),
+      node_resolver,
+      permission_set,
+    )?;
     for task_name in &task_names {
       if let Some(script) = package_json_scripts.get(task_name) {
         let exit_code = run_task(RunTaskOptions {
@@ -380,6 +391,43 @@ impl ShellCommand for NpmCommand {
   }
 }
 
+/// Runs deno with the permission set the task was run with, unless the
+/// command selects one itself.
+struct DenoCommand {
+  permission_set: String,
+}
+
+impl ShellCommand for DenoCommand {
+  fn execute(
+    &self,
+    context: ShellCommandContext,
+  ) -> LocalBoxFuture<'static, ExecuteResult> {
+    let mut args = context.args.clone();
+    let takes_permission_set = args
+      .first()
+      .is_some_and(|arg| PERMISSION_SET_SUBCOMMANDS.contains(&arg.as_str()));
+    if takes_permission_set && !has_permission_set_flag(&args[1..]) {
+      args.insert(1, format!("--permission-set={}", self.permission_set));
+    }
+    ExecutableCommand::new("deno".to_string(), std::env::current_exe().unwrap())
+      .execute(ShellCommandContext { args, ..context })
+  }
+}
+
+/// Whether the flags of a subcommand select a permission set. The arguments
+/// after the script or `--` are the script's, so they aren't checked.
+fn has_permission_set_flag(args: &[String]) -> bool {
+  args
+    .iter()
+    .take_while(|arg| *arg != "--" && arg.starts_with('-'))
+    .any(|arg| {
+      arg == "-P"
+        || arg.starts_with("-P=")
+        || arg == "--permission-set"
+        || arg.starts_with("--permission-set=")
+    })
+}
+
 struct NpxCommand;
 
 impl ShellCommand for NpxCommand {
@@ -477,6 +525,7 @@ impl ShellCommand for NodeModulesFileRunCommand {
 fn resolve_custom_commands(
   npm_resolver: &dyn CliNpmResolver,
   node_resolver: &NodeResolver,
+  permission_set: Option<&String>,
 ) -> Result<HashMap<String, Rc<dyn ShellCommand>>, AnyError> {
   let mut commands = match npm_resolver.as_inner() {
     InnerCliNpmResolverRef::Byonm(npm_resolver) => {
@@ -488,6 +537,14 @@ fn resolve_custom_commands(
     }
   };
   commands.insert("npm".to_string(), Rc::new(NpmCommand));
+  if let Some(permission_set) = permission_set {
+    commands.insert(
+      "deno".to_string(),
+      Rc::new(DenoCommand {
+        permission_set: permission_set.clone(),
+      }),
+    );
+  }
   Ok(commands)
 }
 
@@ -627,6 +684,23 @@ mod test {
     );
   }
 
+  #[test]
+  fn test_has_permission_set_flag() {
+    let args =
+      |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert!(has_permission_set_flag(&args(&["-P", "main.ts"])));
+    assert!(has_permission_set_flag(&args(&["-P=server", "main.ts"])));
+    assert!(has_permission_set_flag(&args(&[
+      "--check",
+      "--permission-set=server",
+      "main.ts"
+    ])));
+    assert!(!has_permission_set_flag(&args(&["--check", "main.ts"])));
+    // the flags of the script
+    assert!(!has_permission_set_flag(&args(&["main.ts", "-P"])));
+    assert!(!has_permission_set_flag(&args(&["--", "-P"])));
+  }
+
   #[test]
   fn test_resolve_execution_path_from_npx_shim() {
     // example shim on unix
@@ -23,10 +23,15 @@ use crate::util::path::matches_pattern_or_exact_path;
 use crate::worker::CliMainWorkerFactory;
 use crate::worker::CoverageCollector;
 
+use deno_ast::swc::ast::Decl;
+use deno_ast::swc::ast::ModuleItem;
+use deno_ast::swc::ast::Program;
+use deno_ast::swc::ast::Stmt;
 use deno_ast::swc::common::comments::CommentKind;
 use deno_ast::MediaType;
 use deno_ast::SourceRangedForSpanned;
 use deno_config::glob::FilePatterns;
+use deno_config::glob::PathOrPattern;
 use deno_core::anyhow;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context as _;
@@ -38,6 +43,7 @@ use deno_core::futures::stream;
 use deno_core::futures::FutureExt;
 use deno_core::futures::StreamExt;
 use deno_core::located_script_name;
+use deno_core::serde_json;
 use deno_core::serde_v8;
 use deno_core::stats::RuntimeActivity;
 use deno_core::stats::RuntimeActivityDiff;
@@ -79,6 +85,7 @@ use std::fmt::Write as _;
 use std::future::poll_fn;
 use std::io::Write;
 use std::num::NonZeroUsize;
+use std::ops::Range;
 use std::path::Path;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicUsize;
@@ -1166,6 +1173,7 @@ fn extract_files_from_regex_blocks(
   file_line_index: usize,
   blocks_regex: &Regex,
   lines_regex: &Regex,
+  wrap_in_test: bool,
 ) -> Result<Vec<File>, AnyError> {
   let files = blocks_regex
     .captures_iter(source)
@@ -1212,12 +1220,10 @@ fn extract_files_from_regex_blocks(
       let body = block.get(2).unwrap();
       let text = body.as_str();
 
-      // TODO(caspervonb) generate an inline source map
-      let mut file_source = String::new();
-      for line in lines_regex.captures_iter(text) {
-        let text = line.get(1).unwrap();
-        writeln!(file_source, "{}", text.as_str()).unwrap();
-      }
+      let lines = lines_regex
+        .captures_iter(text)
+        .map(|line| line.get(1).unwrap().as_str())
+        .collect::<Vec<_>>();
 
       let file_specifier = ModuleSpecifier::parse(&format!(
         "{}${}-{}",
@@ -1231,6 +1237,23 @@ fn extract_files_from_regex_blocks(
           .map(|s| ModuleSpecifier::parse(&s).unwrap())
           .unwrap_or(file_specifier);
 
+      let file_source = if wrap_in_test {
+        wrap_example_in_test(
+          specifier,
+          &file_specifier,
+          file_media_type,
+          file_line_index + line_offset + 1,
+          &lines,
+        )
+      } else {
+        // TODO(caspervonb) generate an inline source map
+        let mut file_source = String::new();
+        for line in &lines {
+          writeln!(file_source, "{}", line).unwrap();
+        }
+        file_source
+      };
+
       Some(File {
         specifier: file_specifier,
         maybe_headers: None,
@@ -1242,10 +1265,118 @@ fn extract_files_from_regex_blocks(
   Ok(files)
 }
 
+/// Wraps the code of a documentation example in a `Deno.test`, so it's run
+/// as a test. Imports, exports and ambient declarations are hoisted out of
+/// the test, above the line of the fence when there's room for them, and the
+/// rest of the code is kept on the lines it has in the documented file, so
+/// the locations in errors point at the documentation.
+fn wrap_example_in_test(
+  documented_specifier: &ModuleSpecifier,
+  specifier: &ModuleSpecifier,
+  media_type: MediaType,
+  fence_line_number: usize,
+  lines: &[&str],
+) -> String {
+  let mut body = lines.join("\n");
+  let hoisted_ranges = find_hoisted_ranges(specifier, media_type, &body);
+  let mut hoisted_source = String::new();
+  for range in &hoisted_ranges {
+    writeln!(hoisted_source, "{}", &body[range.clone()]).unwrap();
+  }
+  // remove the hoisted items from the end, so the other ranges stay valid,
+  // but keep their line breaks
+  for range in hoisted_ranges.into_iter().rev() {
+    let line_breaks = "\n".repeat(body[range.clone()].matches('\n').count());
+    body.replace_range(range, &line_breaks);
+  }
+
+  let test_name = format!(
+    "{} (line {})",
+    documented_specifier
+      .path_segments()
+      .and_then(|mut segments| segments.next_back())
+      .unwrap_or_default(),
+    fence_line_number + 1
+  );
+
+  let padding_lines = fence_line_number.saturating_sub(1);
+  let hoisted_lines = hoisted_source.matches('\n').count();
+  let hoist_above = hoisted_lines <= padding_lines;
+  let mut source = String::new();
+  if hoist_above {
+    source.push_str(&"\n".repeat(padding_lines - hoisted_lines));
+    source.push_str(&hoisted_source);
+  } else {
+    source.push_str(&"\n".repeat(padding_lines));
+  }
+  writeln!(
+    source,
+    "Deno.test({}, async () => {{",
+    serde_json::to_string(&test_name).unwrap()
+  )
+  .unwrap();
+  source.push_str(&body);
+  source.push_str("\n});\n");
+  if !hoist_above {
+    // imports are hoisted by the module and the test only runs once the
+    // module is evaluated, so the items also work below the test
+    source.push_str(&hoisted_source);
+  }
+  source
+}
+
+/// The byte ranges of the top level items that can't be in the body of a
+/// function: imports, exports, namespaces and ambient declarations.
+fn find_hoisted_ranges(
+  specifier: &ModuleSpecifier,
+  media_type: MediaType,
+  text: &str,
+) -> Vec<Range<usize>> {
+  let Ok(parsed_source) = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier: specifier.clone(),
+    text: text.into(),
+    media_type,
+    capture_tokens: false,
+    maybe_syntax: None,
+    scope_analysis: false,
+  }) else {
+    // the type checking will surface the error
+    return Vec::new();
+  };
+  let program = parsed_source.program();
+  let program: &Program = &program;
+  let Program::Module(module) = program else {
+    return Vec::new();
+  };
+  let source_start = parsed_source.text_info_lazy().range().start;
+  module
+    .body
+    .iter()
+    .filter(|item| match item {
+      ModuleItem::ModuleDecl(_) => true,
+      ModuleItem::Stmt(Stmt::Decl(decl)) => match decl {
+        Decl::Class(class) => class.declare,
+        Decl::Fn(function) => function.declare,
+        Decl::Var(var) => var.declare,
+        Decl::TsEnum(ts_enum) => ts_enum.declare,
+        Decl::TsModule(_) => true,
+        _ => false,
+      },
+      _ => false,
+    })
+    .map(|item| {
+      let range = item.range();
+      range.start.as_byte_index(source_start)
+        ..range.end.as_byte_index(source_start)
+    })
+    .collect()
+}
+
 fn extract_files_from_source_comments(
   specifier: &ModuleSpecifier,
   source: Arc<str>,
   media_type: MediaType,
+  wrap_in_test: bool,
 ) -> Result<Vec<File>, AnyError> {
   let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
     specifier: specifier.clone(),
@@ -1276,6 +1407,7 @@ fn extract_files_from_source_comments(
         parsed_source.text_info_lazy().line_index(comment.start()),
         blocks_regex,
         lines_regex,
+        wrap_in_test,
       )
     })
     .flatten()
@@ -1288,6 +1420,7 @@ fn extract_files_from_fenced_blocks(
   specifier: &ModuleSpecifier,
   source: &str,
   media_type: MediaType,
+  wrap_in_test: bool,
 ) -> Result<Vec<File>, AnyError> {
   // The pattern matches code blocks as well as anything in HTML comment syntax,
   // but it stores the latter without any capturing groups. This way, a simple
@@ -1304,12 +1437,14 @@ fn extract_files_from_fenced_blocks(
     /* file line index */ 0,
     blocks_regex,
     lines_regex,
+    wrap_in_test,
   )
 }
 
 async fn fetch_inline_files(
   file_fetcher: &FileFetcher,
   specifiers: Vec<ModuleSpecifier>,
+  wrap_in_test: bool,
 ) -> Result<Vec<File>, AnyError> {
   let mut files = Vec::new();
   for specifier in specifiers {
@@ -1324,12 +1459,14 @@ async fn fetch_inline_files(
         &file.specifier,
         &file.source,
         file.media_type,
+        wrap_in_test,
       )
     } else {
       extract_files_from_source_comments(
         &file.specifier,
         file.source,
         file.media_type,
+        wrap_in_test,
       )
     };
 
@@ -1340,11 +1477,16 @@ async fn fetch_inline_files(
 }
 
 /// Type check a collection of module and document specifiers.
+///
+/// When `run_doc` is set, the code blocks of the documentation are wrapped in
+/// tests and the specifiers of these test modules are returned, so they can
+/// be run along with the executable modules.
 pub async fn check_specifiers(
   file_fetcher: &FileFetcher,
   main_graph_container: &Arc<MainModuleGraphContainer>,
   specifiers: Vec<(ModuleSpecifier, TestMode)>,
-) -> Result<(), AnyError> {
+  run_doc: bool,
+) -> Result<Vec<ModuleSpecifier>, AnyError> {
   let inline_files = fetch_inline_files(
     file_fetcher,
     specifiers
@@ -1357,8 +1499,17 @@ pub async fn check_specifiers(
         }
       })
       .collect(),
+    run_doc,
   )
   .await?;
+  let doc_test_specifiers = if run_doc {
+    inline_files
+      .iter()
+      .map(|file| file.specifier.clone())
+      .collect()
+  } else {
+    Vec::new()
+  };
 
   let mut module_specifiers = specifiers
     .into_iter()
@@ -1384,7 +1535,7 @@ pub async fn check_specifiers(
     .check_specifiers(&module_specifiers)
     .await?;
 
-  Ok(())
+  Ok(doc_test_specifiers)
 }
 
 static HAS_TEST_RUN_SIGINT_HANDLER: AtomicBool = AtomicBool::new(false);
@@ -1644,6 +1795,44 @@ fn is_supported_test_ext(path: &Path) -> bool {
   }
 }
 
+/// Finds the readmes of the included directories and of the directories of
+/// the included files, or of the base directory when nothing is included.
+/// They hold the examples users see first, so they're run even when they
+/// aren't part of the included files.
+fn find_readmes(files: &FilePatterns) -> Vec<ModuleSpecifier> {
+  let dirs = match &files.include {
+    Some(include) => include
+      .inner()
+      .iter()
+      .filter_map(|path_or_pattern| match path_or_pattern {
+        PathOrPattern::Path(path) if path.is_dir() => Some(path.clone()),
+        PathOrPattern::Path(path) => path.parent().map(ToOwned::to_owned),
+        _ => None,
+      })
+      .collect::<Vec<_>>(),
+    None => vec![files.base.clone()],
+  };
+  let mut readmes = Vec::new();
+  for dir in dirs {
+    let Some(path) = ["README.md", "readme.md", "Readme.md"]
+      .iter()
+      .map(|name| dir.join(name))
+      .find(|path| path.is_file())
+    else {
+      continue;
+    };
+    if files.exclude.matches_path(&path) {
+      continue;
+    }
+    if let Ok(specifier) = ModuleSpecifier::from_file_path(path) {
+      if !readmes.contains(&specifier) {
+        readmes.push(specifier);
+      }
+    }
+  }
+  readmes
+}
+
 /// Collects specifiers marking them with the appropriate test mode while maintaining the natural
 /// input order.
 ///
@@ -1651,10 +1840,14 @@ fn is_supported_test_ext(path: &Path) -> bool {
 /// `TestMode::Documentation`.
 /// - Specifiers matching the `is_supported_test_path` are marked as `TestMode::Executable`.
 /// - Specifiers matching both predicates are marked as `TestMode::Both`
+///
+/// When `run_doc` is set, the readmes found by `find_readmes` are included
+/// as well.
 fn collect_specifiers_with_test_mode(
   cli_options: &CliOptions,
   files: FilePatterns,
   include_inline: &bool,
+  run_doc: bool,
 ) -> Result<Vec<(ModuleSpecifier, TestMode)>, AnyError> {
   // todo(dsherret): there's no need to collect twice as it's slow
   let vendor_folder = cli_options.vendor_dir_path();
@@ -1665,25 +1858,34 @@ fn collect_specifiers_with_test_mode(
   )?;
 
   if *include_inline {
-    return collect_specifiers(
-      files,
-      vendor_folder.map(ToOwned::to_owned),
-      |e| is_supported_test_ext(e.path),
-    )
-    .map(|specifiers| {
-      specifiers
-        .into_iter()
-        .map(|specifier| {
-          let mode = if module_specifiers.contains(&specifier) {
-            TestMode::Both
-          } else {
-            TestMode::Documentation
-          };
-
-          (specifier, mode)
-        })
-        .collect()
-    });
+    let readmes = if run_doc {
+      find_readmes(&files)
+    } else {
+      Vec::new()
+    };
+    let mut specifiers_with_mode =
+      collect_specifiers(files, vendor_folder.map(ToOwned::to_owned), |e| {
+        is_supported_test_ext(e.path)
+      })?
+      .into_iter()
+      .map(|specifier| {
+        let mode = if module_specifiers.contains(&specifier) {
+          TestMode::Both
+        } else {
+          TestMode::Documentation
+        };
+
+        (specifier, mode)
+      })
+      .collect::<Vec<_>>();
+
+    for readme in readmes {
+      if !specifiers_with_mode.iter().any(|(s, _)| *s == readme) {
+        specifiers_with_mode.push((readme, TestMode::Documentation));
+      }
+    }
+
+    return Ok(specifiers_with_mode);
   }
 
   let specifiers_with_mode = module_specifiers
@@ -1707,9 +1909,10 @@ async fn fetch_specifiers_with_test_mode(
   file_fetcher: &FileFetcher,
   files: FilePatterns,
   doc: &bool,
+  run_doc: bool,
 ) -> Result<Vec<(ModuleSpecifier, TestMode)>, AnyError> {
   let mut specifiers_with_mode =
-    collect_specifiers_with_test_mode(cli_options, files, doc)?;
+    collect_specifiers_with_test_mode(cli_options, files, doc, run_doc)?;
 
   for (specifier, mode) in &mut specifiers_with_mode {
     let file = file_fetcher
@@ -1745,6 +1948,7 @@ pub async fn run_tests(
     file_fetcher,
     test_options.files.clone(),
     &test_options.doc,
+    test_options.run_doc,
   )
   .await?;
 
@@ -1754,10 +1958,11 @@ pub async fn run_tests(
 
   let main_graph_container = factory.main_module_graph_container().await?;
 
-  check_specifiers(
+  let doc_test_specifiers = check_specifiers(
     file_fetcher,
     main_graph_container,
     specifiers_with_mode.clone(),
+    test_options.run_doc,
   )
   .await?;
 
@@ -1777,6 +1982,7 @@ pub async fn run_tests(
         TestMode::Documentation => None,
         _ => Some(s),
       })
+      .chain(doc_test_specifiers)
       .collect(),
     TestSpecifiersOptions {
       cwd: Url::from_directory_path(cli_options.initial_cwd()).map_err(
@@ -1866,6 +2072,19 @@ pub async fn run_tests_with_watch(
             is_supported_test_path_predicate,
           )
         }?;
+        // markdown files can't be loaded as modules, so keep them out of
+        // the graph and only check them when they change
+        let (mut markdown_files, test_modules): (Vec<_>, Vec<_>) =
+          test_modules.into_iter().partition(|specifier| {
+            MediaType::from_specifier(specifier) == MediaType::Unknown
+          });
+        if test_options.run_doc {
+          for readme in find_readmes(&test_options.files) {
+            if !markdown_files.contains(&readme) {
+              markdown_files.push(readme);
+            }
+          }
+        }
 
         let permissions =
           Permissions::from_options(&cli_options.permissions_options()?)?;
@@ -1888,9 +2107,17 @@ pub async fn run_tests_with_watch(
               result.insert(test_module_specifier.clone());
             }
           }
+          for specifier in markdown_files {
+            let changed = specifier
+              .to_file_path()
+              .is_ok_and(|path| changed_paths.contains(&path));
+            if changed {
+              result.insert(specifier);
+            }
+          }
           result
         } else {
-          test_modules.clone()
+          test_modules.iter().cloned().chain(markdown_files).collect()
         };
 
         let worker_factory =
@@ -1900,6 +2127,7 @@ pub async fn run_tests_with_watch(
           file_fetcher,
           test_options.files.clone(),
           &test_options.doc,
+          test_options.run_doc,
         )
         .await?
         .into_iter()
@@ -1908,10 +2136,11 @@ pub async fn run_tests_with_watch(
 
         let main_graph_container =
           factory.main_module_graph_container().await?;
-        check_specifiers(
+        let doc_test_specifiers = check_specifiers(
           file_fetcher,
           main_graph_container,
           specifiers_with_mode.clone(),
+          test_options.run_doc,
         )
         .await?;
 
@@ -1928,6 +2157,7 @@ pub async fn run_tests_with_watch(
               TestMode::Documentation => None,
               _ => Some(s),
             })
+            .chain(doc_test_specifiers)
             .collect(),
           TestSpecifiersOptions {
             cwd: Url::from_directory_path(cli_options.initial_cwd()).map_err(
@@ -2060,4 +2290,73 @@ mod inner_test {
     assert!(!is_supported_test_path(Path::new("notatest.js")));
     assert!(!is_supported_test_path(Path::new("NotAtest.ts")));
   }
+  #[test]
+  fn test_wrap_example_in_test() {
+    let documented_specifier =
+      ModuleSpecifier::parse("file:///mod.ts").unwrap();
+    let specifier = ModuleSpecifier::parse("file:///mod.ts$3-7.ts").unwrap();
+    let source = wrap_example_in_test(
+      &documented_specifier,
+      &specifier,
+      MediaType::TypeScript,
+      3,
+      &[
+        "import { add } from \"./mod.ts\";",
+        "",
+        "assertEquals(add(1, 2), 3);",
+      ],
+    );
+    assert_eq!(
+      source,
+      concat!(
+        "\n",
+        "import { add } from \"./mod.ts\";\n",
+        "Deno.test(\"mod.ts (line 4)\", async () => {\n",
+        "\n",
+        "\n",
+        "assertEquals(add(1, 2), 3);\n",
+        "});\n",
+      )
+    );
+  }
+
+  #[test]
+  fn test_wrap_example_in_test_hoisted_items() {
+    let documented_specifier =
+      ModuleSpecifier::parse("file:///mod.ts").unwrap();
+    let specifier = ModuleSpecifier::parse("file:///mod.ts$1-8.ts").unwrap();
+    let source = wrap_example_in_test(
+      &documented_specifier,
+      &specifier,
+      MediaType::TypeScript,
+      1,
+      &[
+        "import {",
+        "  add, // the function",
+        "} from \"./mod.ts\";",
+        "declare const a: number;",
+        "export const b = add(a, 1);",
+        "console.log(b);",
+      ],
+    );
+    // there's no room above the fence, so the items are below the test
+    assert_eq!(
+      source,
+      concat!(
+        "Deno.test(\"mod.ts (line 2)\", async () => {\n",
+        "\n",
+        "\n",
+        "\n",
+        "\n",
+        "\n",
+        "console.log(b);\n",
+        "});\n",
+        "import {\n",
+        "  add, // the function\n",
+        "} from \"./mod.ts\";\n",
+        "declare const a: number;\n",
+        "export const b = add(a, 1);\n",
+      )
+    );
+  }
 }
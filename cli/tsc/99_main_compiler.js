@@ -34,10 +34,12 @@ delete Object.prototype.__proto__;
     "AtomicOperation",
     "CreateHttpClientOptions",
     "DatagramConn",
+    "HotContext",
     "HttpClient",
     "Kv",
     "KvListIterator",
     "KvU64",
+    "SelfUpdateOptions",
     "UnsafeCallback",
     "UnsafePointer",
     "UnsafePointerView",
@@ -50,9 +52,11 @@ delete Object.prototype.__proto__;
     "flockSync",
     "funlock",
     "funlockSync",
+    "hot",
     "listen",
     "listenDatagram",
     "openKv",
+    "selfUpdate",
     "umask",
   ]);
   const unstableMsgSuggestion =
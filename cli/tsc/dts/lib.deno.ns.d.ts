@@ -4726,6 +4726,88 @@ declare namespace Deno {
      *
      * @default {false} */
     windowsRawArguments?: boolean;
+
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Resource limits of the spawned process, set with `setrlimit`. Not
+     * supported on Windows.
+     *
+     * @experimental */
+    rlimits?: {
+      /** CPU time, in seconds. */
+      cpu?: number;
+      /** Size of the address space, in bytes. */
+      memory?: number;
+      /** Maximum number of open file descriptors. */
+      openFiles?: number;
+    };
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Starts the spawned process in a new process group. Not supported on
+     * Windows.
+     *
+     * @experimental
+     * @default {false} */
+    newProcessGroup?: boolean;
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Starts the spawned process in a new session, which also puts it in a
+     * new process group. Not supported on Windows.
+     *
+     * @experimental
+     * @default {false} */
+    newSession?: boolean;
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Prevents the spawned process from gaining privileges, e.g. by
+     * executing setuid binaries. Linux only.
+     *
+     * @experimental
+     * @default {false} */
+    noNewPrivileges?: boolean;
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Kills the spawned process when the thread that spawned it exits,
+     * including when the program crashes. Linux only.
+     *
+     * This is the thread rather than the whole program, so a process spawned
+     * by a worker is killed when the worker is terminated, even if the
+     * program keeps running.
+     *
+     * @experimental
+     * @default {false} */
+    killOnParentExit?: boolean;
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Restricts the spawned process's filesystem access to the given paths
+     * with Landlock. The command itself and the libraries it loads must be
+     * readable. Requires Linux 5.13 or later, and implies `noNewPrivileges`.
+     *
+     * Every filesystem right known to the kernel is restricted, so linking
+     * or renaming files across directories (Linux 5.19), truncating files
+     * (Linux 6.2) and `ioctl` on devices (Linux 6.10) are only restricted on
+     * kernels that support it. Network access isn't restricted by Landlock,
+     * use `seccomp` for that.
+     *
+     * @experimental
+     */
+    landlock?: {
+      /** Paths that may be read and executed. */
+      read?: string[];
+      /** Paths that may be read, executed and written. */
+      write?: string[];
+    };
+    /** **UNSTABLE**: New API, yet to be vetted.
+     *
+     * Restricts the system calls the spawned process may make with a
+     * seccomp filter. Linux on x86-64 and ARM64 only, and implies
+     * `noNewPrivileges`.
+     *
+     * @experimental */
+    seccomp?: {
+      /** Denies creating sockets other than Unix domain sockets. */
+      denyNetwork?: boolean;
+    };
   }
 
   /**
@@ -2082,6 +2082,122 @@ declare namespace Deno {
     readonly value: bigint;
   }
 
+  /**
+   * The hot module replacement context of a module, returned by
+   * {@linkcode Deno.hot}.
+   *
+   * @category Runtime
+   * @experimental
+   */
+  export interface HotContext {
+    /** Data persisted between the replaced and the new instance of the
+     * module. */
+    readonly data: Record<string, unknown>;
+
+    /** Accept updates of this module. When it changes, the module is
+     * evaluated again and the callback receives its new namespace. */
+    accept(callback?: (module: any) => void | Promise<void>): void;
+    /** Accept updates of the given dependencies of this module. When one of
+     * them changes, it is evaluated again and the callback receives its new
+     * namespace, while this module is kept as is.
+     *
+     * Files that aren't modules, like text files passed to `--unstable-hmr`,
+     * can be accepted too. The callback then receives their new contents as
+     * `{ default: text }`. */
+    accept(
+      deps: string | string[],
+      callback: (module: any) => void | Promise<void>,
+    ): void;
+    /** Register a callback run before this module is replaced, to clean up
+     * side effects and store state in `data`. */
+    dispose(
+      callback: (data: Record<string, unknown>) => void | Promise<void>,
+    ): void;
+    /** Opt this module out of hot module replacement, so changes to it
+     * restart the program. */
+    decline(): void;
+  }
+
+  /**
+   * Returns the hot module replacement context of the module, used to
+   * control how updates are applied. Only available when running with
+   * `--unstable-hmr`.
+   *
+   * Modules that can't be patched in place, like JSON modules or modules
+   * with changed top-level code, are evaluated again together with their
+   * importers, up to the modules that accept the update. If no module
+   * accepts it, the program is restarted.
+   *
+   * ```ts
+   * import config from "./config.json" with { type: "json" };
+   *
+   * let current = config;
+   * const hot = Deno.hot(import.meta);
+   * hot.accept("./config.json", (module) => {
+   *   current = module.default;
+   * });
+   *
+   * // run with `--unstable-hmr=template.html`
+   * const url = new URL("./template.html", import.meta.url);
+   * let template = await Deno.readTextFile(url);
+   * hot.accept("./template.html", (module) => {
+   *   template = module.default;
+   * });
+   * ```
+   *
+   * @category Runtime
+   * @experimental
+   */
+  export function hot(importMeta: ImportMeta | string): HotContext;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Options for {@linkcode Deno.selfUpdate}. At least one of `sha256` or
+   * `signature` must be provided.
+   *
+   * @category Runtime
+   * @experimental
+   */
+  export interface SelfUpdateOptions {
+    /** The expected SHA-256 checksum of the new executable, as hex. */
+    sha256?: string;
+    /** A base64 encoded Ed25519 signature of the new executable. */
+    signature?: string;
+    /** The base64 encoded Ed25519 public key to verify `signature` with. */
+    publicKey?: string;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Replaces the running executable with a new build from a local path or an
+   * `http:`/`https:` URL. Only available in executables created with
+   * `deno compile`.
+   *
+   * Requires the executable to be compiled with `--unstable-self-update`.
+   *
+   * The new executable is verified against the provided checksum or signature,
+   * must itself be created by `deno compile` and is run once to check that it
+   * starts. If it can't be swapped in, the original executable is restored.
+   * The update takes effect the next time the executable is started.
+   *
+   * Requires `allow-write` permission for the executable, and `allow-read` or
+   * `allow-net` permission for the source.
+   *
+   * ```ts
+   * await Deno.selfUpdate("https://example.com/releases/my-app", {
+   *   sha256: "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9",
+   * });
+   * ```
+   *
+   * @tags allow-read, allow-write, allow-net
+   * @category Runtime
+   * @experimental
+   */
+  export function selfUpdate(
+    source: string | URL,
+    options: SelfUpdateOptions,
+  ): Promise<void>;
+
   /**
    * A namespace containing runtime APIs available in Jupyter notebooks.
    *
@@ -2278,6 +2394,100 @@ declare namespace Deno {
         buffers?: Uint8Array[];
       },
     ): Promise<void>;
+
+    /**
+     * A bidirectional channel between the kernel and the frontend, as used by
+     * interactive widgets.
+     *
+     * @category Jupyter
+     * @experimental
+     */
+    export interface Comm {
+      /** The unique id of the comm. */
+      readonly id: string;
+      /** The name of the target the comm was opened for. */
+      readonly targetName: string;
+      /** Whether the comm has been closed by either side. */
+      readonly closed: boolean;
+
+      /** Send a `comm_msg` to the frontend. */
+      send(
+        data?: Record<string, unknown>,
+        extra?: {
+          metadata?: Record<string, unknown>;
+          buffers?: Uint8Array[];
+        },
+      ): Promise<void>;
+
+      /** Close the comm, notifying the frontend with a `comm_close`. */
+      close(
+        data?: Record<string, unknown>,
+        extra?: {
+          metadata?: Record<string, unknown>;
+        },
+      ): Promise<void>;
+
+      /** Register a callback for messages sent by the frontend. */
+      onMessage(
+        callback: (
+          data: Record<string, unknown>,
+          buffers: Uint8Array[],
+        ) => void | Promise<void>,
+      ): void;
+
+      /** Register a callback for when the frontend closes the comm. */
+      onClose(
+        callback: (data: Record<string, unknown>) => void | Promise<void>,
+      ): void;
+    }
+
+    /**
+     * Open a comm to the given target in the frontend.
+     *
+     * ```
+     * const comm = await Deno.jupyter.openComm("jupyter.widget", {
+     *   state: { _model_name: "IntSliderModel", value: 5 },
+     *   buffer_paths: [],
+     * });
+     * comm.onMessage((data) => console.log(data));
+     * ```
+     *
+     * @category Jupyter
+     * @experimental
+     */
+    export function openComm(
+      targetName: string,
+      data?: Record<string, unknown>,
+      extra?: {
+        metadata?: Record<string, unknown>;
+        buffers?: Uint8Array[];
+      },
+    ): Promise<Comm>;
+
+    /**
+     * Register a handler for comms opened by the frontend for the given
+     * target. Comms opened for targets without a handler are closed right
+     * away.
+     *
+     * @category Jupyter
+     * @experimental
+     */
+    export function registerCommTarget(
+      targetName: string,
+      callback: (
+        comm: Comm,
+        data: Record<string, unknown>,
+        buffers: Uint8Array[],
+      ) => void | Promise<void>,
+    ): void;
+
+    /**
+     * Remove the handler registered for the given target.
+     *
+     * @category Jupyter
+     * @experimental
+     */
+    export function unregisterCommTarget(targetName: string): void;
   }
 }
 
@@ -2322,7 +2532,7 @@ declare interface WorkerOptions {
    * const worker = new Worker(
    *   new URL("deno_worker.ts", import.meta.url).href, {
    *     type: "module",
-   *     deno: {
+   *     system: {
    *       permissions: {
    *         read: true,
    *       },
@@ -2331,9 +2541,43 @@ declare interface WorkerOptions {
    * );
    * ```
    */
-  deno?: {
+  system?: {
     /** Set to `"none"` to disable all the permissions in the worker. */
     permissions?: Deno.PermissionOptions;
+    /** Limits on the resources the worker may use. A worker that exceeds
+     * its heap or wall-clock time limit is terminated, and an `error` event
+     * is dispatched on the `Worker` object.
+     *
+     * ```ts
+     * const worker = new Worker(
+     *   new URL("deno_worker.ts", import.meta.url).href, {
+     *     type: "module",
+     *     system: {
+     *       resourceLimits: {
+     *         maxHeapSizeMb: 64,
+     *         maxWallTimeMs: 10_000,
+     *       },
+     *     },
+     *   }
+     * );
+     * worker.onerror = (e) => {
+     *   console.error(e.message);
+     *   e.preventDefault();
+     * };
+     * ```
+     */
+    resourceLimits?: {
+      /** The maximum size of the worker's heap, in megabytes. */
+      maxHeapSizeMb?: number;
+      /** The size of the worker thread's stack, in megabytes. Must be at
+       * least 2. */
+      stackSizeMb?: number;
+      /** The maximum wall-clock time the worker may exist for, in
+       * milliseconds, counted from its creation, so loading its modules
+       * counts towards it. This is not CPU time: time the worker spends idle,
+       * e.g. waiting on timers, I/O or messages, counts towards it too. */
+      maxWallTimeMs?: number;
+    };
   };
 }
 
@@ -0,0 +1,251 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+
+/// The changed lines of each file. The ranges hold 1-indexed line numbers
+/// of the new version of the file, with an exclusive end.
+pub type ChangedLines = BTreeMap<PathBuf, Vec<Range<usize>>>;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GitDiffTarget {
+  /// The working tree compared to a revision, including untracked files.
+  Revision(String),
+  /// The index compared to `HEAD`.
+  Staged,
+}
+
+/// Gets the lines changed in the git repository containing `cwd` by
+/// reading the output of `git diff`.
+pub fn changed_lines(
+  cwd: &Path,
+  target: &GitDiffTarget,
+) -> Result<ChangedLines, AnyError> {
+  let root = run_git(cwd, &["rev-parse", "--show-toplevel"])?;
+  let root = PathBuf::from(root.trim_end());
+  let mut args = vec![
+    "-c",
+    "core.quotePath=false",
+    "diff",
+    "--unified=0",
+    "--no-color",
+    "--no-ext-diff",
+    // the paths are parsed with these prefixes, whatever the configuration
+    "--src-prefix=a/",
+    "--dst-prefix=b/",
+    "--no-renames",
+    "--diff-filter=AM",
+  ];
+  match target {
+    GitDiffTarget::Revision(revision) => args.push(revision.as_str()),
+    GitDiffTarget::Staged => args.push("--cached"),
+  }
+  args.push("--");
+  let mut changed_lines = parse_unified_diff(&root, &run_git(cwd, &args)?);
+
+  if matches!(target, GitDiffTarget::Staged) {
+    // the line numbers are the ones of the index, which only match the
+    // working tree when the file doesn't have unstaged changes as well
+    let unstaged = run_git(
+      cwd,
+      &[
+        "-c",
+        "core.quotePath=false",
+        "diff",
+        "--name-only",
+        "--no-renames",
+        "--",
+      ],
+    )?;
+    let partially_staged = unstaged
+      .lines()
+      .filter(|line| !line.is_empty())
+      .filter(|path| changed_lines.contains_key(&root.join(path)))
+      .collect::<Vec<_>>();
+    if !partially_staged.is_empty() {
+      bail!(
+        "Files with both staged and unstaged changes can't be formatted. Stage or stash the changes of:\n{}",
+        partially_staged
+          .iter()
+          .map(|path| format!("  {path}"))
+          .collect::<Vec<_>>()
+          .join("\n")
+      );
+    }
+  }
+
+  if matches!(target, GitDiffTarget::Revision(_)) {
+    let untracked = run_git(
+      cwd,
+      &[
+        "-c",
+        "core.quotePath=false",
+        "ls-files",
+        "--full-name",
+        "--others",
+        "--exclude-standard",
+      ],
+    )?;
+    for path in untracked.lines().filter(|line| !line.is_empty()) {
+      changed_lines.insert(root.join(path), vec![1..usize::MAX]);
+    }
+  }
+
+  Ok(changed_lines)
+}
+
+/// Creates a tar archive of the files of `cwd` at the given revision. The
+/// paths in the archive are relative to `cwd`.
+pub fn archive(cwd: &Path, revision: &str) -> Result<Vec<u8>, AnyError> {
+  run_git_raw(cwd, &["archive", "--format=tar", revision])
+}
+
+fn run_git(cwd: &Path, args: &[&str]) -> Result<String, AnyError> {
+  let output = run_git_raw(cwd, args)?;
+  Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+fn run_git_raw(cwd: &Path, args: &[&str]) -> Result<Vec<u8>, AnyError> {
+  let output = Command::new("git")
+    .args(args)
+    .current_dir(cwd)
+    .output()
+    .context("Failed running git. Is it installed?")?;
+  if !output.status.success() {
+    bail!(
+      "Failed running `git {}`: {}",
+      args.join(" "),
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+  }
+  Ok(output.stdout)
+}
+
+/// Parses the output of `git diff --unified=0`.
+fn parse_unified_diff(root: &Path, diff: &str) -> ChangedLines {
+  let mut changed_lines = ChangedLines::new();
+  let mut current_path = None;
+  // the number of lines of the current hunk that still need to be skipped,
+  // since they might look like a header
+  let mut remaining_hunk_lines = 0;
+  for line in diff.lines() {
+    if remaining_hunk_lines > 0 {
+      if !line.starts_with('\\') {
+        remaining_hunk_lines -= 1;
+      }
+      continue;
+    }
+    if line.starts_with("diff ") {
+      current_path = None;
+    } else if let Some(path) = line.strip_prefix("+++ ") {
+      // deleted files have a path of /dev/null
+      current_path = path.strip_prefix("b/").map(|path| root.join(path));
+    } else if let Some(header) = line.strip_prefix("@@ ") {
+      let Some(hunk) = parse_hunk_header(header) else {
+        continue;
+      };
+      remaining_hunk_lines = hunk.old_count + hunk.new_count;
+      if let Some(path) = &current_path {
+        changed_lines
+          .entry(path.clone())
+          .or_default()
+          .push(hunk.new_lines());
+      }
+    }
+  }
+  changed_lines
+}
+
+struct Hunk {
+  old_count: usize,
+  new_start: usize,
+  new_count: usize,
+}
+
+impl Hunk {
+  fn new_lines(&self) -> Range<usize> {
+    if self.new_count == 0 {
+      // lines were only removed after `new_start`, so use the lines
+      // around the removal
+      self.new_start.max(1)..self.new_start + 2
+    } else {
+      self.new_start..self.new_start + self.new_count
+    }
+  }
+}
+
+/// Parses a hunk header like `-1,2 +3,4 @@`, where the counts are optional.
+fn parse_hunk_header(header: &str) -> Option<Hunk> {
+  fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+      Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+      None => Some((range.parse().ok()?, 1)),
+    }
+  }
+
+  let mut parts = header.split(' ');
+  let (_, old_count) = parse_range(parts.next()?.strip_prefix('-')?)?;
+  let (new_start, new_count) = parse_range(parts.next()?.strip_prefix('+')?)?;
+  Some(Hunk {
+    old_count,
+    new_start,
+    new_count,
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn parses_unified_diff() {
+    let diff = r#"diff --git a/src/a.ts b/src/a.ts
+index 1111111..2222222 100644
+--- a/src/a.ts
++++ b/src/a.ts
+@@ -1 +1 @@
+-const a=1
++const a = 1
+@@ -5,0 +6,2 @@ function b() {
++++ b/not_a_header.ts
++  return;
+@@ -10,3 +11,0 @@ function c() {
+-removed
+-removed
+-removed
+diff --git a/deleted.ts b/deleted.ts
+deleted file mode 100644
+index 3333333..0000000
+--- a/deleted.ts
++++ /dev/null
+@@ -1 +0,0 @@
+-const deleted = true;
+diff --git a/new.ts b/new.ts
+new file mode 100644
+index 0000000..4444444
+--- /dev/null
++++ b/new.ts
+@@ -0,0 +1,3 @@
++const a = 1;
++const b = 2;
++\
+\ No newline at end of file
+"#;
+    let root = PathBuf::from("/repo");
+    let changed_lines = parse_unified_diff(&root, diff);
+    assert_eq!(
+      changed_lines,
+      ChangedLines::from([
+        (root.join("src/a.ts"), vec![1..2, 6..8, 11..13]),
+        (root.join("new.ts"), vec![1..4]),
+      ])
+    );
+  }
+}
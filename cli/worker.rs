@@ -393,6 +393,20 @@ impl CliMainWorker {
   ) -> Result<v8::Global<v8::Value>, AnyError> {
     self.worker.js_runtime.execute_script(name, source_code)
   }
+
+  /// Exposes `Deno.selfUpdate()`. The worker must have been created with
+  /// the `deno_self_update` extension.
+  pub fn enable_self_update(&mut self) -> Result<(), AnyError> {
+    self.worker.js_runtime.lazy_load_es_module_with_code(
+      "ext:cli/40_self_update.js",
+      deno_core::ascii_str_include!("js/40_self_update.js"),
+    )?;
+    self.execute_script_static(
+      deno_core::located_script_name!(),
+      "Deno[Deno.internal].enableSelfUpdate();",
+    )?;
+    Ok(())
+  }
 }
 
 pub struct CliMainWorkerFactory {
@@ -647,6 +661,8 @@ impl CliMainWorkerFactory {
       feature_checker,
       skip_op_registration: shared.options.skip_op_registration,
       v8_code_cache: shared.code_cache.clone(),
+      permission_prompter: None,
+      async_permission_prompter: None,
     };
 
     let mut worker = MainWorker::bootstrap_from_options(
@@ -851,6 +867,9 @@ fn create_web_worker_callback(
       strace_ops: shared.options.strace_ops.clone(),
       close_on_idle: args.close_on_idle,
       maybe_worker_metadata: args.maybe_worker_metadata,
+      permission_prompter: None,
+      async_permission_prompter: None,
+      resource_limits: args.resource_limits,
     };
 
     WebWorker::bootstrap_from_options(
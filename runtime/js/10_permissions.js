@@ -4,6 +4,7 @@ import { primordials } from "ext:core/mod.js";
 import {
   op_query_permission,
   op_request_permission,
+  op_request_permission_async,
   op_revoke_permission,
 } from "ext:core/ops";
 const {
@@ -241,12 +242,19 @@ class Permissions {
     return cache(desc, status);
   }
 
-  request(desc) {
-    try {
-      return PromiseResolve(this.requestSync(desc));
-    } catch (error) {
-      return PromiseReject(error);
+  async request(desc) {
+    if (!isValidDescriptor(desc)) {
+      throw new TypeError(
+        `The provided value "${desc?.name}" is not a valid permission name.`,
+      );
     }
+
+    formDescriptor(desc);
+
+    // unlike `requestSync`, doesn't block the event loop while an async
+    // prompter answers
+    const status = await op_request_permission_async(desc);
+    return cache(desc, status);
   }
 
   requestSync(desc) {
@@ -47,6 +47,7 @@ function createWorker(
   name,
   workerType,
   closeOnIdle,
+  resourceLimits,
 ) {
   return op_create_worker({
     hasSourceCode,
@@ -56,6 +57,7 @@ function createWorker(
     specifier,
     workerType,
     closeOnIdle,
+    resourceLimits,
   });
 }
 
@@ -95,7 +97,7 @@ class Worker extends EventTarget {
     super();
     specifier = String(specifier);
     const {
-      ext,
+      system,
       name,
       type = "classic",
     } = options;
@@ -127,10 +129,11 @@ class Worker extends EventTarget {
       specifier,
       hasSourceCode,
       sourceCode,
-      ext?.permissions,
+      system?.permissions,
       this.#name,
       workerType,
-      !(ext?.persistent ?? true),
+      !(system?.persistent ?? true),
+      system?.resourceLimits,
     );
     this.#id = id;
     this.#pollControl();
@@ -0,0 +1,257 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+import { internals, primordials } from "ext:core/mod.js";
+const {
+  ArrayIsArray,
+  ArrayPrototypePush,
+  ArrayPrototypeShift,
+  ObjectHasOwn,
+  SafeArrayIterator,
+  SafeMap,
+  SafeMapIterator,
+  SafeSet,
+  SafeSetIterator,
+  StringPrototypeEndsWith,
+  TypeError,
+} = primordials;
+
+// NOTE: keep in sync with `HMR_VERSION_QUERY_PARAM` in `cli/tools/run/hmr.rs`
+const HMR_VERSION_QUERY_PARAM = "__hmr";
+
+const _state = Symbol("[[state]]");
+const _reset = Symbol("[[reset]]");
+
+/** Hot contexts, keyed by the unversioned module URL. */
+const hotContexts = new SafeMap();
+
+/** The update computed by `prepareUpdate`, applied by `applyUpdate`. */
+let pendingUpdate = null;
+
+function baseModuleUrl(url) {
+  const parsed = new URL(url);
+  parsed.searchParams.delete(HMR_VERSION_QUERY_PARAM);
+  return parsed.href;
+}
+
+function versionedModuleUrl(url, version) {
+  const parsed = new URL(url);
+  parsed.searchParams.set(HMR_VERSION_QUERY_PARAM, `${version}`);
+  return parsed.href;
+}
+
+class HotContext {
+  #url;
+  #selfAcceptCallbacks = [];
+  #depAcceptCallbacks = new SafeMap();
+  #disposeCallbacks = [];
+  #declined = false;
+  /** Data persisted between the old and the new instance of a module. */
+  data = { __proto__: null };
+
+  constructor(url) {
+    this.#url = url;
+  }
+
+  /**
+   * Accept updates of this module, or of the given dependencies when called
+   * with specifiers. The callback receives the re-evaluated module.
+   */
+  accept(deps, callback) {
+    if (typeof deps === "function" || deps === undefined) {
+      ArrayPrototypePush(this.#selfAcceptCallbacks, deps ?? (() => {}));
+      return;
+    }
+    if (typeof callback !== "function") {
+      throw new TypeError("Accept callback must be a function");
+    }
+    const specifiers = ArrayIsArray(deps) ? deps : [deps];
+    for (const specifier of new SafeSetIterator(new SafeSet(specifiers))) {
+      const url = new URL(specifier, this.#url).href;
+      const callbacks = this.#depAcceptCallbacks.get(url) ?? [];
+      ArrayPrototypePush(callbacks, callback);
+      this.#depAcceptCallbacks.set(url, callbacks);
+    }
+  }
+
+  /**
+   * Register a callback that is called with `data` before this module is
+   * replaced, to clean up side effects and persist state.
+   */
+  dispose(callback) {
+    if (typeof callback !== "function") {
+      throw new TypeError("Dispose callback must be a function");
+    }
+    ArrayPrototypePush(this.#disposeCallbacks, callback);
+  }
+
+  /** Mark this module as not hot-replaceable, forcing a full restart. */
+  decline() {
+    this.#declined = true;
+  }
+
+  get [_state]() {
+    return {
+      declined: this.#declined,
+      selfAcceptCallbacks: this.#selfAcceptCallbacks,
+      depAcceptCallbacks: this.#depAcceptCallbacks,
+      disposeCallbacks: this.#disposeCallbacks,
+    };
+  }
+
+  [_reset]() {
+    this.#selfAcceptCallbacks = [];
+    this.#depAcceptCallbacks = new SafeMap();
+    this.#disposeCallbacks = [];
+    this.#declined = false;
+  }
+}
+
+/**
+ * Returns the hot context of the module with the given `import.meta`.
+ */
+function hot(importMeta) {
+  const url = baseModuleUrl(
+    typeof importMeta === "string" ? importMeta : importMeta.url,
+  );
+  let context = hotContexts.get(url);
+  if (context === undefined) {
+    context = new HotContext(url);
+    hotContexts.set(url, context);
+  }
+  return context;
+}
+
+/**
+ * Walks up the importers of the changed module until every path reaches a
+ * module that accepts the update. Returns the URLs of the modules that have
+ * to be evaluated again, or `null` if the update can't be applied without a
+ * restart.
+ */
+function prepareUpdate(changedUrl, importers) {
+  pendingUpdate = null;
+  if (!ObjectHasOwn(importers, changedUrl)) {
+    return prepareTextUpdate(changedUrl);
+  }
+  const invalidated = new SafeSet();
+  const boundaries = [];
+  const queue = [changedUrl];
+  while (queue.length > 0) {
+    const url = ArrayPrototypeShift(queue);
+    if (invalidated.has(url)) {
+      continue;
+    }
+    invalidated.add(url);
+
+    const state = hotContexts.get(url)?.[_state];
+    if (state?.declined) {
+      return null;
+    }
+    if (state?.selfAcceptCallbacks.length > 0) {
+      ArrayPrototypePush(boundaries, {
+        url,
+        dep: null,
+        callbacks: state.selfAcceptCallbacks,
+      });
+      continue;
+    }
+
+    const parents = ObjectHasOwn(importers, url) ? importers[url] : [];
+    if (parents.length === 0) {
+      // reached an entrypoint without anybody accepting the update
+      return null;
+    }
+    for (const parent of new SafeSetIterator(new SafeSet(parents))) {
+      const callbacks = hotContexts.get(parent)?.[_state]
+        .depAcceptCallbacks.get(url);
+      if (callbacks !== undefined && callbacks.length > 0) {
+        ArrayPrototypePush(boundaries, { url: parent, dep: url, callbacks });
+      } else {
+        ArrayPrototypePush(queue, parent);
+      }
+    }
+  }
+
+  const invalidatedUrls = [...new SafeSetIterator(invalidated)];
+  pendingUpdate = { invalidated: invalidatedUrls, boundaries };
+  return invalidatedUrls;
+}
+
+/**
+ * Prepares the update of a file that isn't a module of the program, which
+ * is passed as text to the modules that accept it. Returns `null` if no
+ * module accepts it.
+ */
+function prepareTextUpdate(changedUrl) {
+  const boundaries = [];
+  for (const { 0: url, 1: context } of new SafeMapIterator(hotContexts)) {
+    const callbacks = context[_state].depAcceptCallbacks.get(changedUrl);
+    if (callbacks !== undefined && callbacks.length > 0) {
+      ArrayPrototypePush(boundaries, {
+        url,
+        dep: changedUrl,
+        callbacks,
+        isText: true,
+      });
+    }
+  }
+  if (boundaries.length === 0) {
+    return null;
+  }
+  pendingUpdate = { invalidated: [], boundaries };
+  return [];
+}
+
+/**
+ * Disposes the invalidated modules and evaluates the update boundaries
+ * again, passing the new module namespaces to the accept callbacks. Accepted
+ * text files are passed as `{ default: text }`.
+ */
+async function applyUpdate(version, text) {
+  const update = pendingUpdate;
+  pendingUpdate = null;
+  if (update === null) {
+    return false;
+  }
+
+  for (const url of new SafeArrayIterator(update.invalidated)) {
+    const context = hotContexts.get(url);
+    if (context === undefined) {
+      continue;
+    }
+    for (
+      const callback of new SafeArrayIterator(
+        context[_state].disposeCallbacks,
+      )
+    ) {
+      await callback(context.data);
+    }
+    context[_reset]();
+  }
+
+  for (
+    const { url, dep, callbacks, isText } of new SafeArrayIterator(
+      update.boundaries,
+    )
+  ) {
+    let module;
+    if (isText) {
+      module = { default: text };
+    } else {
+      const target = dep ?? url;
+      const options =
+        StringPrototypeEndsWith(new URL(target).pathname, ".json")
+          ? { with: { type: "json" } }
+          : undefined;
+      module = await import(versionedModuleUrl(target, version), options);
+    }
+    for (const callback of new SafeArrayIterator(callbacks)) {
+      await callback(module);
+    }
+  }
+
+  return true;
+}
+
+internals.hmr = { prepareUpdate, applyUpdate };
+
+export { hot };
@@ -181,6 +181,13 @@ function spawnChildInner(opFn, command, apiName, {
   signal = undefined,
   windowsRawArguments = false,
   ipc = -1,
+  rlimits = undefined,
+  newProcessGroup = false,
+  newSession = false,
+  noNewPrivileges = false,
+  killOnParentExit = false,
+  landlock = undefined,
+  seccomp = undefined,
 } = { __proto__: null }) {
   const child = opFn({
     cmd: pathFromURL(command),
@@ -195,6 +202,15 @@ function spawnChildInner(opFn, command, apiName, {
     stderr,
     windowsRawArguments,
     ipc,
+    sandbox: {
+      rlimits,
+      newProcessGroup,
+      newSession,
+      noNewPrivileges,
+      killOnParentExit,
+      landlock,
+      seccomp,
+    },
   }, apiName);
   return new ChildProcess(illegalConstructorKey, {
     ...child,
@@ -399,6 +415,13 @@ function spawnSync(command, {
   stdout = "piped",
   stderr = "piped",
   windowsRawArguments = false,
+  rlimits = undefined,
+  newProcessGroup = false,
+  newSession = false,
+  noNewPrivileges = false,
+  killOnParentExit = false,
+  landlock = undefined,
+  seccomp = undefined,
 } = { __proto__: null }) {
   if (stdin === "piped") {
     throw new TypeError(
@@ -417,6 +440,15 @@ function spawnSync(command, {
     stdout,
     stderr,
     windowsRawArguments,
+    sandbox: {
+      rlimits,
+      newProcessGroup,
+      newSession,
+      noNewPrivileges,
+      killOnParentExit,
+      landlock,
+      seccomp,
+    },
   });
   return {
     success: result.status.success,
@@ -24,6 +24,7 @@ import * as buffer from "ext:runtime/13_buffer.js";
 import * as fs from "ext:deno_fs/30_fs.js";
 import * as os from "ext:runtime/30_os.js";
 import * as fsEvents from "ext:runtime/40_fs_events.js";
+import * as hmr from "ext:runtime/40_hmr.js";
 import * as process from "ext:runtime/40_process.js";
 import * as signals from "ext:runtime/40_signals.js";
 import * as tty from "ext:runtime/40_tty.js";
@@ -262,6 +263,8 @@ const unstableIds = {
   unsafeProto: 10,
   webgpu: 11,
   workerOptions: 12,
+  hmr: 13,
+  selfUpdate: 14,
 };
 
 const denoNsUnstableById = { __proto__: null };
@@ -316,6 +319,13 @@ denoNsUnstableById[unstableIds.webgpu] = {
 
 // denoNsUnstableById[unstableIds.workerOptions] = { __proto__: null }
 
+denoNsUnstableById[unstableIds.hmr] = {
+  hot: hmr.hot,
+};
+
+// `Deno.selfUpdate` is added by the CLI in compiled executables
+// denoNsUnstableById[unstableIds.selfUpdate] = { __proto__: null }
+
 // when editing this list, also update unstableDenoProps in cli/tsc/99_main_compiler.js
 const denoNsUnstable = {
   listenDatagram: net.createListenDatagram(
@@ -341,6 +351,7 @@ const denoNsUnstable = {
   KvU64: kv.KvU64,
   KvListIterator: kv.KvListIterator,
   cron: cron.cron,
+  hot: hmr.hot,
 };
 
 export { denoNs, denoNsUnstable, denoNsUnstableById, unstableIds };
@@ -75,6 +75,12 @@ pub static UNSTABLE_GRANULAR_FLAGS: &[(
     "Enable unstable file system APIs",
     4,
   ),
+  (
+    // enabled by `--unstable-hmr`, which also takes the files to watch
+    "hmr",
+    "Enable unstable hot module replacement API",
+    13,
+  ),
   (
     ops::http::UNSTABLE_FEATURE_NAME,
     "Enable unstable HTTP APIs",
@@ -95,6 +101,7 @@ pub static UNSTABLE_GRANULAR_FLAGS: &[(
     "Enable unstable process APIs",
     8,
   ),
+  ("self-update", "Enable unstable system.selfUpdate API", 14),
   ("temporal", "Enable unstable Temporal API", 9),
   (
     "unsafe-proto",
@@ -175,6 +175,7 @@ fn op_get_exit_code(state: &mut OpState) -> i32 {
 #[op2(fast)]
 fn op_exit(state: &mut OpState) {
   let code = state.borrow::<ExitCode>().get();
+  deno_permissions::audit::print_allow_flags();
   std::process::exit(code)
 }
 
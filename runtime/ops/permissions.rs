@@ -1,17 +1,22 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use ::deno_permissions::parse_sys_kind;
+use ::deno_permissions::request_with_async_prompter;
+use ::deno_permissions::NetDescriptor;
 use ::deno_permissions::PermissionState;
+use ::deno_permissions::Permissions;
 use ::deno_permissions::PermissionsContainer;
 use deno_core::error::custom_error;
 use deno_core::error::uri_error;
 use deno_core::error::AnyError;
 use deno_core::op2;
-use deno_core::url;
 use deno_core::OpState;
 use serde::Deserialize;
 use serde::Serialize;
+use std::cell::RefCell;
 use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
 
 deno_core::extension!(
   deno_permissions,
@@ -19,6 +24,7 @@ deno_core::extension!(
     op_query_permission,
     op_revoke_permission,
     op_request_permission,
+    op_request_permission_async,
   ],
 );
 
@@ -62,12 +68,8 @@ pub fn op_query_permission(
   let perm = match args.name.as_ref() {
     "read" => permissions.read.query(path.map(Path::new)),
     "write" => permissions.write.query(path.map(Path::new)),
-    "net" => permissions.net.query(
-      match args.host.as_deref() {
-        None => None,
-        Some(h) => Some(parse_host(h)?),
-      }
-      .as_ref(),
+    "net" => permissions.net.query_descriptor(
+      args.host.as_deref().map(parse_host).transpose()?.as_ref(),
     ),
     "env" => permissions.env.query(args.variable.as_deref()),
     "sys" => permissions
@@ -97,12 +99,8 @@ pub fn op_revoke_permission(
   let perm = match args.name.as_ref() {
     "read" => permissions.read.revoke(path.map(Path::new)),
     "write" => permissions.write.revoke(path.map(Path::new)),
-    "net" => permissions.net.revoke(
-      match args.host.as_deref() {
-        None => None,
-        Some(h) => Some(parse_host(h)?),
-      }
-      .as_ref(),
+    "net" => permissions.net.revoke_descriptor(
+      args.host.as_deref().map(parse_host).transpose()?.as_ref(),
     ),
     "env" => permissions.env.revoke(args.variable.as_deref()),
     "sys" => permissions
@@ -128,16 +126,36 @@ pub fn op_request_permission(
   #[serde] args: PermissionArgs,
 ) -> Result<PermissionStatus, AnyError> {
   let mut permissions = state.borrow_mut::<PermissionsContainer>().0.lock();
+  let perm = request_permission(&mut permissions, &args)?;
+  Ok(PermissionStatus::from(perm))
+}
+
+/// Like `op_request_permission`, but answers the prompt with the async
+/// prompter of the worker, if it has one, without blocking its event loop.
+#[op2(async)]
+#[serde]
+pub async fn op_request_permission_async(
+  state: Rc<RefCell<OpState>>,
+  #[serde] args: PermissionArgs,
+) -> Result<PermissionStatus, AnyError> {
+  let permissions = state.borrow().borrow::<PermissionsContainer>().clone();
+  let perm = request_with_async_prompter(|| {
+    request_permission(&mut permissions.0.lock(), &args)
+  })
+  .await?;
+  Ok(PermissionStatus::from(perm))
+}
+
+fn request_permission(
+  permissions: &mut Permissions,
+  args: &PermissionArgs,
+) -> Result<PermissionState, AnyError> {
   let path = args.path.as_deref();
   let perm = match args.name.as_ref() {
     "read" => permissions.read.request(path.map(Path::new)),
     "write" => permissions.write.request(path.map(Path::new)),
-    "net" => permissions.net.request(
-      match args.host.as_deref() {
-        None => None,
-        Some(h) => Some(parse_host(h)?),
-      }
-      .as_ref(),
+    "net" => permissions.net.request_descriptor(
+      args.host.as_deref().map(parse_host).transpose()?.as_ref(),
     ),
     "env" => permissions.env.request(args.variable.as_deref()),
     "sys" => permissions
@@ -153,15 +171,9 @@ pub fn op_request_permission(
       ))
     }
   };
-  Ok(PermissionStatus::from(perm))
+  Ok(perm)
 }
 
-fn parse_host(host_str: &str) -> Result<(String, Option<u16>), AnyError> {
-  let url = url::Url::parse(&format!("http://{host_str}/"))
-    .map_err(|_| uri_error("Invalid host"))?;
-  if url.path() != "/" {
-    return Err(uri_error("Invalid host"));
-  }
-  let hostname = url.host_str().unwrap();
-  Ok((hostname.to_string(), url.port()))
+fn parse_host(host_str: &str) -> Result<NetDescriptor, AnyError> {
+  NetDescriptor::from_str(host_str).map_err(|_| uri_error("Invalid host"))
 }
@@ -1,5 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+mod sandbox;
+
 use super::check_unstable;
 use deno_core::anyhow::Context;
 use deno_core::error::type_error;
@@ -151,6 +153,7 @@ pub struct SpawnArgs {
   #[cfg(windows)]
   windows_raw_arguments: bool,
   ipc: Option<i32>,
+  sandbox: sandbox::SandboxOptions,
 
   #[serde(flatten)]
   stdio: ChildStdio,
@@ -258,6 +261,15 @@ fn create_command(
     command.uid(uid);
   }
 
+  if let Some(option) = args.sandbox.first_option_name() {
+    check_unstable(
+      state,
+      UNSTABLE_FEATURE_NAME,
+      &format!("system.Command.{option}"),
+    );
+  }
+  sandbox::apply(&mut command, args.sandbox)?;
+
   if args.stdio.stdin.is_ipc() {
     args.ipc = Some(0);
   } else {
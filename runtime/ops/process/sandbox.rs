@@ -0,0 +1,480 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Options that confine a subprocess: resource limits, a new process group or
+//! session and, on Linux, no-new-privileges, kill-on-parent-exit, Landlock
+//! filesystem rules and a seccomp network filter.
+
+#[cfg(not(target_os = "linux"))]
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use serde::Deserialize;
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SandboxOptions {
+  rlimits: Option<Rlimits>,
+  new_process_group: bool,
+  new_session: bool,
+  no_new_privileges: bool,
+  kill_on_parent_exit: bool,
+  landlock: Option<LandlockRules>,
+  seccomp: Option<SeccompRules>,
+}
+
+impl SandboxOptions {
+  /// The name of the first option that is set, as all of them are unstable.
+  pub fn first_option_name(&self) -> Option<&'static str> {
+    [
+      ("rlimits", self.rlimits.is_some()),
+      ("newProcessGroup", self.new_process_group),
+      ("newSession", self.new_session),
+      ("noNewPrivileges", self.no_new_privileges),
+      ("killOnParentExit", self.kill_on_parent_exit),
+      ("landlock", self.landlock.is_some()),
+      ("seccomp", self.seccomp.is_some()),
+    ]
+    .into_iter()
+    .find(|(_, is_set)| *is_set)
+    .map(|(name, _)| name)
+  }
+
+  #[cfg(not(target_os = "linux"))]
+  fn has_linux_options(&self) -> bool {
+    self.no_new_privileges
+      || self.kill_on_parent_exit
+      || self.landlock.is_some()
+      || self.seccomp.is_some()
+  }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Rlimits {
+  /// CPU time, in seconds.
+  cpu: Option<u64>,
+  /// Size of the address space, in bytes.
+  memory: Option<u64>,
+  open_files: Option<u64>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct LandlockRules {
+  /// Paths the subprocess may read and execute.
+  read: Vec<String>,
+  /// Paths the subprocess may read, execute and write.
+  write: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct SeccompRules {
+  /// Denies creating sockets other than Unix domain sockets.
+  deny_network: bool,
+}
+
+#[cfg(not(unix))]
+pub fn apply(
+  _command: &mut std::process::Command,
+  options: SandboxOptions,
+) -> Result<(), AnyError> {
+  if options.rlimits.is_some()
+    || options.new_process_group
+    || options.new_session
+    || options.has_linux_options()
+  {
+    return Err(type_error(
+      "Subprocess sandboxing options are not supported on this platform",
+    ));
+  }
+  Ok(())
+}
+
+#[cfg(unix)]
+pub fn apply(
+  command: &mut std::process::Command,
+  options: SandboxOptions,
+) -> Result<(), AnyError> {
+  use std::os::unix::process::CommandExt;
+
+  #[cfg(not(target_os = "linux"))]
+  if options.has_linux_options() {
+    return Err(type_error(
+      "noNewPrivileges, killOnParentExit, landlock and seccomp are only supported on Linux",
+    ));
+  }
+  #[cfg(target_os = "linux")]
+  let linux_sandbox = linux::LinuxSandbox::new(&options)?;
+
+  if options.new_process_group && !options.new_session {
+    command.process_group(0);
+  }
+  let new_session = options.new_session;
+  let rlimits = options.rlimits.unwrap_or_default();
+  let rlimits = [
+    (libc::RLIMIT_CPU, rlimits.cpu),
+    (libc::RLIMIT_AS, rlimits.memory),
+    (libc::RLIMIT_NOFILE, rlimits.open_files),
+  ];
+
+  // SAFETY: the closure runs in the forked child, so it only makes
+  // async-signal-safe calls and doesn't allocate.
+  unsafe {
+    command.pre_exec(move || {
+      if new_session && libc::setsid() == -1 {
+        return Err(std::io::Error::last_os_error());
+      }
+      for (resource, limit) in rlimits {
+        if let Some(limit) = limit {
+          #[allow(clippy::unnecessary_cast)]
+          let rlimit = libc::rlimit {
+            rlim_cur: limit as libc::rlim_t,
+            rlim_max: limit as libc::rlim_t,
+          };
+          if libc::setrlimit(resource, &rlimit) == -1 {
+            return Err(std::io::Error::last_os_error());
+          }
+        }
+      }
+      #[cfg(target_os = "linux")]
+      linux_sandbox.apply()?;
+      Ok(())
+    });
+  }
+  Ok(())
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+  use super::SandboxOptions;
+  use deno_core::anyhow::Context;
+  use deno_core::error::type_error;
+  use deno_core::error::AnyError;
+  use std::ffi::CString;
+  use std::io;
+  use std::os::fd::AsRawFd;
+  use std::os::fd::FromRawFd;
+  use std::os::fd::OwnedFd;
+
+  // from linux/landlock.h
+  const LANDLOCK_CREATE_RULESET_VERSION: libc::c_uint = 1 << 0;
+  const LANDLOCK_ACCESS_FS_EXECUTE: u64 = 1 << 0;
+  const LANDLOCK_ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+  const LANDLOCK_ACCESS_FS_READ_FILE: u64 = 1 << 2;
+  const LANDLOCK_ACCESS_FS_READ_DIR: u64 = 1 << 3;
+  // the rights added by ABI versions 2, 3 and 5
+  const LANDLOCK_ACCESS_FS_REFER: u64 = 1 << 13;
+  const LANDLOCK_ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+  const LANDLOCK_ACCESS_FS_IOCTL_DEV: u64 = 1 << 15;
+  const LANDLOCK_ACCESS_FS_READ: u64 = LANDLOCK_ACCESS_FS_EXECUTE
+    | LANDLOCK_ACCESS_FS_READ_FILE
+    | LANDLOCK_ACCESS_FS_READ_DIR;
+  // the rights that can be granted on a file rather than a directory
+  const LANDLOCK_ACCESS_FS_FILE: u64 = LANDLOCK_ACCESS_FS_EXECUTE
+    | LANDLOCK_ACCESS_FS_WRITE_FILE
+    | LANDLOCK_ACCESS_FS_READ_FILE
+    | LANDLOCK_ACCESS_FS_TRUNCATE
+    | LANDLOCK_ACCESS_FS_IOCTL_DEV;
+  const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+
+  /// Returns the filesystem access rights known to the given Landlock ABI
+  /// version, all of which are restricted. The network rules of version 4 and
+  /// the scoping of version 6 aren't applied, see `seccomp` for the network.
+  fn landlock_access_fs(abi: i64) -> u64 {
+    let mut access = (1 << 13) - 1;
+    if abi >= 2 {
+      access |= LANDLOCK_ACCESS_FS_REFER;
+    }
+    if abi >= 3 {
+      access |= LANDLOCK_ACCESS_FS_TRUNCATE;
+    }
+    if abi >= 5 {
+      access |= LANDLOCK_ACCESS_FS_IOCTL_DEV;
+    }
+    access
+  }
+
+  #[repr(C)]
+  struct LandlockRulesetAttr {
+    handled_access_fs: u64,
+  }
+
+  #[repr(C, packed)]
+  struct LandlockPathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: i32,
+  }
+
+  // from linux/filter.h, linux/bpf_common.h, linux/seccomp.h and
+  // linux/audit.h
+  const SECCOMP_MODE_FILTER: libc::c_ulong = 2;
+  #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+  mod seccomp {
+    // BPF_LD | BPF_W | BPF_ABS
+    pub const BPF_LD_W_ABS: u16 = 0x20;
+    // BPF_JMP | BPF_JEQ | BPF_K
+    pub const BPF_JMP_JEQ_K: u16 = 0x15;
+    // BPF_JMP | BPF_JGE | BPF_K
+    pub const BPF_JMP_JGE_K: u16 = 0x35;
+    // BPF_RET | BPF_K
+    pub const BPF_RET_K: u16 = 0x06;
+    pub const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+    pub const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+    pub const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+    pub const SECCOMP_DATA_NR_OFFSET: u32 = 0;
+    pub const SECCOMP_DATA_ARCH_OFFSET: u32 = 4;
+    // the low 32 bits of the first argument, on little-endian targets
+    pub const SECCOMP_DATA_ARG0_OFFSET: u32 = 16;
+    pub const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+    #[cfg(target_arch = "x86_64")]
+    pub const AUDIT_ARCH: u32 = 0xc000_003e;
+    #[cfg(target_arch = "aarch64")]
+    pub const AUDIT_ARCH: u32 = 0xc000_00b7;
+  }
+
+  #[repr(C)]
+  #[derive(Clone, Copy)]
+  struct SockFilter {
+    code: u16,
+    jt: u8,
+    jf: u8,
+    k: u32,
+  }
+
+  #[repr(C)]
+  struct SockFprog {
+    len: libc::c_ushort,
+    filter: *const SockFilter,
+  }
+
+  /// Returns a filter that denies creating sockets other than Unix domain
+  /// sockets, and io_uring instances that could create them.
+  #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+  fn deny_network_filter() -> Option<[SockFilter; 11]> {
+    use seccomp::*;
+
+    const fn stmt(code: u16, k: u32) -> SockFilter {
+      SockFilter {
+        code,
+        jt: 0,
+        jf: 0,
+        k,
+      }
+    }
+
+    const fn jump(code: u16, k: u32, jt: u8, jf: u8) -> SockFilter {
+      SockFilter { code, jt, jf, k }
+    }
+
+    let deny = SECCOMP_RET_ERRNO | libc::EACCES as u32;
+    Some([
+      stmt(BPF_LD_W_ABS, SECCOMP_DATA_ARCH_OFFSET),
+      jump(BPF_JMP_JEQ_K, AUDIT_ARCH, 1, 0),
+      stmt(BPF_RET_K, SECCOMP_RET_KILL_PROCESS),
+      stmt(BPF_LD_W_ABS, SECCOMP_DATA_NR_OFFSET),
+      jump(BPF_JMP_JGE_K, X32_SYSCALL_BIT, 4, 0),
+      jump(BPF_JMP_JEQ_K, libc::SYS_io_uring_setup as u32, 3, 0),
+      jump(BPF_JMP_JEQ_K, libc::SYS_socket as u32, 0, 3),
+      stmt(BPF_LD_W_ABS, SECCOMP_DATA_ARG0_OFFSET),
+      jump(BPF_JMP_JEQ_K, libc::AF_UNIX as u32, 1, 0),
+      stmt(BPF_RET_K, deny),
+      stmt(BPF_RET_K, SECCOMP_RET_ALLOW),
+    ])
+  }
+
+  #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+  fn deny_network_filter() -> Option<[SockFilter; 11]> {
+    None
+  }
+
+  /// The Linux specific options, prepared in the parent so that applying
+  /// them in the child doesn't allocate.
+  pub struct LinuxSandbox {
+    parent_pid: libc::pid_t,
+    kill_on_parent_exit: bool,
+    no_new_privileges: bool,
+    landlock_ruleset: Option<OwnedFd>,
+    seccomp_filter: Option<[SockFilter; 11]>,
+  }
+
+  impl LinuxSandbox {
+    pub fn new(options: &SandboxOptions) -> Result<Self, AnyError> {
+      let landlock_ruleset = match &options.landlock {
+        Some(rules) => Some(
+          create_landlock_ruleset(&rules.read, &rules.write)
+            .context("Failed to create Landlock ruleset")?,
+        ),
+        None => None,
+      };
+      let seccomp_filter = match &options.seccomp {
+        Some(rules) if rules.deny_network => match deny_network_filter() {
+          Some(filter) => Some(filter),
+          None => {
+            return Err(type_error(
+              "seccomp is not supported on this architecture",
+            ))
+          }
+        },
+        _ => None,
+      };
+      Ok(Self {
+        // SAFETY: libc call
+        parent_pid: unsafe { libc::getpid() },
+        kill_on_parent_exit: options.kill_on_parent_exit,
+        // Landlock and seccomp filters can only be applied by unprivileged
+        // processes that can't gain privileges
+        no_new_privileges: options.no_new_privileges
+          || landlock_ruleset.is_some()
+          || seccomp_filter.is_some(),
+        landlock_ruleset,
+        seccomp_filter,
+      })
+    }
+
+    /// Applies the options to the current process.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called in a forked child, before it executes the
+    /// command.
+    pub unsafe fn apply(&self) -> io::Result<()> {
+      // the signal is sent when the thread that forked this process exits,
+      // not its whole process, so a subprocess spawned by a worker is killed
+      // when the worker's thread exits
+      if self.kill_on_parent_exit {
+        if libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL as libc::c_ulong)
+          == -1
+        {
+          return Err(io::Error::last_os_error());
+        }
+        // the parent may have exited before the signal was set up
+        if libc::getppid() != self.parent_pid {
+          libc::raise(libc::SIGKILL);
+        }
+      }
+      if self.no_new_privileges
+        && libc::prctl(
+          libc::PR_SET_NO_NEW_PRIVS,
+          1 as libc::c_ulong,
+          0 as libc::c_ulong,
+          0 as libc::c_ulong,
+          0 as libc::c_ulong,
+        ) == -1
+      {
+        return Err(io::Error::last_os_error());
+      }
+      if let Some(ruleset) = &self.landlock_ruleset {
+        if libc::syscall(
+          libc::SYS_landlock_restrict_self,
+          ruleset.as_raw_fd(),
+          0 as libc::c_uint,
+        ) == -1
+        {
+          return Err(io::Error::last_os_error());
+        }
+      }
+      if let Some(filter) = &self.seccomp_filter {
+        let prog = SockFprog {
+          len: filter.len() as libc::c_ushort,
+          filter: filter.as_ptr(),
+        };
+        if libc::prctl(
+          libc::PR_SET_SECCOMP,
+          SECCOMP_MODE_FILTER,
+          &prog as *const SockFprog,
+        ) == -1
+        {
+          return Err(io::Error::last_os_error());
+        }
+      }
+      Ok(())
+    }
+  }
+
+  fn create_landlock_ruleset(
+    read: &[String],
+    write: &[String],
+  ) -> Result<OwnedFd, AnyError> {
+    // SAFETY: querying the version doesn't take a ruleset attribute
+    let abi = unsafe {
+      libc::syscall(
+        libc::SYS_landlock_create_ruleset,
+        std::ptr::null::<LandlockRulesetAttr>(),
+        0 as libc::size_t,
+        LANDLOCK_CREATE_RULESET_VERSION,
+      )
+    };
+    if abi < 1 {
+      return Err(io::Error::last_os_error().into());
+    }
+    let access_fs = landlock_access_fs(abi);
+    let attr = LandlockRulesetAttr {
+      handled_access_fs: access_fs,
+    };
+    // SAFETY: the attribute outlives the call, and the returned file
+    // descriptor is owned by the caller
+    let ruleset = unsafe {
+      let fd = libc::syscall(
+        libc::SYS_landlock_create_ruleset,
+        &attr as *const LandlockRulesetAttr,
+        std::mem::size_of::<LandlockRulesetAttr>(),
+        0 as libc::c_uint,
+      );
+      if fd == -1 {
+        return Err(io::Error::last_os_error().into());
+      }
+      OwnedFd::from_raw_fd(fd as libc::c_int)
+    };
+
+    let rules = read
+      .iter()
+      .map(|path| (path, LANDLOCK_ACCESS_FS_READ))
+      .chain(write.iter().map(|path| (path, access_fs)));
+    for (path, access) in rules {
+      add_landlock_rule(&ruleset, path, access)
+        .with_context(|| format!("Failed to add Landlock rule for '{path}'"))?;
+    }
+    Ok(ruleset)
+  }
+
+  fn add_landlock_rule(
+    ruleset: &OwnedFd,
+    path: &str,
+    access: u64,
+  ) -> Result<(), AnyError> {
+    let c_path = CString::new(path)?;
+    // SAFETY: the path and attributes outlive the calls, and the opened file
+    // descriptor is owned by `parent`
+    unsafe {
+      let fd = libc::open(c_path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC);
+      if fd == -1 {
+        return Err(io::Error::last_os_error().into());
+      }
+      let parent = OwnedFd::from_raw_fd(fd);
+      let mut stat: libc::stat = std::mem::zeroed();
+      if libc::fstat(parent.as_raw_fd(), &mut stat) == -1 {
+        return Err(io::Error::last_os_error().into());
+      }
+      let allowed_access = if (stat.st_mode & libc::S_IFMT) == libc::S_IFDIR {
+        access
+      } else {
+        access & LANDLOCK_ACCESS_FS_FILE
+      };
+      let attr = LandlockPathBeneathAttr {
+        allowed_access,
+        parent_fd: parent.as_raw_fd(),
+      };
+      if libc::syscall(
+        libc::SYS_landlock_add_rule,
+        ruleset.as_raw_fd(),
+        LANDLOCK_RULE_PATH_BENEATH,
+        &attr as *const LandlockPathBeneathAttr,
+        0 as libc::c_uint,
+      ) == -1
+      {
+        return Err(io::Error::last_os_error().into());
+      }
+    }
+    Ok(())
+  }
+}
@@ -9,7 +9,9 @@ use crate::web_worker::WebWorkerType;
 use crate::web_worker::WorkerControlEvent;
 use crate::web_worker::WorkerId;
 use crate::web_worker::WorkerMetadata;
+use crate::web_worker::WorkerResourceLimits;
 use crate::worker::FormatJsErrorFn;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op2;
 use deno_core::serde::Deserialize;
@@ -30,6 +32,15 @@ use std::sync::Arc;
 
 pub const UNSTABLE_FEATURE_NAME: &str = "worker-options";
 
+// workers are spawned with the default stack size of Rust threads, a smaller
+// stack can overflow before V8's stack limit is reached
+const MIN_WORKER_STACK_SIZE_MB: usize = 2;
+
+/// Converts a size in megabytes to bytes, or `None` if it overflows.
+fn mb_to_bytes(size_mb: usize) -> Option<usize> {
+  size_mb.checked_mul(1 << 20)
+}
+
 pub struct CreateWebWorkerArgs {
   pub name: String,
   pub worker_id: WorkerId,
@@ -39,6 +50,7 @@ pub struct CreateWebWorkerArgs {
   pub worker_type: WebWorkerType,
   pub close_on_idle: bool,
   pub maybe_worker_metadata: Option<WorkerMetadata>,
+  pub resource_limits: WorkerResourceLimits,
 }
 
 pub type CreateWebWorkerCb = dyn Fn(CreateWebWorkerArgs) -> (WebWorker, SendableWebWorkerHandle)
@@ -117,6 +129,7 @@ pub struct CreateWorkerArgs {
   specifier: String,
   worker_type: WebWorkerType,
   close_on_idle: bool,
+  resource_limits: Option<WorkerResourceLimits>,
 }
 
 /// Create worker as the host
@@ -150,9 +163,38 @@ fn op_create_worker(
     super::check_unstable(
       state,
       UNSTABLE_FEATURE_NAME,
-      "Worker.deno.permissions",
+      "Worker.system.permissions",
+    );
+  }
+  if args.resource_limits.is_some() {
+    super::check_unstable(
+      state,
+      UNSTABLE_FEATURE_NAME,
+      "Worker.system.resourceLimits",
     );
   }
+  let resource_limits = args.resource_limits.unwrap_or_default();
+  if resource_limits.max_heap_size_mb == Some(0) {
+    return Err(type_error("maxHeapSizeMb must be greater than 0"));
+  }
+  if resource_limits
+    .max_heap_size_mb
+    .is_some_and(|size| mb_to_bytes(size).is_none())
+  {
+    return Err(type_error("maxHeapSizeMb is too large"));
+  }
+  let stack_size = match resource_limits.stack_size_mb {
+    Some(size) if size < MIN_WORKER_STACK_SIZE_MB => {
+      return Err(type_error(format!(
+        "stackSizeMb must be at least {MIN_WORKER_STACK_SIZE_MB}"
+      )));
+    }
+    Some(size) => Some(
+      mb_to_bytes(size)
+        .ok_or_else(|| type_error("stackSizeMb is too large"))?,
+    ),
+    None => None,
+  };
   let parent_permissions = state.borrow_mut::<PermissionsContainer>();
   let worker_permissions = if let Some(child_permissions_arg) = args.permissions
   {
@@ -176,7 +218,11 @@ fn op_create_worker(
   >(1);
 
   // Setup new thread
-  let thread_builder = std::thread::Builder::new().name(format!("{worker_id}"));
+  let mut thread_builder =
+    std::thread::Builder::new().name(format!("{worker_id}"));
+  if let Some(stack_size) = stack_size {
+    thread_builder = thread_builder.stack_size(stack_size);
+  }
   let maybe_worker_metadata = if let Some(data) = maybe_worker_metadata {
     let transferables =
       deserialize_js_transferables(state, data.transferables)?;
@@ -204,6 +250,7 @@ fn op_create_worker(
         worker_type,
         close_on_idle: args.close_on_idle,
         maybe_worker_metadata,
+        resource_limits,
       });
 
     // Send thread safe handle from newly created worker to host thread
@@ -0,0 +1,192 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! An audit log of permission checks. When enabled, every check of a
+//! permission is written as a line of JSON, regardless of whether it was
+//! granted by a flag, granted or denied by a prompt, or denied.
+//!
+//! The log can be turned into the `--allow-*` flags a program needs with
+//! [`allow_flags`], and [`print_allow_flags`] prints the flags for the checks
+//! made so far when the program exits.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use deno_core::parking_lot::Mutex;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use once_cell::sync::Lazy;
+
+use crate::location::current_location;
+
+static AUDIT_ENABLED: AtomicBool = AtomicBool::new(false);
+
+static AUDIT_LOG: Lazy<Mutex<Option<Box<dyn Write + Send>>>> =
+  Lazy::new(|| Mutex::new(None));
+
+/// The permission and descriptor of every check, without duplicates.
+static CHECKS: Lazy<Mutex<BTreeSet<(String, Option<String>)>>> =
+  Lazy::new(|| Mutex::new(BTreeSet::new()));
+
+static ALLOW_FLAGS_PRINTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditResult {
+  Granted,
+  Denied,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+  /// The name of the permission, as in its `--allow-*` flag.
+  pub permission: String,
+  /// The path, host, variable, command or kind that was checked. `None`
+  /// when access to the whole permission was checked.
+  pub descriptor: Option<String>,
+  pub api_name: Option<String>,
+  pub result: AuditResult,
+  pub location: Option<String>,
+}
+
+/// Starts writing an audit entry for every permission check to `writer`.
+pub fn set_audit_log(writer: Box<dyn Write + Send>) {
+  *AUDIT_LOG.lock() = Some(writer);
+  AUDIT_ENABLED.store(true, Ordering::Relaxed);
+}
+
+#[inline(always)]
+pub fn is_enabled() -> bool {
+  AUDIT_ENABLED.load(Ordering::Relaxed)
+}
+
+pub(crate) fn record(
+  permission: &str,
+  descriptor: Option<Cow<str>>,
+  api_name: Option<&str>,
+  granted: bool,
+) {
+  let location = current_location().map(|location| location.to_string());
+  let entry = AuditEntry {
+    permission: permission.to_string(),
+    descriptor: descriptor.map(|d| d.into_owned()),
+    api_name: api_name.map(|a| a.to_string()),
+    result: if granted {
+      AuditResult::Granted
+    } else {
+      AuditResult::Denied
+    },
+    location,
+  };
+  CHECKS
+    .lock()
+    .insert((entry.permission.clone(), entry.descriptor.clone()));
+  if let Some(writer) = AUDIT_LOG.lock().as_mut() {
+    // an audit log that can't be written to shouldn't crash the program
+    let mut line = serde_json::to_vec(&entry).unwrap();
+    line.push(b'\n');
+    let _ = writer.write_all(&line).and_then(|_| writer.flush());
+  }
+}
+
+/// Returns the minimal set of `--allow-*` flags that grants every
+/// permission check in the audit log, including the denied ones.
+pub fn allow_flags<'a>(
+  entries: impl IntoIterator<Item = &'a AuditEntry>,
+) -> Vec<String> {
+  // `None` means the whole permission is needed
+  let mut needed: BTreeMap<&str, Option<BTreeSet<&str>>> = BTreeMap::new();
+  for entry in entries {
+    // checks of `all` only test whether `--allow-all` was passed
+    if entry.permission == "all" {
+      continue;
+    }
+    let descriptors = needed
+      .entry(entry.permission.as_str())
+      .or_insert_with(|| Some(BTreeSet::new()));
+    match (descriptors.as_mut(), &entry.descriptor) {
+      (Some(descriptors), Some(descriptor)) => {
+        descriptors.insert(descriptor.as_str());
+      }
+      _ => *descriptors = None,
+    }
+  }
+  needed
+    .into_iter()
+    .map(|(permission, descriptors)| match descriptors {
+      Some(descriptors) => format!(
+        "--allow-{}={}",
+        permission,
+        descriptors.into_iter().collect::<Vec<_>>().join(",")
+      ),
+      None => format!("--allow-{permission}"),
+    })
+    .collect()
+}
+
+/// Prints the `--allow-*` flags that grant the permission checks made so far
+/// to stderr, once, when the audit log is enabled. Called when the program
+/// exits.
+#[allow(clippy::print_stderr)]
+pub fn print_allow_flags() {
+  if !is_enabled() || ALLOW_FLAGS_PRINTED.swap(true, Ordering::Relaxed) {
+    return;
+  }
+  let entries = CHECKS
+    .lock()
+    .iter()
+    .map(|(permission, descriptor)| AuditEntry {
+      permission: permission.clone(),
+      descriptor: descriptor.clone(),
+      api_name: None,
+      result: AuditResult::Granted,
+      location: None,
+    })
+    .collect::<Vec<_>>();
+  let flags = allow_flags(&entries);
+  if flags.is_empty() {
+    eprintln!("No permissions were checked.");
+  } else {
+    eprintln!(
+      "Flags granting the checked permissions: {}",
+      flags.join(" ")
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn allow_flags_from_entries() {
+    let log = r#"{"permission":"read","descriptor":"/b","apiName":"Deno.readFile()","result":"granted","location":"file:///main.ts:1:7"}
+{"permission":"read","descriptor":"/a","apiName":"Deno.readFile()","result":"denied","location":null}
+{"permission":"read","descriptor":"/a","apiName":null,"result":"granted","location":null}
+{"permission":"env","descriptor":"HOME","apiName":null,"result":"granted","location":null}
+{"permission":"env","descriptor":null,"apiName":"Deno.env.toObject()","result":"granted","location":null}
+{"permission":"hrtime","descriptor":null,"apiName":null,"result":"granted","location":null}
+{"permission":"all","descriptor":null,"apiName":null,"result":"denied","location":null}
+{"permission":"net","descriptor":"deno.land:443","apiName":"fetch()","result":"granted","location":null}"#;
+    let entries = log
+      .lines()
+      .map(|line| serde_json::from_str::<AuditEntry>(line).unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(entries[0].result, AuditResult::Granted);
+    assert_eq!(entries[0].location.as_deref(), Some("file:///main.ts:1:7"));
+    assert_eq!(
+      allow_flags(&entries),
+      vec![
+        "--allow-env",
+        "--allow-hrtime",
+        "--allow-net=deno.land:443",
+        "--allow-read=/a,/b",
+      ]
+    );
+  }
+}
@@ -25,6 +25,9 @@ use std::ffi::OsStr;
 use std::fmt;
 use std::fmt::Debug;
 use std::hash::Hash;
+use std::net::IpAddr;
+use std::net::Ipv6Addr;
+use std::path::Component;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -32,19 +35,32 @@ use std::string::ToString;
 use std::sync::Arc;
 use which::which;
 
+pub mod audit;
+pub mod location;
 pub mod prompter;
 use prompter::permission_prompt;
+use prompter::PromptRequest;
 use prompter::PromptResponse;
+use prompter::RememberFor;
 use prompter::PERMISSION_EMOJI;
 
+pub use prompter::request_with_async_prompter;
+pub use prompter::set_prompt_answer_store;
 pub use prompter::set_prompt_callbacks;
+pub use prompter::set_prompter;
+pub use prompter::set_structured_prompter;
+pub use prompter::set_thread_async_prompter;
+pub use prompter::set_thread_prompter;
+pub use prompter::AsyncPermissionPrompter;
 pub use prompter::PromptCallback;
+pub use prompter::StructuredPermissionPrompter;
 
 /// Fast exit from permission check routines if this permission
-/// is in the "fully-granted" state.
+/// is in the "fully-granted" state. Checks still go through when they
+/// need to be written to the audit log.
 macro_rules! skip_check_if_is_permission_fully_granted {
   ($this:ident) => {
-    if $this.is_allow_all() {
+    if $this.is_allow_all() && !($this.audit && audit::is_enabled()) {
       return Ok(());
     }
   };
@@ -141,7 +157,9 @@ impl PermissionState {
     )
   }
 
-  /// Check the permission state. bool is whether a prompt was issued.
+  /// Check the permission state. The first bool is whether the answer to a
+  /// prompt should be remembered, the second whether it granted access to
+  /// the whole permission.
   #[inline]
   fn check(
     self,
@@ -150,14 +168,17 @@ impl PermissionState {
     info: Option<&str>,
     prompt: bool,
   ) -> (Result<(), AnyError>, bool, bool) {
-    self.check2(name, api_name, || info.map(|s| s.to_string()), prompt)
+    self.check2(name, api_name, None, || info.map(|s| s.to_string()), prompt)
   }
 
+  /// Like [`PermissionState::check`], with the resolved descriptor, which
+  /// prompt answers are remembered for, separate from the displayed info.
   #[inline]
   fn check2(
     self,
     name: &str,
     api_name: Option<&str>,
+    descriptor: Option<String>,
     info: impl Fn() -> Option<String>,
     prompt: bool,
   ) -> (Result<(), AnyError>, bool, bool) {
@@ -167,23 +188,36 @@ impl PermissionState {
         (Ok(()), false, false)
       }
       PermissionState::Prompt if prompt => {
+        let info_value = info();
         let msg = format!(
           "{} access{}",
           name,
-          info()
+          info_value
+            .as_ref()
             .map(|info| { format!(" to {info}") })
             .unwrap_or_default(),
         );
-        match permission_prompt(&msg, name, api_name, true) {
+        let answer = permission_prompt(&PromptRequest {
+          name: name.to_string(),
+          descriptor,
+          api_name: api_name.map(|a| a.to_string()),
+          location: location::current_location(),
+          is_unary: true,
+          message: msg,
+        });
+        let remember = answer.remember_for != RememberFor::Once;
+        match answer.response {
           PromptResponse::Allow => {
             Self::log_perm_access(name, info);
-            (Ok(()), true, false)
+            (Ok(()), remember, false)
           }
           PromptResponse::AllowAll => {
             Self::log_perm_access(name, info);
-            (Ok(()), true, true)
+            (Ok(()), remember, remember)
+          }
+          PromptResponse::Deny => {
+            (Err(Self::error(name, info)), remember, false)
           }
-          PromptResponse::Deny => (Err(Self::error(name, info)), true, false),
         }
       }
       _ => (Err(Self::error(name, info)), false, false),
@@ -208,6 +242,8 @@ pub struct UnitPermission {
   pub description: &'static str,
   pub state: PermissionState,
   pub prompt: bool,
+  /// Whether checks are written to the audit log, if it's enabled.
+  pub audit: bool,
 }
 
 impl UnitPermission {
@@ -217,18 +253,23 @@ impl UnitPermission {
 
   pub fn request(&mut self) -> PermissionState {
     if self.state == PermissionState::Prompt {
-      if PromptResponse::Allow
-        == permission_prompt(
-          &format!("access to {}", self.description),
-          self.name,
-          Some("system.permissions.query()"),
-          false,
-        )
-      {
-        self.state = PermissionState::Granted;
+      let answer = permission_prompt(&PromptRequest {
+        name: self.name.to_string(),
+        descriptor: None,
+        api_name: Some("system.permissions.query()".to_string()),
+        location: location::current_location(),
+        is_unary: false,
+        message: format!("access to {}", self.description),
+      });
+      let state = if answer.response == PromptResponse::Allow {
+        PermissionState::Granted
       } else {
-        self.state = PermissionState::Denied;
+        PermissionState::Denied
+      };
+      if answer.remember_for == RememberFor::Once {
+        return state;
       }
+      self.state = state;
     }
     self.state
   }
@@ -241,15 +282,18 @@ impl UnitPermission {
   }
 
   pub fn check(&mut self) -> Result<(), AnyError> {
-    let (result, prompted, _is_allow_all) =
+    let (result, remember, _is_allow_all) =
       self.state.check(self.name, None, None, self.prompt);
-    if prompted {
+    if remember {
       if result.is_ok() {
         self.state = PermissionState::Granted;
       } else {
         self.state = PermissionState::Denied;
       }
     }
+    if self.audit && audit::is_enabled() {
+      audit::record(self.name, None, None, result.is_ok());
+    }
     result
   }
 
@@ -320,6 +364,10 @@ pub trait Descriptor: Eq + Clone + Hash {
 
   fn flag_name() -> &'static str;
   fn name(&self) -> Cow<str>;
+  /// Whether `self` covers everything that `other` covers. Both may be
+  /// patterns, like path globs or wildcard hosts, so this is used both to
+  /// match a checked descriptor against the allow and deny lists and to find
+  /// deny list entries that partially overlap a queried descriptor.
   // By default, specifies no-stronger-than relationship.
   // As this is not strict, it's only true when descriptors are the same.
   fn stronger_than(&self, other: &Self) -> bool {
@@ -339,6 +387,7 @@ pub struct UnaryPermission<T: Descriptor + Hash> {
   prompt_denied_global: bool,
   prompt_denied_list: HashSet<T>,
   prompt: bool,
+  audit: bool,
 }
 
 impl<T: Descriptor + Hash> Default for UnaryPermission<T> {
@@ -351,6 +400,7 @@ impl<T: Descriptor + Hash> Default for UnaryPermission<T> {
       prompt_denied_global: Default::default(),
       prompt_denied_list: Default::default(),
       prompt: Default::default(),
+      audit: Default::default(),
     }
   }
 }
@@ -369,6 +419,20 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
       && self.prompt_denied_list.is_empty()
   }
 
+  /// Queries a descriptor, which may be a pattern like a path glob or a
+  /// wildcard host.
+  pub fn query_descriptor(&self, desc: Option<&T>) -> PermissionState {
+    self.query_desc(desc, AllowPartial::TreatAsPartialGranted)
+  }
+
+  pub fn request_descriptor(&mut self, desc: Option<&T>) -> PermissionState {
+    self.request_desc(desc, || None)
+  }
+
+  pub fn revoke_descriptor(&mut self, desc: Option<&T>) -> PermissionState {
+    self.revoke_desc(desc)
+  }
+
   pub fn check_all_api(
     &mut self,
     api_name: Option<&str>,
@@ -384,19 +448,47 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
     api_name: Option<&str>,
     get_display_name: impl Fn() -> Option<String>,
   ) -> Result<(), AnyError> {
-    skip_check_if_is_permission_fully_granted!(self);
-    let (result, prompted, is_allow_all) = self
+    let result = if self.is_allow_all() {
+      Ok(())
+    } else {
+      self.check_desc_inner(
+        desc,
+        assert_non_partial,
+        api_name,
+        get_display_name,
+      )
+    };
+    if self.audit && audit::is_enabled() {
+      audit::record(
+        T::flag_name(),
+        desc.map(|d| d.name()),
+        api_name,
+        result.is_ok(),
+      );
+    }
+    result
+  }
+
+  fn check_desc_inner(
+    &mut self,
+    desc: Option<&T>,
+    assert_non_partial: bool,
+    api_name: Option<&str>,
+    get_display_name: impl Fn() -> Option<String>,
+  ) -> Result<(), AnyError> {
+    let (result, remember, is_allow_all) = self
       .query_desc(desc, AllowPartial::from(!assert_non_partial))
       .check2(
         T::flag_name(),
         api_name,
+        desc.map(|d| d.name().into_owned()),
         || match get_display_name() {
           Some(display_name) => Some(display_name),
           None => desc.map(|d| format!("\"{}\"", d.name())),
         },
         self.prompt,
       );
-    if prompted {
+    if remember {
       if result.is_ok() {
         if is_allow_all {
           self.insert_granted(None);
@@ -469,32 +561,37 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
     }
     let mut message = String::with_capacity(40);
     message.push_str(&format!("{} access", T::flag_name()));
-    match get_display_name() {
-      Some(display_name) => {
-        message.push_str(&format!(" to \"{}\"", display_name))
-      }
-      None => {
-        if let Some(desc) = desc {
-          message.push_str(&format!(" to \"{}\"", desc.name()));
-        }
-      }
+    let display_name =
+      get_display_name().or_else(|| desc.map(|d| d.name().into_owned()));
+    if let Some(display_name) = &display_name {
+      message.push_str(&format!(" to \"{}\"", display_name));
     }
-    match permission_prompt(
-      &message,
-      T::flag_name(),
-      Some("system.permissions.request()"),
-      true,
-    ) {
+    let answer = permission_prompt(&PromptRequest {
+      name: T::flag_name().to_string(),
+      descriptor: desc.map(|d| d.name().into_owned()),
+      api_name: Some("system.permissions.request()".to_string()),
+      location: location::current_location(),
+      is_unary: true,
+      message,
+    });
+    let remember = answer.remember_for != RememberFor::Once;
+    match answer.response {
       PromptResponse::Allow => {
-        self.insert_granted(desc.cloned());
+        if remember {
+          self.insert_granted(desc.cloned());
+        }
         PermissionState::Granted
       }
       PromptResponse::Deny => {
-        self.insert_prompt_denied(desc.cloned());
+        if remember {
+          self.insert_prompt_denied(desc.cloned());
+        }
         PermissionState::Denied
       }
       PromptResponse::AllowAll => {
-        self.insert_granted(None);
+        if remember {
+          self.insert_granted(None);
+        }
         PermissionState::Granted
       }
     }
@@ -617,6 +714,7 @@ impl<T: Descriptor + Hash> UnaryPermission<T> {
     perms.flag_denied_global = self.flag_denied_global;
     perms.prompt_denied_global = self.prompt_denied_global;
     perms.prompt = self.prompt;
+    perms.audit = self.audit;
     perms.flag_denied_list.clone_from(&self.flag_denied_list);
     perms
       .prompt_denied_list
@@ -654,7 +752,7 @@ impl Descriptor for ReadDescriptor {
   }
 
   fn stronger_than(&self, other: &Self) -> bool {
-    other.0.starts_with(&self.0)
+    path_stronger_than(&self.0, &other.0)
   }
 }
 
@@ -686,16 +784,197 @@ impl Descriptor for WriteDescriptor {
   }
 
   fn stronger_than(&self, other: &Self) -> bool {
-    other.0.starts_with(&self.0)
+    path_stronger_than(&self.0, &other.0)
   }
 }
 
+/// The host of a [`NetDescriptor`].
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
-pub struct NetDescriptor(pub FQDN, pub Option<u16>);
+pub enum NetHost {
+  Fqdn(FQDN),
+  /// `*.example.com`, which matches every subdomain of `example.com`, but not
+  /// `example.com` itself.
+  Wildcard(FQDN),
+  /// `10.0.0.0/8`, which matches every IP address of the block. The address
+  /// is stored with the host bits cleared.
+  Cidr(IpAddr, u8),
+}
+
+impl NetHost {
+  fn parse(host: &str) -> Result<Self, AnyError> {
+    if let Some((addr, prefix_len)) = host.split_once('/') {
+      let addr = addr
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .with_context(|| format!("Invalid CIDR block: {host}"))?;
+      let max_prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+      let prefix_len = prefix_len
+        .parse::<u8>()
+        .ok()
+        .filter(|len| *len <= max_prefix_len)
+        .with_context(|| format!("Invalid CIDR block: {host}"))?;
+      return Ok(NetHost::Cidr(mask_ip(addr, prefix_len), prefix_len));
+    }
+    if let Some(domain) = host.strip_prefix("*.") {
+      if domain.is_empty() || domain.contains('*') {
+        return Err(type_error(format!("Invalid wildcard host: {host}")));
+      }
+      return Ok(NetHost::Wildcard(parse_fqdn(&normalize_host(domain)?)?));
+    }
+    if host.contains('*') {
+      return Err(type_error(format!(
+        "Invalid wildcard host: {host}. Wildcards are only supported as the first label, like \"*.example.com\""
+      )));
+    }
+    Ok(NetHost::Fqdn(parse_fqdn(&normalize_host(host)?)?))
+  }
+
+  fn stronger_than(&self, other: &Self) -> bool {
+    match (self, other) {
+      (NetHost::Fqdn(a), NetHost::Fqdn(b)) => a == b,
+      (NetHost::Wildcard(a), NetHost::Fqdn(b) | NetHost::Wildcard(b)) => {
+        self == other || is_subdomain(b, a)
+      }
+      (NetHost::Cidr(addr, prefix_len), NetHost::Fqdn(host)) => {
+        match parse_host_ip(host) {
+          Some(ip) => mask_ip(ip, *prefix_len) == *addr,
+          None => false,
+        }
+      }
+      (NetHost::Cidr(a, a_len), NetHost::Cidr(b, b_len)) => {
+        a_len <= b_len && mask_ip(*b, *a_len) == *a
+      }
+      _ => false,
+    }
+  }
+}
+
+impl fmt::Display for NetHost {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      NetHost::Fqdn(fqdn) => write!(f, "{fqdn}"),
+      NetHost::Wildcard(fqdn) => write!(f, "*.{fqdn}"),
+      NetHost::Cidr(addr, prefix_len) => write!(f, "{addr}/{prefix_len}"),
+    }
+  }
+}
+
+/// Normalizes the host like the URL parser does, which converts
+/// internationalized domain names to punycode, lowercases them and expands
+/// IPv4 shorthands like `127.1`.
+fn normalize_host(host: &str) -> Result<String, AnyError> {
+  // the host of a special scheme is parsed as a domain or an IP address,
+  // while the host of other schemes is left as is
+  let url = Url::parse(&format!("http://{host}/"))
+    .map_err(|_| uri_error(format!("Invalid host: {host}")))?;
+  match url.host_str() {
+    // make sure the host didn't contain a user name, a query or the like
+    Some(normalized) if url.as_str() == format!("http://{normalized}/") => {
+      Ok(normalized.to_string())
+    }
+    _ => Err(uri_error(format!("Invalid host: {host}"))),
+  }
+}
+
+fn parse_fqdn(host: &str) -> Result<FQDN, AnyError> {
+  FQDN::from_str(host).map_err(|_| uri_error(format!("Invalid host: {host}")))
+}
+
+/// Whether `domain` is a strict subdomain of `parent`.
+fn is_subdomain(domain: &FQDN, parent: &FQDN) -> bool {
+  let domain = domain.to_string().to_ascii_lowercase();
+  let parent = parent.to_string().to_ascii_lowercase();
+  domain
+    .strip_suffix(&parent)
+    .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.'))
+}
+
+fn parse_host_ip(host: &FQDN) -> Option<IpAddr> {
+  let host = host.to_string();
+  host
+    .trim_end_matches('.')
+    .trim_start_matches('[')
+    .trim_end_matches(']')
+    .parse()
+    .ok()
+}
+
+fn mask_ip(addr: IpAddr, prefix_len: u8) -> IpAddr {
+  match addr {
+    IpAddr::V4(addr) => {
+      let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+      IpAddr::V4((u32::from(addr) & mask).into())
+    }
+    IpAddr::V6(addr) => {
+      let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+      IpAddr::V6((u128::from(addr) & mask).into())
+    }
+  }
+}
+
+/// An inclusive range of ports, like `8000-8100`. A single port is a range
+/// with the same start and end.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct PortRange {
+  pub start: u16,
+  pub end: u16,
+}
+
+impl PortRange {
+  pub fn single(port: u16) -> Self {
+    Self {
+      start: port,
+      end: port,
+    }
+  }
+
+  fn contains(&self, other: &PortRange) -> bool {
+    self.start <= other.start && other.end <= self.end
+  }
+}
+
+impl FromStr for PortRange {
+  type Err = AnyError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (start, end) = s.split_once('-').unwrap_or((s, s));
+    let range = PortRange {
+      start: start
+        .parse()
+        .with_context(|| format!("Invalid port: {start}"))?,
+      end: end
+        .parse()
+        .with_context(|| format!("Invalid port: {end}"))?,
+    };
+    if range.start > range.end {
+      return Err(type_error(format!("Invalid port range: {s}")));
+    }
+    Ok(range)
+  }
+}
+
+impl fmt::Display for PortRange {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.start == self.end {
+      write!(f, "{}", self.start)
+    } else {
+      write!(f, "{}-{}", self.start, self.end)
+    }
+  }
+}
+
+/// A host, wildcard subdomain or CIDR block with an optional port range. This
+/// replaced the former `NetDescriptor(FQDN, Option<u16>)` in 0.19.0.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct NetDescriptor(pub NetHost, pub Option<PortRange>);
 
 impl NetDescriptor {
   fn new<T: AsRef<str>>(host: &&(T, Option<u16>)) -> Self {
-    NetDescriptor(fqdn!(host.0.as_ref()), host.1)
+    NetDescriptor(
+      NetHost::Fqdn(fqdn!(host.0.as_ref())),
+      host.1.map(PortRange::single),
+    )
   }
 }
 
@@ -724,33 +1003,79 @@ impl Descriptor for NetDescriptor {
   }
 
   fn stronger_than(&self, other: &Self) -> bool {
-    self.0 == other.0 && (self.1.is_none() || self.1 == other.1)
+    self.0.stronger_than(&other.0)
+      && match (&self.1, &other.1) {
+        (None, _) => true,
+        (Some(a), Some(b)) => a.contains(b),
+        (Some(_), None) => false,
+      }
   }
 }
 
 impl FromStr for NetDescriptor {
   type Err = AnyError;
 
+  /// Parses a host, a `*.` wildcard host or a CIDR block, optionally
+  /// followed by a port or a range of ports, like `*.example.com:8000-8100`.
   fn from_str(s: &str) -> Result<Self, Self::Err> {
-    // Set the scheme to `unknown` to parse the URL, as we really don't know
-    // what the scheme is. We only using Url::parse to parse the host and port
-    // and don't care about the scheme.
-    let url = url::Url::parse(&format!("unknown://{s}"))?;
-    let hostname = url
-      .host_str()
-      .ok_or(url::ParseError::EmptyHost)?
-      .to_string();
-
-    Ok(NetDescriptor(fqdn!(&hostname), url.port()))
+    let (host, ports) = if let Some(rest) = s.strip_prefix('[') {
+      // bracketed IPv6 address, like `[::1]:8000` or `[fd00::]/8:443`
+      let (addr, rest) = rest
+        .split_once(']')
+        .filter(|(addr, _)| addr.parse::<Ipv6Addr>().is_ok())
+        .with_context(|| format!("Invalid host: {s}"))?;
+      let (prefix_len, ports) = match rest.strip_prefix('/') {
+        Some(rest) => match rest.split_once(':') {
+          Some((prefix_len, ports)) => (Some(prefix_len), Some(ports)),
+          None => (Some(rest), None),
+        },
+        None if rest.is_empty() => (None, None),
+        None => (
+          None,
+          Some(
+            rest
+              .strip_prefix(':')
+              .with_context(|| format!("Invalid host: {s}"))?,
+          ),
+        ),
+      };
+      let host = match prefix_len {
+        Some(prefix_len) => format!("{addr}/{prefix_len}"),
+        None => format!("[{addr}]"),
+      };
+      (host, ports)
+    } else if let Some((addr, rest)) = s.split_once('/') {
+      // CIDR block, like `10.0.0.0/8` or `fd00::/8:443`
+      match rest.split_once(':') {
+        Some((prefix_len, ports)) => {
+          (format!("{addr}/{prefix_len}"), Some(ports))
+        }
+        None => (s.to_string(), None),
+      }
+    } else if s.parse::<Ipv6Addr>().is_ok() {
+      (format!("[{s}]"), None)
+    } else {
+      match s.rsplit_once(':') {
+        Some((host, ports)) => (host.to_string(), Some(ports)),
+        None => (s.to_string(), None),
+      }
+    };
+    if host.is_empty() {
+      return Err(uri_error(format!("Invalid host: {s}")));
+    }
+    Ok(NetDescriptor(
+      NetHost::parse(&host)?,
+      ports.map(PortRange::from_str).transpose()?,
+    ))
   }
 }
 
 impl fmt::Display for NetDescriptor {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    f.write_str(&match self.1 {
-      None => self.0.to_string(),
-      Some(port) => format!("{}:{}", self.0, port),
-    })
+    match &self.1 {
+      None => write!(f, "{}", self.0),
+      Some(ports) => write!(f, "{}:{}", self.0, ports),
+    }
   }
 }
 
@@ -948,7 +1273,7 @@ impl Descriptor for FfiDescriptor {
   }
 
   fn stronger_than(&self, other: &Self) -> bool {
-    other.0.starts_with(&self.0)
+    path_stronger_than(&self.0, &other.0)
   }
 }
 
@@ -1381,6 +1706,7 @@ impl Permissions {
       flag_denied_global: global_from_option(deny_list),
       flag_denied_list: T::parse(deny_list)?,
       prompt,
+      audit: true,
       ..Default::default()
     })
   }
@@ -1450,7 +1776,8 @@ impl Permissions {
     })
   }
 
-  /// Create a set of permissions that explicitly allow everything.
+  /// Create a set of permissions that explicitly allow everything. Checks of
+  /// these permissions are not written to the audit log.
   pub fn allow_all() -> Self {
     Self {
       read: UnaryPermission::allow_all(),
@@ -1460,8 +1787,14 @@ impl Permissions {
       sys: UnaryPermission::allow_all(),
       run: UnaryPermission::allow_all(),
       ffi: UnaryPermission::allow_all(),
-      all: Permissions::new_all(true),
-      hrtime: Permissions::new_hrtime(true, false),
+      all: UnitPermission {
+        audit: false,
+        ..Permissions::new_all(true)
+      },
+      hrtime: UnitPermission {
+        audit: false,
+        ..Permissions::new_hrtime(true, false)
+      },
     }
   }
 
@@ -1815,6 +2148,7 @@ const fn unit_permission_from_flag_bools(
       PermissionState::Prompt
     },
     prompt,
+    audit: true,
   }
 }
 
@@ -1862,7 +2196,13 @@ fn parse_path_list<T: Descriptor + Hash>(
         if raw_path.as_os_str().is_empty() {
           Err(AnyError::msg("Empty path is not allowed"))
         } else {
-          resolve_from_cwd(Path::new(&raw_path)).map(f)
+          let path = resolve_from_cwd(Path::new(&raw_path))?;
+          if is_glob(&path) {
+            glob::Pattern::new(&path.to_string_lossy()).with_context(|| {
+              format!("Invalid glob pattern: {}", raw_path.display())
+            })?;
+          }
+          Ok(f(path))
         }
       })
       .collect()
@@ -1871,6 +2211,94 @@ fn parse_path_list<T: Descriptor + Hash>(
   }
 }
 
+/// Whether the path is a glob pattern, which is the case when one of its
+/// components contains `*`, `?` or a `[...]` character class, like for the
+/// glob crate. Patterns also support `**`, and the characters are matched
+/// literally when put in a class, for example `[[]id]` for `[id]`.
+fn is_glob(path: &Path) -> bool {
+  path
+    .components()
+    .any(|component| is_glob_component(&component))
+}
+
+fn is_glob_component(component: &Component) -> bool {
+  let Component::Normal(name) = component else {
+    return false;
+  };
+  let name = name.to_string_lossy();
+  has_wildcard(&name)
+    || name.find('[').is_some_and(|start| {
+      // a class holds at least one character, so `[]]` is a class of `]`
+      name.get(start + 2..).is_some_and(|rest| rest.contains(']'))
+    })
+}
+
+fn has_wildcard(text: &str) -> bool {
+  text.contains(['*', '?'])
+}
+
+/// The components of a glob pattern before the first one with a wildcard or
+/// a character class.
+fn glob_base(pattern: &Path) -> PathBuf {
+  pattern
+    .components()
+    .take_while(|component| !is_glob_component(component))
+    .collect()
+}
+
+/// Whether the glob pattern matches the path or one of its ancestors.
+fn glob_matches(pattern: &Path, path: &Path) -> bool {
+  const MATCH_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: !cfg!(windows),
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+  };
+
+  match glob::Pattern::new(&pattern.to_string_lossy()) {
+    Ok(pattern) => path
+      .ancestors()
+      .any(|ancestor| pattern.matches_path_with(ancestor, MATCH_OPTIONS)),
+    Err(_) => false,
+  }
+}
+
+/// Whether the path or glob pattern `a` covers `b`. A path covers itself and
+/// everything below it, including patterns below it, and a pattern covers
+/// the paths it matches and everything below them. Which paths another
+/// pattern matches isn't known, so a pattern only covers a pattern with
+/// wildcards when it's identical. Paths with brackets look like patterns
+/// with only character classes, so those are matched as paths.
+fn path_stronger_than(a: &Path, b: &Path) -> bool {
+  if a == b {
+    return true;
+  }
+  match (is_glob(a), is_glob(b)) {
+    (false, false) => b.starts_with(a),
+    (false, true) => glob_base(b).starts_with(a),
+    (true, false) => glob_matches(a, b),
+    (true, true) => !has_wildcard(&b.to_string_lossy()) && glob_matches(a, b),
+  }
+}
+
+/// Whether the descriptor named `a` is stronger than the one named `b`, both
+/// as returned by [`Descriptor::name`] for the permission `flag_name`. Used
+/// to match saved prompt answers, which only keep the names, to requests.
+pub(crate) fn descriptor_name_stronger_than(
+  flag_name: &str,
+  a: &str,
+  b: &str,
+) -> bool {
+  match flag_name {
+    "read" | "write" | "ffi" => path_stronger_than(Path::new(a), Path::new(b)),
+    "net" => match (NetDescriptor::from_str(a), NetDescriptor::from_str(b)) {
+      (Ok(a), Ok(b)) => a.stronger_than(&b),
+      _ => a == b,
+    },
+    "env" => EnvDescriptor::new(a).stronger_than(&EnvDescriptor::new(b)),
+    _ => a == b,
+  }
+}
+
 fn parse_sys_list(
   list: &Option<Vec<String>>,
 ) -> Result<HashSet<SysDescriptor>, AnyError> {
@@ -2119,42 +2547,42 @@ impl<'de> Deserialize<'de> for ChildPermissionsArg {
           if key == "env" {
             let arg = serde_json::from_value::<ChildUnaryPermissionArg>(value);
             child_permissions_arg.env = arg.map_err(|e| {
-              de::Error::custom(format!("(deno.permissions.env) {e}"))
+              de::Error::custom(format!("(system.permissions.env) {e}"))
             })?;
           } else if key == "hrtime" {
             let arg = serde_json::from_value::<ChildUnitPermissionArg>(value);
             child_permissions_arg.hrtime = arg.map_err(|e| {
-              de::Error::custom(format!("(deno.permissions.hrtime) {e}"))
+              de::Error::custom(format!("(system.permissions.hrtime) {e}"))
             })?;
           } else if key == "net" {
             let arg = serde_json::from_value::<ChildUnaryPermissionArg>(value);
             child_permissions_arg.net = arg.map_err(|e| {
-              de::Error::custom(format!("(deno.permissions.net) {e}"))
+              de::Error::custom(format!("(system.permissions.net) {e}"))
             })?;
           } else if key == "ffi" {
             let arg = serde_json::from_value::<ChildUnaryPermissionArg>(value);
             child_permissions_arg.ffi = arg.map_err(|e| {
-              de::Error::custom(format!("(deno.permissions.ffi) {e}"))
+              de::Error::custom(format!("(system.permissions.ffi) {e}"))
             })?;
           } else if key == "read" {
             let arg = serde_json::from_value::<ChildUnaryPermissionArg>(value);
             child_permissions_arg.read = arg.map_err(|e| {
-              de::Error::custom(format!("(deno.permissions.read) {e}"))
+              de::Error::custom(format!("(system.permissions.read) {e}"))
             })?;
           } else if key == "run" {
             let arg = serde_json::from_value::<ChildUnaryPermissionArg>(value);
             child_permissions_arg.run = arg.map_err(|e| {
-              de::Error::custom(format!("(deno.permissions.run) {e}"))
+              de::Error::custom(format!("(system.permissions.run) {e}"))
             })?;
           } else if key == "sys" {
             let arg = serde_json::from_value::<ChildUnaryPermissionArg>(value);
             child_permissions_arg.sys = arg.map_err(|e| {
-              de::Error::custom(format!("(deno.permissions.sys) {e}"))
+              de::Error::custom(format!("(system.permissions.sys) {e}"))
             })?;
           } else if key == "write" {
             let arg = serde_json::from_value::<ChildUnaryPermissionArg>(value);
             child_permissions_arg.write = arg.map_err(|e| {
-              de::Error::custom(format!("(deno.permissions.write) {e}"))
+              de::Error::custom(format!("(system.permissions.write) {e}"))
             })?;
           } else {
             return Err(de::Error::custom("unknown permission name"));
@@ -2206,6 +2634,10 @@ pub fn create_child_permissions(
 #[cfg(test)]
 mod tests {
   use super::*;
+  use deno_core::futures::executor::block_on;
+  use deno_core::futures::future;
+  use deno_core::futures::future::BoxFuture;
+  use deno_core::futures::FutureExt;
   use deno_core::serde_json::json;
   use prompter::tests::*;
 
@@ -2503,6 +2935,219 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_check_net_patterns() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec![
+        "*.internal.example.com",
+        "10.0.0.0/8",
+        "[fd00::]/8:443",
+        "localhost:8000-8100"
+      ]),
+      deny_net: Some(svec!["*.secret.internal.example.com"]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    let domain_tests = vec![
+      ("api.internal.example.com", 443, true),
+      ("a.b.internal.example.com", 80, true),
+      ("internal.example.com", 443, false),
+      ("evilinternal.example.com", 443, false),
+      ("db.secret.internal.example.com", 443, false),
+      ("10.1.2.3", 22, true),
+      ("11.1.2.3", 22, false),
+      ("[fd12::1]", 443, true),
+      ("[fd12::1]", 80, false),
+      ("[fe80::1]", 443, false),
+      ("localhost", 8000, true),
+      ("localhost", 8100, true),
+      ("localhost", 7999, false),
+      ("localhost", 8101, false),
+    ];
+
+    for (host, port, is_ok) in domain_tests {
+      assert_eq!(
+        is_ok,
+        perms.net.check(&(host, Some(port)), None).is_ok(),
+        "{}:{}",
+        host,
+        port,
+      );
+    }
+
+    let query_tests = vec![
+      ("*.api.internal.example.com", PermissionState::Granted),
+      ("*.internal.example.com", PermissionState::GrantedPartial),
+      ("*.example.com", PermissionState::Prompt),
+      ("10.2.0.0/16", PermissionState::Granted),
+      ("10.0.0.0/7", PermissionState::Prompt),
+      ("localhost:8050-8060", PermissionState::Granted),
+      ("localhost:8050-8200", PermissionState::Prompt),
+    ];
+
+    for (host, state) in query_tests {
+      let desc = NetDescriptor::from_str(host).unwrap();
+      assert_eq!(perms.net.query_descriptor(Some(&desc)), state, "{}", host);
+    }
+  }
+
+  #[test]
+  fn test_parse_net_descriptor() {
+    let descriptors = [
+      ("deno.land", "deno.land"),
+      ("deno.land:80", "deno.land:80"),
+      ("*.deno.land:8000-8100", "*.deno.land:8000-8100"),
+      ("10.1.2.3/8", "10.0.0.0/8"),
+      ("fd00::1/8:443", "fd00::/8:443"),
+      ("[fd00::]/8", "fd00::/8"),
+      ("::1", "[::1]"),
+      ("[::1]:8000", "[::1]:8000"),
+      ("Deno.LAND", "deno.land"),
+      ("bücher.example:443", "xn--bcher-kva.example:443"),
+      ("*.BÜCHER.example", "*.xn--bcher-kva.example"),
+      ("127.1", "127.0.0.1"),
+      ("0x7f.1:8000", "127.0.0.1:8000"),
+    ];
+    for (input, expected) in descriptors {
+      assert_eq!(
+        NetDescriptor::from_str(input).unwrap().to_string(),
+        expected,
+        "{}",
+        input
+      );
+    }
+
+    for input in [
+      "",
+      ":",
+      "a.*.deno.land",
+      "*.",
+      "10.0.0.0/33",
+      "deno.land:8100-8000",
+      "[::q]:8000",
+      "user@deno.land",
+      "deno.land?a",
+    ] {
+      assert!(NetDescriptor::from_str(input).is_err(), "{}", input);
+    }
+  }
+
+  #[test]
+  fn test_check_path_globs() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_read: Some(vec![
+        PathBuf::from("/data/**/*.json"),
+        PathBuf::from("/logs/*"),
+      ]),
+      allow_write: Some(vec![PathBuf::from("/app")]),
+      deny_write: Some(vec![PathBuf::from("/app/**/.env")]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    let read_tests = [
+      ("/data/a.json", true),
+      ("/data/x/y/b.json", true),
+      ("/data/a.txt", false),
+      ("/other/a.json", false),
+      ("/logs/today.log", true),
+      // below a directory matching the pattern
+      ("/logs/2024/01.log", true),
+      ("/logs", false),
+    ];
+    for (path, is_ok) in read_tests {
+      assert_eq!(
+        is_ok,
+        perms.read.check(Path::new(path), None).is_ok(),
+        "{}",
+        path
+      );
+    }
+
+    let write_tests = [
+      ("/app/main.ts", true),
+      ("/app/.env", false),
+      ("/app/config/.env", false),
+      ("/app", false),
+    ];
+    for (path, is_ok) in write_tests {
+      assert_eq!(
+        is_ok,
+        perms.write.check(Path::new(path), None).is_ok(),
+        "{}",
+        path
+      );
+    }
+    perms.write.check_partial(Path::new("/app"), None).unwrap();
+
+    assert_eq!(
+      perms.read.query(Some(Path::new("/logs/today.log"))),
+      PermissionState::Granted
+    );
+    assert_eq!(
+      perms.read.query(Some(Path::new("/logs/2024/*"))),
+      PermissionState::Prompt
+    );
+    assert_eq!(
+      perms.read.query(Some(Path::new("/data/**/*.json"))),
+      PermissionState::Granted
+    );
+    assert_eq!(
+      perms.write.query(Some(Path::new("/app"))),
+      PermissionState::GrantedPartial
+    );
+
+    assert!(Permissions::new_unary::<ReadDescriptor>(
+      &Some(vec![PathBuf::from("/data/[*.json")]),
+      &None,
+      false
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn test_check_path_glob_classes() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_read: Some(vec![
+        PathBuf::from("/src/[ab].ts"),
+        // the brackets of the directory are escaped
+        PathBuf::from("/pages/[[]id]/*"),
+        // not a class, so a path
+        PathBuf::from("/tmp/[]"),
+      ]),
+      ..Default::default()
+    })
+    .unwrap();
+
+    let read_tests = [
+      ("/src/a.ts", true),
+      ("/src/b.ts", true),
+      ("/src/c.ts", false),
+      ("/pages/[id]/index.ts", true),
+      ("/pages/[id]/nested/index.ts", true),
+      ("/pages/i/index.ts", false),
+      ("/pages/[id]", false),
+      ("/tmp/[]/a.txt", true),
+    ];
+    for (path, is_ok) in read_tests {
+      assert_eq!(
+        is_ok,
+        perms.read.check(Path::new(path), None).is_ok(),
+        "{}",
+        path
+      );
+    }
+
+    assert!(is_glob(Path::new("/src/[ab].ts")));
+    assert!(is_glob(Path::new("/src/[]].ts")));
+    assert!(!is_glob(Path::new("/src/[.ts")));
+    assert!(!is_glob(Path::new("/src/[].ts")));
+  }
+
   #[test]
   fn check_specifiers() {
     set_prompter(Box::new(TestPrompter));
@@ -3308,6 +3953,118 @@ mod tests {
     );
   }
 
+  struct TestStructuredPrompter {
+    answer: prompter::PromptAnswer,
+    requests: Arc<Mutex<Vec<PromptRequest>>>,
+  }
+
+  impl StructuredPermissionPrompter for TestStructuredPrompter {
+    fn prompt(&self, request: &PromptRequest) -> prompter::PromptAnswer {
+      self.requests.lock().push(request.clone());
+      self.answer
+    }
+  }
+
+  #[test]
+  fn test_thread_prompter_remember_for() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      prompt: true,
+      ..Default::default()
+    })
+    .unwrap();
+
+    let guard = set_thread_prompter(Arc::new(TestStructuredPrompter {
+      answer: prompter::PromptAnswer {
+        response: PromptResponse::Allow,
+        remember_for: RememberFor::Once,
+      },
+      requests: requests.clone(),
+    }));
+    assert!(perms.env.check("HOME", Some("Deno.env.get()")).is_ok());
+    assert!(perms.env.check("HOME", Some("Deno.env.get()")).is_ok());
+    assert_eq!(perms.env.query(Some("HOME")), PermissionState::Prompt);
+    drop(guard);
+    {
+      let requests = requests.lock();
+      assert_eq!(requests.len(), 2);
+      assert_eq!(requests[0].name, "env");
+      assert_eq!(requests[0].descriptor.as_deref(), Some("HOME"));
+      assert_eq!(requests[0].api_name.as_deref(), Some("Deno.env.get()"));
+      assert!(requests[0].is_unary);
+    }
+
+    let _guard = set_thread_prompter(Arc::new(TestStructuredPrompter {
+      answer: prompter::PromptAnswer {
+        response: PromptResponse::Deny,
+        remember_for: RememberFor::Session,
+      },
+      requests: requests.clone(),
+    }));
+    assert!(perms.env.check("PATH", None).is_err());
+    assert!(perms.env.check("PATH", None).is_err());
+    assert_eq!(perms.env.query(Some("PATH")), PermissionState::Denied);
+    assert_eq!(requests.lock().len(), 3);
+  }
+
+  struct TestAsyncPrompter {
+    answer: prompter::PromptAnswer,
+    requests: Arc<Mutex<Vec<PromptRequest>>>,
+  }
+
+  impl AsyncPermissionPrompter for TestAsyncPrompter {
+    fn prompt(
+      &self,
+      request: PromptRequest,
+    ) -> BoxFuture<'static, prompter::PromptAnswer> {
+      self.requests.lock().push(request);
+      future::ready(self.answer).boxed()
+    }
+  }
+
+  #[test]
+  fn test_request_with_async_prompter() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      prompt: true,
+      ..Default::default()
+    })
+    .unwrap();
+
+    let _guard = set_thread_async_prompter(Arc::new(TestAsyncPrompter {
+      answer: prompter::PromptAnswer {
+        response: PromptResponse::Allow,
+        remember_for: RememberFor::Session,
+      },
+      requests: requests.clone(),
+    }));
+    let state = block_on(request_with_async_prompter(|| {
+      perms.env.request(Some("HOME"))
+    }));
+    assert_eq!(state, PermissionState::Granted);
+    assert_eq!(perms.env.query(Some("HOME")), PermissionState::Granted);
+    {
+      let requests = requests.lock();
+      assert_eq!(requests.len(), 1);
+      assert_eq!(requests[0].descriptor.as_deref(), Some("HOME"));
+      assert_eq!(
+        requests[0].api_name.as_deref(),
+        Some("system.permissions.request()")
+      );
+    }
+
+    // granted permissions don't prompt
+    let state = block_on(request_with_async_prompter(|| {
+      perms.env.request(Some("HOME"))
+    }));
+    assert_eq!(state, PermissionState::Granted);
+    assert_eq!(requests.lock().len(), 1);
+
+    // synchronous checks block on the async prompter
+    assert!(perms.env.check("PATH", None).is_ok());
+    assert_eq!(requests.lock().len(), 2);
+  }
+
   #[test]
   fn test_handle_empty_value() {
     set_prompter(Box::new(TestPrompter));
@@ -0,0 +1,96 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! The location in the program of the code that checks a permission, which
+//! is written to the audit log and passed to permission prompters.
+
+use std::cell::RefCell;
+use std::fmt;
+
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+
+thread_local! {
+  static LOCATION_CALLBACK: RefCell<Option<LocationCallback>> =
+    RefCell::new(None);
+}
+
+/// Where in the program a permission was checked.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckLocation {
+  pub specifier: String,
+  pub line_number: u32,
+  pub column_number: u32,
+}
+
+impl fmt::Display for CheckLocation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}:{}:{}",
+      self.specifier, self.line_number, self.column_number
+    )
+  }
+}
+
+/// Returns the location of the code that caused a permission check, for
+/// example the innermost user code frame of the JavaScript stack.
+pub type LocationCallback = Box<dyn Fn() -> Option<CheckLocation>>;
+
+/// Sets the callback used to get the location of permission checks made on
+/// the current thread. The previous callback is restored when the returned
+/// guard is dropped, so the guards must be dropped in reverse order.
+#[must_use]
+pub fn set_location_callback(
+  callback: LocationCallback,
+) -> LocationCallbackGuard {
+  let previous =
+    LOCATION_CALLBACK.with(|cell| cell.borrow_mut().replace(callback));
+  LocationCallbackGuard { previous }
+}
+
+pub struct LocationCallbackGuard {
+  previous: Option<LocationCallback>,
+}
+
+impl Drop for LocationCallbackGuard {
+  fn drop(&mut self) {
+    let previous = self.previous.take();
+    LOCATION_CALLBACK.with(|cell| *cell.borrow_mut() = previous);
+  }
+}
+
+pub(crate) fn current_location() -> Option<CheckLocation> {
+  LOCATION_CALLBACK.with(|cell| match &*cell.borrow() {
+    Some(callback) => callback(),
+    None => None,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn location(specifier: &str) -> LocationCallback {
+    let specifier = specifier.to_string();
+    Box::new(move || {
+      Some(CheckLocation {
+        specifier: specifier.clone(),
+        line_number: 1,
+        column_number: 1,
+      })
+    })
+  }
+
+  #[test]
+  fn guard_restores_previous_callback() {
+    let outer = set_location_callback(location("file:///outer.ts"));
+    {
+      let _inner = set_location_callback(location("file:///inner.ts"));
+      assert_eq!(current_location().unwrap().specifier, "file:///inner.ts");
+    }
+    assert_eq!(current_location().unwrap().specifier, "file:///outer.ts");
+    drop(outer);
+    assert_eq!(current_location(), None);
+  }
+}
@@ -1,15 +1,26 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use deno_core::error::AnyError;
+use deno_core::futures::executor::block_on;
+use deno_core::futures::future::BoxFuture;
 use deno_core::parking_lot::Mutex;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
 use deno_terminal::colors;
 use once_cell::sync::Lazy;
+use std::cell::RefCell;
 use std::fmt::Write;
 use std::io::BufRead;
 use std::io::IsTerminal;
 use std::io::StderrLock;
 use std::io::StdinLock;
 use std::io::Write as IoWrite;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::descriptor_name_stronger_than;
+use crate::location::CheckLocation;
 
 /// Helper function to make control characters visible so users can see the underlying filename.
 fn escape_control_characters(s: &str) -> std::borrow::Cow<str> {
@@ -36,38 +47,284 @@ pub const PERMISSION_EMOJI: &str = "⚠️";
 // 10kB of permission prompting should be enough for anyone
 const MAX_PERMISSION_PROMPT_LENGTH: usize = 10 * 1024;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum PromptResponse {
   Allow,
   Deny,
   AllowAll,
 }
 
+/// A request for a permission, as passed to [`StructuredPermissionPrompter`]s.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptRequest {
+  /// The name of the permission, as in its `--allow-*` flag.
+  pub name: String,
+  /// The path, host, variable, command or kind access is requested to, as
+  /// resolved by the permission, e.g. an absolute path or a normalized host.
+  /// `None` when access to the whole permission is requested.
+  pub descriptor: Option<String>,
+  pub api_name: Option<String>,
+  /// Where in the program access was requested, if known.
+  pub location: Option<CheckLocation>,
+  /// Whether [`PromptResponse::AllowAll`] is a valid response.
+  pub is_unary: bool,
+  /// The message shown by the terminal prompter.
+  pub message: String,
+}
+
+/// How long the answer to a prompt applies for.
+#[derive(
+  Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum RememberFor {
+  /// Only the access that was prompted for is answered, the next access
+  /// prompts again.
+  Once,
+  /// The answer applies until the program exits.
+  #[default]
+  Session,
+  /// The answer applies until the program exits and is saved to the
+  /// [`PromptAnswerStore`], if one is set, for later runs.
+  Project,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptAnswer {
+  pub response: PromptResponse,
+  pub remember_for: RememberFor,
+}
+
 static PERMISSION_PROMPTER: Lazy<Mutex<Box<dyn PermissionPrompter>>> =
   Lazy::new(|| Mutex::new(Box::new(TtyPrompter)));
 
+static STRUCTURED_PERMISSION_PROMPTER: Lazy<
+  Mutex<Option<Arc<dyn StructuredPermissionPrompter>>>,
+> = Lazy::new(|| Mutex::new(None));
+
+static PROMPT_ANSWER_STORE: Lazy<Mutex<Option<PromptAnswerStore>>> =
+  Lazy::new(|| Mutex::new(None));
+
+thread_local! {
+  static THREAD_PERMISSION_PROMPTER:
+    RefCell<Option<Arc<dyn StructuredPermissionPrompter>>> = RefCell::new(None);
+  static THREAD_ASYNC_PERMISSION_PROMPTER:
+    RefCell<Option<Arc<dyn AsyncPermissionPrompter>>> = RefCell::new(None);
+  static PENDING_PROMPT: RefCell<PendingPrompt> =
+    const { RefCell::new(PendingPrompt::None) };
+}
+
+/// How [`permission_prompt`] answers while [`request_with_async_prompter`]
+/// runs a request.
+enum PendingPrompt {
+  None,
+  /// The request is recorded and denied for this access only, so it can be
+  /// answered by the async prompter.
+  Capture(Option<PromptRequest>),
+  /// The answer of the async prompter to the recorded request.
+  Answer(PromptAnswer),
+}
+
 static MAYBE_BEFORE_PROMPT_CALLBACK: Lazy<Mutex<Option<PromptCallback>>> =
   Lazy::new(|| Mutex::new(None));
 
 static MAYBE_AFTER_PROMPT_CALLBACK: Lazy<Mutex<Option<PromptCallback>>> =
   Lazy::new(|| Mutex::new(None));
 
-pub fn permission_prompt(
-  message: &str,
-  flag: &str,
-  api_name: Option<&str>,
-  is_unary: bool,
-) -> PromptResponse {
+/// Answers a permission request, from the [`PromptAnswerStore`] if it has
+/// an answer for it, otherwise by prompting with the prompter of the current
+/// thread, the global structured prompter or the global prompter, in that
+/// order.
+pub fn permission_prompt(request: &PromptRequest) -> PromptAnswer {
+  let stored = PROMPT_ANSWER_STORE
+    .lock()
+    .as_ref()
+    .and_then(|store| store.get(&request.name, request.descriptor.as_deref()));
+  if let Some(response) = stored {
+    return PromptAnswer {
+      response: match response {
+        PromptResponse::AllowAll if !request.is_unary => PromptResponse::Allow,
+        response => response,
+      },
+      remember_for: RememberFor::Session,
+    };
+  }
+  let pending = PENDING_PROMPT.with(|cell| cell.replace(PendingPrompt::None));
+  let mut answer = match pending {
+    PendingPrompt::Capture(_) => {
+      PENDING_PROMPT.with(|cell| {
+        *cell.borrow_mut() = PendingPrompt::Capture(Some(request.clone()))
+      });
+      return PromptAnswer {
+        response: PromptResponse::Deny,
+        remember_for: RememberFor::Once,
+      };
+    }
+    PendingPrompt::Answer(answer) => answer,
+    PendingPrompt::None => {
+      run_before_prompt_callback();
+      let answer = prompt_blocking(request);
+      run_after_prompt_callback();
+      answer
+    }
+  };
+  if !request.is_unary && answer.response == PromptResponse::AllowAll {
+    answer.response = PromptResponse::Allow;
+  }
+  if answer.remember_for == RememberFor::Project {
+    if let Some(store) = PROMPT_ANSWER_STORE.lock().as_mut() {
+      // the answer still applies to this run if it can't be saved
+      if let Err(err) = store.insert(
+        &request.name,
+        request.descriptor.as_deref(),
+        answer.response,
+      ) {
+        log::warn!("Failed to save permission prompt answer: {err:#}");
+      }
+    }
+  }
+  answer
+}
+
+fn prompt_blocking(request: &PromptRequest) -> PromptAnswer {
+  let structured_prompter = THREAD_PERMISSION_PROMPTER
+    .with(|cell| cell.borrow().clone())
+    .or_else(|| {
+      THREAD_ASYNC_PERMISSION_PROMPTER
+        .with(|cell| cell.borrow().clone())
+        .map(|prompter| {
+          Arc::new(BlockingPrompter(prompter))
+            as Arc<dyn StructuredPermissionPrompter>
+        })
+    })
+    .or_else(|| STRUCTURED_PERMISSION_PROMPTER.lock().clone());
+  match structured_prompter {
+    Some(prompter) => prompter.prompt(request),
+    None => PromptAnswer {
+      response: PERMISSION_PROMPTER.lock().prompt(
+        &request.message,
+        &request.name,
+        request.api_name.as_deref(),
+        request.is_unary,
+      ),
+      remember_for: RememberFor::Session,
+    },
+  }
+}
+
+fn run_before_prompt_callback() {
   if let Some(before_callback) = MAYBE_BEFORE_PROMPT_CALLBACK.lock().as_mut() {
     before_callback();
   }
-  let r = PERMISSION_PROMPTER
-    .lock()
-    .prompt(message, flag, api_name, is_unary);
+}
+
+fn run_after_prompt_callback() {
   if let Some(after_callback) = MAYBE_AFTER_PROMPT_CALLBACK.lock().as_mut() {
     after_callback();
   }
-  r
+}
+
+/// Runs `request`, a permission request like
+/// [`crate::UnaryPermission::request`], answering its prompt, if it has one,
+/// with the async prompter of the current thread. The thread keeps running
+/// other tasks while the prompter answers, unlike with
+/// [`permission_prompt`].
+///
+/// `request` is run once with its prompt recorded instead of answered, which
+/// leaves the permission unchanged, and, if it prompted, once more with the
+/// answer of the async prompter. Without an async prompter, `request` is
+/// run once and prompts as usual.
+pub async fn request_with_async_prompter<R>(
+  mut request: impl FnMut() -> R,
+) -> R {
+  let Some(prompter) =
+    THREAD_ASYNC_PERMISSION_PROMPTER.with(|cell| cell.borrow().clone())
+  else {
+    return request();
+  };
+  PENDING_PROMPT.with(|cell| *cell.borrow_mut() = PendingPrompt::Capture(None));
+  let result = request();
+  let pending = PENDING_PROMPT.with(|cell| cell.replace(PendingPrompt::None));
+  let PendingPrompt::Capture(Some(prompt_request)) = pending else {
+    return result;
+  };
+  run_before_prompt_callback();
+  let answer = prompter.prompt(prompt_request).await;
+  run_after_prompt_callback();
+  PENDING_PROMPT
+    .with(|cell| *cell.borrow_mut() = PendingPrompt::Answer(answer));
+  let result = request();
+  // the permission may have been granted or denied while prompting, in
+  // which case the answer is unused
+  PENDING_PROMPT.with(|cell| *cell.borrow_mut() = PendingPrompt::None);
+  result
+}
+
+/// Sets the prompter used when no structured prompter is set.
+pub fn set_prompter(prompter: Box<dyn PermissionPrompter>) {
+  *PERMISSION_PROMPTER.lock() = prompter;
+}
+
+/// Sets the prompter used on threads that don't have a prompter of their
+/// own, in place of the prompter set with [`set_prompter`].
+pub fn set_structured_prompter(
+  prompter: Arc<dyn StructuredPermissionPrompter>,
+) {
+  *STRUCTURED_PERMISSION_PROMPTER.lock() = Some(prompter);
+}
+
+/// Sets the prompter used for permission requests made on the current
+/// thread, e.g. by a worker. The previous prompter of the thread is restored
+/// when the returned guard is dropped.
+#[must_use]
+pub fn set_thread_prompter(
+  prompter: Arc<dyn StructuredPermissionPrompter>,
+) -> ThreadPrompterGuard {
+  let previous =
+    THREAD_PERMISSION_PROMPTER.with(|cell| cell.replace(Some(prompter)));
+  ThreadPrompterGuard(PreviousPrompter::Blocking(previous))
+}
+
+/// Sets the async prompter used for permission requests made on the current
+/// thread, e.g. by a worker. Requests run with
+/// [`request_with_async_prompter`] are answered without blocking the thread,
+/// other prompts of the thread block on it if the thread has no prompter
+/// set with [`set_thread_prompter`]. The previous async prompter of the
+/// thread is restored when the returned guard is dropped.
+#[must_use]
+pub fn set_thread_async_prompter(
+  prompter: Arc<dyn AsyncPermissionPrompter>,
+) -> ThreadPrompterGuard {
+  let previous =
+    THREAD_ASYNC_PERMISSION_PROMPTER.with(|cell| cell.replace(Some(prompter)));
+  ThreadPrompterGuard(PreviousPrompter::Async(previous))
+}
+
+enum PreviousPrompter {
+  Blocking(Option<Arc<dyn StructuredPermissionPrompter>>),
+  Async(Option<Arc<dyn AsyncPermissionPrompter>>),
+}
+
+pub struct ThreadPrompterGuard(PreviousPrompter);
+
+impl Drop for ThreadPrompterGuard {
+  fn drop(&mut self) {
+    match &mut self.0 {
+      PreviousPrompter::Blocking(previous) => THREAD_PERMISSION_PROMPTER
+        .with(|cell| *cell.borrow_mut() = previous.take()),
+      PreviousPrompter::Async(previous) => THREAD_ASYNC_PERMISSION_PROMPTER
+        .with(|cell| *cell.borrow_mut() = previous.take()),
+    }
+  }
+}
+
+/// Sets the store answers remembered for the project are saved to and
+/// looked up in.
+pub fn set_prompt_answer_store(store: PromptAnswerStore) {
+  *PROMPT_ANSWER_STORE.lock() = Some(store);
 }
 
 pub fn set_prompt_callbacks(
@@ -80,6 +337,129 @@ pub fn set_prompt_callbacks(
 
 pub type PromptCallback = Box<dyn FnMut() + Send + Sync>;
 
+/// A prompter that answers structured permission requests, e.g. by showing a
+/// dialog in a GUI.
+///
+/// Permission checks are synchronous, so `prompt` is called on the thread
+/// that requested the permission and blocks it, including its event loop,
+/// until it returns. A prompter that answers from another thread, like a UI
+/// thread, should send the request there and wait for the answer. See
+/// [`AsyncPermissionPrompter`] for a prompter that doesn't block
+/// `system.permissions.request()`.
+pub trait StructuredPermissionPrompter: Send + Sync {
+  fn prompt(&self, request: &PromptRequest) -> PromptAnswer;
+}
+
+/// A prompter that answers structured permission requests asynchronously,
+/// e.g. by sending them to a UI thread over a channel and awaiting the
+/// answer.
+///
+/// `system.permissions.request()` awaits the returned future on the event
+/// loop of the requesting worker, which keeps running other tasks meanwhile.
+/// Implicit prompts of synchronous permission checks, like the one of
+/// `system.readTextFileSync()`, still block the thread on the future, so it
+/// must not depend on the thread's event loop to complete.
+pub trait AsyncPermissionPrompter: Send + Sync {
+  fn prompt(&self, request: PromptRequest) -> BoxFuture<'static, PromptAnswer>;
+}
+
+/// Answers synchronous permission checks with an [`AsyncPermissionPrompter`]
+/// by blocking on its answer.
+struct BlockingPrompter(Arc<dyn AsyncPermissionPrompter>);
+
+impl StructuredPermissionPrompter for BlockingPrompter {
+  fn prompt(&self, request: &PromptRequest) -> PromptAnswer {
+    block_on(self.0.prompt(request.clone()))
+  }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredAnswer {
+  name: String,
+  /// `None` when the answer applies to the whole permission.
+  descriptor: Option<String>,
+  response: PromptResponse,
+}
+
+/// Prompt answers that were remembered for the project, saved as a JSON
+/// file.
+pub struct PromptAnswerStore {
+  path: PathBuf,
+  answers: Vec<StoredAnswer>,
+}
+
+impl PromptAnswerStore {
+  /// Loads the answers saved at `path`. A missing file is an empty store.
+  pub fn load(path: PathBuf) -> Result<Self, AnyError> {
+    let answers = match std::fs::read_to_string(&path) {
+      Ok(text) => serde_json::from_str(&text)?,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+      Err(err) => return Err(err.into()),
+    };
+    Ok(Self { path, answers })
+  }
+
+  /// Returns the saved answer for access to `descriptor`, or to the whole
+  /// permission if `descriptor` is `None`. Answers apply to the descriptors
+  /// their descriptor is stronger than, like a path answers for the files
+  /// below it, and denials take precedence, like with `--deny-*` flags.
+  pub fn get(
+    &self,
+    name: &str,
+    descriptor: Option<&str>,
+  ) -> Option<PromptResponse> {
+    let covering = self
+      .answers
+      .iter()
+      .rev()
+      .filter(|answer| {
+        answer.name == name
+          && match (answer.descriptor.as_deref(), descriptor) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(a), Some(b)) => descriptor_name_stronger_than(name, a, b),
+          }
+      })
+      .collect::<Vec<_>>();
+    if covering
+      .iter()
+      .any(|answer| answer.response == PromptResponse::Deny)
+    {
+      return Some(PromptResponse::Deny);
+    }
+    covering.first().map(|answer| answer.response)
+  }
+
+  /// Saves an answer, replacing any earlier answer for the same access.
+  pub fn insert(
+    &mut self,
+    name: &str,
+    descriptor: Option<&str>,
+    response: PromptResponse,
+  ) -> Result<(), AnyError> {
+    let descriptor = match response {
+      PromptResponse::AllowAll => None,
+      _ => descriptor.map(|d| d.to_string()),
+    };
+    self
+      .answers
+      .retain(|answer| answer.name != name || answer.descriptor != descriptor);
+    self.answers.push(StoredAnswer {
+      name: name.to_string(),
+      descriptor,
+      response,
+    });
+    // write to a temporary file and rename it, so the store isn't left
+    // truncated if writing fails half way
+    let mut temp_path = self.path.clone().into_os_string();
+    temp_path.push(".tmp");
+    std::fs::write(&temp_path, serde_json::to_vec_pretty(&self.answers)?)?;
+    std::fs::rename(&temp_path, &self.path)?;
+    Ok(())
+  }
+}
+
 pub trait PermissionPrompter: Send + Sync {
   fn prompt(
     &mut self,
@@ -458,7 +838,86 @@ pub mod tests {
     }
   }
 
-  pub fn set_prompter(prompter: Box<dyn PermissionPrompter>) {
-    *PERMISSION_PROMPTER.lock() = prompter;
+  #[test]
+  fn prompt_answer_store() {
+    let path = std::env::temp_dir()
+      .join(format!("deno_prompt_answers_{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut store = PromptAnswerStore::load(path.clone()).unwrap();
+    assert_eq!(store.get("read", Some("/a")), None);
+    store
+      .insert("read", Some("/a"), PromptResponse::Allow)
+      .unwrap();
+    store
+      .insert("read", Some("/b"), PromptResponse::Deny)
+      .unwrap();
+    store
+      .insert("env", Some("HOME"), PromptResponse::AllowAll)
+      .unwrap();
+    store
+      .insert("read", Some("/a"), PromptResponse::Deny)
+      .unwrap();
+
+    let mut temp_path = path.clone().into_os_string();
+    temp_path.push(".tmp");
+    assert!(!PathBuf::from(temp_path).exists());
+
+    let store = PromptAnswerStore::load(path.clone()).unwrap();
+    assert_eq!(store.get("read", Some("/a")), Some(PromptResponse::Deny));
+    assert_eq!(store.get("read", Some("/b")), Some(PromptResponse::Deny));
+    assert_eq!(store.get("read", Some("/c")), None);
+    assert_eq!(store.get("read", None), None);
+    assert_eq!(store.get("write", Some("/a")), None);
+    assert_eq!(
+      store.get("env", Some("PATH")),
+      Some(PromptResponse::AllowAll)
+    );
+    assert_eq!(store.get("env", None), Some(PromptResponse::AllowAll));
+
+    std::fs::remove_file(&path).unwrap();
+  }
+
+  #[test]
+  fn prompt_answer_store_matches_stronger_descriptors() {
+    let path = std::env::temp_dir().join(format!(
+      "deno_prompt_answers_stronger_{}.json",
+      std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+
+    let mut store = PromptAnswerStore::load(path.clone()).unwrap();
+    store
+      .insert("read", Some("/data"), PromptResponse::Allow)
+      .unwrap();
+    store
+      .insert("read", Some("/data/secret"), PromptResponse::Deny)
+      .unwrap();
+    store
+      .insert("net", Some("*.example.com"), PromptResponse::Allow)
+      .unwrap();
+    store
+      .insert("env", Some("HOME"), PromptResponse::Allow)
+      .unwrap();
+
+    assert_eq!(
+      store.get("read", Some("/data/logs/a.log")),
+      Some(PromptResponse::Allow)
+    );
+    assert_eq!(
+      store.get("read", Some("/data/secret/key")),
+      Some(PromptResponse::Deny)
+    );
+    assert_eq!(store.get("read", Some("/database")), None);
+    assert_eq!(store.get("read", None), None);
+    assert_eq!(
+      store.get("net", Some("api.example.com:443")),
+      Some(PromptResponse::Allow)
+    );
+    assert_eq!(store.get("net", Some("example.com")), None);
+    assert_eq!(store.get("env", Some("HOME")), Some(PromptResponse::Allow));
+    assert_eq!(store.get("env", Some("HOMEPATH")), None);
+
+    std::fs::remove_file(&path).unwrap();
   }
 }
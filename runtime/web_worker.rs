@@ -7,12 +7,14 @@ use crate::shared::runtime;
 use crate::tokio_util::create_and_run_current_thread;
 use crate::worker::create_op_metrics;
 use crate::worker::import_meta_resolve_callback;
+use crate::worker::set_permission_location_callback;
 use crate::worker::validate_import_attributes_callback;
 use crate::worker::FormatJsErrorFn;
 use crate::BootstrapOptions;
 use deno_broadcast_channel::InMemoryBroadcastChannel;
 use deno_cache::CreateCache;
 use deno_cache::SqliteBackedCache;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
 use deno_core::futures::channel::mpsc;
@@ -20,6 +22,7 @@ use deno_core::futures::future::poll_fn;
 use deno_core::futures::stream::StreamExt;
 use deno_core::futures::task::AtomicWaker;
 use deno_core::located_script_name;
+use deno_core::parking_lot::Mutex;
 use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use deno_core::serde_json::json;
@@ -45,6 +48,12 @@ use deno_http::DefaultHttpPropertyExtractor;
 use deno_io::Stdio;
 use deno_kv::dynamic::MultiBackendDbHandler;
 use deno_node::NodeResolver;
+use deno_permissions::location::LocationCallbackGuard;
+use deno_permissions::prompter::set_thread_async_prompter;
+use deno_permissions::prompter::set_thread_prompter;
+use deno_permissions::prompter::AsyncPermissionPrompter;
+use deno_permissions::prompter::StructuredPermissionPrompter;
+use deno_permissions::prompter::ThreadPrompterGuard;
 use deno_permissions::PermissionsContainer;
 use deno_terminal::colors;
 use deno_tls::RootCertStoreProvider;
@@ -65,6 +74,7 @@ use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::task::Context;
 use std::task::Poll;
+use std::time::Duration;
 
 pub struct WorkerMetadata {
   pub buffer: DetachedBuffer,
@@ -99,6 +109,71 @@ pub enum WebWorkerType {
   Module,
 }
 
+/// Limits on the resources a worker may use. A worker that exceeds its heap
+/// or wall-clock time limit is terminated, and an error event is dispatched
+/// on its `Worker` object in the parent.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerResourceLimits {
+  /// The maximum size of the worker's V8 heap, in megabytes.
+  pub max_heap_size_mb: Option<usize>,
+  /// The size of the worker thread's stack, in megabytes. The limit of the
+  /// JavaScript stack itself is set for all workers with
+  /// `--v8-flags=--stack-size`.
+  pub stack_size_mb: Option<usize>,
+  /// The maximum wall-clock time the worker may exist for, in milliseconds,
+  /// counted from its creation. Time the worker spends loading modules or
+  /// idle counts towards it, as CPU time isn't measured.
+  pub max_wall_time_ms: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum ExceededResourceLimit {
+  Heap { max_heap_size_mb: usize },
+  WallTime { max_wall_time_ms: u64 },
+}
+
+impl ExceededResourceLimit {
+  fn to_error(self) -> AnyError {
+    let message = match self {
+      Self::Heap { max_heap_size_mb } => {
+        format!("heap limit of {max_heap_size_mb} MB exceeded")
+      }
+      Self::WallTime { max_wall_time_ms } => {
+        format!("wall-clock time limit of {max_wall_time_ms} ms exceeded")
+      }
+    };
+    generic_error(format!("Worker terminated: {message}"))
+  }
+}
+
+/// Terminates a worker that exceeded one of its resource limits. Unlike a
+/// termination requested by the parent, the worker still reports an error to
+/// the parent afterwards.
+#[derive(Clone)]
+struct ResourceLimitTerminator {
+  exceeded: Arc<Mutex<Option<ExceededResourceLimit>>>,
+  isolate_handle: v8::IsolateHandle,
+  terminate_waker: Arc<AtomicWaker>,
+}
+
+impl ResourceLimitTerminator {
+  fn terminate(&self, limit: ExceededResourceLimit) {
+    let mut exceeded = self.exceeded.lock();
+    if exceeded.is_none() {
+      *exceeded = Some(limit);
+      // Stop javascript execution, and wake the event loop in case no
+      // javascript is running
+      self.isolate_handle.terminate_execution();
+      self.terminate_waker.wake();
+    }
+  }
+
+  fn exceeded(&self) -> Option<ExceededResourceLimit> {
+    *self.exceeded.lock()
+  }
+}
+
 /// Events that are sent to host from child
 /// worker.
 pub enum WorkerControlEvent {
@@ -158,6 +233,7 @@ pub struct WebWorkerInternalHandle {
   has_terminated: Arc<AtomicBool>,
   terminate_waker: Arc<AtomicWaker>,
   isolate_handle: v8::IsolateHandle,
+  resource_limit_terminator: ResourceLimitTerminator,
   pub name: String,
   pub worker_type: WebWorkerType,
 }
@@ -178,6 +254,15 @@ impl WebWorkerInternalHandle {
     Ok(())
   }
 
+  /// Returns the error to report to the parent if this worker was terminated
+  /// because it exceeded one of its resource limits.
+  pub fn resource_limit_error(&self) -> Option<AnyError> {
+    self
+      .resource_limit_terminator
+      .exceeded()
+      .map(ExceededResourceLimit::to_error)
+  }
+
   /// Check if this worker is terminated or being terminated
   pub fn is_terminated(&self) -> bool {
     self.has_terminated.load(Ordering::SeqCst)
@@ -321,6 +406,11 @@ fn create_handles(
     has_terminated: has_terminated.clone(),
     terminate_waker: terminate_waker.clone(),
     isolate_handle: isolate_handle.clone(),
+    resource_limit_terminator: ResourceLimitTerminator {
+      exceeded: Default::default(),
+      isolate_handle: isolate_handle.clone(),
+      terminate_waker: terminate_waker.clone(),
+    },
     cancel: CancelHandle::new_rc(),
     sender: ctrl_tx,
     worker_type,
@@ -342,7 +432,13 @@ fn create_handles(
 /// `WebWorker`.
 pub struct WebWorker {
   id: WorkerId,
+  // declared before the runtime so they're dropped first
+  _permission_location_guard: LocationCallbackGuard,
+  _permission_prompter_guard: Option<ThreadPrompterGuard>,
+  _async_permission_prompter_guard: Option<ThreadPrompterGuard>,
   pub js_runtime: JsRuntime,
+  // stops the wall-clock time limit timer when dropped
+  _wall_timer: Option<std::sync::mpsc::Sender<()>>,
   pub name: String,
   close_on_idle: bool,
   has_executed_main_module: bool,
@@ -383,6 +479,14 @@ pub struct WebWorkerOptions {
   pub strace_ops: Option<Vec<String>>,
   pub close_on_idle: bool,
   pub maybe_worker_metadata: Option<WorkerMetadata>,
+  /// Answers permission prompts of this worker in place of the global
+  /// prompter.
+  pub permission_prompter: Option<Arc<dyn StructuredPermissionPrompter>>,
+  /// Answers `system.permissions.request()` of this worker without blocking
+  /// its event loop, and the other prompts of the worker if
+  /// `permission_prompter` isn't set.
+  pub async_permission_prompter: Option<Arc<dyn AsyncPermissionPrompter>>,
+  pub resource_limits: WorkerResourceLimits,
 }
 
 impl WebWorker {
@@ -540,8 +644,18 @@ impl WebWorker {
       options.strace_ops,
     );
 
+    let create_params =
+      options
+        .resource_limits
+        .max_heap_size_mb
+        .map(|max_heap_size_mb| {
+          v8::CreateParams::default()
+            .heap_limits(0, max_heap_size_mb.saturating_mul(1 << 20))
+        });
+
     let mut js_runtime = JsRuntime::new(RuntimeOptions {
       module_loader: Some(options.module_loader.clone()),
+      create_params,
       startup_snapshot: options.startup_snapshot,
       source_map_getter: options.source_map_getter,
       get_error_class_fn: options.get_error_class_fn,
@@ -591,6 +705,38 @@ impl WebWorker {
       (internal_handle, external_handle)
     };
 
+    if let Some(max_heap_size_mb) = options.resource_limits.max_heap_size_mb {
+      let terminator = internal_handle.resource_limit_terminator.clone();
+      js_runtime.add_near_heap_limit_callback(move |current_limit, _| {
+        terminator.terminate(ExceededResourceLimit::Heap { max_heap_size_mb });
+        // give the isolate room to stop executing instead of crashing the
+        // whole process
+        current_limit * 2
+      });
+    }
+
+    let wall_timer =
+      options
+        .resource_limits
+        .max_wall_time_ms
+        .map(|max_wall_time_ms| {
+          let terminator = internal_handle.resource_limit_terminator.clone();
+          let (sender, receiver) = std::sync::mpsc::channel::<()>();
+          std::thread::spawn(move || {
+            let timeout = Duration::from_millis(max_wall_time_ms);
+            // the wait ends early when the worker, and the sender with it,
+            // is dropped
+            if let Err(std::sync::mpsc::RecvTimeoutError::Timeout) =
+              receiver.recv_timeout(timeout)
+            {
+              terminator.terminate(ExceededResourceLimit::WallTime {
+                max_wall_time_ms,
+              });
+            }
+          });
+          sender
+        });
+
     let bootstrap_fn_global = {
       let context = js_runtime.main_context();
       let scope = &mut js_runtime.handle_scope();
@@ -613,10 +759,23 @@ impl WebWorker {
       v8::Global::new(scope, bootstrap_fn)
     };
 
+    let permission_location_guard =
+      set_permission_location_callback(&mut js_runtime);
+    let permission_prompter_guard =
+      options.permission_prompter.take().map(set_thread_prompter);
+    let async_permission_prompter_guard = options
+      .async_permission_prompter
+      .take()
+      .map(set_thread_async_prompter);
+
     (
       Self {
         id: worker_id,
+        _permission_location_guard: permission_location_guard,
+        _permission_prompter_guard: permission_prompter_guard,
+        _async_permission_prompter_guard: async_permission_prompter_guard,
         js_runtime,
+        _wall_timer: wall_timer,
         name,
         internal_handle,
         worker_type: options.worker_type,
@@ -789,6 +948,10 @@ impl WebWorker {
       return Poll::Ready(Ok(()));
     }
 
+    if let Some(err) = self.internal_handle.resource_limit_error() {
+      return Poll::Ready(Err(err));
+    }
+
     self.internal_handle.terminate_waker.register(cx.waker());
 
     match self.js_runtime.poll_event_loop(cx, poll_options) {
@@ -953,6 +1116,13 @@ pub fn run_web_worker(
       result
     };
 
+    // Execution that was stopped because a resource limit was exceeded fails
+    // with a termination error, report the limit instead
+    let result = match internal_handle.resource_limit_error() {
+      Some(err) => Err(err),
+      None => result,
+    };
+
     if let Err(e) = result {
       print_worker_error(&e, &name, format_js_error_fn.as_deref());
       internal_handle
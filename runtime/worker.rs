@@ -39,6 +39,14 @@ use deno_fs::FileSystem;
 use deno_http::DefaultHttpPropertyExtractor;
 use deno_io::Stdio;
 use deno_kv::dynamic::MultiBackendDbHandler;
+use deno_permissions::location::set_location_callback;
+use deno_permissions::location::CheckLocation;
+use deno_permissions::location::LocationCallbackGuard;
+use deno_permissions::prompter::set_thread_async_prompter;
+use deno_permissions::prompter::set_thread_prompter;
+use deno_permissions::prompter::AsyncPermissionPrompter;
+use deno_permissions::prompter::StructuredPermissionPrompter;
+use deno_permissions::prompter::ThreadPrompterGuard;
 use deno_permissions::PermissionsContainer;
 use deno_tls::RootCertStoreProvider;
 use deno_tls::TlsKeys;
@@ -93,6 +101,40 @@ pub fn validate_import_attributes_callback(
   }
 }
 
+/// Reports the innermost user code frame of the JavaScript stack as the
+/// location of permission checks made on this thread, which is used by the
+/// permission audit log and prompts.
+pub(crate) fn set_permission_location_callback(
+  js_runtime: &mut JsRuntime,
+) -> LocationCallbackGuard {
+  let isolate: *mut v8::Isolate = &mut **js_runtime.v8_isolate();
+  let context = js_runtime.main_context();
+  set_location_callback(Box::new(move || {
+    // SAFETY: permission checks run synchronously on the isolate's thread
+    // and the worker drops the callback before it drops the runtime.
+    let isolate = unsafe { &mut *isolate };
+    let scope = &mut v8::CallbackScope::new(isolate);
+    let scope = &mut v8::HandleScope::new(scope);
+    let context = v8::Local::new(scope, &context);
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let stack = v8::StackTrace::current_stack_trace(scope, 16)?;
+    (0..stack.get_frame_count()).find_map(|index| {
+      let frame = stack.get_frame(scope, index)?;
+      let script = frame
+        .get_script_name_or_source_url(scope)?
+        .to_rust_string_lossy(scope);
+      if script.starts_with("ext:") || script.starts_with("node:") {
+        return None;
+      }
+      Some(CheckLocation {
+        specifier: script,
+        line_number: frame.get_line_number() as u32,
+        column_number: frame.get_column() as u32,
+      })
+    })
+  }))
+}
+
 #[derive(Clone, Default)]
 pub struct ExitCode(Arc<AtomicI32>);
 
@@ -114,6 +156,10 @@ impl ExitCode {
 /// All `WebWorker`s created during program execution
 /// are descendants of this worker.
 pub struct MainWorker {
+  // declared before the runtime so they're dropped first
+  _permission_location_guard: LocationCallbackGuard,
+  _permission_prompter_guard: Option<ThreadPrompterGuard>,
+  _async_permission_prompter_guard: Option<ThreadPrompterGuard>,
   pub js_runtime: JsRuntime,
   should_break_on_first_statement: bool,
   should_wait_for_inspector_session: bool,
@@ -202,6 +248,14 @@ pub struct WorkerOptions {
 
   /// V8 code cache for module and script source code.
   pub v8_code_cache: Option<Arc<dyn CodeCache>>,
+
+  /// Answers permission prompts of this worker in place of the global
+  /// prompter.
+  pub permission_prompter: Option<Arc<dyn StructuredPermissionPrompter>>,
+  /// Answers `system.permissions.request()` of this worker without blocking
+  /// its event loop, and the other prompts of the worker if
+  /// `permission_prompter` isn't set.
+  pub async_permission_prompter: Option<Arc<dyn AsyncPermissionPrompter>>,
 }
 
 impl Default for WorkerOptions {
@@ -238,6 +292,8 @@ impl Default for WorkerOptions {
       stdio: Default::default(),
       feature_checker: Default::default(),
       v8_code_cache: Default::default(),
+      permission_prompter: Default::default(),
+      async_permission_prompter: Default::default(),
     }
   }
 }
@@ -663,7 +719,19 @@ impl MainWorker {
       )
     };
 
+    let permission_location_guard =
+      set_permission_location_callback(&mut js_runtime);
+    let permission_prompter_guard =
+      options.permission_prompter.take().map(set_thread_prompter);
+    let async_permission_prompter_guard = options
+      .async_permission_prompter
+      .take()
+      .map(set_thread_async_prompter);
+
     Self {
+      _permission_location_guard: permission_location_guard,
+      _permission_prompter_guard: permission_prompter_guard,
+      _async_permission_prompter_guard: async_permission_prompter_guard,
       js_runtime,
       should_break_on_first_statement: options.should_break_on_first_statement,
       should_wait_for_inspector_session: options
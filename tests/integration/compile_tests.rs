@@ -612,6 +612,33 @@ fn workers_not_in_module_map() {
   output.assert_exit_code(0);
   output.skip_output_check();
 
+  // the worker entrypoint is found by analyzing the `new Worker` call
+  let output = context.new_command().name(exe).env("NO_COLOR", "").run();
+  output.assert_exit_code(0);
+  output.assert_matches_text("Hello from worker!\nReceived 42\nClosing\n");
+}
+
+#[test]
+fn workers_not_analyzable() {
+  let context = TestContext::with_http_server();
+  let temp_dir = context.temp_dir();
+  let exe = if cfg!(windows) {
+    temp_dir.path().join("not_analyzable.exe")
+  } else {
+    temp_dir.path().join("not_analyzable")
+  };
+  let output = context
+    .new_command()
+    .args_vec([
+      "compile",
+      "--output",
+      &exe.to_string_lossy(),
+      "./compile/workers/not_analyzable.ts",
+    ])
+    .run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
   let output = context.new_command().name(exe).env("NO_COLOR", "").run();
   output.assert_exit_code(1);
   output.assert_matches_text(concat!(
@@ -620,6 +647,46 @@ fn workers_not_in_module_map() {
   ));
 }
 
+#[test]
+fn compile_include_assets() {
+  let context = TestContextBuilder::new()
+    .use_copy_temp_dir("compile/assets")
+    .cwd("compile/assets")
+    .build();
+  let temp_dir = context.temp_dir();
+  let project_dir = temp_dir.path().join("compile").join("assets");
+  let exe = project_dir.join(if cfg!(windows) { "main.exe" } else { "main" });
+  let output = context
+    .new_command()
+    .args_vec([
+      "compile",
+      "--include-assets",
+      "templates/*.html",
+      "--include-assets",
+      "data",
+      "--allow-read=templates",
+      "--allow-write=templates",
+      "--output",
+      &exe.to_string_lossy(),
+      "main.ts",
+    ])
+    .run();
+  output.assert_exit_code(0);
+  output.skip_output_check();
+
+  // remove the original files to ensure they're read from the executable,
+  // while the files that weren't embedded are kept
+  temp_dir.remove_file(project_dir.join("templates").join("index.html"));
+  temp_dir.remove_dir_all(project_dir.join("data"));
+
+  context
+    .new_command()
+    .name(&exe)
+    .run()
+    .assert_matches_file("compile/assets/main.out")
+    .assert_exit_code(0);
+}
+
 #[test]
 fn workers_with_include_flag() {
   let context = TestContext::with_http_server();
@@ -845,6 +912,20 @@ fn compile_npm_file_system() {
   });
 }
 
+#[test]
+fn compile_npm_file_system_compressed() {
+  run_npm_bin_compile_test(RunNpmBinCompileOptions {
+    input_specifier: "compile/npm_fs/main.ts",
+    compile_args: vec!["-A", "--compress"],
+    run_args: vec![],
+    output_file: "compile/npm_fs/main.out",
+    node_modules_dir: true,
+    input_name: Some("binary"),
+    expected_name: "binary",
+    exit_code: 0,
+  });
+}
+
 #[test]
 fn compile_npm_bin_esm() {
   run_npm_bin_compile_test(RunNpmBinCompileOptions {
@@ -1091,6 +1172,7 @@ Compile file:///[WILDCARD]/main.ts to [WILDCARD]
 Warning Failed resolving symlink. Ignoring.
     Path: [WILDCARD]
     Message: [WILDCARD])
+Embedded [WILDCARD] from node_modules, [WILDCARD]
 "#,
   );
 
@@ -1269,3 +1351,137 @@ fn standalone_jsr_dynamic_import() {
   output.assert_exit_code(0);
   output.assert_matches_text("Hello world\n");
 }
+
+#[test]
+fn compile_list_targets_offline() {
+  let context = TestContextBuilder::new().add_compile_env_vars().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.create_dir_all("base_binaries");
+  temp_dir.write("base_binaries/denort-x86_64-apple-darwin.zip", "");
+
+  let output = context
+    .new_command()
+    .args("compile --list-targets --base-binaries base_binaries")
+    .env("NO_COLOR", "1")
+    .run();
+  output.assert_exit_code(0);
+  output.assert_matches_text(concat!(
+    "x86_64-unknown-linux-gnu   not available\n",
+    "aarch64-unknown-linux-gnu  not available\n",
+    "x86_64-pc-windows-msvc     not available\n",
+    "x86_64-apple-darwin        available [WILDCARD]denort-x86_64-apple-darwin.zip\n",
+    "aarch64-apple-darwin       not available\n",
+  ));
+}
+
+#[test]
+fn compile_base_binaries_checksum_mismatch() {
+  let context = TestContextBuilder::new().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("main.ts", "console.log(1);");
+  temp_dir.create_dir_all("base_binaries");
+  temp_dir.write("base_binaries/denort-x86_64-apple-darwin.zip", "invalid");
+  temp_dir.write(
+    "base_binaries/denort-x86_64-apple-darwin.zip.sha256sum",
+    format!("{}  denort-x86_64-apple-darwin.zip\n", "0".repeat(64)),
+  );
+
+  let output = context
+    .new_command()
+    .args("compile --target x86_64-apple-darwin --base-binaries base_binaries --output main main.ts")
+    .env_remove("DENORT_BIN")
+    .run();
+  output.assert_exit_code(1);
+  output.assert_matches_text(concat!(
+    "[WILDCARD]error: Checksum mismatch for base binary '[WILDCARD]denort-x86_64-apple-darwin.zip'.\n",
+    "  Expected: 0000000000000000000000000000000000000000000000000000000000000000 (from '[WILDCARD]')\n",
+    "  Actual: [WILDCARD]\n",
+  ));
+}
+
+#[test]
+fn compile_base_binaries_missing_checksum() {
+  let context = TestContextBuilder::new().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("main.ts", "console.log(1);");
+  temp_dir.create_dir_all("base_binaries");
+  temp_dir.write("base_binaries/denort-x86_64-apple-darwin.zip", "invalid");
+
+  let output = context
+    .new_command()
+    .args("compile --target x86_64-apple-darwin --base-binaries base_binaries --output main main.ts")
+    .env_remove("DENORT_BIN")
+    .env("NO_COLOR", "1")
+    .run();
+  output.assert_matches_text(concat!(
+    "[WILDCARD]Warning No checksum found for base binary '[WILDCARD]denort-x86_64-apple-darwin.zip', so it could not be verified. ",
+    "Add a 'denort-x86_64-apple-darwin.zip.sha256sum' or 'SHA256SUMS' file next to it.\n",
+    "[WILDCARD]",
+  ));
+}
+
+#[test]
+fn compile_base_binaries_missing_target() {
+  let context = TestContextBuilder::new().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("main.ts", "console.log(1);");
+  temp_dir.create_dir_all("base_binaries");
+
+  let output = context
+    .new_command()
+    .args("compile --target x86_64-apple-darwin --base-binaries base_binaries --output main main.ts")
+    .env_remove("DENORT_BIN")
+    .run();
+  output.assert_exit_code(1);
+  output.assert_matches_text("[WILDCARD]error: Could not find the base binary for target 'x86_64-apple-darwin' in 'base_binaries'.[WILDCARD]");
+}
+
+#[test]
+fn compile_self_update() {
+  let context = TestContextBuilder::new().add_compile_env_vars().build();
+  let dir = context.temp_dir();
+  let exe_v1 = if cfg!(windows) {
+    dir.path().join("app.exe")
+  } else {
+    dir.path().join("app")
+  };
+  let exe_v2 = if cfg!(windows) {
+    dir.path().join("app_v2.exe")
+  } else {
+    dir.path().join("app_v2")
+  };
+  for (exe, entrypoint, args) in [
+    (
+      &exe_v1,
+      "./compile/self_update/v1.ts",
+      "--unstable-self-update --allow-read --allow-write",
+    ),
+    (&exe_v2, "./compile/self_update/v2.ts", ""),
+  ] {
+    context
+      .new_command()
+      .args(format!(
+        "compile {} --output {} {}",
+        args,
+        exe.to_string_lossy(),
+        entrypoint,
+      ))
+      .run()
+      .skip_output_check()
+      .assert_exit_code(0);
+  }
+
+  context
+    .new_command()
+    .name(&exe_v1)
+    .args(exe_v2.to_string_lossy())
+    .run()
+    .assert_matches_text("Checksum mismatch for the update.\nv1 updated\n")
+    .assert_exit_code(0);
+  context
+    .new_command()
+    .name(&exe_v1)
+    .run()
+    .assert_matches_text("v2\n")
+    .assert_exit_code(0);
+}
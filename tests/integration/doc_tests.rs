@@ -5,6 +5,11 @@ use test_util::itest;
 use util::assert_contains;
 use util::TestContext;
 
+itest!(deno_doc_man {
+  args: "doc --man --name=greet doc/man_page.ts",
+  output: "doc/man_page.out",
+});
+
 itest!(deno_doc_builtin {
   args: "doc",
   output: "doc/deno_doc_builtin.out",
@@ -142,3 +147,39 @@ fn deno_doc_html() {
     .join("~/MyClass.prototype.prop.html")
     .exists());
 }
+
+#[test]
+fn deno_doc_markdown() {
+  let context = TestContext::default();
+  let temp_dir = context.temp_dir();
+  let output = context
+    .new_command()
+    .env("NO_COLOR", "1")
+    .args_vec(vec![
+      "doc",
+      "--markdown",
+      "--name=MyLib",
+      &format!("--output={}", temp_dir.path().to_string_lossy()),
+      "doc/referenced_private_types_fixed.ts",
+    ])
+    .split_output()
+    .run();
+
+  output.assert_exit_code(0);
+  assert_contains!(output.stderr(), "Written 4 files to");
+  let module_dir = temp_dir
+    .path()
+    .join("doc/referenced_private_types_fixed.ts");
+  let index = temp_dir.path().join("index.md").read_to_string();
+  assert_contains!(
+    index,
+    "- [`doc/referenced_private_types_fixed.ts`](doc/referenced_private_types_fixed.ts/index.md)"
+  );
+  let module_index = module_dir.join("index.md").read_to_string();
+  assert_contains!(module_index, "- [`MyClass`](MyClass.md) - Doc comment");
+  assert_contains!(module_index, "- [`MyInterface`](MyInterface.md)");
+  let my_class = module_dir.join("MyClass.md").read_to_string();
+  assert_contains!(my_class, "```ts\nclass MyClass\n```");
+  assert_contains!(my_class, "### `prop`\n\n```ts\nprop: MyInterface\n```");
+  assert!(module_dir.join("MyInterface.md").exists());
+}
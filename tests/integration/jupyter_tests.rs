@@ -628,3 +628,96 @@ async fn jupyter_store_history_false() -> Result<()> {
 
   Ok(())
 }
+
+#[tokio::test]
+async fn jupyter_comm_target() -> Result<()> {
+  let (_ctx, client, _process) = setup().await;
+  client
+    .send(
+      Shell,
+      "execute_request",
+      json!({
+        "silent": false,
+        "store_history": true,
+        "code": r#"Deno.jupyter.registerCommTarget("echo", (comm) => {
+          comm.onMessage((data) => comm.send({ echo: data.value }));
+        });"#,
+      }),
+    )
+    .await?;
+  let reply = client.recv(Shell).await?;
+  assert_eq!(reply.header.msg_type, "execute_reply");
+
+  client
+    .send(
+      Shell,
+      "comm_open",
+      json!({
+        "comm_id": "comm-1",
+        "target_name": "echo",
+        "data": {},
+      }),
+    )
+    .await?;
+  client
+    .send(Shell, "comm_info_request", json!({ "target_name": "echo" }))
+    .await?;
+  let reply = client.recv(Shell).await?;
+  assert_eq!(reply.header.msg_type, "comm_info_reply");
+  assert_json_subset(
+    reply.content,
+    json!({
+      "status": "ok",
+      "comms": {
+        "comm-1": { "target_name": "echo" },
+      },
+    }),
+  );
+
+  client
+    .send(
+      Shell,
+      "comm_msg",
+      json!({
+        "comm_id": "comm-1",
+        "data": { "value": 42 },
+      }),
+    )
+    .await?;
+  loop {
+    let msg = client.recv(IoPub).await?;
+    if msg.header.msg_type == "comm_msg" {
+      assert_json_subset(
+        msg.content,
+        json!({
+          "comm_id": "comm-1",
+          "data": { "echo": 42 },
+        }),
+      );
+      break;
+    }
+  }
+
+  Ok(())
+}
+
+#[tokio::test]
+async fn jupyter_comm_open_unknown_target() -> Result<()> {
+  let (_ctx, client, _process) = setup().await;
+  client
+    .send(
+      Shell,
+      "comm_open",
+      json!({
+        "comm_id": "comm-1",
+        "target_name": "unknown",
+        "data": {},
+      }),
+    )
+    .await?;
+  let reply = client.recv(Shell).await?;
+  assert_eq!(reply.header.msg_type, "comm_close");
+  assert_json_subset(reply.content, json!({ "comm_id": "comm-1" }));
+
+  Ok(())
+}
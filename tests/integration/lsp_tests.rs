@@ -9943,6 +9943,340 @@ fn lsp_format_editor_options() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_range_formatting() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let file = source_file(
+    temp_dir.path().join("file.ts"),
+    "const a  =  1;\nconst b  =  2;\n",
+  );
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let res = client.write_request(
+    "textDocument/rangeFormatting",
+    json!({
+      "textDocument": {
+        "uri": file.uri(),
+      },
+      "range": {
+        "start": { "line": 1, "character": 0 },
+        "end": { "line": 1, "character": 14 },
+      },
+      "options": {
+        "tabSize": 2,
+        "insertSpaces": true,
+      },
+    }),
+  );
+  let edits = res.as_array().unwrap();
+  assert!(!edits.is_empty());
+  for edit in edits {
+    assert_eq!(edit["range"]["start"]["line"], 1);
+    assert_eq!(edit["range"]["end"]["line"], 1);
+  }
+  client.shutdown();
+}
+
+#[test]
+fn lsp_on_type_formatting() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let file = source_file(
+    temp_dir.path().join("file.ts"),
+    "const a  =  1;\nfunction b() {\nreturn  a;\n}\n",
+  );
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  let res = client.write_request(
+    "textDocument/onTypeFormatting",
+    json!({
+      "textDocument": {
+        "uri": file.uri(),
+      },
+      "position": { "line": 3, "character": 1 },
+      "ch": "}",
+      "options": {
+        "tabSize": 2,
+        "insertSpaces": true,
+      },
+    }),
+  );
+  // only the function is formatted
+  let edits = res.as_array().unwrap();
+  assert!(!edits.is_empty());
+  for edit in edits {
+    assert!(edit["range"]["start"]["line"].as_u64().unwrap() >= 1);
+  }
+
+  let res = client.write_request(
+    "textDocument/onTypeFormatting",
+    json!({
+      "textDocument": {
+        "uri": file.uri(),
+      },
+      "position": { "line": 0, "character": 14 },
+      "ch": ";",
+      "options": {
+        "tabSize": 2,
+        "insertSpaces": true,
+      },
+    }),
+  );
+  let edits = res.as_array().unwrap();
+  assert!(!edits.is_empty());
+  for edit in edits {
+    assert_eq!(edit["range"]["end"]["line"], 0);
+  }
+  client.shutdown();
+}
+
+#[test]
+fn lsp_document_links() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("b.ts", "export const b = 1;\n");
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("a.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { b } from \"./b.ts\";\n\n/** See https://deno.land/manual. */\nexport const a = b;\n",
+    },
+  }));
+  let res = client.write_request(
+    "textDocument/documentLink",
+    json!({
+      "textDocument": {
+        "uri": temp_dir.uri().join("a.ts").unwrap(),
+      },
+    }),
+  );
+  let links = res.as_array().unwrap();
+  assert_eq!(links.len(), 2);
+  assert_eq!(links[0]["range"]["start"]["line"], 0);
+  assert_eq!(
+    links[0]["target"],
+    json!(temp_dir.uri().join("b.ts").unwrap())
+  );
+  assert_eq!(
+    links[1],
+    json!({
+      "range": {
+        "start": { "line": 2, "character": 8 },
+        "end": { "line": 2, "character": 32 },
+      },
+      "target": "https://deno.land/manual",
+    })
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_pull_diagnostics() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("b.ts", "export const b: string = 1;\n");
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.set_pull_diagnostics(true).with_capabilities(|c| {
+      c.text_document.as_mut().unwrap().diagnostic = Some(Default::default());
+    });
+  });
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("a.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "const a: number = \"a\";\n",
+    },
+  }));
+  // diagnostics are not published when the client pulls them
+  assert_eq!(diagnostics.all().len(), 0);
+
+  let res = client.write_request(
+    "textDocument/diagnostic",
+    json!({
+      "textDocument": {
+        "uri": temp_dir.uri().join("a.ts").unwrap(),
+      },
+    }),
+  );
+  assert_eq!(res["kind"], "full");
+  let items = res["items"].as_array().unwrap();
+  assert_eq!(items.len(), 1);
+  assert_eq!(items[0]["code"], 2322);
+  let result_id = res["resultId"].as_str().unwrap().to_string();
+
+  let res = client.write_request(
+    "textDocument/diagnostic",
+    json!({
+      "textDocument": {
+        "uri": temp_dir.uri().join("a.ts").unwrap(),
+      },
+      "previousResultId": result_id,
+    }),
+  );
+  assert_eq!(res, json!({ "kind": "unchanged", "resultId": result_id }));
+
+  let res = client.write_request(
+    "workspace/diagnostic",
+    json!({
+      "previousResultIds": [{
+        "uri": temp_dir.uri().join("a.ts").unwrap(),
+        "value": result_id,
+      }],
+    }),
+  );
+  let items = res["items"].as_array().unwrap();
+  let a_item = items
+    .iter()
+    .find(|i| i["uri"] == json!(temp_dir.uri().join("a.ts").unwrap()))
+    .unwrap();
+  assert_eq!(a_item["kind"], "unchanged");
+  let b_item = items
+    .iter()
+    .find(|i| i["uri"] == json!(temp_dir.uri().join("b.ts").unwrap()))
+    .unwrap();
+  assert_eq!(b_item["kind"], "full");
+  assert_eq!(b_item["items"][0]["code"], 2322);
+  client.shutdown();
+}
+
+#[test]
+fn lsp_linked_editing_range() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    json!({ "compilerOptions": { "jsx": "react-jsx" } }).to_string(),
+  );
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("a.tsx").unwrap(),
+      "languageId": "typescriptreact",
+      "version": 1,
+      "text": "export const a = <div>hello</div>;\n",
+    },
+  }));
+  let res = client.write_request(
+    "textDocument/linkedEditingRange",
+    json!({
+      "textDocument": {
+        "uri": temp_dir.uri().join("a.tsx").unwrap(),
+      },
+      "position": { "line": 0, "character": 19 },
+    }),
+  );
+  assert_eq!(
+    res["ranges"],
+    json!([
+      {
+        "start": { "line": 0, "character": 18 },
+        "end": { "line": 0, "character": 21 },
+      },
+      {
+        "start": { "line": 0, "character": 29 },
+        "end": { "line": 0, "character": 32 },
+      },
+    ])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_moniker() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    json!({
+      "name": "@scope/pkg",
+      "version": "1.0.0",
+      "exports": "./mod.ts",
+    })
+    .to_string(),
+  );
+  temp_dir.write(
+    "mod.ts",
+    "export function add(a: number, b: number) {\n  return a + b;\n}\n",
+  );
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "import { add } from \"./mod.ts\";\nconst sum = add(1, 2);\nconsole.log(sum);\n",
+    },
+  }));
+  let res = client.write_request(
+    "textDocument/moniker",
+    json!({
+      "textDocument": {
+        "uri": temp_dir.uri().join("main.ts").unwrap(),
+      },
+      "position": { "line": 1, "character": 13 },
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([{
+      "scheme": "jsr",
+      "identifier": "jsr:@scope/pkg#add",
+      "unique": "scheme",
+      "kind": "import",
+    }])
+  );
+  let res = client.write_request(
+    "textDocument/moniker",
+    json!({
+      "textDocument": {
+        "uri": temp_dir.uri().join("main.ts").unwrap(),
+      },
+      "position": { "line": 2, "character": 13 },
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([{
+      "scheme": "deno",
+      "identifier": format!("{}#sum", temp_dir.uri().join("main.ts").unwrap()),
+      "unique": "project",
+      "kind": "local",
+    }])
+  );
+  client.shutdown();
+}
+
+#[test]
+fn lsp_pull_diagnostics_requires_setting() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.with_capabilities(|c| {
+      c.text_document.as_mut().unwrap().diagnostic = Some(Default::default());
+    });
+  });
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.uri().join("a.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "const a: number = \"a\";\n",
+    },
+  }));
+  // the diagnostics are still published when pulling isn't opted into
+  assert_eq!(diagnostics.all().len(), 1);
+  client.shutdown();
+}
+
 #[test]
 fn lsp_json_no_diagnostics() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
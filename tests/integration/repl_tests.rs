@@ -1134,3 +1134,64 @@ fn eval_file_promise_error() {
   assert_contains!(out, "Uncaught undefined");
   assert!(err.is_empty());
 }
+
+#[test]
+fn pty_repl_commands() {
+  util::with_pty(&["repl"], |mut console| {
+    console.write_line(".help");
+    console.expect(".editor");
+    console.write_line(".foo");
+    console.expect("Invalid REPL keyword \".foo\"");
+    console.write_line(".type 1 + 1");
+    console.expect("number");
+    console.write_line(".hints");
+    console.expect("Inline type hints enabled.");
+    console.write_line(".hints");
+    console.expect("Inline type hints disabled.");
+    console.write_line(".5 + 1");
+    console.expect("1.5");
+  });
+}
+
+#[test]
+fn pty_repl_save_and_load() {
+  let context = TestContextBuilder::default().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  context
+    .new_command()
+    .args_vec(["repl", "-A"])
+    .with_pty(|mut console| {
+      console.write_line("const value = 40 + 2;");
+      console.expect("undefined");
+      console.write_line(".save session.ts");
+      console.expect("Session saved to: session.ts");
+    });
+  temp_dir
+    .path()
+    .join("session.ts")
+    .assert_matches_text("const value = 40 + 2;\n");
+  context
+    .new_command()
+    .args_vec(["repl", "-A"])
+    .with_pty(|mut console| {
+      console.write_line(".load session.ts");
+      console.expect("undefined");
+      console.write_line("value");
+      console.expect("42");
+    });
+}
+
+#[test]
+fn pty_complete_from_types() {
+  util::with_pty(&["repl"], |mut console| {
+    console.write_line("const obj: { typedProp?: number } = {};");
+    console.expect("undefined");
+    // the property only exists in the type, not on the runtime value
+    console.write_line_raw("obj.typed\t");
+    console.expect("obj.typedProp");
+    console.write_line("interface MyInterface { value: string }");
+    console.expect("undefined");
+    console.write_line_raw("let value: MyInter\t = { value: '' }");
+    console.expect("let value: MyInterface");
+  });
+}
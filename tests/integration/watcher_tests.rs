@@ -1806,6 +1806,104 @@ export function foo() {
   check_alive_then_kill(child);
 }
 
+#[flaky_test]
+#[tokio::main]
+async fn run_hmr_json_module_accept() {
+  let t = TempDir::new();
+  let file_to_watch = t.path().join("file_to_watch.js");
+  file_to_watch.write(
+    r#"
+import config from "./config.json" with { type: "json" };
+
+let current = config;
+Deno.hot(import.meta).accept("./config.json", (module) => {
+  current = module.default;
+});
+
+let i = 0;
+setInterval(() => {
+  console.log(i++, current.greeting);
+}, 100);
+"#,
+  );
+  let config_file = t.path().join("config.json");
+  config_file.write(r#"{ "greeting": "Hello" }"#);
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("run")
+    .arg("--unstable-hmr")
+    .arg("-L")
+    .arg("debug")
+    .arg(&file_to_watch)
+    .env("NO_COLOR", "1")
+    .piped_output()
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+  wait_contains("Process started", &mut stderr_lines).await;
+  wait_contains("No package.json file found", &mut stderr_lines).await;
+
+  wait_for_watcher("file_to_watch.js", &mut stderr_lines).await;
+  wait_contains("5 Hello", &mut stdout_lines).await;
+
+  config_file.write(r#"{ "greeting": "Hello world" }"#);
+
+  wait_contains("Re-evaluated changed module", &mut stderr_lines).await;
+  wait_contains("Hello world", &mut stdout_lines).await;
+
+  check_alive_then_kill(child);
+}
+
+#[flaky_test]
+#[tokio::main]
+async fn run_hmr_text_file_accept() {
+  let t = TempDir::new();
+  let file_to_watch = t.path().join("file_to_watch.js");
+  file_to_watch.write(
+    r#"
+const url = new URL("./greeting.txt", import.meta.url);
+let current = Deno.readTextFileSync(url);
+Deno.hot(import.meta).accept("./greeting.txt", (module) => {
+  current = module.default;
+});
+
+let i = 0;
+setInterval(() => {
+  console.log(i++, current);
+}, 100);
+"#,
+  );
+  let text_file = t.path().join("greeting.txt");
+  text_file.write("Hello");
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("run")
+    .arg(format!("--unstable-hmr={}", text_file))
+    .arg("--allow-read")
+    .arg("-L")
+    .arg("debug")
+    .arg(&file_to_watch)
+    .env("NO_COLOR", "1")
+    .piped_output()
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+  wait_contains("Process started", &mut stderr_lines).await;
+  wait_contains("No package.json file found", &mut stderr_lines).await;
+
+  wait_for_watcher("file_to_watch.js", &mut stderr_lines).await;
+  wait_contains("5 Hello", &mut stdout_lines).await;
+
+  text_file.write("Hello world");
+
+  wait_contains("Updated changed file", &mut stderr_lines).await;
+  wait_contains("Hello world", &mut stdout_lines).await;
+
+  check_alive_then_kill(child);
+}
+
 #[flaky_test]
 #[tokio::main]
 async fn run_hmr_uncaught_error() {
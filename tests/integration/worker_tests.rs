@@ -99,6 +99,26 @@ itest!(worker_terminate_tla_crash {
   output: "workers/terminate_tla_crash.js.out",
 });
 
+itest!(worker_resource_limits_heap {
+  args: "run --quiet --unstable-worker-options workers/resource_limits_heap.ts",
+  output: "workers/resource_limits_heap.ts.out",
+  exit_code: 0,
+});
+
+itest!(worker_resource_limits_wall_time {
+  args:
+    "run --quiet --unstable-worker-options workers/resource_limits_wall_time.ts",
+  output: "workers/resource_limits_wall_time.ts.out",
+  exit_code: 0,
+});
+
+itest!(worker_resource_limits_too_large {
+  args:
+    "run --quiet --unstable-worker-options workers/resource_limits_too_large.ts",
+  output: "workers/resource_limits_too_large.ts.out",
+  exit_code: 0,
+});
+
 itest!(worker_error_event {
   args: "run --quiet -A workers/error_event.ts",
   output: "workers/error_event.ts.out",
@@ -0,0 +1,14 @@
+export function add(a: number, b?: number): number {
+  return a + (b ?? 0);
+}
+
+export function sub(a: number, b: number): number;
+export function sub(a: bigint, b: bigint): bigint;
+// deno-lint-ignore no-explicit-any
+export function sub(a: any, b: any): any {
+  return a - b;
+}
+
+export function mul(a: number, b: number, c: number): number {
+  return a * b * c;
+}
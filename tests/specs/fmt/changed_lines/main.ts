@@ -0,0 +1 @@
+let   _a: any;
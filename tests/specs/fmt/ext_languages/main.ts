@@ -0,0 +1,29 @@
+// formats the text of each language from stdin with `deno fmt --ext`, which
+// doesn't need the `fmt` config section of the language
+const inputs: Record<string, string> = {
+  css: "a{color:red}\n",
+  scss: "a{b{color:red}}\n",
+  sass: "a\n  color:red\n",
+  less: "@color:red;a{color:@color}\n",
+  html: "<div>\n<p>hi</p>\n</div>\n",
+  vue: "<template>\n<div>hi</div>\n</template>\n<script>\nconst a=1\n</script>\n",
+  svelte: "<script>\nconst a=1\n</script>\n<p>{a}</p>\n",
+  astro: "---\nconst a=1\n---\n<p>{a}</p>\n",
+  yml: "a:   1\n",
+  yaml: "a:   1\n",
+  sql: "select a from b\n",
+};
+
+for (const [ext, input] of Object.entries(inputs)) {
+  const child = new Deno.Command(Deno.execPath(), {
+    args: ["fmt", "--no-config", `--ext=${ext}`, "-"],
+    stdin: "piped",
+    stdout: "piped",
+  }).spawn();
+  const writer = child.stdin.getWriter();
+  await writer.write(new TextEncoder().encode(input));
+  await writer.close();
+  const { code, stdout } = await child.output();
+  const output = new TextDecoder().decode(stdout);
+  console.log(`${ext}: ${code === 0 && output !== input}`);
+}
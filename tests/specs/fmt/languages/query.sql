@@ -0,0 +1 @@
+select a from b
@@ -0,0 +1,5 @@
+const style = css`a{color:red}`;
+function query() {
+  return sql`select a from b`;
+}
+const page = html`<div>${style}</div>`;
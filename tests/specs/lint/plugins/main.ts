@@ -0,0 +1,3 @@
+export const foo = 1;
+// deno-lint-ignore my-plugin/no-foo
+export const other = { foo: 2 };
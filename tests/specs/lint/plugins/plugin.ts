@@ -0,0 +1,36 @@
+interface Node {
+  type: string;
+  value?: string;
+  span: { start: number; end: number };
+}
+
+interface Context {
+  report(report: {
+    node: Node;
+    message: string;
+    hint?: string;
+    fix?(fixer: { replaceText(node: Node, text: string): unknown }): unknown;
+  }): void;
+}
+
+export default {
+  name: "my-plugin",
+  rules: {
+    "no-foo": {
+      create(context: Context) {
+        return {
+          Identifier(node: Node) {
+            if (node.value === "foo") {
+              context.report({
+                node,
+                message: "Don't name things foo",
+                hint: "Use a more descriptive name",
+                fix: (fixer) => fixer.replaceText(node, "bar"),
+              });
+            }
+          },
+        };
+      },
+    },
+  },
+};
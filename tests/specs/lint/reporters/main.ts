@@ -0,0 +1 @@
+const t = 5;
@@ -0,0 +1,6 @@
+console.log(Deno.env.get("AUDIT_TEST_VAR"));
+try {
+  Deno.readTextFileSync("./data.txt");
+} catch (err) {
+  console.log(err.name);
+}
@@ -0,0 +1,4 @@
+Deno.env.get("AUDIT_TEST_VAR");
+Deno.env.toObject();
+// the flags are also printed when the program exits itself
+Deno.exit(0);
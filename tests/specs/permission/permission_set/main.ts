@@ -0,0 +1,6 @@
+for (const name of ["read", "env"] as const) {
+  const { state } = await Deno.permissions.query(
+    name === "read" ? { name, path: "./data.txt" } : { name, variable: "HOME" },
+  );
+  console.log(name, state);
+}
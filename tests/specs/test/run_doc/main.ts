@@ -0,0 +1,29 @@
+/**
+ * ```ts
+ * import { add } from "./main.ts";
+ *
+ * if (add(1, 2) !== 3) throw new Error("wrong sum");
+ * ```
+ *
+ * ```ts
+ * import { add } from "./main.ts";
+ *
+ * if (add(2, 2) !== 5) throw new Error("wrong sum");
+ * ```
+ *
+ * ```ts
+ * import {
+ *   add, // the function under test
+ * } from "./main.ts";
+ * import type { Sum } from "./main.ts";
+ *
+ * declare const expected: Sum;
+ * export const sum: Sum = add(3, 3);
+ * if (sum !== 6) throw new Error("wrong sum");
+ * ```
+ */
+export function add(a: number, b: number): Sum {
+  return a + b;
+}
+
+export type Sum = number;
@@ -0,0 +1,25 @@
+const template = Deno.readTextFileSync(
+  new URL("./templates/index.html", import.meta.url),
+);
+console.log(template.replace("{{name}}", "world").trim());
+
+const configUrl = new URL("./data/config.json", import.meta.url);
+const config = JSON.parse(await Deno.readTextFile(configUrl));
+console.log(config.value);
+
+const stat = Deno.statSync(configUrl);
+console.log(stat.isFile, stat.size, stat.mtime instanceof Date);
+
+const templatesUrl = new URL("./templates", import.meta.url);
+const names = [...Deno.readDirSync(templatesUrl)].map((entry) => entry.name);
+console.log(names.sort().join(", "));
+
+// files that weren't embedded are read from and written to the real file system
+console.log(
+  Deno.readTextFileSync(
+    new URL("./templates/ignored.txt", import.meta.url),
+  ).trim(),
+);
+const writtenUrl = new URL("./templates/written.txt", import.meta.url);
+Deno.writeTextFileSync(writtenUrl, "written");
+console.log(Deno.readTextFileSync(writtenUrl));
@@ -0,0 +1,15 @@
+const source = Deno.args[0];
+const data = await Deno.readFile(source);
+const digest = await crypto.subtle.digest("SHA-256", data);
+const sha256 = Array.from(new Uint8Array(digest))
+  .map((b) => b.toString(16).padStart(2, "0"))
+  .join("");
+
+try {
+  await Deno.selfUpdate(source, { sha256: "0".repeat(64) });
+} catch (err) {
+  console.log(err.message.split("\n")[0]);
+}
+
+await Deno.selfUpdate(source, { sha256 });
+console.log("v1 updated");
@@ -0,0 +1,13 @@
+// The worker specifier is not a string literal, so ./worker.ts can't be
+// found when compiling and the worker initialization will fail unless
+// worker.ts is passed as a side module.
+
+const specifier = ["./worker", "ts"].join(".");
+const worker = new Worker(
+  new URL(specifier, import.meta.url),
+  { type: "module" },
+);
+
+setTimeout(() => {
+  worker.postMessage(42);
+}, 500);
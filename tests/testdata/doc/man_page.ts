@@ -0,0 +1,16 @@
+/**
+ * Greets the user from the command line.
+ *
+ * Prints a greeting for the `--name` passed to it.
+ *
+ * @example
+ * ```sh
+ * greet --name=Deno
+ * ```
+ *
+ * @module
+ */
+
+export function greet(name: string): string {
+  return `Hello ${name}`;
+}
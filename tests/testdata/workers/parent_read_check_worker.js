@@ -1,6 +1,6 @@
 const worker = new Worker(
   import.meta.resolve("./read_check_granular_worker.js"),
-  { type: "module", deno: { permissions: "none" } },
+  { type: "module", system: { permissions: "none" } },
 );
 
 onmessage = ({ data }) => {
@@ -2,7 +2,7 @@ new Worker(
   "http://localhost:4545/workers/dynamic_remote.ts",
   {
     type: "module",
-    deno: {
+    system: {
       permissions: {
         net: false,
       },
@@ -0,0 +1,8 @@
+const worker = new Worker(
+  import.meta.resolve("./resource_limits_heap_worker.ts"),
+  { type: "module", system: { resourceLimits: { maxHeapSizeMb: 32 } } },
+);
+worker.onerror = (e) => {
+  console.log("error event:", e.message);
+  e.preventDefault();
+};
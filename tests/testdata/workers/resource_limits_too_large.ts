@@ -0,0 +1,15 @@
+for (
+  const resourceLimits of [
+    { maxHeapSizeMb: Number.MAX_SAFE_INTEGER },
+    { stackSizeMb: Number.MAX_SAFE_INTEGER },
+  ]
+) {
+  try {
+    new Worker(
+      import.meta.resolve("./resource_limits_heap_worker.ts"),
+      { type: "module", system: { resourceLimits } },
+    );
+  } catch (e) {
+    console.log(`${e.name}: ${e.message}`);
+  }
+}
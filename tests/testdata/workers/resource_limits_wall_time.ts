@@ -0,0 +1,8 @@
+const worker = new Worker(
+  import.meta.resolve("./resource_limits_wall_time_worker.ts"),
+  { type: "module", system: { resourceLimits: { maxWallTimeMs: 500 } } },
+);
+worker.onerror = (e) => {
+  console.log("error event:", e.message);
+  e.preventDefault();
+};
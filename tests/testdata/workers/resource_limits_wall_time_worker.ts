@@ -0,0 +1,4 @@
+setInterval(() => {
+  const end = Date.now() + 100;
+  while (Date.now() < end);
+}, 200);
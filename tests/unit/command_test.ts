@@ -975,3 +975,167 @@ Deno.test(
     );
   },
 );
+
+Deno.test(
+  {
+    permissions: { run: true, read: true },
+    ignore: Deno.build.os === "windows",
+  },
+  async function commandRlimits() {
+    const { stdout } = await new Deno.Command("sh", {
+      args: ["-c", "ulimit -n"],
+      rlimits: { openFiles: 64 },
+    }).output();
+    assertEquals(new TextDecoder().decode(stdout), "64\n");
+  },
+);
+
+Deno.test(
+  {
+    permissions: { run: true, read: true },
+    ignore: Deno.build.os !== "linux",
+  },
+  async function commandNewSession() {
+    const { stdout } = await new Deno.Command("cat", {
+      args: ["/proc/self/stat"],
+      newSession: true,
+    }).output();
+    // pid (comm) state ppid pgrp session ...
+    const fields = new TextDecoder().decode(stdout).split(" ");
+    assertEquals(fields[4], fields[0]);
+    assertEquals(fields[5], fields[0]);
+  },
+);
+
+Deno.test(
+  {
+    permissions: { run: true, read: true },
+    ignore: Deno.build.os !== "linux",
+  },
+  async function commandNoNewPrivileges() {
+    const { stdout } = await new Deno.Command("grep", {
+      args: ["NoNewPrivs", "/proc/self/status"],
+      noNewPrivileges: true,
+    }).output();
+    assertEquals(new TextDecoder().decode(stdout), "NoNewPrivs:\t1\n");
+  },
+);
+
+Deno.test(
+  {
+    permissions: { run: true, read: true },
+    ignore: Deno.build.os !== "linux" ||
+      !["x86_64", "aarch64"].includes(Deno.build.arch),
+  },
+  async function commandSeccompDenyNetwork() {
+    const { success, stderr } = await new Deno.Command(Deno.execPath(), {
+      args: ["eval", "Deno.listen({ port: 0 })"],
+      seccomp: { denyNetwork: true },
+    }).output();
+    assert(!success);
+    assertStringIncludes(
+      new TextDecoder().decode(stderr),
+      "PermissionDenied",
+    );
+  },
+);
+
+Deno.test(
+  {
+    permissions: { run: true, read: true },
+    ignore: Deno.build.os === "linux",
+  },
+  function commandLinuxOnlyOptions() {
+    assertThrows(
+      () =>
+        new Deno.Command("echo", { noNewPrivileges: true }).outputSync(),
+      TypeError,
+    );
+  },
+);
+
+function isLandlockEnabled() {
+  try {
+    return Deno.readTextFileSync("/sys/kernel/security/lsm").includes(
+      "landlock",
+    );
+  } catch {
+    return false;
+  }
+}
+
+Deno.test(
+  {
+    permissions: { run: true, read: true, write: true },
+    ignore: Deno.build.os !== "linux" || !isLandlockEnabled(),
+  },
+  async function commandLandlock() {
+    const allowed = Deno.makeTempDirSync();
+    const denied = Deno.makeTempDirSync();
+    Deno.writeTextFileSync(`${denied}/file.txt`, "denied");
+    const systemPaths = ["/bin", "/etc", "/lib", "/lib64", "/usr"].filter(
+      (path) => {
+        try {
+          Deno.statSync(path);
+          return true;
+        } catch {
+          return false;
+        }
+      },
+    );
+
+    const { success, stderr } = await new Deno.Command("/bin/sh", {
+      args: [
+        "-c",
+        `echo allowed > ${allowed}/file.txt && cat ${denied}/file.txt`,
+      ],
+      landlock: { read: systemPaths, write: [allowed] },
+    }).output();
+    assert(!success);
+    assertEquals(Deno.readTextFileSync(`${allowed}/file.txt`), "allowed\n");
+    assertStringIncludes(
+      new TextDecoder().decode(stderr),
+      "Permission denied",
+    );
+
+    Deno.removeSync(allowed, { recursive: true });
+    Deno.removeSync(denied, { recursive: true });
+  },
+);
+
+Deno.test(
+  {
+    permissions: { run: true, read: true },
+    ignore: Deno.build.os !== "linux",
+  },
+  async function commandKillOnParentExit() {
+    const { stdout } = await new Deno.Command(Deno.execPath(), {
+      args: [
+        "eval",
+        "--unstable-process",
+        `const child = new Deno.Command("sleep", {
+          args: ["60"],
+          killOnParentExit: true,
+        }).spawn();
+        console.log(child.pid);
+        Deno.exit(0);`,
+      ],
+    }).output();
+    const pid = parseInt(new TextDecoder().decode(stdout));
+
+    // the orphaned process is reparented, and may stay a zombie if its new
+    // parent doesn't reap it
+    const isRunning = () => {
+      try {
+        const stat = Deno.readTextFileSync(`/proc/${pid}/stat`);
+        return stat.split(" ")[2] !== "Z";
+      } catch {
+        return false;
+      }
+    };
+    for (let i = 0; i < 50 && isRunning(); i++) {
+      await new Promise((resolve) => setTimeout(resolve, 100));
+    }
+    assert(!isRunning());
+  },
+);
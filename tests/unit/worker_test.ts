@@ -315,7 +315,7 @@ Deno.test({
   fn: async function () {
     const denoWorker = new Worker(
       resolveWorker("deno_worker.ts"),
-      { type: "module", deno: { permissions: "inherit" } },
+      { type: "module", system: { permissions: "inherit" } },
     );
 
     const { promise, resolve } = Promise.withResolvers<string>();
@@ -416,7 +416,7 @@ Deno.test({
 Deno.test("Worker inherits permissions", async function () {
   const worker = new Worker(
     resolveWorker("read_check_worker.js"),
-    { type: "module", deno: { permissions: "inherit" } },
+    { type: "module", system: { permissions: "inherit" } },
   );
 
   const { promise, resolve } = Promise.withResolvers<boolean>();
@@ -432,7 +432,7 @@ Deno.test("Worker inherits permissions", async function () {
 Deno.test("Worker limit children permissions", async function () {
   const worker = new Worker(
     resolveWorker("read_check_worker.js"),
-    { type: "module", deno: { permissions: { read: false } } },
+    { type: "module", system: { permissions: { read: false } } },
   );
 
   const { promise, resolve } = Promise.withResolvers<boolean>();
@@ -451,7 +451,7 @@ Deno.test("Worker limit children permissions granularly", async function () {
     workerUrl,
     {
       type: "module",
-      deno: {
+      system: {
         permissions: {
           env: ["foo"],
           hrtime: true,
@@ -502,7 +502,7 @@ Deno.test("Nested worker limit children permissions", async function () {
   /** This worker has permissions but doesn't grant them to its children */
   const worker = new Worker(
     resolveWorker("parent_read_check_worker.js"),
-    { type: "module", deno: { permissions: "inherit" } },
+    { type: "module", system: { permissions: "inherit" } },
   );
   // deno-lint-ignore no-explicit-any
   const { promise, resolve } = Promise.withResolvers<any>();
@@ -548,7 +548,7 @@ Deno.test({
       () => {
         const worker = new Worker(
           resolveWorker("deno_worker.ts"),
-          { type: "module", deno: { permissions: { env: true } } },
+          { type: "module", system: { permissions: { env: true } } },
         );
         worker.terminate();
       },
@@ -561,7 +561,7 @@ Deno.test({
 Deno.test("Worker with disabled permissions", async function () {
   const worker = new Worker(
     resolveWorker("no_permissions_worker.js"),
-    { type: "module", deno: { permissions: "none" } },
+    { type: "module", system: { permissions: "none" } },
   );
 
   const { promise, resolve } = Promise.withResolvers<boolean>();
@@ -577,7 +577,7 @@ Deno.test("Worker with disabled permissions", async function () {
 Deno.test("Worker permissions are not inherited with empty permission object", async function () {
   const worker = new Worker(
     resolveWorker("permission_echo.js"),
-    { type: "module", deno: { permissions: {} } },
+    { type: "module", system: { permissions: {} } },
   );
 
   // deno-lint-ignore no-explicit-any
@@ -602,7 +602,7 @@ Deno.test("Worker permissions are not inherited with empty permission object", a
 Deno.test("Worker permissions are not inherited with single specified permission", async function () {
   const worker = new Worker(
     resolveWorker("permission_echo.js"),
-    { type: "module", deno: { permissions: { net: true } } },
+    { type: "module", system: { permissions: { net: true } } },
   );
 
   // deno-lint-ignore no-explicit-any
@@ -630,10 +630,10 @@ Deno.test("Worker with invalid permission arg", function () {
       new Worker(`data:,close();`, {
         type: "module",
         // @ts-expect-error invalid env value
-        deno: { permissions: { env: "foo" } },
+        system: { permissions: { env: "foo" } },
       }),
     TypeError,
-    '(deno.permissions.env) invalid value: string "foo", expected "inherit" or boolean or string[]',
+    '(system.permissions.env) invalid value: string "foo", expected "inherit" or boolean or string[]',
   );
 });
 
@@ -680,7 +680,7 @@ Deno.test({
     const { promise, resolve } = Promise.withResolvers<void>();
     const worker = new Worker(
       resolveWorker("http_worker.js"),
-      { type: "module", deno: { permissions: "inherit" } },
+      { type: "module", system: { permissions: "inherit" } },
     );
     worker.onmessage = () => {
       resolve();
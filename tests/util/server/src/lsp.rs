@@ -404,6 +404,12 @@ impl InitializeParamsBuilder {
     self
   }
 
+  pub fn set_pull_diagnostics(&mut self, value: bool) -> &mut Self {
+    let options = self.initialization_options_mut();
+    options.insert("pullDiagnostics".to_string(), value.into());
+    self
+  }
+
   pub fn set_tls_certificate(&mut self, value: impl AsRef<str>) -> &mut Self {
     let options = self.initialization_options_mut();
     options.insert(